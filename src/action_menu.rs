@@ -0,0 +1,134 @@
+//! Little popup of what can be done to the selected task, for anyone who'd rather not remember
+//! which letter does what. Only lists what makes sense for the task as it is right now, and every
+//! entry just calls whatever its direct key would have
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::Line,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::{
+    layout_util::saturating_height,
+    tasks::{CancelState, Id, Task, TaskStatus},
+    theme,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Terminate,
+    Negotiate,
+    Details,
+    Note,
+    Archive,
+    Unarchive,
+    Remove,
+}
+
+impl Action {
+    fn label(self) -> &'static str {
+        match self {
+            Action::Terminate => "Terminate        ENTER",
+            Action::Negotiate => "Negotiate        N",
+            Action::Details => "Details          D",
+            Action::Note => "Note             S-N",
+            Action::Archive => "Archive          A",
+            Action::Unarchive => "Unarchive        A",
+            Action::Remove => "Remove           DEL",
+        }
+    }
+
+    /// What's on offer for `task`. Details always are, the rest depend on how it's doing. An
+    /// archived task is read-only, so it's only details, bringing it back or getting rid of it
+    fn for_task(task: &Task, archived: bool) -> Vec<Action> {
+        if archived {
+            return vec![Action::Details, Action::Unarchive, Action::Remove];
+        }
+        let mut actions = vec![];
+        if task.status.is_stoppable() && task.cancel == CancelState::None {
+            actions.push(Action::Terminate);
+        }
+        if task.status == TaskStatus::OnStrike && task.demand_secs.is_some() {
+            actions.push(Action::Negotiate);
+        }
+        actions.push(Action::Details);
+        actions.push(Action::Note);
+        if task.status.is_terminal() {
+            actions.extend([Action::Archive, Action::Remove]);
+        }
+        actions
+    }
+}
+
+#[derive(Debug)]
+pub struct ActionMenu {
+    pub id: Id,
+    actions: Vec<Action>,
+    pub state: ListState,
+}
+
+impl ActionMenu {
+    pub fn new(task: &Task, archived: bool) -> Self {
+        Self {
+            id: task.id,
+            actions: Action::for_task(task, archived),
+            state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    /// Size the popup needs, including borders
+    pub fn size(&self) -> (u16, u16) {
+        let widest = self
+            .actions
+            .iter()
+            .map(|a| a.label().len())
+            .max()
+            .unwrap_or(0);
+        // Room for the "> " and the title
+        (
+            (widest as u16 + 4).max(16),
+            saturating_height(self.actions.len() + 2),
+        )
+    }
+
+    /// Wraps list down
+    pub fn next(&mut self) {
+        let idx = self
+            .state
+            .selected()
+            .map_or(0, |i| (i + 1) % self.actions.len());
+        self.state.select(Some(idx));
+    }
+
+    /// Wraps list up
+    pub fn previous(&mut self) {
+        let len = self.actions.len();
+        let idx = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+        self.state.select(Some(idx));
+    }
+
+    pub fn action(&self) -> Action {
+        self.actions[self.state.selected().unwrap_or(0) % self.actions.len()]
+    }
+}
+
+impl Widget for &mut ActionMenu {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let items: Vec<ListItem> = self
+            .actions
+            .iter()
+            .map(|action| ListItem::from(action.label()))
+            .collect();
+        let controls = Line::from(vec![" Do ".into(), theme::key("<ENTER> ")]);
+        let block = Block::new()
+            .title(format!(" Task {} ", self.id))
+            .borders(Borders::ALL)
+            .border_style(theme::current().text)
+            .title_bottom(controls.centered());
+        let list = List::new(items)
+            .block(block)
+            .highlight_symbol(theme::current().highlight_symbol());
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
@@ -0,0 +1,3789 @@
+//! The app itself: every screen and popup, what each key does to them, and keeping up with
+//! the tasks' messages. It draws into a plain buffer like any other widget, so it can be checked
+//! against a `TestBackend` without a terminal or a single worker (see `App::with_tasks`). `launch`
+//! is what puts it on a real terminal
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io::{self, Stdout},
+    mem, panic,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use clap::Parser;
+use color_eyre::eyre::Result;
+use crossterm::{
+    event::{self, DisableFocusChange, EnableFocusChange, Event, KeyCode, KeyEvent, KeyModifiers},
+    execute,
+    terminal::{enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Block, Clear, Padding, Paragraph, Sparkline, StatefulWidget, Widget},
+    DefaultTerminal, Frame, Terminal, TerminalOptions, Viewport,
+};
+use tokio::{sync::mpsc, task};
+use tracing::{debug, error, info, trace, warn};
+
+use crate::{
+    action_menu::{Action, ActionMenu},
+    architecture::{Channel, Plumbing},
+    banner::Banner,
+    batch::Batch,
+    candidates::{self, TaskFile},
+    chain::Chain,
+    chaos::{self, ChaosConfig},
+    clock::{Clock, SystemClock},
+    column_menu::ColumnMenu,
+    columns::Column,
+    compat::{self, Compat},
+    completion::Outcome,
+    config::{Cli, Config},
+    controls::{self, Control, Toggles},
+    cpu::{self, CpuTime},
+    demo::{self, Player},
+    duration_prompt::DurationPrompt,
+    guard_popup::GuardPopup,
+    guards::Verdict,
+    health::{Health, Severity},
+    history,
+    inbox::{self, Inbox},
+    journal::{self, Entry, Journal, Unclean},
+    latency::{self, Histogram, Stamped},
+    layout_util::{centered_rect, saturating_height, split_with_min},
+    log_dedup,
+    log_pane::LogPane,
+    nav::{ListNav, Move},
+    negotiation::Negotiation,
+    note_editor::NoteEditor,
+    pool_editor::{Form, PoolEditor, DEFAULT_POOL_PATH},
+    preflight::{self, Root},
+    progress_csv::ProgressCsv,
+    recording::{Recorder, Recording},
+    report,
+    retry::{self, Attempt, Decision},
+    session,
+    shutdown_summary::ShutdownSummary,
+    split::{self, SplitMode},
+    status_file::StatusFile,
+    status_server, strings,
+    suspend::{self, Suspend},
+    tally::Tally,
+    task_detail::{TaskDetail, TaskPanel},
+    task_picker::{self, TaskPicker},
+    task_table::TaskTable,
+    tasks::{
+        self, CancelEvent, CancelState, Id, Orders, ReportingMode, StatusClass, Task, TaskError,
+        TaskKind, TaskRxMsg, TaskStatus, TaskTxMsg, WorkerStatus,
+    },
+    templates::{self, Template},
+    theme::{self, Chrome},
+    timeline::Timeline,
+    timing::{self, FrameLimiter},
+    tombstones::{Stray, Tombstones},
+    tour::{self, Step, Tour},
+    ui_state::{self, UiState},
+    view::ViewState,
+    visibility::Visibility,
+    webhook::{self, EventKind, Webhook},
+    wiring::Wiring,
+    wrap::wrap,
+    CandidateTask,
+};
+
+/// How much `<`/`>` change work intensity by
+const INTENSITY_STEP: f32 = 0.1;
+/// How long toasts stay up
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+/// How long a key hint takes over the controls line
+const HINT_DURATION: Duration = Duration::from_secs(2);
+/// After this many showings of the same hint, we figure they've got it
+const HINT_REPEATS: u8 = 3;
+/// How long the warning about tasks queued for a thread stays up
+const THREAD_BANNER_DURATION: Duration = Duration::from_secs(10);
+/// How long tasks get to stop on their own after F3 before we leave without them
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+/// The same candidate added again this soon is a key bouncing, not a second task
+const DUPLICATE_WINDOW: Duration = Duration::from_millis(250);
+/// Narrower than this and the inspect side panel doesn't fit next to the table
+const SIDE_PANEL_MIN_WIDTH: u16 = 110;
+/// How far in from the table's edge the action menu sits, to clear the glyph and ID columns
+const MENU_INDENT: u16 = 8;
+/// Rows under the numbers in the debug overlay
+const DEBUG_SPARKLINE_HEIGHT: u16 = 2;
+/// Where `F7` looks if the config doesn't say
+const DEFAULT_BATCH_PATH: &str = "./batch.toml";
+
+/// Injected panics go in the log like any other fault. Anything else still goes to the hooks
+/// ratatui and color_eyre put in, which put the terminal back first
+fn quiet_chaos_panics() {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if chaos::is_injected(info.payload()) {
+            error!(target: chaos::TARGET, "{info}");
+        } else {
+            hook(info);
+        }
+    }));
+}
+
+pub async fn launch(
+    cli: Cli,
+    config: Config,
+    mut health: Health,
+    recording: Option<Recording>,
+    status: Option<status_server::Shared>,
+) -> Result<ShutdownSummary> {
+    let mut terminal = ratatui::init();
+    // Terminals that don't do focus events never send any, and we carry on as if focused
+    execute!(io::stdout(), EnableFocusChange)?;
+    quiet_chaos_panics();
+    // Named for the session like the report, so each run's curves are a file of their own
+    let progress_csv = config.progress_csv.as_ref().map(|path| match &cli.session {
+        Some(name) => session::unique(path, name),
+        None => path.clone(),
+    });
+    let mut app = App::new(config, &cli);
+    if let Some(path) = progress_csv {
+        match ProgressCsv::create(&path, app.clock.now_instant()) {
+            Ok(csv) => {
+                info!("writing progress to {}", path.display());
+                app.progress_csv = Some(csv);
+            }
+            Err(e) => health.error(format!("can't write progress to {}: {e}", path.display())),
+        }
+    }
+    if app.config.journal.enabled {
+        let path = app.config.journal.path.clone();
+        // Before it's started over, or there'd be nothing left to read
+        match journal::recover(&path) {
+            Ok(Some(unclean)) => {
+                let count = app.recover(&unclean);
+                warn!("the last session didn't close, recovered {count} tasks from its journal");
+                health.warn(format!(
+                    "The last session{} ended without closing. Its {count} tasks are archived \
+                     as they were, and anything still going is marked abandoned. Press A to \
+                     show them",
+                    unclean
+                        .session
+                        .as_ref()
+                        .map(|name| format!(" ({name})"))
+                        .unwrap_or_default()
+                ));
+            }
+            Ok(None) => {}
+            Err(e) => health.warn(format!("can't read the journal {}: {e}", path.display())),
+        }
+        let session = app.session.clone();
+        match Journal::create(
+            &path,
+            session.as_deref(),
+            app.clock.now_local(),
+            app.clock.now_instant(),
+        ) {
+            Ok(journal) => app.journal = Some(journal),
+            Err(e) => health.warn(format!("can't journal to {}: {e}", path.display())),
+        }
+    }
+    app.root = preflight::decide(preflight::euid(), cli.allow_root) == Root::Allowed;
+    app.status_server = status;
+    app.suspend.listen();
+    // A replay rolls the dice the recording did, a recording rolls some worth writing down
+    let seed = match &recording {
+        Some(recording) => Some(recording.seed),
+        None => cli.record.is_some().then(rand::random),
+    };
+    if let Some(seed) = seed {
+        tasks::seed_workers(seed);
+        app.picker.seed(seed);
+    }
+    if let (Some(path), Some(seed)) = (&cli.record, seed) {
+        match Recorder::create(path, seed) {
+            Ok(recorder) => {
+                info!("recording to {}", path.display());
+                app.recorder = Some(recorder);
+            }
+            Err(e) => health.error(format!("can't record to {}: {e}", path.display())),
+        }
+    }
+    if let Some(recording) = recording {
+        info!("replaying {} keys", recording.keys.len());
+        app.replay = Some(Replay::new(
+            recording,
+            cli.replay_speed,
+            app.clock.now_instant(),
+        ));
+    }
+    app.with_health(health).run(&mut terminal).await
+}
+
+/// The keys from a recording that haven't gone in yet
+#[derive(Debug)]
+struct Replay {
+    keys: VecDeque<(Duration, KeyEvent)>,
+    started: Instant,
+    speed: f64,
+}
+
+impl Replay {
+    fn new(recording: Recording, speed: f64, now: Instant) -> Self {
+        Self {
+            keys: recording.keys.into(),
+            started: now,
+            speed,
+        }
+    }
+
+    /// When the next key's meant to go in
+    fn next_at(&self) -> Option<Instant> {
+        let &(at, _) = self.keys.front()?;
+        Some(self.started + at.div_f64(self.speed))
+    }
+
+    /// The next key, if it's time for it
+    fn due(&mut self, now: Instant) -> Option<KeyEvent> {
+        if self.next_at()? > now {
+            return None;
+        }
+        self.keys.pop_front().map(|(_, key)| key)
+    }
+}
+
+#[derive(Debug)]
+pub struct App {
+    picker: TaskPicker,
+    task_table: TaskTable,
+    detail: TaskDetail,
+    timeline: Timeline,
+    logs: LogPane,
+    /// Only around while we're talking a task down
+    negotiation: Option<Negotiation>,
+    menu: Option<ActionMenu>,
+    /// Only around while the column popup is open
+    column_menu: Option<ColumnMenu>,
+    note_editor: Option<NoteEditor>,
+    /// Only around while a duration's being chosen for a new task
+    duration_prompt: Option<DurationPrompt>,
+    pool_editor: PoolEditor,
+    /// Where task events get POSTed, if anywhere
+    webhook: Option<Webhook>,
+    /// Where finished tasks get written down, if anywhere
+    history_path: Option<PathBuf>,
+    /// `status_file`, for status bars
+    status_file: Option<StatusFile>,
+    /// What the `http.listen` server hands out, if there is one
+    status_server: Option<status_server::Shared>,
+    /// Where the sort and filter are kept between runs. `None` with `--reset-ui`
+    ui_state_path: Option<PathBuf>,
+    /// Only around while the batch popup is up
+    batch: Option<Batch>,
+    /// A task a guard wants a second look at, until it's added or not
+    guard_popup: Option<GuardPopup>,
+    /// Batch tasks with a delay, waiting for their time to come. They have rows already, this
+    /// is just when to activate them. Kept in order of `Instant`
+    scheduled: Vec<(Instant, tasks::Id)>,
+    view_state: ViewState,
+    exit: bool,
+    tasks: Vec<tasks::Task>,
+    tasks_created: tasks::Id, // Tokio ID's will be reused. We don't want that!
+    // Tasks send us updates through this
+    mpsc_rx: mpsc::Receiver<Stamped<TaskTxMsg>>,
+    mpsc_tx: mpsc::Sender<Stamped<TaskTxMsg>>,
+    /// Warning line shown above the table until it expires
+    banner: Option<Banner>,
+    /// Short-lived message in the bottom padding, for telling the user why nothing happened
+    toast: Option<Banner>,
+    /// Why that key didn't do anything. Goes where the controls are, briefly
+    hint: Option<Banner>,
+    /// How many times each hint has been shown, for giving up on it
+    hints_shown: HashMap<String, u8>,
+    config: Config,
+    /// When to redraw, and how often we have been
+    frames: FrameLimiter,
+    /// Timing numbers in the corner, for when it feels slow
+    show_debug: bool,
+    /// `?`: what the one-letter headers and the glyphs stand for. Any key puts it away
+    show_help: bool,
+    /// The config's `[[template]]`s that checked out
+    templates: Vec<Template>,
+    /// `T` was just pressed on the main screen, so the next key says which template
+    choosing_template: bool,
+    /// `T` then `?`: every template and what it adds. Any key puts it away, like the help
+    show_templates: bool,
+    /// What startup found wrong, from `main` and `new`. In the help, and on its own before the
+    /// main screen if any of it's a warning
+    health: Health,
+    /// Showing `health` until Enter
+    show_health: bool,
+    /// What this terminal gets wrong, worked out once
+    compat: Compat,
+    /// First run only, see `tour`
+    tour: Tour,
+    /// The tour's been finished or skipped, here or on some earlier run. Kept with the UI state
+    tour_done: bool,
+    /// Where to dump the JSON report at exit, if anywhere
+    report_path: Option<PathBuf>,
+    /// `--session`, or the name `main` made up. The tests' apps don't have one
+    session: Option<String>,
+    /// Only around after F3, while we wait for everyone to put their tools down
+    shutdown: Option<Shutdown>,
+    /// Rows removed recently, so their workers' last words aren't mistaken for a bug
+    tombstones: Tombstones,
+    /// Messages for removed rows, and for IDs we never had at all
+    late_messages: usize,
+    /// Heartbeats heard, for the debug overlay
+    heartbeats: usize,
+    /// Worker messages off the channel that didn't fit in their frame
+    inbox: Inbox,
+    /// The inbox filled up before the channel was empty, so some worker's last word might not
+    /// be heard yet
+    behind: bool,
+    /// Ctrl+Z, and whether the terminal's ours right now
+    suspend: Suspend,
+    protocol_errors: usize,
+    /// Whether the channels still work, and how many sends it's skipped since they didn't
+    wiring: Wiring,
+    /// How long task messages waited in the channel, all of them since startup
+    latency: Histogram,
+    /// Running as root, with `--allow-root`
+    root: bool,
+    /// Everything App times or stamps goes by this. Frame pacing excepted, that's about the real
+    /// terminal
+    clock: Box<dyn Clock>,
+    /// `--record`, while it's still writing
+    recorder: Option<Recorder>,
+    /// `progress_csv`, while it's still writing
+    progress_csv: Option<ProgressCsv>,
+    /// The `journal`, while it's still writing
+    journal: Option<Journal>,
+    /// What the journal last heard about each task, by ID: its status, and whether it's been
+    /// asked to stop. Anything past the end hasn't been journaled at all
+    journaled: Vec<(TaskStatus, bool)>,
+    /// `--replay`, until it runs out of keys or someone presses one of their own
+    replay: Option<Replay>,
+    /// F10's macro, same deal
+    demo: Option<Player>,
+    /// F9: the whole UI is down to one line under whatever was on the terminal before
+    minimized: bool,
+    /// The latest task to end, for the minimized line
+    last_ended: Option<String>,
+    /// The candidate of the last task added by hand, for `n` to add again. Only this session's
+    last_added: Option<CandidateTask>,
+    /// Whatever `add_task` was last given and when, so a bounce doesn't add it twice
+    last_add: Option<(String, Instant)>,
+}
+
+/// Who we're still waiting on to stop, and how long we're willing to wait
+#[derive(Debug)]
+struct Shutdown {
+    deadline: Instant,
+    waiting: HashSet<tasks::Id>,
+    total: usize,
+}
+
+impl Shutdown {
+    fn new(waiting: HashSet<tasks::Id>, now: Instant) -> Self {
+        Self {
+            deadline: now + SHUTDOWN_GRACE,
+            total: waiting.len(),
+            waiting,
+        }
+    }
+
+    /// Either a CancelReport or the handle finishing counts. Saying it twice doesn't
+    fn ack(&mut self, id: tasks::Id) {
+        self.waiting.remove(&id);
+    }
+
+    fn is_done(&self, now: Instant) -> bool {
+        self.waiting.is_empty() || now >= self.deadline
+    }
+
+    /// Goes where the controls usually are
+    fn line(&self, now: Instant) -> Line<'_> {
+        let left = self.deadline.saturating_duration_since(now);
+        Line::from(format!(
+            " shutting down — {}/{} tasks acknowledged — forcing in {}s (F3 to force now) ",
+            self.total - self.waiting.len(),
+            self.total,
+            left.as_secs_f32().ceil() as u64
+        ))
+        .style(theme::current().notice)
+        .bold()
+    }
+}
+
+impl App {
+    pub fn new(config: Config, cli: &Cli) -> Self {
+        // Edits to the pool go back wherever it came from, or a file of its own if nowhere
+        let pool_path = config
+            .tasks_file
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_POOL_PATH));
+        let mut health = Health::default();
+        let (pool, savable) =
+            task_picker::load_pool(&pool_path, config.tasks_file.is_some(), &mut health);
+        let templates = templates::compile(&config.templates, &pool, &mut health);
+        let compat = Compat::detect(config.ui.legacy_terminal);
+        let nav = ListNav::new(config.ui.wrap_lists);
+        let mut picker = TaskPicker::new(config.picker.keep_last, pool, nav);
+        picker.cooldown = Duration::from_secs(config.picker.cooldown_secs);
+        picker.stats = config
+            .history_file
+            .as_deref()
+            .map(|path| task_picker::load_history(path, &mut health));
+        let webhook = config.webhook_url.clone().and_then(|url| {
+            Webhook::new(url, webhook::DEFAULT_TIMEOUT)
+                .inspect_err(|e| health.error(format!("couldn't set up the webhook: {e}")))
+                .ok()
+        });
+        let ui_state_path = (!cli.fresh_ui()).then(|| PathBuf::from(ui_state::DEFAULT_PATH));
+        let mut task_table = TaskTable::new(
+            config.ui.flash,
+            config.ui.ascii_glyphs,
+            config.ui.columns.clone(),
+            nav,
+        );
+        task_table.compact = config.ui.compact;
+        task_table.momentum = config.momentum;
+        task_table.still = config.work.low_power;
+        task_table.visibility = Visibility::new(config.ui.hidden_statuses.clone());
+        let mut tour_done = false;
+        if let Some(path) = &ui_state_path {
+            let (state, problem) = UiState::load(path);
+            if let Some(problem) = problem {
+                health.warn(problem);
+            }
+            tour_done = state.tour_done;
+            task_table.restore(state);
+        }
+        // Nothing's ever been saved, so they've probably never been here. Without anywhere to
+        // keep the UI state it'd only come back next time
+        let first_run =
+            !cli.config.exists() && !config.history_file.as_deref().is_some_and(Path::exists);
+        let tour = match ui_state_path.is_some() && first_run && !tour_done {
+            true => Tour::new(),
+            false => Tour::default(),
+        };
+        // Used by tasks to bubble a message up
+        let (mpsc_tx, mpsc_rx) = mpsc::channel(inbox::CHANNEL_CAPACITY);
+        let mut frames = FrameLimiter::new(config.timing.with_overrides(cli));
+        frames.set_low_power(config.work.low_power, Instant::now());
+        let clock: Box<dyn Clock> = Box::new(SystemClock);
+        Self {
+            picker,
+            pool_editor: PoolEditor::new(savable.then_some(pool_path)),
+            webhook,
+            history_path: config.history_file.clone(),
+            status_file: config.status_file.clone().map(StatusFile::new),
+            status_server: None,
+            ui_state_path,
+            task_table,
+            detail: TaskDetail::default(),
+            timeline: Timeline::new(clock.now_local(), config.ui.ascii_glyphs),
+            logs: LogPane::default(),
+            negotiation: None,
+            menu: None,
+            column_menu: None,
+            note_editor: None,
+            duration_prompt: None,
+            batch: None,
+            guard_popup: None,
+            scheduled: vec![],
+            tasks: vec![],
+            tasks_created: 0,
+            view_state: ViewState::Monitor,
+            exit: false,
+            mpsc_rx,
+            mpsc_tx,
+            banner: None,
+            toast: None,
+            hint: None,
+            hints_shown: HashMap::new(),
+            config,
+            frames,
+            show_debug: false,
+            show_help: false,
+            templates,
+            choosing_template: false,
+            show_templates: false,
+            health,
+            show_health: false,
+            compat,
+            tour,
+            tour_done,
+            report_path: cli.report.clone(),
+            session: cli.session.clone(),
+            shutdown: None,
+            tombstones: Tombstones::default(),
+            late_messages: 0,
+            heartbeats: 0,
+            inbox: Inbox::default(),
+            behind: false,
+            suspend: Suspend::default(),
+            latency: Histogram::default(),
+            root: false,
+            clock,
+            protocol_errors: 0,
+            wiring: Wiring::default(),
+            recorder: None,
+            progress_csv: None,
+            journal: None,
+            journaled: vec![],
+            replay: None,
+            demo: None,
+            minimized: false,
+            last_ended: None,
+            last_added: None,
+            last_add: None,
+        }
+    }
+
+    /// An app with `tasks` already in the table and nothing behind them: no workers, nothing
+    /// read from or written to disk but the candidates file, and the picker's dice seeded. For
+    /// drawing into a `TestBackend`. Each task's ID has to be its place in the list, the same as
+    /// the app's own
+    pub fn with_tasks(config: Config, tasks: Vec<Task>) -> Self {
+        let cli = Cli::parse_from(["clustrctrl", "--reset-ui"]);
+        let mut app = App::new(config, &cli);
+        app.picker = TaskPicker::new(
+            false,
+            task_picker::builtin_pool(),
+            ListNav::new(app.config.ui.wrap_lists),
+        );
+        app.picker.cooldown = Duration::from_secs(app.config.picker.cooldown_secs);
+        app.picker.seed(0);
+        app.tasks_created = tasks.len();
+        app.tasks = tasks;
+        app
+    }
+
+    /// Adds what `main` found to what `new` did, and puts the lot up before the main screen if
+    /// any of it's worth a look
+    pub fn with_health(mut self, mut health: Health) -> Self {
+        health.merge(mem::take(&mut self.health));
+        self.show_health = health.needs_attention();
+        self.health = health;
+        self
+    }
+
+    /// Progress goes to `csv` as well, like `progress_csv` does
+    pub fn with_progress_csv(mut self, csv: ProgressCsv) -> Self {
+        self.progress_csv = Some(csv);
+        self
+    }
+
+    /// Tasks get journaled to `journal` from here on
+    pub fn with_journal(mut self, journal: Journal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Rows for what a session that didn't close left in its journal, straight into the
+    /// archive. How many there were
+    pub fn recover(&mut self, unclean: &Unclean) -> usize {
+        for recovered in &unclean.tasks {
+            let id = self.tasks_created;
+            let task = recovered.task(id);
+            self.journaled.push((task.status, true));
+            self.tasks.push(task);
+            self.task_table.archive.set(id, true);
+            self.tasks_created += 1;
+        }
+        unclean.tasks.len()
+    }
+
+    /// Which channels have broken, if any
+    pub fn wiring(&self) -> &Wiring {
+        &self.wiring
+    }
+
+    /// Marked as running as root, like `--allow-root` as root is
+    pub fn with_root(mut self, root: bool) -> Self {
+        self.root = root;
+        self
+    }
+
+    /// `compat` instead of what was detected, for trying Windows' quirks anywhere
+    pub fn with_compat(mut self, compat: Compat) -> Self {
+        self.compat = compat;
+        self
+    }
+
+    /// `clock` instead of the system's, for tests that need time to stand still
+    pub fn with_clock(mut self, clock: Box<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Where the workers' messages go, for tests that stand in for them
+    pub fn task_sender(&self) -> mpsc::Sender<Stamped<TaskTxMsg>> {
+        self.mpsc_tx.clone()
+    }
+
+    /// Every row, in ID order
+    pub fn tasks(&self) -> &[Task] {
+        &self.tasks
+    }
+
+    /// Until it's told to quit, then whatever was left running for `main` to mention
+    pub async fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<ShutdownSummary> {
+        // What the terminal's actually set up for, which `minimized` only asks for
+        let mut inline = false;
+        while !self.exit {
+            if self.suspend.due() {
+                self.sleep_it_off(terminal, inline)?;
+            }
+            // Somebody else stopped us, and whatever's on screen now isn't ours
+            if self.suspend.take_continued() {
+                terminal.clear()?;
+                self.frames.mark_dirty();
+            }
+            if self.minimized != inline {
+                inline = self.minimized;
+                set_viewport(terminal, inline)?;
+                self.frames.mark_dirty();
+            }
+            let now = Instant::now();
+            if self.suspend.can_draw() && self.frames.should_draw(now) {
+                terminal.draw(|frame| self.view(frame))?;
+                self.frames.drew(now, now.elapsed());
+            }
+            let wait = self.frames.wait(Instant::now());
+            if self.update(wait).await? {
+                self.frames.mark_dirty();
+            }
+            task::yield_now().await;
+        }
+        // Goodbye goes where the status line was, instead of after it
+        if inline {
+            terminal.clear()?;
+        }
+        self.save_ui_state();
+        if let Some(status_file) = &self.status_file {
+            status_file.remove();
+        }
+        self.close_progress_csv();
+        self.close_journal();
+        let mut report = None;
+        // Named for the session, and never over one an earlier run of it left
+        let report_path = self.report_path.as_ref().map(|path| match &self.session {
+            Some(name) => session::unique(path, name),
+            None => path.clone(),
+        });
+        if let Some(path) = report_path {
+            match report::write_report(&path, self.session.as_deref(), &self.tasks, &self.latency) {
+                Ok(_) => {
+                    info!("wrote report to {}", path.display());
+                    report = Some(path);
+                }
+                Err(e) => error!("problem writing report to {}: {e}", path.display()),
+            }
+        }
+        Ok(ShutdownSummary::of(
+            &self.tasks,
+            report,
+            self.clock.now_local(),
+        ))
+    }
+
+    /// Hands the terminal back to the shell, stops until `fg`, and takes it again the way it was
+    /// (inline or not) before anything's drawn
+    fn sleep_it_off(&mut self, terminal: &mut DefaultTerminal, inline: bool) -> Result<()> {
+        info!("suspending");
+        if self.config.work.pause_suspended {
+            self.tell_everyone(TaskRxMsg::Pause(true));
+        }
+        execute!(io::stdout(), DisableFocusChange)?;
+        ratatui::restore();
+        suspend::stop();
+        // Continued, which could be hours later
+        enable_raw_mode()?;
+        if !inline {
+            execute!(io::stdout(), EnterAlternateScreen)?;
+        }
+        execute!(io::stdout(), EnableFocusChange)?;
+        terminal.clear()?;
+        self.suspend.resumed();
+        self.frames.mark_dirty();
+        if self.config.work.pause_suspended {
+            self.tell_everyone(TaskRxMsg::Pause(self.orders().paused));
+        }
+        info!("back from suspend");
+        Ok(())
+    }
+
+    /// Draws everything, overlays included. The whole UI goes through here, so a `TestBackend`
+    /// frame gets exactly what the terminal would
+    pub fn view(&mut self, frame: &mut Frame) {
+        let area = frame.area();
+        if self.minimized {
+            frame.render_widget(self.minimized_line(), area);
+            return;
+        }
+        frame.render_widget(&mut *self, area);
+        if let Some(step) = self.tour.step() {
+            let target = self.tour_target(step, area);
+            tour::render(step, target, area, frame.buffer_mut());
+        }
+        // Goes over everything, the detail view included
+        if self.show_debug {
+            let lines = self.debug_overlay();
+            let width = 34.min(area.width);
+            let overlay_area = Rect {
+                x: area.right().saturating_sub(width.saturating_add(1)),
+                y: area.y.saturating_add(1),
+                width,
+                height: saturating_height(lines.len() + DEBUG_SPARKLINE_HEIGHT as usize + 2),
+            }
+            .intersection(area);
+            frame.render_widget(Clear, overlay_area);
+            let block = Block::bordered()
+                .title(" debug ")
+                .border_style(theme::current().dim);
+            let [text_area, spark_area] =
+                split_with_min(block.inner(overlay_area), saturating_height(lines.len()), 0);
+            frame.render_widget(block, overlay_area);
+            frame.render_widget(Paragraph::new(lines), text_area);
+            frame.render_widget(self.render_sparkline(spark_area.width), spark_area);
+        }
+        if self.show_help {
+            let width = 44.min(area.width);
+            let lines = self.help_overlay(width.saturating_sub(4));
+            let help_area = centered_rect(area, width, saturating_height(lines.len() + 2));
+            frame.render_widget(Clear, help_area);
+            let block = Block::bordered()
+                .title(strings::get("help.title"))
+                .title_bottom(Line::from(strings::get("help.close")).centered())
+                .padding(Padding::horizontal(1));
+            frame.render_widget(Paragraph::new(lines).block(block), help_area);
+        }
+        if self.show_templates {
+            let lines: Vec<Line> = self.templates.iter().map(Template::line).collect();
+            let width = 72.min(area.width);
+            let templates_area = centered_rect(area, width, saturating_height(lines.len() + 2));
+            frame.render_widget(Clear, templates_area);
+            let block = Block::bordered()
+                .title(strings::get("templates.title"))
+                .title_bottom(Line::from(strings::get("help.close")).centered())
+                .padding(Padding::horizontal(1));
+            frame.render_widget(Paragraph::new(lines).block(block), templates_area);
+        }
+        if self.show_health {
+            let width = 72.min(area.width);
+            let lines = self.health_lines(width.saturating_sub(4));
+            let health_area = centered_rect(area, width, saturating_height(lines.len() + 2));
+            frame.render_widget(Clear, health_area);
+            let border = match self.health.worst() {
+                Some(Severity::Error) => theme::current().alert,
+                _ => theme::current().notice,
+            };
+            let block = Block::bordered()
+                .title(strings::get("health.title"))
+                .title_bottom(Line::from(strings::get("health.continue")).centered())
+                .border_style(border)
+                .padding(Padding::horizontal(1));
+            frame.render_widget(Paragraph::new(lines).block(block), health_area);
+        }
+    }
+
+    /// What startup found, worst first, each wrapped to `width` under its severity
+    fn health_lines(&self, width: u16) -> Vec<Line<'static>> {
+        let theme = theme::current();
+        let mut lines = vec![];
+        for item in self.health.sorted() {
+            let style = match item.severity {
+                Severity::Error => theme.alert,
+                Severity::Warning => theme.notice,
+                Severity::Info => theme.dim,
+            };
+            let tag = format!("{} ", strings::get(item.severity.key()));
+            let indent = tag.chars().count();
+            let wrapped = wrap(&item.message, (width as usize).saturating_sub(indent));
+            for (i, text) in wrapped.into_iter().enumerate() {
+                let lead = match i {
+                    0 => Span::styled(tag.clone(), style),
+                    _ => Span::raw(" ".repeat(indent)),
+                };
+                lines.push(Line::from(vec![lead, text.into()]));
+            }
+        }
+        lines
+    }
+
+    /// The header letters compact mode uses, the glyphs in the table right now, and anything
+    /// startup found. `width` is only for wrapping that last one
+    fn help_overlay(&self, width: u16) -> Vec<Line<'static>> {
+        let theme = theme::current();
+        let mut lines = vec![Line::styled(strings::get("help.headers"), theme.dim)];
+        lines.extend(Column::ALL.iter().map(|column| {
+            Line::from(vec![
+                Span::styled(format!("{:<3}", column.letter()), theme.key),
+                column.title().into(),
+            ])
+        }));
+        let legend = self.task_table.legend(&self.tasks);
+        if !legend.spans.is_empty() {
+            lines.push(Line::default());
+            lines.push(Line::styled(strings::get("help.glyphs"), theme.dim));
+            lines.push(legend);
+        }
+        if !self.templates.is_empty() {
+            lines.push(Line::default());
+            lines.push(Line::styled(strings::get("help.templates"), theme.dim));
+            lines.extend(self.templates.iter().map(|template| {
+                Line::from(vec![
+                    Span::styled(format!("{:<3}", template.key), theme.key),
+                    template.name.clone().into(),
+                ])
+            }));
+        }
+        lines.push(Line::default());
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<3}", "a"), theme.key),
+            strings::get("help.architecture").into(),
+        ]));
+        if !self.health.is_empty() {
+            lines.push(Line::default());
+            lines.push(Line::styled(strings::get("help.startup"), theme.dim));
+            lines.extend(self.health_lines(width));
+        }
+        lines
+    }
+
+    /// "clustrctrl: 3 running · 62% · last: task 7 finished", or how the shutdown's going
+    fn minimized_line(&self) -> Line<'_> {
+        let theme = theme::current();
+        let mut spans = vec![Span::styled("clustrctrl: ", theme.accent)];
+        if let Some(shutdown) = &self.shutdown {
+            spans.extend(shutdown.line(self.clock.now_instant()).spans);
+            return Line::from(spans);
+        }
+        let tally = Tally::of(self.task_table.kept(&self.tasks));
+        let mut facts = vec![match tally.working() {
+            0 => "nothing running".to_string(),
+            n => format!("{n} running"),
+        }];
+        if let Some(pct) = tally.overall_pct {
+            facts.push(format!("{pct}%"));
+        }
+        if let Some(last) = &self.last_ended {
+            facts.push(format!("last: {last}"));
+        }
+        spans.push(facts.join(" · ").into());
+        spans.push(Span::styled("  (any key to come back)", theme.dim));
+        Line::from(spans)
+    }
+
+    /// Over the full-screen logs: how many tasks are where, then anything that wants a look
+    fn status_strip(&self) -> Line<'_> {
+        let theme = theme::current();
+        let tally = Tally::of(self.task_table.kept(&self.tasks));
+        let groups = [
+            (tally.working(), "running"),
+            (tally.waiting, "waiting"),
+            (tally.done, "done"),
+            (tally.cancelled, "cancelled"),
+            (tally.failed, "failed"),
+        ];
+        let facts: Vec<String> = groups
+            .iter()
+            .filter(|(n, _)| *n > 0)
+            .map(|(n, what)| format!("{n} {what}"))
+            .collect();
+        let mut spans = vec![Span::raw(match facts.is_empty() {
+            true => "no tasks yet".to_string(),
+            false => facts.join(" · "),
+        })];
+        let used = cpu::total(
+            self.task_table
+                .kept(&self.tasks)
+                .filter_map(|task| task.cpu),
+        );
+        if let Some(used) = used {
+            spans.push(Span::styled(format!("  {used} CPU"), theme.dim));
+        }
+        let striking = tally.striking;
+        if striking > 0 {
+            spans.push(Span::styled(
+                format!("  ! {striking} on strike"),
+                theme.alert,
+            ));
+        }
+        let stopping = self
+            .tasks
+            .iter()
+            .filter(|task| task.cancel == CancelState::Requested && task.end.is_none())
+            .count();
+        if stopping > 0 {
+            spans.push(Span::styled(format!("  {stopping} stopping"), theme.notice));
+        }
+        if self.config.work.low_power {
+            spans.push(Span::styled(
+                format!("  {}", strings::get("low_power.label")),
+                theme.dim,
+            ));
+        }
+        Line::from(spans)
+    }
+
+    fn debug_overlay(&self) -> Vec<Line<'_>> {
+        let timing = self.frames.config;
+        let render = &self.frames.render_ms;
+        let mut lines = vec![
+            Line::from(format!(
+                "poll {}ms  tick {}ms",
+                timing.poll_ms, timing.tick_ms
+            )),
+            Line::from(format!(
+                "cap {}fps  drawing {}fps",
+                timing.max_fps,
+                self.frames.fps()
+            )),
+            Line::from(
+                match (self.frames.is_focused(), self.frames.is_low_power()) {
+                    (true, false) => "focused, full speed".to_string(),
+                    (true, true) => format!(
+                        "focused, low power, a tick per {}s",
+                        timing::LOW_POWER_TICK.as_secs()
+                    ),
+                    (false, _) => format!(
+                        "unfocused, a frame per {}s",
+                        timing::UNFOCUSED_CADENCE.as_secs()
+                    ),
+                },
+            ),
+            Line::from(
+                match (
+                    render.percentile(0.5),
+                    render.percentile(0.95),
+                    render.max(),
+                ) {
+                    (Some(p50), Some(p95), Some(max)) => {
+                        format!("draw p50 {p50:.1} p95 {p95:.1} max {max:.1}ms")
+                    }
+                    _ => "draw -".to_string(),
+                },
+            ),
+        ];
+        // Dummies hold a blocking thread from activation to harvest, whether they got one yet or not
+        let in_flight = self
+            .tasks
+            .iter()
+            .filter(|task| task.kind == TaskKind::Dummy && task.completion.is_running())
+            .count();
+        lines.push(Line::from(format!(
+            "blocking {in_flight} in flight, max {}",
+            self.config.work.max_blocking_threads
+        )));
+        lines.push(Line::from(format!(
+            "late msgs {}  protocol errors {}",
+            self.late_messages, self.protocol_errors
+        )));
+        lines.push(Line::from(format!("heartbeats {}", self.heartbeats)));
+        let backwards: Vec<&Task> = self.tasks.iter().filter(|t| t.backwards > 0).collect();
+        lines.push(Line::from(format!(
+            "progress backwards {} in {} tasks",
+            backwards.iter().map(|t| t.backwards).sum::<u32>(),
+            backwards.len()
+        )));
+        lines.push(Line::from(format!(
+            "msgs deferred {}, {} a frame",
+            self.inbox.len(),
+            timing.message_budget
+        )));
+        lines.push(Line::from(format!(
+            "msg delivery, {} so far",
+            self.latency.total()
+        )));
+        // Each count right under its label, as wide as the label is
+        let counts: Vec<String> = self
+            .latency
+            .buckets()
+            .map(|(label, count)| format!("{count:>width$}", width = label.chars().count()))
+            .collect();
+        lines.push(Line::from(latency::LABELS.join(" ")));
+        lines.push(Line::from(counts.join(" ")));
+        if let Some(webhook) = &self.webhook {
+            lines.push(Line::from(format!(
+                "webhook failures {}",
+                webhook.failures()
+            )));
+        }
+        lines
+    }
+
+    /// Newest draws on the right, as many as fit. Tenths of a millisecond, since Sparkline
+    /// only does whole numbers
+    fn render_sparkline(&self, width: u16) -> Sparkline<'_> {
+        let render = &self.frames.render_ms;
+        let skip = render.len().saturating_sub(width as usize);
+        let data: Vec<u64> = render
+            .samples()
+            .skip(skip)
+            .map(|ms| (ms * 10.0).round() as u64)
+            .collect();
+        Sparkline::default()
+            .data(data)
+            .style(theme::current().accent)
+    }
+
+    /// Waits up to `wait` for a key, then catches up on everything else.
+    /// Returns: whether anything changed that's worth a redraw
+    ///
+    /// Every pass goes the same way, and the frame after it is drawn from what it left:
+    /// 1. every key and terminal event that's waiting, and any replayed or demo keys due
+    /// 2. every message the workers have sent
+    /// 3. workers that were already done before 2, harvested
+    /// 4. whatever the clock says is due: expiries, schedules, watchdogs, the shutdown deadline
+    ///
+    /// 2 before 3 is what keeps a worker's last word (a `CancelReport`, its last `Progress`) from
+    /// landing after it's been marked Finished
+    async fn update(&mut self, wait: Duration) -> Result<bool> {
+        let mut changed = self.drain_input(wait)?;
+        changed |= self.step().await;
+        Ok(changed)
+    }
+
+    /// Steps 2 to 4 of `update`, which don't need a terminal
+    pub async fn step(&mut self) -> bool {
+        let mut done = self.finished_workers();
+        let mut changed = self.drain_messages();
+        // A worker's last word might not have fit in this frame. It's harvested once it's heard,
+        // and nobody is while there's some left on the channel that could be theirs
+        done.retain(|&id| !self.behind && !self.inbox.holds(id));
+        changed |= self.reconcile(&done).await;
+        changed |= self.tick();
+        changed
+    }
+
+    /// Step 1. Only the first event is waited for, the rest are only taken if they're there
+    /// already
+    fn drain_input(&mut self, wait: Duration) -> Result<bool> {
+        //If I were doing it all over again I'd use a proper event-driven architecture
+        //Like in the templates
+        // Don't sit waiting for a real key past when the next replayed (or demo) one's due
+        let next = [
+            self.replay.as_ref().and_then(Replay::next_at),
+            self.demo.as_ref().and_then(Player::next_at),
+        ];
+        let mut wait = match next.into_iter().flatten().min() {
+            Some(at) => wait.min(at.saturating_duration_since(self.clock.now_instant())),
+            None => wait,
+        };
+        let mut changed = false;
+        while event::poll(wait)? {
+            match event::read()? {
+                Event::Key(event) if compat::accepts(&event) => self.handle_real_key(event),
+                Event::FocusLost => self.set_focused(false),
+                Event::FocusGained => self.set_focused(true),
+                // Resizes and the like still need a fresh frame
+                _ => {}
+            };
+            changed = true;
+            wait = Duration::ZERO;
+        }
+        changed |= self.play_due();
+        Ok(changed)
+    }
+
+    /// Which workers are done, to be taken before `drain_messages`. Whatever a worker sent was
+    /// in the channel before it returned, so its last word always gets read before its handle's
+    /// harvested. Anything that finishes after this waits for the next pass
+    pub fn finished_workers(&self) -> Vec<Id> {
+        self.tasks
+            .iter()
+            .filter(|task| task.is_done())
+            .map(|task| task.id)
+            .collect()
+    }
+
+    /// Step 2: everything in the channel, in the order it was sent, or a budget's worth of it
+    /// (see `inbox`). Everything that was in the channel when this started is taken off it if
+    /// the inbox has room, so whatever `finished_workers` saw finish has its messages in the
+    /// inbox by the end. If it doesn't, the rest stays on the channel and `behind` says so
+    pub fn drain_messages(&mut self) -> bool {
+        let budget = self.frames.config.message_budget;
+        // Senders that keep up could keep this going forever, so no more than that's taken
+        let wanted = self.mpsc_rx.len().max(budget);
+        let room = self.inbox.room(budget);
+        self.wiring.check_inbound(&self.mpsc_tx);
+        for _ in 0..wanted.min(room) {
+            match self.mpsc_rx.try_recv() {
+                Ok(stamped) => self.inbox.push(stamped),
+                Err(e) => {
+                    self.wiring.heard(e);
+                    break;
+                }
+            }
+        }
+        self.behind = room < wanted && !self.mpsc_rx.is_empty();
+        let batch = self.inbox.take(budget);
+        let changed = !batch.is_empty();
+        // Legally speaking, this is struct and tokio abuse.
+        for stamped in batch {
+            // Strays count too, they waited just as long
+            let now = self.clock.now_instant();
+            self.latency.record(stamped.age(now));
+            let msg = stamped.msg;
+            if self.tasks.get(msg.id()).is_none() || self.task_table.removals.is_gone(msg.id()) {
+                self.stray(&msg, now);
+                continue;
+            }
+            // Output has its own buffer, and would push the interesting stuff out of this one.
+            // Heartbeats would do the same, every few seconds
+            let noise = match msg {
+                TaskTxMsg::Output { .. } => true,
+                TaskTxMsg::Heartbeat(_) => !self.config.work.audit_heartbeats,
+                _ => false,
+            };
+            if !noise {
+                self.tasks[msg.id()].record(format!("← {msg:?}"), &*self.clock);
+            }
+            self.tasks[msg.id()].heard_from(now);
+            self.tasks[msg.id()].last_heard = Some((msg.kind(), now));
+            self.record(|recorder| recorder.task(&msg));
+            match msg {
+                TaskTxMsg::Progress {
+                    id,
+                    pct,
+                    fatigue,
+                    cpu_ms,
+                    cpu_estimated,
+                } => {
+                    trace!("got a progress report from {id}: {pct}%");
+                    if pct > 100 {
+                        self.protocol_errors += 1;
+                        warn!(
+                            "protocol warning: task {id} says it's {pct}% done, counting it as 100"
+                        );
+                    }
+                    let was = self.tasks[id].progress;
+                    let pct = pct.min(100);
+                    if !self.tasks[id].progressed(pct, fatigue, now) {
+                        self.protocol_errors += 1;
+                        warn!(
+                            "protocol warning: task {id} went back from {was}% to {pct}%, keeping \
+                             {was}%"
+                        );
+                    } else if self.tasks[id].progress != was {
+                        self.plot(id);
+                    }
+                    // Unlike progress it's still worth having late, it was used either way
+                    self.tasks[id].cpu = Some(CpuTime {
+                        ms: cpu_ms,
+                        estimated: cpu_estimated,
+                    });
+                }
+                TaskTxMsg::StatusChange { id, status } => {
+                    let was = self.tasks[id].status;
+                    if !self.tasks[id].report(status.into(), &*self.clock) {
+                        continue;
+                    }
+                    match status {
+                        WorkerStatus::OnStrike { demand_secs } => {
+                            info!(
+                                "task {id} refuses to work until it gets a {demand_secs} sec \
+                                 break"
+                            );
+                            self.tasks[id].demand_secs = Some(demand_secs);
+                            if self.config.work.auto_grant_breaks {
+                                info!("granting task {id} its break without asking you");
+                                self.wiring.tell(
+                                    &mut self.tasks[id],
+                                    TaskRxMsg::Bargain(demand_secs),
+                                    &*self.clock,
+                                );
+                            }
+                        }
+                        _ if was == TaskStatus::OnStrike => {
+                            info!("task {id} has reached an agreement, and will resume");
+                            self.tasks[id].demand_secs = None;
+                        }
+                        WorkerStatus::Finalizing => trace!("task {id} is on its last block"),
+                        _ => trace!("task {id} is {status:?}"),
+                    }
+                }
+                TaskTxMsg::Fault { id, error } => {
+                    error!("task {id} has failed: {error}");
+                    if self.tasks[id].report(TaskStatus::Failed, &*self.clock) {
+                        self.tasks[id].cancel_reason = Some(error);
+                    }
+                }
+                TaskTxMsg::Resignation { id, reason } => {
+                    warn!("task {id} has quit: {reason}");
+                    if self.tasks[id].report(TaskStatus::Canceled, &*self.clock) {
+                        self.tasks[id].demand_secs = None;
+                        self.tasks[id].cancel_reason = Some(reason);
+                    }
+                }
+                TaskTxMsg::CancelReport(id) => {
+                    info!("task {id} has sent word of termination");
+                    if let Some(shutdown) = &mut self.shutdown {
+                        shutdown.ack(id);
+                    }
+                    self.tasks[id].report(TaskStatus::Canceled, &*self.clock);
+                    self.tasks[id].stop_acked(self.clock.now_local());
+                }
+                TaskTxMsg::Output { id, line } => {
+                    trace!("task {id} says: {line}");
+                    self.tasks[id].push_output(line);
+                }
+                TaskTxMsg::Heartbeat(id) => {
+                    self.heartbeats += 1;
+                    self.tasks[id].heartbeat_at = Some(now);
+                }
+                TaskTxMsg::Milestone { id, label } => {
+                    debug!("task {id} got to a milestone: {label}");
+                    self.tasks[id].milestone(&label, &*self.clock);
+                }
+                TaskTxMsg::ExitReport { id, code } => match code {
+                    // Finished gets set when we harvest the handle, same as the dummies
+                    Some(0) => info!("task {id} exited cleanly"),
+                    _ => {
+                        warn!("task {id} failed with exit code {code:?}");
+                        self.tasks[id].report(TaskStatus::Failed, &*self.clock);
+                        self.tasks[id].exit_code = code;
+                    }
+                },
+            };
+        }
+        changed
+    }
+
+    /// Step 3: harvests `done`'s handles, says how each one ended to whoever wants to know, and
+    /// retries the failures. See `finished_workers`
+    pub async fn reconcile(&mut self, done: &[Id]) -> bool {
+        // This is kind of redundant given we have an MPSC channel that reports doneness.
+        // Architectural skill issue, in hindsight
+        let mut changed = false;
+        let mut failed = vec![];
+        for &id in done {
+            let was = self.tasks[id].progress;
+            let outcome = self.tasks[id].check_done(&*self.clock).await;
+            // Finishing is the last step to 100
+            if self.tasks[id].progress != was {
+                self.plot(id);
+            }
+            let task = &mut self.tasks[id];
+            if let Some(outcome) = outcome {
+                changed = true;
+                if let Some(shutdown) = &mut self.shutdown {
+                    shutdown.ack(task.id);
+                }
+                match outcome {
+                    Outcome::Returned(res) => {
+                        if let Some(sum) = res {
+                            info!("task {} finished and reported: {sum}", task.id);
+                            if task.kind == TaskKind::Dummy && !tasks::is_sane_sum(sum) {
+                                self.protocol_errors += 1;
+                                warn!(
+                                    "protocol warning: task {} summed to {sum}, which it couldn't \
+                                     have",
+                                    task.id
+                                );
+                            }
+                        } else {
+                            warn!(
+                                "task {} finished after termination and reported no sum",
+                                task.id
+                            )
+                        }
+                    }
+                    Outcome::Panicked(e) => error!("task {} panicked: {e}", task.id),
+                    Outcome::Aborted => {
+                        error!("task {} was aborted before it could finish", task.id);
+                    }
+                }
+                // Whatever it is by now is how it ended
+                self.last_ended = Some(format!(
+                    "task {} {}",
+                    task.id,
+                    match task.status {
+                        TaskStatus::Failed => "failed",
+                        TaskStatus::Canceled => "stopped",
+                        _ => "finished",
+                    }
+                ));
+                if let Some(webhook) = &self.webhook {
+                    let kind = match task.status {
+                        TaskStatus::Failed => EventKind::Failed,
+                        TaskStatus::Canceled => EventKind::Canceled,
+                        _ => EventKind::Finished,
+                    };
+                    webhook.send(webhook::Event::new(kind, task, self.clock.now_local()));
+                }
+                if let Some(path) = &self.history_path {
+                    let record = history::Record {
+                        session: self.session.clone(),
+                        ..history::Record::from(&*task)
+                    };
+                    if let Err(e) = history::append(path, &record) {
+                        error!("couldn't add task {} to {}: {e}", task.id, path.display());
+                    }
+                    self.picker.record(&record);
+                }
+                if task.status == TaskStatus::Failed {
+                    failed.push(task.id);
+                }
+                if task.status == TaskStatus::Finished {
+                    let at = task.end.unwrap_or_else(|| self.clock.now_local());
+                    self.picker.finished(&task.candidate, at);
+                }
+            }
+        }
+        // Nothing new starts on the way out, retries included
+        if self.shutdown.is_none() {
+            for id in failed {
+                self.retry_failed(id);
+            }
+        }
+        changed
+    }
+
+    /// Step 4: everything that happens because time's passed rather than because anyone said so
+    pub fn tick(&mut self) -> bool {
+        let mut changed = false;
+        let now = self.clock.now_instant();
+        // A run of repeats the stream's still holding a count for, with nothing after it
+        log_dedup::flush();
+        changed |= self.advance_tour();
+        for id in self.task_table.removals.expire(now) {
+            self.discard(id, now);
+        }
+        self.tombstones.expire(now);
+        let patience = Duration::from_secs(self.config.work.thread_wait_secs);
+        let mut queued = 0;
+        for task in self.tasks.iter_mut() {
+            if task.check_thread_wait(patience, &*self.clock) {
+                warn!(
+                    "task {} hasn't started, it's probably waiting for a thread",
+                    task.id
+                );
+                queued += 1;
+            }
+            task.eta.update(now);
+            // Sleeping, striking and stopped tasks aren't making progress, so it stays put
+            if self.config.ui.smooth_progress && task.status == TaskStatus::Running {
+                task.smooth.update(now);
+            }
+        }
+        if queued > 0 {
+            let waiting = self
+                .tasks
+                .iter()
+                .filter(|task| task.status == TaskStatus::WaitingForThread)
+                .count();
+            self.banner = Some(Banner::new(
+                format!(
+                    "{waiting} task(s) waiting for a thread, all {} are taken. Stop some or add \
+                     fewer at once",
+                    self.config.work.max_blocking_threads
+                ),
+                THREAD_BANNER_DURATION,
+                &*self.clock,
+            ));
+        }
+        // Anything scheduled that's come due. Sorted, so the due ones are all at the front.
+        // Shutting down empties this out, so nothing new starts on the way out
+        let due = self.scheduled.partition_point(|&(at, _)| at <= now);
+        for (_, id) in self.scheduled.drain(..due).collect::<Vec<_>>() {
+            info!("scheduled task {id} is up");
+            self.activate_task(id);
+            changed = true;
+        }
+        changed |= self.start_dependents();
+        // The task might have moved on (or been stopped) while we were making up our minds
+        if let Some(negotiation) = &self.negotiation {
+            let id = negotiation.id;
+            if !matches!(self.tasks[id].status, TaskStatus::OnStrike) {
+                self.close_negotiation();
+                self.toast(&strings::fill("toast.strike_over", &[("id", &id)]));
+            }
+        }
+        let clock = &*self.clock;
+        let expired = |banner: &Banner| banner.is_expired(clock);
+        if self.banner.as_ref().is_some_and(expired) {
+            self.banner = None;
+        }
+        // Toasts vanishing on their own is motion too, so those ones wait for a key instead
+        if !theme::current().reduce_motion && self.toast.as_ref().is_some_and(expired) {
+            self.toast = None;
+        }
+        if self.hint.as_ref().is_some_and(expired) {
+            self.hint = None;
+        }
+        if self
+            .shutdown
+            .as_ref()
+            .is_some_and(|shutdown| shutdown.is_done(now))
+        {
+            self.give_up();
+        }
+        // Same counts as the strip and the minimized line, so they can't tell different stories
+        if let Some(status_file) = &mut self.status_file {
+            status_file.update(Tally::of(self.task_table.kept(&self.tasks)), &*self.clock);
+        }
+        if let Some(shared) = &self.status_server {
+            status_server::refresh(shared, &self.tasks);
+        }
+        changed |= self.enforce_time_limits(now);
+        if let Some(csv) = &mut self.progress_csv {
+            if let Err(e) = csv.tick(now) {
+                error!("stopped writing {}: {e}", csv.path().display());
+                self.progress_csv = None;
+            }
+        }
+        self.journal_changes();
+        if let Some(journal) = &mut self.journal {
+            if let Err(e) = journal.tick(now) {
+                warn!("stopped journaling to {}: {e}", journal.path().display());
+                self.journal = None;
+            }
+        }
+        changed
+    }
+
+    /// A key someone actually pressed, which takes over from a replay or a demo. F10 only
+    /// stops the demo, instead of starting it again
+    pub fn handle_real_key(&mut self, event: KeyEvent) {
+        // Before the replay hears about it, so there's still one to come back to
+        if suspend::is_suspend_key(&event) {
+            self.ask_to_suspend();
+            return;
+        }
+        if self.replay.take().is_some() {
+            info!("replay interrupted by a key");
+            self.toast(strings::get("toast.replay_stopped"));
+        }
+        if self.demo.take().is_some() {
+            info!("demo interrupted by a key");
+            self.toast(strings::get("toast.demo_stopped"));
+            if event.code == demo::KEY {
+                return;
+            }
+        }
+        self.record(|recorder| recorder.key(&event));
+        self.handle_key_event(event)
+    }
+
+    /// Puts through whatever replayed keys are due by now, and the demo's next one if it's
+    /// time. Returns whether there were any
+    pub fn play_due(&mut self) -> bool {
+        let mut changed = false;
+        while let Some(key) = self
+            .replay
+            .as_mut()
+            .and_then(|replay| replay.due(self.clock.now_instant()))
+        {
+            self.handle_key_event(key);
+            changed = true;
+        }
+        if self
+            .replay
+            .as_ref()
+            .is_some_and(|replay| replay.keys.is_empty())
+        {
+            self.replay = None;
+            info!("replay finished");
+            self.toast(strings::get("toast.replay_over"));
+            changed = true;
+        }
+        if let Some(key) = self
+            .demo
+            .as_mut()
+            .and_then(|demo| demo.due(self.clock.now_instant()))
+        {
+            self.handle_key_event(key);
+            changed = true;
+        }
+        if self.demo.as_ref().is_some_and(Player::is_done) {
+            self.demo = None;
+            info!("demo finished");
+        }
+        changed
+    }
+
+    /// Ctrl+Z, from anywhere. The loop does it before the next frame
+    fn ask_to_suspend(&mut self) {
+        if !suspend::SUPPORTED {
+            self.hint(strings::get("hint.no_suspend").to_string());
+            return;
+        }
+        if self.suspend.request() {
+            info!("asked to suspend");
+        }
+    }
+
+    /// F10 from the main screen. A macro that doesn't compile was warned about at startup,
+    /// this only says so again
+    fn start_demo(&mut self) {
+        match self.config.demo.steps() {
+            Ok(steps) => {
+                info!("playing demo macro {}", self.config.demo.play);
+                self.demo = Some(Player::new(steps, self.clock.now_instant()));
+            }
+            Err(e) => self.hint(strings::fill("hint.no_demo", &[("error", &e)])),
+        }
+    }
+
+    pub fn handle_key_event(&mut self, event: KeyEvent) {
+        trace!("key down: {:?}", event);
+        let event = compat::alternate(event);
+        if theme::current().reduce_motion {
+            self.toast = None;
+        }
+        // Anything brings the full UI back, except F3, which still leaves the usual way
+        if self.minimized && event.code != KeyCode::F(3) {
+            self.minimized = false;
+            return;
+        }
+        if self.show_help {
+            self.show_help = false;
+            // The help says they're there, so it's a way in
+            match event.code {
+                KeyCode::Char('T') if !self.templates.is_empty() => self.show_templates = true,
+                KeyCode::Char('a') => {
+                    self.task_table.clear_selection();
+                    self.view_state = ViewState::Architecture;
+                }
+                _ => {}
+            }
+            return;
+        }
+        if self.show_templates {
+            self.show_templates = false;
+            return;
+        }
+        // F3 still works, in case what's wrong is bad enough to leave over
+        if self.show_health && event.code != KeyCode::F(3) {
+            match event.code {
+                KeyCode::Enter => self.show_health = false,
+                _ => self.hint(strings::get("hint.health_continue").to_string()),
+            }
+            return;
+        }
+        // All the tour takes is Esc, everything else goes where it would anyway
+        if event.code == KeyCode::Esc && !self.tour.is_over() {
+            info!("tour skipped at {:?}", self.tour.step());
+            self.tour.skip();
+            self.end_tour();
+            return;
+        }
+        // On the way out, the only thing left to do is leave faster
+        if self.shutdown.is_some() {
+            if let KeyCode::F(3) = event.code {
+                warn!("shutdown forced");
+                self.give_up();
+            }
+            return;
+        }
+        if let ViewState::Filter = self.view_state {
+            self.handle_filter_key(event);
+            return;
+        }
+        if let ViewState::Note = self.view_state {
+            self.handle_note_key(event);
+            return;
+        }
+        if let ViewState::Duration = self.view_state {
+            self.handle_duration_key(event);
+            return;
+        }
+        if let ViewState::Pool = self.view_state {
+            self.handle_pool_key(event);
+            return;
+        }
+        if let ViewState::Columns = self.view_state {
+            self.handle_columns_key(event);
+            return;
+        }
+        if self.choosing_template {
+            self.choosing_template = false;
+            self.pick_template(event);
+            return;
+        }
+        match event.code {
+            KeyCode::Char('k') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_chaos()
+            }
+            KeyCode::Char('k') | KeyCode::Up => match self.view_state {
+                ViewState::TaskAdd => self.picker.previous(),
+                ViewState::Inspect => self.task_table.previous(&self.tasks),
+                ViewState::Detail => self.detail.previous(),
+                ViewState::Timeline => self.timeline.previous(),
+                ViewState::Menu => {
+                    if let Some(menu) = &mut self.menu {
+                        menu.previous();
+                    }
+                }
+                ViewState::Negotiate => {
+                    if let Some(negotiation) = &mut self.negotiation {
+                        negotiation.previous();
+                    }
+                }
+                ViewState::Monitor => self.while_inspecting("J/K"),
+                // Filter, note, duration, pool and column keys never get this far
+                ViewState::Filter
+                | ViewState::Note
+                | ViewState::Duration
+                | ViewState::Pool
+                | ViewState::Columns
+                | ViewState::Batch
+                | ViewState::Guard
+                | ViewState::Logs
+                | ViewState::Architecture => {}
+            },
+            KeyCode::Char('j') | KeyCode::Down => match self.view_state {
+                ViewState::TaskAdd => self.picker.next(),
+                ViewState::Inspect => self.task_table.next(&self.tasks),
+                ViewState::Detail => self.detail.next(),
+                ViewState::Timeline => self.timeline.next(),
+                ViewState::Menu => {
+                    if let Some(menu) = &mut self.menu {
+                        menu.next();
+                    }
+                }
+                ViewState::Negotiate => {
+                    if let Some(negotiation) = &mut self.negotiation {
+                        negotiation.next();
+                    }
+                }
+                ViewState::Monitor => self.while_inspecting("J/K"),
+                ViewState::Filter
+                | ViewState::Note
+                | ViewState::Duration
+                | ViewState::Pool
+                | ViewState::Columns
+                | ViewState::Batch
+                | ViewState::Guard
+                | ViewState::Logs
+                | ViewState::Architecture => {}
+            },
+            KeyCode::Home => self.jump(Move::First),
+            KeyCode::End => self.jump(Move::Last),
+            KeyCode::PageUp => self.jump(Move::PageUp),
+            KeyCode::PageDown => self.jump(Move::PageDown),
+
+            // Sorting only matters where the table is
+            KeyCode::Char('s') => match self.view_state {
+                ViewState::Monitor | ViewState::Inspect => {
+                    self.task_table.toggle_sort();
+                    self.save_ui_state();
+                }
+                _ => self.table_only("S"),
+            },
+
+            // The menu lists these keys next to its entries, so they work from there too
+            KeyCode::Char('d') => match self.view_state {
+                ViewState::Inspect | ViewState::Menu => self.run_action(Action::Details),
+                _ => self.while_inspecting("D"),
+            },
+
+            KeyCode::Char('n') => match self.view_state {
+                ViewState::Inspect | ViewState::Menu => self.run_action(Action::Negotiate),
+                ViewState::Monitor => self.add_again(),
+                _ => self.while_inspecting("N"),
+            },
+
+            KeyCode::Char('N') => match self.view_state {
+                ViewState::Inspect | ViewState::Menu => self.run_action(Action::Note),
+                _ => self.while_inspecting("S-N"),
+            },
+
+            // Archive and Unarchive are the same key, the menu only ever offers one of them
+            KeyCode::Char('a') => match self.view_state {
+                ViewState::Inspect | ViewState::Menu => match self.selected_archived() {
+                    Some(true) => self.run_action(Action::Unarchive),
+                    Some(false) => self.run_action(Action::Archive),
+                    None => {}
+                },
+                _ => self.while_inspecting("a"),
+            },
+
+            KeyCode::Delete => match self.view_state {
+                ViewState::Inspect | ViewState::Menu => self.run_action(Action::Remove),
+                _ => self.while_inspecting("Del"),
+            },
+
+            KeyCode::Char('h') => match self.view_state {
+                ViewState::Monitor | ViewState::Inspect => self.toggle_hide_finished(),
+                _ => self.table_only("H"),
+            },
+
+            KeyCode::Char('V') => match self.view_state {
+                ViewState::Monitor | ViewState::Inspect => self.reset_view(),
+                _ => self.table_only("V"),
+            },
+
+            KeyCode::Char('u') => match self.view_state {
+                ViewState::Monitor | ViewState::Inspect => self.undo_removal(),
+                _ => self.table_only("u"),
+            },
+
+            KeyCode::Char('A') => match self.view_state {
+                ViewState::Monitor | ViewState::Inspect => self.toggle_archive_open(),
+                _ => self.table_only("A"),
+            },
+
+            KeyCode::Char(key @ (']' | '[')) => match self.view_state {
+                ViewState::Inspect => self.jump_to_status(key == ']'),
+                _ => self.while_inspecting("]/["),
+            },
+
+            KeyCode::Char('o') => {
+                if let ViewState::Inspect = self.view_state {
+                    if !self.task_table.toggle_expanded(&self.tasks) {
+                        self.hint(strings::get("hint.nothing_to_expand").to_string());
+                    }
+                } else {
+                    self.while_inspecting("O");
+                }
+            }
+
+            KeyCode::Char('m') => {
+                if let ViewState::Inspect = self.view_state {
+                    self.open_menu();
+                } else {
+                    self.while_inspecting("M");
+                }
+            }
+
+            KeyCode::Char('c') => {
+                if let ViewState::Inspect = self.view_state {
+                    self.column_menu = Some(ColumnMenu::new(&self.task_table.columns));
+                    self.view_state = ViewState::Columns;
+                } else {
+                    self.while_inspecting("C");
+                }
+            }
+
+            KeyCode::Char('/') => {
+                if let ViewState::Inspect = self.view_state {
+                    self.task_table.editing_filter = true;
+                    self.view_state = ViewState::Filter;
+                } else {
+                    self.while_inspecting("/");
+                }
+            }
+
+            KeyCode::Char('e') => {
+                if let ViewState::TaskAdd = self.view_state {
+                    self.pool_editor.clamp(self.picker.pool.len());
+                    self.view_state = ViewState::Pool;
+                } else {
+                    self.picker_only("E");
+                }
+            }
+
+            KeyCode::Tab => {
+                if let ViewState::TaskAdd = self.view_state {
+                    self.open_duration_prompt();
+                } else {
+                    self.picker_only("Tab");
+                }
+            }
+
+            KeyCode::Char('r') => match self.view_state {
+                ViewState::TaskAdd if self.picker.is_committed() => {
+                    debug!("R again before the picker closed, ignoring it")
+                }
+                ViewState::TaskAdd => {
+                    let ct = self.picker.select_random();
+                    self.add_task(ct, None);
+                }
+                _ => self.picker_only("R"),
+            },
+
+            KeyCode::Enter => match self.view_state {
+                ViewState::TaskAdd if self.picker.is_committed() => {
+                    debug!("Enter again before the picker closed, ignoring it")
+                }
+                ViewState::TaskAdd => self.add_task(self.picker.select(), None),
+                // Headers aren't tasks, all there is to do with one is fold it
+                ViewState::Inspect if self.task_table.toggle_fold(&self.tasks) => {}
+                ViewState::Inspect if self.config.ui.enter_opens_menu => self.open_menu(),
+                ViewState::Inspect => self.run_action(Action::Terminate),
+                ViewState::Menu => {
+                    if let Some(action) = self.menu.as_ref().map(ActionMenu::action) {
+                        self.run_action(action);
+                    }
+                }
+                ViewState::Negotiate => self.send_bargain(),
+                ViewState::Batch => self.add_batch(),
+                ViewState::Guard => self.answer_guard(true),
+                ViewState::Monitor => self.while_inspecting("Enter"),
+                ViewState::Detail
+                | ViewState::Filter
+                | ViewState::Timeline
+                | ViewState::Note
+                | ViewState::Duration
+                | ViewState::Pool
+                | ViewState::Columns
+                | ViewState::Logs
+                | ViewState::Architecture => {}
+            },
+
+            //Go to task add IFF we're at main menu
+            KeyCode::F(1) => {
+                match self.view_state {
+                    ViewState::TaskAdd
+                    | ViewState::Inspect
+                    | ViewState::Detail
+                    | ViewState::Negotiate
+                    | ViewState::Filter
+                    | ViewState::Batch
+                    | ViewState::Guard
+                    | ViewState::Timeline
+                    | ViewState::Menu
+                    | ViewState::Note
+                    | ViewState::Duration
+                    | ViewState::Pool
+                    | ViewState::Columns
+                    | ViewState::Logs
+                    | ViewState::Architecture => self.main_screen_only("F1"),
+                    ViewState::Monitor => {
+                        self.view_state = ViewState::TaskAdd;
+                        self.picker.regen(self.clock.now_local()); // Pick fresh pool entries
+                    }
+                };
+            }
+            // Go to inspect mode IFF we're at main menu
+            KeyCode::F(2) => match self.view_state {
+                ViewState::TaskAdd
+                | ViewState::Inspect
+                | ViewState::Detail
+                | ViewState::Negotiate
+                | ViewState::Filter
+                | ViewState::Batch
+                | ViewState::Guard
+                | ViewState::Timeline
+                | ViewState::Menu
+                | ViewState::Note
+                | ViewState::Duration
+                | ViewState::Pool
+                | ViewState::Columns
+                | ViewState::Logs
+                | ViewState::Architecture => self.main_screen_only("F2"),
+                ViewState::Monitor if self.tasks.is_empty() => {
+                    self.toast(strings::get("toast.nothing_to_inspect"));
+                }
+                ViewState::Monitor => {
+                    self.view_state = ViewState::Inspect;
+                    // If table is not empty and nothing selected, select first row
+                    let table = &self.task_table;
+                    if table.selected(&self.tasks).is_none()
+                        && table.selected_group(&self.tasks).is_none()
+                    {
+                        self.task_table.select_first(&self.tasks);
+                    }
+                }
+            },
+
+            // The stream keeps its place either way, see LogPane
+            KeyCode::Char('f') => match self.view_state {
+                ViewState::Monitor => self.view_state = ViewState::Logs,
+                ViewState::Logs => self.view_state = ViewState::Monitor,
+                _ => self.main_screen_only("F"),
+            },
+
+            KeyCode::Char('=') => match self.view_state {
+                ViewState::Monitor | ViewState::Inspect => self.cycle_split(),
+                _ => self.table_only("="),
+            },
+
+            // Turn the heat up/down. Monitor only since it's the 'hands off' mode
+            KeyCode::Char('<') => {
+                if let ViewState::Monitor = self.view_state {
+                    self.change_intensity(-INTENSITY_STEP);
+                } else {
+                    self.main_screen_only("<");
+                }
+            }
+            KeyCode::Char('>') => {
+                if let ViewState::Monitor = self.view_state {
+                    self.change_intensity(INTENSITY_STEP);
+                } else {
+                    self.main_screen_only(">");
+                }
+            }
+
+            // Same deal as F1, and the file is read fresh every time
+            KeyCode::F(7) => {
+                if let ViewState::Monitor = self.view_state {
+                    let path = self
+                        .config
+                        .batch_file
+                        .clone()
+                        .unwrap_or_else(|| PathBuf::from(DEFAULT_BATCH_PATH));
+                    let batch = Batch::load(&path, &self.config.compiled_guards);
+                    if let Err(e) = &batch.items {
+                        warn!("batch file {} has problems: {e}", path.display());
+                    }
+                    self.batch = Some(batch);
+                    self.view_state = ViewState::Batch;
+                } else {
+                    self.main_screen_only("F7");
+                }
+            }
+
+            KeyCode::F(8) => {
+                if let ViewState::Monitor = self.view_state {
+                    self.view_state = ViewState::Timeline;
+                } else {
+                    self.main_screen_only("F8");
+                }
+            }
+
+            // We can always exit
+            KeyCode::F(3) => self.exit(),
+            KeyCode::F(9) => self.minimized = true,
+            KeyCode::F(10) => match self.view_state {
+                ViewState::Monitor => self.start_demo(),
+                _ => self.main_screen_only("F10"),
+            },
+            KeyCode::Char('b') => match self.view_state {
+                ViewState::Monitor | ViewState::Inspect => self.toggle_low_power(),
+                _ => self.main_screen_only("B"),
+            },
+            KeyCode::Char('z') => match self.view_state {
+                ViewState::Monitor | ViewState::Inspect => self.toggle_compact(),
+                _ => self.main_screen_only("Z"),
+            },
+            KeyCode::Char('?') => match self.view_state {
+                ViewState::Monitor | ViewState::Inspect => self.show_help = true,
+                _ => self.main_screen_only("?"),
+            },
+            KeyCode::Char('T') => match self.view_state {
+                ViewState::Monitor => self.choose_template(),
+                _ => self.main_screen_only("T"),
+            },
+            KeyCode::F(12) if self.config.ui.debug_overlay => self.show_debug = !self.show_debug,
+            KeyCode::F(12) => self.hint(strings::get("hint.no_debug_overlay").to_string()),
+
+            // Go back unless we're @ main menu
+            KeyCode::Esc => match self.view_state {
+                // A kept filter goes first, another Esc leaves
+                ViewState::Inspect if !self.task_table.filter().is_empty() => {
+                    self.edit_filter(|table| table.clear_filter());
+                    self.save_ui_state();
+                }
+                ViewState::TaskAdd | ViewState::Inspect => {
+                    self.view_state = ViewState::Monitor;
+                    self.task_table.clear_selection();
+                }
+                ViewState::Timeline | ViewState::Logs | ViewState::Architecture => {
+                    self.view_state = ViewState::Monitor
+                }
+                // Back to the table, selection intact
+                ViewState::Detail => self.view_state = ViewState::Inspect,
+                ViewState::Negotiate => self.close_negotiation(),
+                ViewState::Menu => self.close_menu(),
+                ViewState::Batch => {
+                    self.batch = None;
+                    self.view_state = ViewState::Monitor;
+                }
+                ViewState::Guard => self.answer_guard(false),
+                ViewState::Monitor
+                | ViewState::Filter
+                | ViewState::Note
+                | ViewState::Duration
+                | ViewState::Pool
+                | ViewState::Columns => {}
+            },
+            // Printable keys are the ones people expect to do something
+            KeyCode::Char(ch) => self.unbound(ch),
+            _ => {}
+        }
+    }
+
+    fn main_screen_only(&mut self, key: &str) {
+        self.hint(strings::fill("hint.main_screen_only", &[("key", &key)]));
+    }
+
+    fn while_inspecting(&mut self, key: &str) {
+        let how = match self.view_state {
+            ViewState::Monitor => strings::get("hint.from_main_screen"),
+            _ => strings::get("hint.from_elsewhere"),
+        };
+        self.hint(strings::fill(
+            "hint.while_inspecting",
+            &[("key", &key), ("how", &how)],
+        ));
+    }
+
+    fn table_only(&mut self, key: &str) {
+        self.hint(strings::fill("hint.table_only", &[("key", &key)]));
+    }
+
+    fn picker_only(&mut self, key: &str) {
+        self.hint(strings::fill("hint.picker_only", &[("key", &key)]));
+    }
+
+    fn unbound(&mut self, ch: char) {
+        self.hint(strings::fill("hint.unbound", &[("key", &ch)]));
+    }
+
+    /// Shows why a key did nothing, unless this exact hint has already been shown enough
+    fn hint(&mut self, text: String) {
+        let shown = self.hints_shown.entry(text.clone()).or_insert(0);
+        if *shown >= HINT_REPEATS {
+            return;
+        }
+        *shown += 1;
+        trace!("hint: {text}");
+        self.hint = Some(Banner::new(text, HINT_DURATION, &*self.clock));
+    }
+
+    /// Home/End/PageUp/PageDown, for the lists they make sense in
+    fn jump(&mut self, to: Move) {
+        match self.view_state {
+            ViewState::TaskAdd => self.picker.navigate(to),
+            ViewState::Inspect => self.task_table.navigate(&self.tasks, to),
+            // Nothing to pick on the main screen, so paging goes to the message stream
+            ViewState::Monitor | ViewState::Logs => match to {
+                Move::PageUp => self.logs.page_up(),
+                Move::PageDown => self.logs.page_down(),
+                Move::Last => self.logs.follow(),
+                _ if self.view_state == ViewState::Monitor => self.while_inspecting("Home"),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    /// Typing goes into the filter, so only the keys that mean something else get through
+    fn handle_filter_key(&mut self, event: KeyEvent) {
+        match event.code {
+            KeyCode::Char(ch) => self.edit_filter(|table| table.push_filter(ch)),
+            KeyCode::Backspace => self.edit_filter(|table| table.pop_filter()),
+            KeyCode::Up => self.task_table.previous(&self.tasks),
+            KeyCode::Down => self.task_table.next(&self.tasks),
+            KeyCode::Home => self.task_table.navigate(&self.tasks, Move::First),
+            KeyCode::End => self.task_table.navigate(&self.tasks, Move::Last),
+            KeyCode::PageUp => self.task_table.navigate(&self.tasks, Move::PageUp),
+            KeyCode::PageDown => self.task_table.navigate(&self.tasks, Move::PageDown),
+            // Keep the filter, back to picking rows
+            KeyCode::Enter => {
+                self.task_table.editing_filter = false;
+                self.view_state = ViewState::Inspect;
+                self.save_ui_state();
+            }
+            KeyCode::Esc => {
+                self.edit_filter(|table| table.clear_filter());
+                self.view_state = ViewState::Inspect;
+                self.save_ui_state();
+            }
+            KeyCode::F(3) => self.exit(),
+            _ => {}
+        }
+    }
+
+    /// Same idea as the filter, except Enter is a new line and saving needs Ctrl
+    fn handle_note_key(&mut self, event: KeyEvent) {
+        let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
+        let Some(editor) = &mut self.note_editor else {
+            self.view_state = ViewState::Inspect;
+            return;
+        };
+        match event.code {
+            KeyCode::Enter | KeyCode::Char('s') if ctrl => self.save_note(),
+            KeyCode::Enter => editor.push('\n'),
+            KeyCode::Char(ch) if !ctrl => editor.push(ch),
+            KeyCode::Backspace => editor.pop(),
+            KeyCode::Esc => self.close_note(),
+            KeyCode::F(3) => self.exit(),
+            _ => {}
+        }
+    }
+
+    /// Digits and nudges go to the prompt, Enter starts the task if they make sense
+    fn handle_duration_key(&mut self, event: KeyEvent) {
+        let Some(prompt) = &mut self.duration_prompt else {
+            self.view_state = ViewState::TaskAdd;
+            return;
+        };
+        let nudge = if event.modifiers.contains(KeyModifiers::SHIFT) {
+            10
+        } else {
+            1
+        };
+        match event.code {
+            KeyCode::Char(ch) => prompt.push(ch),
+            KeyCode::Backspace => prompt.pop(),
+            KeyCode::Left => prompt.step(-nudge),
+            KeyCode::Right => prompt.step(nudge),
+            KeyCode::Enter => {
+                if let Some(secs) = prompt.value() {
+                    let prompt = self.duration_prompt.take().expect("just had it");
+                    self.add_task(Some(prompt.candidate), Some(secs));
+                }
+            }
+            // Back to the picker, which hasn't moved
+            KeyCode::Esc => {
+                self.duration_prompt = None;
+                self.view_state = ViewState::TaskAdd;
+            }
+            KeyCode::F(3) => self.exit(),
+            _ => {}
+        }
+    }
+
+    /// Tab in the picker. Shell tasks take as long as their command does, so only dummies ask
+    fn open_duration_prompt(&mut self) {
+        let Some(ct) = self.picker.select() else {
+            return;
+        };
+        if ct.command.is_some() {
+            self.hint(strings::fill("hint.shell_duration", &[("name", &ct.name)]));
+            return;
+        }
+        self.duration_prompt = Some(DurationPrompt::new(ct));
+        self.view_state = ViewState::Duration;
+    }
+
+    /// The pool screen has its own list and sometimes a form, so it gets all the keys. Every
+    /// change is saved straight away
+    fn handle_pool_key(&mut self, event: KeyEvent) {
+        let len = self.picker.pool.len();
+        if let Some(form) = &mut self.pool_editor.form {
+            match event.code {
+                KeyCode::Tab | KeyCode::BackTab => form.on_description = !form.on_description,
+                KeyCode::Enter if !form.on_description => form.on_description = true,
+                KeyCode::Enter => self.add_candidate(),
+                KeyCode::Char(ch) => form.push(ch),
+                KeyCode::Backspace => form.pop(),
+                KeyCode::Esc => self.pool_editor.form = None,
+                KeyCode::F(3) => self.exit(),
+                _ => {}
+            }
+            return;
+        }
+        match event.code {
+            KeyCode::Char('j') | KeyCode::Down => self.pool_editor.next(len),
+            KeyCode::Char('k') | KeyCode::Up => self.pool_editor.previous(len),
+            KeyCode::Char('a') => self.pool_editor.form = Some(Form::default()),
+            KeyCode::Char(' ') => self.toggle_candidate(),
+            KeyCode::Char('x') | KeyCode::Delete => self.delete_candidate(),
+            // Back to a freshly rolled picker, so what just changed shows up
+            KeyCode::Esc => {
+                self.view_state = ViewState::TaskAdd;
+                self.picker.regen(self.clock.now_local());
+            }
+            KeyCode::F(3) => self.exit(),
+            KeyCode::Char(ch) => self.unbound(ch),
+            _ => {}
+        }
+    }
+
+    /// Shift+J/K come through as the capitals, so they're told apart from plain j/k for free
+    fn handle_columns_key(&mut self, event: KeyEvent) {
+        let Some(menu) = &mut self.column_menu else {
+            self.view_state = ViewState::Inspect;
+            return;
+        };
+        match event.code {
+            KeyCode::Char('j') | KeyCode::Down => menu.next(),
+            KeyCode::Char('k') | KeyCode::Up => menu.previous(),
+            KeyCode::Char('J') => menu.move_down(),
+            KeyCode::Char('K') => menu.move_up(),
+            KeyCode::Char(' ') => {
+                let toggled = menu.toggle();
+                if !toggled {
+                    self.toast(strings::get("toast.id_column_stays"));
+                }
+            }
+            KeyCode::Enter => self.apply_columns(),
+            KeyCode::Esc => {
+                self.column_menu = None;
+                self.view_state = ViewState::Inspect;
+            }
+            KeyCode::F(3) => self.exit(),
+            KeyCode::Char(ch) => self.unbound(ch),
+            _ => {}
+        }
+    }
+
+    fn apply_columns(&mut self) {
+        let Some(menu) = self.column_menu.take() else {
+            return;
+        };
+        self.view_state = ViewState::Inspect;
+        if menu.draft == self.task_table.columns {
+            return;
+        }
+        info!("table columns changed");
+        self.task_table.columns = menu.draft.clone();
+        self.config.ui.columns = menu.draft;
+        self.config.save();
+    }
+
+    fn add_candidate(&mut self) {
+        let Some(form) = &self.pool_editor.form else {
+            return;
+        };
+        let ct = form.candidate();
+        let name = ct.name.clone();
+        match candidates::add(&mut self.picker.pool, ct) {
+            Ok(()) => {
+                info!("added {name} to the candidate pool");
+                self.pool_editor.form = None;
+                self.pool_editor
+                    .state
+                    .select(Some(self.picker.pool.len() - 1));
+                self.save_pool();
+            }
+            Err(e) => self.toast(&strings::fill("toast.add_failed", &[("error", &e)])),
+        }
+    }
+
+    fn toggle_candidate(&mut self) {
+        let Some(idx) = self.pool_editor.state.selected() else {
+            return;
+        };
+        match candidates::toggle(&mut self.picker.pool, idx) {
+            Ok(enabled) => {
+                let name = &self.picker.pool[idx].name;
+                info!("{} {name}", if enabled { "enabled" } else { "disabled" });
+                self.save_pool();
+            }
+            Err(e) => self.toast(&strings::fill("toast.disable_failed", &[("error", &e)])),
+        }
+    }
+
+    fn delete_candidate(&mut self) {
+        let Some(idx) = self.pool_editor.state.selected() else {
+            return;
+        };
+        match candidates::remove(&mut self.picker.pool, idx) {
+            Ok(ct) => {
+                info!("deleted {} from the candidate pool", ct.name);
+                self.pool_editor.clamp(self.picker.pool.len());
+                self.save_pool();
+            }
+            Err(e) => self.toast(&strings::fill("toast.delete_failed", &[("error", &e)])),
+        }
+    }
+
+    fn save_pool(&mut self) {
+        let Some(path) = &self.pool_editor.path else {
+            self.toast(strings::get("toast.pool_not_saved"));
+            return;
+        };
+        match TaskFile::save(path, &self.picker.pool) {
+            Ok(()) => debug!("saved the candidate pool to {}", path.display()),
+            Err(e) => {
+                warn!(
+                    "couldn't save the candidate pool to {}: {e}",
+                    path.display()
+                );
+                self.toast(&strings::fill("toast.pool_save_failed", &[("error", &e)]));
+            }
+        }
+    }
+
+    /// `h`: Finished and Canceled rows out of the table, or back in
+    fn toggle_hide_finished(&mut self) {
+        let hiding = self.task_table.visibility.toggle_quick();
+        info!(
+            "{} finished and canceled rows",
+            if hiding { "hiding" } else { "showing" }
+        );
+        // The highlight moves off a row that's gone, but there has to be one to move to
+        if self.view_state == ViewState::Inspect && self.task_table.selected(&self.tasks).is_none()
+        {
+            self.task_table.select_first(&self.tasks);
+        }
+        self.save_ui_state();
+    }
+
+    /// `V`: sort, filter, folds and expanded rows all back to how they start
+    fn reset_view(&mut self) {
+        if !self.task_table.reset_view() {
+            self.hint(strings::get("hint.view_default").to_string());
+            return;
+        }
+        // Like clearing the filter, something to be on if inspecting found nothing before
+        if self.view_state == ViewState::Inspect && self.task_table.selected(&self.tasks).is_none()
+        {
+            self.task_table.select_first(&self.tasks);
+        }
+        self.save_ui_state();
+    }
+
+    /// Changes the filter. The table keeps track of the selected task itself, this is just
+    /// for when there wasn't one (everything had been filtered out) and now there's something
+    fn edit_filter(&mut self, edit: impl FnOnce(&mut TaskTable)) {
+        edit(&mut self.task_table);
+        self.task_table.resolve(&self.tasks);
+        if self.task_table.selected(&self.tasks).is_none() {
+            self.task_table.select_first(&self.tasks);
+        }
+    }
+
+    /// Calls out for the actual task, mostly handles UI juggling. The guards get a say first
+    /// `target_secs` is how long it should sleep, if it was chosen. Otherwise the worker rolls
+    fn add_task(&mut self, ct: Option<CandidateTask>, target_secs: Option<u64>) {
+        let Some(ct) = ct else {
+            //Should be recoverable so we'll just ignore it otherwise
+            error!("attempted to select task from picker but got none");
+            return;
+        };
+        self.view_state = ViewState::Monitor;
+        if self.refuse_while_stopping(&ct.name) {
+            return;
+        }
+        let now = self.clock.now_instant();
+        let bounced = self.last_add.as_ref().is_some_and(|(name, at)| {
+            *name == ct.name && now.saturating_duration_since(*at) < DUPLICATE_WINDOW
+        });
+        if bounced {
+            debug!(
+                "not adding {} again so soon, it's probably a duplicate",
+                ct.name
+            );
+            return;
+        }
+        self.last_add = Some((ct.name.clone(), now));
+        info!("selected candidate task {:?}", ct);
+        match self.config.compiled_guards.check(&ct) {
+            Verdict::Allow => {
+                self.picker.confirm(&ct);
+                self.last_added = Some(ct.clone());
+                self.spawn_task(&ct, target_secs);
+            }
+            Verdict::Confirm(guard) => {
+                info!("guard `{}` wants {} confirmed", guard.pattern(), ct.name);
+                self.guard_popup = Some(GuardPopup::new(ct, guard, target_secs));
+                self.view_state = ViewState::Guard;
+            }
+            Verdict::Block(guard) => {
+                warn!("guard `{}` refused {}", guard.pattern(), ct.name);
+                let text = strings::fill(
+                    "toast.guard_blocked",
+                    &[("name", &ct.name), ("reason", &guard.reason())],
+                );
+                self.toast(&text);
+            }
+        }
+    }
+
+    /// `n`: another of whatever was added last, straight past the picker. The guards still get
+    /// their say
+    fn add_again(&mut self) {
+        let Some(ct) = self.last_added.clone() else {
+            self.hint(strings::get("hint.nothing_to_repeat").to_string());
+            return;
+        };
+        let before = self.tasks.len();
+        self.add_task(Some(ct), None);
+        if let Some(task) = self.tasks.get(before) {
+            let text = strings::fill(
+                "toast.added_again",
+                &[("name", &task.name), ("id", &task.id)],
+            );
+            self.toast(&text);
+        }
+    }
+
+    /// Adds the task the guard asked about, or forgets it
+    fn answer_guard(&mut self, add: bool) {
+        let Some(prompt) = self.guard_popup.take() else {
+            return;
+        };
+        self.view_state = ViewState::Monitor;
+        if add {
+            info!("adding {} despite the guard", prompt.candidate.name);
+            self.picker.confirm(&prompt.candidate);
+            self.last_added = Some(prompt.candidate.clone());
+            self.spawn_task(&prompt.candidate, prompt.target_secs);
+        }
+    }
+
+    /// The part of adding a task that doesn't care where it came from
+    fn spawn_task(&mut self, ct: &CandidateTask, target_secs: Option<u64>) {
+        if self.refuse_while_stopping(&ct.name) {
+            return;
+        }
+        let id = self.tasks_created; //This counter becomes the unique 'ID'
+        let copy = tasks::next_copy(&self.tasks, &ct.name);
+        let mut task = Task::pending(ct, id, copy);
+        task.target_secs = target_secs;
+        self.spawn(task);
+    }
+
+    /// Starts a task that's been made but not added yet, and adds it. One that won't start is
+    /// only kept if `ui.keep_failed_spawns` says
+    fn spawn(&mut self, mut task: Task) {
+        let id = task.id;
+        let kind = match task.activate(
+            self.mpsc_tx.clone(),
+            self.config.work.intensity,
+            self.chaos(),
+            self.orders(),
+            &*self.clock,
+        ) {
+            Ok(()) => EventKind::Created,
+            Err(e) => {
+                error!("couldn't start {}: {e}", task.candidate);
+                self.toast(&strings::fill(
+                    "toast.start_failed",
+                    &[("name", &task.candidate), ("error", &e)],
+                ));
+                // IDs are indexes, so no row means this one never gets used
+                if !self.config.ui.keep_failed_spawns {
+                    return;
+                }
+                task.mark_failed_to_start(e.to_string(), &*self.clock);
+                EventKind::Failed
+            }
+        };
+        self.tasks.push(task);
+        self.tasks_created += 1;
+        self.announce(id, kind);
+    }
+
+    /// `T` on the main screen. The next key picks which
+    fn choose_template(&mut self) {
+        if self.templates.is_empty() {
+            self.hint(strings::get("hint.no_templates").to_string());
+            return;
+        }
+        self.choosing_template = true;
+        self.toast(strings::get("toast.choose_template"));
+    }
+
+    /// The key after `T`: a template's, `?` for the list, or anything else to forget it
+    fn pick_template(&mut self, event: KeyEvent) {
+        self.toast = None;
+        let KeyCode::Char(ch) = event.code else {
+            return;
+        };
+        if ch == templates::LIST_KEY {
+            self.show_templates = true;
+            return;
+        }
+        let Some(template) = self.templates.iter().find(|t| t.key == ch).cloned() else {
+            self.hint(strings::fill("hint.no_template", &[("key", &ch)]));
+            return;
+        };
+        let added = self.add_template(&template);
+        if added > 0 {
+            let text = strings::fill(
+                "toast.template_added",
+                &[("name", &template.name), ("count", &added)],
+            );
+            self.toast(&text);
+        }
+    }
+
+    /// All of a template's tasks, with its settings, past the picker but not the guards. One
+    /// that wants confirming is left to the picker, where there's a popup to ask in. How many
+    /// it added
+    fn add_template(&mut self, template: &Template) -> usize {
+        let ct = &template.candidate;
+        if self.refuse_while_stopping(&ct.name) {
+            return 0;
+        }
+        match self.config.compiled_guards.check(ct) {
+            Verdict::Allow => {}
+            Verdict::Confirm(guard) => {
+                info!(
+                    "guard `{}` wants {} confirmed, not adding it from a template",
+                    guard.pattern(),
+                    ct.name
+                );
+                self.toast(&strings::fill(
+                    "toast.template_guarded",
+                    &[("name", &ct.name)],
+                ));
+                return 0;
+            }
+            Verdict::Block(guard) => {
+                warn!("guard `{}` refused {}", guard.pattern(), ct.name);
+                let text = strings::fill(
+                    "toast.guard_blocked",
+                    &[("name", &ct.name), ("reason", &guard.reason())],
+                );
+                self.toast(&text);
+                return 0;
+            }
+        }
+        info!(
+            "adding {} from template `{}`",
+            template.summary(),
+            template.name
+        );
+        self.picker.confirm(ct);
+        let before = self.tasks.len();
+        for _ in 0..template.count {
+            let copy = tasks::next_copy(&self.tasks, &ct.name);
+            self.spawn(template.task(self.tasks_created, copy));
+        }
+        self.tasks.len() - before
+    }
+
+    /// Asks whatever's run past its template's time limit to stop
+    fn enforce_time_limits(&mut self, now: Instant) -> bool {
+        let late: Vec<Id> = self
+            .tasks
+            .iter()
+            .filter(|task| task.out_of_time(now))
+            .map(|task| task.id)
+            .collect();
+        for &id in &late {
+            let task = &mut self.tasks[id];
+            let secs = task.time_limit.unwrap_or_default().as_secs();
+            match self.wiring.request_stop(task, &*self.clock) {
+                Ok(()) => {
+                    info!("task {id} has run for its {secs}s, stopping it");
+                    task.cancel_reason = Some(format!("ran out of time ({secs}s)"));
+                }
+                // Already in the banner, no use saying so every frame
+                Err(_) if self.wiring.is_broken() => {}
+                Err(e) => error!("couldn't stop task {id} at its time limit: {e}"),
+            }
+        }
+        !late.is_empty()
+    }
+
+    /// A row now, a worker once `at` comes around
+    fn schedule_task(&mut self, ct: &CandidateTask, at: Instant) {
+        let id = self.add_pending(ct, vec![]);
+        self.scheduled.push((at, id));
+    }
+
+    /// A row with no worker yet. One with anything in `depends_on` gets it once they've all
+    /// finished (see `start_dependents`), the rest whenever they're activated
+    fn add_pending(&mut self, ct: &CandidateTask, depends_on: Vec<tasks::Id>) -> tasks::Id {
+        let id = self.tasks_created;
+        let copy = tasks::next_copy(&self.tasks, &ct.name);
+        let mut task = Task::pending(ct, id, copy);
+        task.depends_on = depends_on;
+        self.tasks.push(task);
+        self.tasks_created += 1;
+        id
+    }
+
+    /// Tasks that haven't started because they're waiting on others
+    fn dependents(&self) -> Vec<tasks::Id> {
+        self.tasks
+            .iter()
+            .filter(|task| task.status == TaskStatus::Scheduled && !task.depends_on.is_empty())
+            .map(|task| task.id)
+            .collect()
+    }
+
+    /// Starts the dependents whose every dependency has finished. Any whose wait can't end (one
+    /// failed, or was stopped) is called off instead, and so is everything after it in turn.
+    /// Retries don't count, the one it waited on is still the one that failed
+    fn start_dependents(&mut self) -> bool {
+        let mut changed = false;
+        for id in self.dependents() {
+            let task = &self.tasks[id];
+            let over = |dep: &tasks::Id| self.tasks[*dep].status.is_terminal();
+            let finished = |dep: &tasks::Id| self.tasks[*dep].status == TaskStatus::Finished;
+            if let Some(&dep) = task
+                .depends_on
+                .iter()
+                .find(|dep| over(dep) && !finished(dep))
+            {
+                info!("calling off task {id}, #{dep} that it waits on didn't finish");
+                let task = &mut self.tasks[id];
+                task.cancel_pending(&*self.clock);
+                task.cancel_reason = Some(format!("#{dep} didn't finish"));
+                self.announce(id, EventKind::Canceled);
+                changed = true;
+            } else if task.depends_on.iter().all(finished) {
+                info!("everything task {id} waits on has finished, starting it");
+                self.activate_task(id);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Schedules another try at a task that's just failed, if its policy (or the config's) says
+    /// to, or marks it as the last one if that's all it gets
+    fn retry_failed(&mut self, id: tasks::Id) {
+        let now = self.clock.now_instant();
+        let task = &mut self.tasks[id];
+        let policy = task.retry.unwrap_or(self.config.retry);
+        let n = task.attempt.map_or(1, |attempt| attempt.n);
+        match retry::decide(&policy, n, now) {
+            Decision::Off => {}
+            Decision::GiveUp => {
+                warn!("task {id} failed its last try, {n} of {n}");
+                task.out_of_retries = true;
+            }
+            Decision::Retry { next, at } => {
+                task.attempt.get_or_insert(Attempt { n, of: next.of });
+                let retry_id = self.tasks_created;
+                let retry = task.retry(retry_id, next);
+                info!(
+                    "task {id} failed, trying again as task {retry_id} in {}s ({next})",
+                    at.saturating_duration_since(now).as_secs()
+                );
+                self.tasks.push(retry);
+                self.tasks_created += 1;
+                self.scheduled.push((at, retry_id));
+                self.scheduled.sort_by_key(|&(at, _)| at);
+            }
+        }
+    }
+
+    /// Calls off any tries still waiting to have another go at the same thing as `id`. Says
+    /// which they were
+    fn call_off_retries(&mut self, id: tasks::Id) -> Vec<tasks::Id> {
+        let links = self.tasks.iter().map(|task| (task.id, task.retry_of));
+        let waiting: Vec<tasks::Id> = retry::attempts(links, id)
+            .into_iter()
+            .filter(|&other| self.scheduled.iter().any(|&(_, due)| due == other))
+            .collect();
+        for &other in &waiting {
+            self.scheduled.retain(|&(_, due)| due != other);
+            if let Err(e) = self
+                .wiring
+                .request_stop(&mut self.tasks[other], &*self.clock)
+            {
+                error!("couldn't call off retry {other}: {e}");
+                continue;
+            }
+            info!("called off retry {other} of task {id}");
+            self.announce(other, EventKind::Canceled);
+        }
+        waiting
+    }
+
+    /// Starts a scheduled task's worker. It's had a row all along, so one that can't start keeps
+    /// it whatever `keep_failed_spawns` says
+    fn activate_task(&mut self, id: tasks::Id) {
+        // Batch delays run out whether anyone's looking or not, so these can start paused
+        let (tx, intensity, chaos, orders) = (
+            self.mpsc_tx.clone(),
+            self.config.work.intensity,
+            self.chaos(),
+            self.orders(),
+        );
+        let task = &mut self.tasks[id];
+        let kind = match task.activate(tx, intensity, chaos, orders, &*self.clock) {
+            Ok(()) => EventKind::Created,
+            Err(e) => {
+                error!("couldn't start {}: {e}", task.candidate);
+                let text =
+                    strings::fill("toast.start_failed", &[("name", &task.name), ("error", &e)]);
+                task.mark_failed_to_start(e.to_string(), &*self.clock);
+                self.toast(&text);
+                EventKind::Failed
+            }
+        };
+        self.announce(id, kind);
+    }
+
+    /// Lets the webhook know, if there is one. Never waits on it
+    fn announce(&self, id: tasks::Id, kind: EventKind) {
+        if let Some(webhook) = &self.webhook {
+            webhook.send(webhook::Event::new(
+                kind,
+                &self.tasks[id],
+                self.clock.now_local(),
+            ));
+        }
+    }
+
+    /// Adds everything in the confirmed batch. Delayed ones wait in `scheduled` instead
+    fn add_batch(&mut self) {
+        let Some(batch) = self.batch.take() else {
+            return;
+        };
+        self.view_state = ViewState::Monitor;
+        // The popup already showed what was wrong, and Enter doesn't mean "add nothing"
+        let Ok(items) = batch.items else {
+            return;
+        };
+        let now = self.clock.now_instant();
+        let (mut added, mut later, mut blocked) = (0, 0, 0);
+        // Each item's rows, for whatever comes `after` it
+        let mut rows: Vec<Vec<tasks::Id>> = Vec::with_capacity(items.len());
+        for item in &items {
+            let mut these = vec![];
+            // The popup already said why
+            if item.is_blocked() {
+                blocked += item.count;
+                rows.push(these);
+                continue;
+            }
+            // One that couldn't start and wasn't kept has no row to wait on
+            if item.after.is_some_and(|after| rows[after].is_empty()) {
+                warn!(
+                    "not adding {}, nothing it waits on got added",
+                    item.candidate
+                );
+                rows.push(these);
+                continue;
+            }
+            for _ in 0..item.count {
+                match (item.after, item.delay) {
+                    (Some(after), _) => {
+                        these.push(self.add_pending(&item.candidate, rows[after].clone()));
+                        later += 1;
+                    }
+                    (None, Some(delay)) => {
+                        these.push(self.tasks_created);
+                        self.schedule_task(&item.candidate, now + delay);
+                        later += 1;
+                    }
+                    (None, None) => {
+                        let id = self.tasks_created;
+                        self.spawn_task(&item.candidate, None);
+                        if self.tasks.get(id).is_some() {
+                            these.push(id);
+                        }
+                        added += 1;
+                    }
+                }
+            }
+            rows.push(these);
+        }
+        self.scheduled.sort_by_key(|&(at, _)| at);
+        info!("batch added {added} tasks, {later} more scheduled, {blocked} blocked by guards");
+        if blocked > 0 {
+            self.toast(&strings::fill(
+                "toast.batch_blocked",
+                &[("added", &(added + later)), ("blocked", &blocked)],
+            ));
+        } else if later > 0 {
+            self.toast(&strings::fill(
+                "toast.batch_delayed",
+                &[("added", &added), ("later", &later)],
+            ));
+        }
+    }
+
+    /// How a task that's still going compares to its candidate's history, as of right now.
+    /// Nothing for finished tasks, or without a history file
+    fn vs_avg(&self, task: &Task) -> Option<history::Comparison> {
+        if !task.status.is_working() {
+            return None;
+        }
+        let elapsed = (self.clock.now_local() - task.start?).num_seconds().max(0) as u64;
+        let stats = self.picker.stats.as_ref()?.get(&task.candidate);
+        history::compare(elapsed, stats, &self.config.pace)
+    }
+
+    /// Index into `tasks` of whatever's highlighted
+    fn selected_index(&self) -> Option<usize> {
+        let id = self.task_table.selected(&self.tasks)?;
+        self.tasks.iter().position(|task| task.id == id)
+    }
+
+    fn cancel_selected_task(&mut self) {
+        let Some(id) = self.task_table.selected(&self.tasks) else {
+            warn!("tried to send a cancel message but nothing's selected");
+            return;
+        };
+        // A failed task can't be stopped, but whatever's waiting to try it again can be
+        if self.tasks[id].status == TaskStatus::Failed {
+            let waiting = self.call_off_retries(id);
+            if !waiting.is_empty() {
+                let ids: Vec<String> = waiting.iter().map(|id| format!("#{id}")).collect();
+                self.toast(&strings::fill(
+                    "toast.retry_called_off",
+                    &[("id", &id), ("retries", &ids.join(", "))],
+                ));
+                return;
+            }
+        }
+        let clock = &*self.clock;
+        let wiring = &mut self.wiring;
+        let result = tasks::find_mut(&mut self.tasks, id).and_then(|task| {
+            // Nothing's listening yet, so it's enough that it never gets started
+            let scheduled = task.status == TaskStatus::Scheduled;
+            wiring.request_stop(task, clock).map(|()| scheduled)
+        });
+        match result {
+            Ok(true) => {
+                info!("called off scheduled task {id}");
+                self.scheduled.retain(|&(_, waiting)| waiting != id);
+                self.announce(id, EventKind::Canceled);
+            }
+            Ok(false) => info!("sent a cancel message to task {id}"),
+            Err(e @ TaskError::AlreadyTerminal(..)) => {
+                self.toast(&strings::fill("toast.cant_stop", &[("error", &e)]))
+            }
+            Err(e) => {
+                error!("couldn't cancel task {id}: {e}");
+                self.toast(&strings::fill("toast.stop_failed", &[("error", &e)]));
+            }
+        }
+    }
+
+    /// Nudges work intensity, tells running tasks, and remembers it for next time
+    fn change_intensity(&mut self, by: f32) {
+        // Round so repeated steps don't drift into 0.30000000000000004x
+        let new = ((self.config.work.intensity + by) * 10.0).round() / 10.0;
+        let new = new.clamp(tasks::MIN_INTENSITY, tasks::MAX_INTENSITY);
+        if new == self.config.work.intensity {
+            return;
+        }
+        self.config.work.intensity = new;
+        info!("work intensity set to {new:.1}x");
+        self.tell_everyone(TaskRxMsg::SetIntensity(new));
+        self.config.save();
+    }
+
+    /// Only new tasks see the change. Ones already running keep whatever they started with
+    fn toggle_chaos(&mut self) {
+        let chaos = !self.config.work.chaos;
+        self.config.work.chaos = chaos;
+        if chaos {
+            warn!(target: chaos::TARGET, "chaos on: new tasks will misbehave on purpose");
+            self.toast(strings::get("toast.chaos_on"));
+        } else {
+            info!(target: chaos::TARGET, "chaos off");
+            self.toast(strings::get("toast.chaos_off"));
+        }
+        self.config.save();
+    }
+
+    /// Workers already going hear about it too, unlike chaos
+    fn toggle_low_power(&mut self) {
+        let low_power = !self.config.work.low_power;
+        self.config.work.low_power = low_power;
+        self.frames
+            .set_low_power(low_power, self.clock.now_instant());
+        self.task_table.still = low_power || !self.frames.is_focused();
+        self.tell_everyone(TaskRxMsg::SetReporting(self.reporting()));
+        if low_power {
+            info!("low power on: sparse reports, slow ticks, no animations");
+            self.toast(strings::get("toast.low_power_on"));
+        } else {
+            info!("low power off");
+            self.toast(strings::get("toast.low_power_off"));
+        }
+        self.config.save();
+    }
+
+    fn chaos(&self) -> ChaosConfig {
+        if self.config.work.chaos {
+            ChaosConfig::demo()
+        } else {
+            ChaosConfig::default()
+        }
+    }
+
+    fn toggle_compact(&mut self) {
+        let compact = !self.config.ui.compact;
+        self.config.ui.compact = compact;
+        self.task_table.compact = compact;
+        if compact {
+            info!("compact mode on");
+            self.toast(strings::get("toast.compact_on"));
+        } else {
+            info!("compact mode off");
+            self.toast(strings::get("toast.compact_off"));
+        }
+        self.config.save();
+    }
+
+    fn cycle_split(&mut self) {
+        let split = self.config.ui.split.next();
+        self.config.ui.split = split;
+        let text = match split {
+            SplitMode::Table => "toast.layout_table",
+            SplitMode::Even => "toast.layout_even",
+            SplitMode::Logger => "toast.layout_logger",
+        };
+        info!("layout set to {}", strings::english(text));
+        self.toast(&strings::fill(
+            "toast.layout",
+            &[("layout", &strings::get(text))],
+        ));
+        self.config.save();
+    }
+
+    /// The tour's next step, if they've done the one it was on. True if that changed anything
+    fn advance_tour(&mut self) -> bool {
+        let before = self.tour.step();
+        if self.tour.observe(self.view_state, !self.tasks.is_empty()) {
+            info!("tour finished");
+            self.end_tour();
+        }
+        self.tour.step() != before
+    }
+
+    /// Finished or skipped, it's not coming back
+    fn end_tour(&mut self) {
+        self.tour_done = true;
+        self.save_ui_state();
+    }
+
+    /// What the tour's step points at: the controls line, or the picker once it's open
+    fn tour_target(&self, step: Step, area: Rect) -> Rect {
+        match (step, self.view_state) {
+            (Step::PickTask, ViewState::TaskAdd | ViewState::Duration) => self.picker_area(area),
+            _ => Rect {
+                y: area.bottom().saturating_sub(1),
+                height: 1.min(area.height),
+                ..area
+            },
+        }
+    }
+
+    /// Where the picker's drawn, over the middle of `area`
+    fn picker_area(&self, area: Rect) -> Rect {
+        let modal_width = (area.width as f32 * 0.85) as u16;
+        centered_rect(area, modal_width, saturating_height(self.picker.height()))
+    }
+
+    /// Sort, filter and the tour, for next time. Nothing's kept with `--reset-ui`
+    fn save_ui_state(&self) {
+        if let Some(path) = &self.ui_state_path {
+            let state = UiState {
+                tour_done: self.tour_done,
+                ..self.task_table.ui_state()
+            };
+            if let Err(e) = state.save(path) {
+                warn!("couldn't save UI state to {}: {e}", path.display());
+            }
+        }
+    }
+
+    /// A message for a task that isn't in the table: one that's been removed, or one that never
+    /// was, which is a bug in whoever sent it
+    fn stray(&mut self, msg: &TaskTxMsg, now: Instant) {
+        let id = msg.id();
+        match self.tombstones.classify(id, now) {
+            Stray::Late(since) => {
+                self.late_messages += 1;
+                info!(
+                    "late {} from removed task {id}, {}s after removal",
+                    msg.kind(),
+                    since.as_secs()
+                );
+            }
+            Stray::Unknown => {
+                self.protocol_errors += 1;
+                error!(
+                    "protocol error: {} from task {id}, which we never had",
+                    msg.kind()
+                );
+            }
+        }
+    }
+
+    /// A `progress_csv` line for where task `id` is now. The first write that fails ends it
+    fn plot(&mut self, id: Id) {
+        let Some(csv) = &mut self.progress_csv else {
+            return;
+        };
+        if let Err(e) = csv.record(self.clock.now_local(), id, self.tasks[id].progress) {
+            error!("stopped writing {}: {e}", csv.path().display());
+            self.progress_csv = None;
+        }
+    }
+
+    /// The rest of `progress_csv` out, before the report and the summary
+    fn close_progress_csv(&mut self) {
+        let Some(csv) = self.progress_csv.take() else {
+            return;
+        };
+        let path = csv.path().to_path_buf();
+        match csv.close() {
+            Ok(()) => info!("wrote progress to {}", path.display()),
+            Err(e) => error!("couldn't finish {}: {e}", path.display()),
+        }
+    }
+
+    /// Journals whatever's new since last time: tasks made, statuses moved on to and stops asked
+    /// for. The first write that fails ends it
+    fn journal_changes(&mut self) {
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        let at = self.clock.now_local().to_rfc3339();
+        let mut entries = vec![];
+        for task in &self.tasks[self.journaled.len().min(self.tasks.len())..] {
+            entries.push(Entry::Created {
+                id: task.id,
+                candidate: task.candidate.clone(),
+                copy: task.copy,
+                description: task.description.clone(),
+                at: at.clone(),
+            });
+            self.journaled.push((TaskStatus::Scheduled, false));
+        }
+        for (task, (status, stop_asked)) in self.tasks.iter().zip(&mut self.journaled) {
+            if task.cancel != CancelState::None && !*stop_asked {
+                *stop_asked = true;
+                entries.push(Entry::CancelRequested {
+                    id: task.id,
+                    at: at.clone(),
+                });
+            }
+            if task.status != *status {
+                *status = task.status;
+                entries.push(Entry::Status {
+                    id: task.id,
+                    status: task.status,
+                    progress: task.progress,
+                    at: at.clone(),
+                });
+            }
+        }
+        if let Err(e) = entries.iter().try_for_each(|entry| journal.write(entry)) {
+            warn!("stopped journaling to {}: {e}", journal.path().display());
+            self.journal = None;
+        }
+    }
+
+    /// The `closed` line that says this session ended properly, after anything still to journal
+    fn close_journal(&mut self) {
+        self.journal_changes();
+        let Some(journal) = self.journal.take() else {
+            return;
+        };
+        let path = journal.path().to_path_buf();
+        if let Err(e) = journal.close(self.clock.now_local()) {
+            warn!("couldn't close {}: {e}", path.display());
+        }
+    }
+
+    /// Writes to the recording, if there is one. The first write that fails ends it
+    fn record(&mut self, write: impl FnOnce(&mut Recorder) -> io::Result<()>) {
+        let Some(recorder) = &mut self.recorder else {
+            return;
+        };
+        if let Err(e) = write(recorder) {
+            error!("stopped recording, couldn't write to it: {e}");
+            self.recorder = None;
+        }
+    }
+
+    fn toast(&mut self, text: &str) {
+        self.toast = Some(Banner::new(text.to_string(), TOAST_DURATION, &*self.clock));
+    }
+
+    /// What to do when the terminal says it's lost focus (or got it back): draw less, stop
+    /// animating, and pause the dummies if the config says to
+    fn set_focused(&mut self, focused: bool) {
+        if focused == self.frames.is_focused() {
+            return;
+        }
+        self.frames.set_focused(focused, self.clock.now_instant());
+        self.task_table.still = !focused || self.config.work.low_power;
+        if self.config.work.pause_unfocused {
+            self.tell_everyone(TaskRxMsg::Pause(!focused));
+        }
+        if focused {
+            info!("focus back, full speed");
+        } else {
+            info!("focus lost, slowing down");
+        }
+    }
+
+    /// What every worker's been told, so new ones can be told too
+    fn orders(&self) -> Orders {
+        Orders {
+            paused: self.config.work.pause_unfocused && !self.frames.is_focused(),
+            stopping: self.shutdown.is_some(),
+            reporting: self.reporting(),
+        }
+    }
+
+    fn reporting(&self) -> ReportingMode {
+        match self.config.work.low_power {
+            true => ReportingMode::Sparse,
+            false => ReportingMode::Normal,
+        }
+    }
+
+    /// Nothing new starts once we're on the way out. Keys are already ignored by then, this is
+    /// for everything else that adds tasks
+    fn refuse_while_stopping(&mut self, name: &str) -> bool {
+        if self.shutdown.is_none() {
+            return false;
+        }
+        warn!("not adding {name}, we're shutting down");
+        self.toast(strings::get("toast.shutting_down"));
+        true
+    }
+
+    /// Sends the same thing down every channel that still has a task on the other end
+    fn tell_everyone(&mut self, msg: TaskRxMsg) {
+        for task in self.tasks.iter_mut().filter(|t| t.completion.is_running()) {
+            self.wiring.tell(task, msg, &*self.clock);
+        }
+    }
+
+    fn open_negotiation(&mut self) {
+        let Some(task) = self
+            .selected_index()
+            .and_then(|selected| self.tasks.get(selected))
+        else {
+            warn!("tried to negotiate with a task that doesn't exist");
+            return;
+        };
+        match (task.status, task.demand_secs) {
+            (TaskStatus::OnStrike, Some(demand_secs)) => {
+                self.negotiation = Some(Negotiation::new(task.id, demand_secs));
+                self.view_state = ViewState::Negotiate;
+            }
+            _ => self.toast(&strings::fill("toast.not_on_strike", &[("id", &task.id)])),
+        }
+    }
+
+    fn open_menu(&mut self) {
+        match self
+            .selected_index()
+            .and_then(|selected| self.tasks.get(selected))
+        {
+            Some(task) => {
+                let archived = self.task_table.archive.contains(task.id);
+                self.menu = Some(ActionMenu::new(task, archived));
+                self.view_state = ViewState::Menu;
+            }
+            None => warn!("tried to open the action menu for a task that doesn't exist"),
+        }
+    }
+
+    fn close_menu(&mut self) {
+        self.menu = None;
+        if let ViewState::Menu = self.view_state {
+            self.view_state = ViewState::Inspect;
+        }
+    }
+
+    /// Does something to the selected task. The direct keys and the menu both come through
+    /// here, so they can't end up doing different things
+    fn run_action(&mut self, action: Action) {
+        self.close_menu();
+        let read_only = matches!(action, Action::Terminate | Action::Negotiate | Action::Note)
+            && self.selected_archived() == Some(true);
+        if read_only {
+            self.hint(strings::get("hint.archived_read_only").to_string());
+            return;
+        }
+        match action {
+            Action::Terminate => self.cancel_selected_task(),
+            Action::Negotiate => self.open_negotiation(),
+            Action::Details => self.open_detail(),
+            Action::Note => self.open_note(),
+            Action::Archive => self.archive_selected(true),
+            Action::Unarchive => self.archive_selected(false),
+            Action::Remove => self.remove_selected(),
+        }
+    }
+
+    /// Whether the selected task is archived, or `None` if nothing's selected
+    fn selected_archived(&self) -> Option<bool> {
+        self.selected_index()
+            .and_then(|selected| self.tasks.get(selected))
+            .map(|task| self.task_table.archive.contains(task.id))
+    }
+
+    /// Moves the selected task into the archive or back out. Only finished tasks go in, since
+    /// anything still running would change where nobody's looking
+    fn archive_selected(&mut self, archived: bool) {
+        let Some(task) = self
+            .selected_index()
+            .and_then(|selected| self.tasks.get(selected))
+        else {
+            warn!("tried to archive a task that doesn't exist");
+            return;
+        };
+        let id = task.id;
+        if archived && !task.status.is_terminal() {
+            self.hint(strings::fill("hint.archive_unfinished", &[("id", &id)]));
+            return;
+        }
+        self.task_table.archive.set(id, archived);
+        if archived {
+            info!("archived task {id}");
+        } else {
+            info!("unarchived task {id}");
+        }
+    }
+
+    /// Takes the selected task out of the table, where `u` can put it back for a few seconds.
+    /// Only finished tasks go, for the same reason as the archive. History's already been
+    /// written by then, so there's nothing to hold back until it's discarded
+    fn remove_selected(&mut self) {
+        let Some(task) = self
+            .selected_index()
+            .and_then(|selected| self.tasks.get(selected))
+        else {
+            warn!("tried to remove a task that doesn't exist");
+            return;
+        };
+        let id = task.id;
+        if !task.status.is_terminal() {
+            self.hint(strings::fill("hint.remove_unfinished", &[("id", &id)]));
+            return;
+        }
+        let now = self.clock.now_instant();
+        if let Some(pushed_out) = self.task_table.removals.remove(id, now) {
+            self.discard(pushed_out, now);
+        }
+        // The highlight moves on to whatever slid into the row
+        self.task_table.resolve(&self.tasks);
+        info!("removed task {id}");
+        self.toast(&strings::fill("toast.removed", &[("id", &id)]));
+    }
+
+    /// Puts back the latest removal, highlighted, where it was before
+    fn undo_removal(&mut self) {
+        let Some(id) = self.task_table.removals.undo(self.clock.now_instant()) else {
+            self.hint(strings::get("hint.nothing_to_undo").to_string());
+            return;
+        };
+        self.task_table.select(&self.tasks, id);
+        if self.task_table.selected(&self.tasks) != Some(id) {
+            // Put back behind the filter, or in the folded archive
+            self.hint(strings::fill("hint.back_but_hidden", &[("id", &id)]));
+        }
+        info!("put task {id} back");
+    }
+
+    /// A removal that can't be undone anymore. From here on its messages are strays
+    fn discard(&mut self, id: Id, now: Instant) {
+        self.tombstones.bury(id, now);
+        info!("discarded task {id}");
+    }
+
+    fn toggle_archive_open(&mut self) {
+        if self.task_table.archive.is_empty() {
+            self.hint(strings::get("hint.nothing_archived").to_string());
+            return;
+        }
+        // The highlight goes by ID, so it stays put unless its task has just been folded away
+        self.task_table.archive.toggle_open();
+    }
+
+    /// Highlights the next task (or the previous) with one of the statuses `jump_statuses` asks
+    /// for, or anything of the same sort
+    fn jump_to_status(&mut self, forward: bool) {
+        let targets: Vec<StatusClass> = self
+            .config
+            .ui
+            .jump_statuses
+            .iter()
+            .map(TaskStatus::class)
+            .collect();
+        let found = self.task_table.jump_to(&self.tasks, forward, |task| {
+            targets.contains(&task.status.class())
+        });
+        if !found {
+            let names: Vec<String> = self
+                .config
+                .ui
+                .jump_statuses
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            let names = match names.is_empty() {
+                true => strings::get("hint.no_jump_statuses").to_string(),
+                false => names.join(", "),
+            };
+            self.hint(strings::fill(
+                "hint.nothing_to_jump_to",
+                &[("statuses", &names)],
+            ));
+        }
+    }
+
+    fn open_note(&mut self) {
+        match self
+            .selected_index()
+            .and_then(|selected| self.tasks.get(selected))
+        {
+            Some(task) => {
+                self.note_editor = Some(NoteEditor::new(task));
+                self.view_state = ViewState::Note;
+            }
+            None => warn!("tried to write a note on a task that doesn't exist"),
+        }
+    }
+
+    fn close_note(&mut self) {
+        self.note_editor = None;
+        if let ViewState::Note = self.view_state {
+            self.view_state = ViewState::Inspect;
+        }
+    }
+
+    /// Notes are ours, not the task's, so nothing gets sent anywhere
+    fn save_note(&mut self) {
+        let Some(editor) = &self.note_editor else {
+            return;
+        };
+        let id = editor.id;
+        let note = editor.note();
+        match &note {
+            Some(_) => info!("saved a note on task {id}"),
+            None => info!("took the note off task {id}"),
+        }
+        self.tasks[id].note = note;
+        self.close_note();
+    }
+
+    fn close_negotiation(&mut self) {
+        self.negotiation = None;
+        if let ViewState::Negotiate = self.view_state {
+            self.view_state = ViewState::Inspect;
+        }
+    }
+
+    /// Sends whatever answer is under the cursor. The task decides what happens next
+    fn send_bargain(&mut self) {
+        let Some(negotiation) = &self.negotiation else {
+            return;
+        };
+        let id = negotiation.id;
+        let granted_secs = negotiation.offer().granted_secs(negotiation.demand_secs);
+        if self.wiring.tell(
+            &mut self.tasks[id],
+            TaskRxMsg::Bargain(granted_secs),
+            &*self.clock,
+        ) {
+            info!("offered task {id} a {granted_secs} sec break");
+        }
+        self.close_negotiation();
+    }
+
+    fn open_detail(&mut self) {
+        match self
+            .selected_index()
+            .and_then(|selected| self.tasks.get(selected))
+        {
+            Some(task) => {
+                self.detail.open(task);
+                self.view_state = ViewState::Detail;
+            }
+            None => warn!("tried to open details for a task that doesn't exist"),
+        }
+    }
+
+    /// Asks everyone to stop, then waits (in `update`) until they all have or we run out of
+    /// patience. Sleeping tasks are waiting on their channel, so they hear it right away.
+    /// Only a work block in progress can hold it up. Tasks that were already asked on their
+    /// own don't get asked twice, but they're still waited on
+    fn exit(&mut self) {
+        let waiting = std::mem::take(&mut self.scheduled)
+            .into_iter()
+            .map(|(_, id)| id);
+        for id in waiting.chain(self.dependents()).collect::<Vec<_>>() {
+            self.tasks[id].cancel_pending(&*self.clock);
+            self.announce(id, EventKind::Canceled);
+        }
+        let now = self.clock.now_local();
+        let mut running = HashSet::new();
+        let mut already_pending = 0;
+        for task in self.tasks.iter_mut().filter(|t| t.completion.is_running()) {
+            running.insert(task.id);
+            if task.cancel != CancelState::None {
+                already_pending += 1;
+            } else if self
+                .wiring
+                .tell(task, TaskRxMsg::EveryoneStopPls, &*self.clock)
+            {
+                task.stop_requested(now);
+            }
+        }
+        info!(
+            "requested stop for {} tasks, {already_pending} were already pending",
+            running.len()
+        );
+        if running.is_empty() {
+            self.exit = true;
+        } else {
+            self.shutdown = Some(Shutdown::new(running, self.clock.now_instant()));
+        }
+    }
+
+    /// Leaves without whoever's still going. Shell tasks get their worker aborted, which takes
+    /// the child with it. A dummy's blocking thread can't be interrupted, so it's left to finish
+    /// its block on its own. Either way it's in the report
+    fn give_up(&mut self) {
+        let waiting = self
+            .shutdown
+            .as_ref()
+            .map(|shutdown| shutdown.waiting.clone())
+            .unwrap_or_default();
+        if !waiting.is_empty() {
+            warn!(
+                "leaving without {} tasks that didn't stop in time",
+                waiting.len()
+            );
+        }
+        for id in waiting {
+            let task = &mut self.tasks[id];
+            if !task.completion.is_running() {
+                continue;
+            }
+            let event = match task.kind {
+                TaskKind::Shell => {
+                    task.completion.abort();
+                    CancelEvent::Force
+                }
+                TaskKind::Dummy => CancelEvent::Abandon,
+            };
+            task.cancel = task.cancel.next(event);
+        }
+        self.exit = true;
+    }
+}
+
+/// Full screen on the alternate screen, or one inline line on the normal one below whatever the
+/// shell had printed. Each way gets a fresh terminal, since ratatui can't change viewport on
+/// an existing one. Raw mode stays on throughout, so keys still come to us either way
+fn set_viewport(terminal: &mut DefaultTerminal, inline: bool) -> Result<()> {
+    let backend = || CrosstermBackend::new(io::stdout());
+    if inline {
+        execute!(io::stdout(), LeaveAlternateScreen)?;
+        *terminal = Terminal::with_options(
+            backend(),
+            TerminalOptions {
+                viewport: Viewport::Inline(1),
+            },
+        )?;
+    } else {
+        // Blanked first, or the last status line would be left in the scrollback
+        terminal.clear()?;
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        *terminal = Terminal::<CrosstermBackend<Stdout>>::new(backend())?;
+    }
+    terminal.clear()?;
+    Ok(())
+}
+
+/// " Label <KEY>" for each, styled, and a " …" if some were left off. `terse` is " KEY Short"
+fn controls_line(controls: &[Control], cut: bool, terse: bool) -> Line<'static> {
+    let mut spans = vec![];
+    for control in controls {
+        if terse {
+            spans.push(Span::from(" "));
+            spans.push(theme::key(control.key));
+            spans.push(Span::from(format!(" {}", control.short())));
+        } else {
+            spans.push(Span::from(format!(" {} ", control.label)));
+            spans.push(theme::key(format!("<{}>", control.key)));
+        }
+    }
+    if cut {
+        spans.push(Span::from(format!(" {}", controls::ELLIPSIS)));
+    }
+    spans.push(Span::from(" "));
+    Line::from(spans)
+}
+
+impl Widget for &mut App {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mode = match self.view_state {
+            ViewState::Monitor => None,
+            ViewState::Inspect => Some("inspect"),
+            ViewState::TaskAdd => Some("task add"),
+            ViewState::Detail => Some("detail"),
+            ViewState::Negotiate => Some("negotiate"),
+            ViewState::Filter => Some("filter"),
+            ViewState::Batch => Some("batch"),
+            ViewState::Guard => Some("guard"),
+            ViewState::Timeline => Some("timeline"),
+            ViewState::Menu => Some("actions"),
+            ViewState::Note => Some("note"),
+            ViewState::Pool => Some("pool"),
+            ViewState::Columns => Some("columns"),
+            ViewState::Logs => Some("logs"),
+            ViewState::Duration => Some("duration"),
+            ViewState::Architecture => Some("architecture"),
+        };
+        let name = match &self.session {
+            Some(session) => format!("clustrctrl · {session}"),
+            None => "clustrctrl".to_string(),
+        };
+        let title = match mode {
+            Some(mode) => Line::from(format!("  {name} ━ [{mode}] ").bold()),
+            None => Line::from(format!("  {name}  ").bold()),
+        };
+        let selected = match self.view_state {
+            ViewState::Inspect => self
+                .selected_index()
+                .and_then(|selected| self.tasks.get(selected))
+                .map(|task| task.status),
+            _ => None,
+        };
+        let toggles = Toggles {
+            enter_opens_menu: self.config.ui.enter_opens_menu,
+            filtering: !self.task_table.filter().is_empty(),
+            archived: self.selected_archived() == Some(true),
+            header: self
+                .task_table
+                .selected_group(&self.tasks)
+                .map(|class| self.task_table.groups.is_folded(class)),
+            repeat: self.last_added.is_some(),
+            templates: !self.templates.is_empty(),
+            alt_keys: self.compat.show_alt_keys,
+        };
+        let chrome = Chrome::pick(self.config.ui.compact);
+        // Corners and the border on either side of the title take a couple of columns
+        let (shown, cut) = controls::fit(
+            controls::controls(self.view_state, selected, toggles),
+            area.width.saturating_sub(4) as usize,
+            chrome.terse,
+        );
+        let controls = controls_line(&shown, cut, chrome.terse);
+
+        // Without a row of their own, toasts have the controls' place until they go
+        let toast = self.toast.as_ref().filter(|_| chrome.toast_row.is_none());
+        let controls = match (&self.shutdown, &self.hint, toast) {
+            (Some(shutdown), _, _) => shutdown.line(self.clock.now_instant()),
+            (None, Some(hint), _) => {
+                Line::from(format!(" {} ", hint.text)).style(theme::current().notice)
+            }
+            (None, None, Some(toast)) => Line::from(format!(" {} ", toast.text)).italic(),
+            (None, None, None) => controls,
+        };
+
+        let mut intensity = Line::from(format!(" work {:.1}x ", self.config.work.intensity));
+        if self.config.work.low_power {
+            intensity.spans.push(Span::styled(
+                strings::get("low_power.label"),
+                theme::current().dim,
+            ));
+            intensity.spans.push(" ".into());
+        }
+        // Hard to miss, and it doesn't go away
+        if self.root {
+            intensity
+                .spans
+                .insert(0, Span::styled(" ROOT ", theme::current().alert.reversed()));
+        }
+        // Nobody should mistake the fallout for real bugs mid-demo
+        if self.config.work.chaos {
+            intensity.spans.insert(
+                0,
+                Span::styled(" CHAOS ", theme::current().alert.reversed()),
+            );
+        }
+        let main_block = Block::bordered()
+            .title(title.left_aligned())
+            .title(intensity.right_aligned())
+            .title_bottom(controls.centered())
+            .border_set(theme::current().border(chrome.border))
+            .padding(chrome.padding);
+
+        // Render the main block first to draw the borders
+        let mut internal_area = main_block.inner(area);
+        main_block.render(area, buf);
+
+        // Toasts go in the bottom padding, just above the controls
+        if let (Some(toast), Some(row)) = (&self.toast, chrome.toast_row) {
+            let toast_area = Rect {
+                y: area.bottom().saturating_sub(row),
+                height: 1.min(area.height),
+                ..internal_area
+            };
+            Line::from(toast.text.as_str())
+                .italic()
+                .centered()
+                .render(toast_area, buf);
+        }
+
+        // What the glyphs mean, for the ones that are actually in the table. Above the toast
+        if let (false, Some(row)) = (self.tasks.is_empty(), chrome.legend_row) {
+            let legend_area = Rect {
+                y: area.bottom().saturating_sub(row),
+                height: 1.min(area.height),
+                ..internal_area
+            };
+            self.task_table
+                .legend(&self.tasks)
+                .centered()
+                .render(legend_area, buf);
+        }
+
+        // Startup warnings get a line of their own above everything else while they last
+        if let Some(banner) = &self.banner {
+            let [banner_area, rest] =
+                Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(internal_area);
+            Line::from(format!(" ⚠ {} ", banner.text))
+                .style(theme::current().banner)
+                .render(banner_area, buf);
+            internal_area = rest;
+        }
+
+        // Broken channels stay up top for good, nothing short of a restart fixes them
+        if let Some(text) = self.wiring.banner() {
+            let [banner_area, rest] =
+                Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(internal_area);
+            Line::from(format!(" ✘ {text} "))
+                .style(theme::current().alert.reversed())
+                .render(banner_area, buf);
+            internal_area = rest;
+        }
+
+        // Detail takes over the whole inside, nothing else to draw
+        if let ViewState::Detail = self.view_state {
+            if let Some(task) = self.tasks.get(self.detail.id) {
+                self.detail.vs_avg = self.vs_avg(task);
+                let links = self.task_table.links(&self.tasks);
+                self.detail.chain = Some(Chain::find(links, task.id))
+                    .filter(|chain| !chain.is_lone())
+                    .map(|chain| chain.describe());
+                let links = self.tasks.iter().map(|t| (t.id, t.retry_of));
+                self.detail.attempts = Some(retry::attempts(links, task.id))
+                    .filter(|attempts| attempts.len() > 1)
+                    .map(|attempts| retry::describe(&attempts));
+                StatefulWidget::render(&mut self.detail, internal_area, buf, &mut &*task);
+                return;
+            }
+        }
+        if let ViewState::Timeline = self.view_state {
+            StatefulWidget::render(&mut self.timeline, internal_area, buf, &mut &self.tasks);
+            return;
+        }
+        if let ViewState::Architecture = self.view_state {
+            let inbound = Channel::of(&self.mpsc_tx);
+            Plumbing::of(&self.tasks, inbound, self.clock.now_instant()).render(internal_area, buf);
+            return;
+        }
+        // The logs get the lot, bar a line so the tasks aren't forgotten about
+        if let ViewState::Logs = self.view_state {
+            let [strip_area, logs_area] = split_with_min(internal_area, 1, 0);
+            self.status_strip().render(strip_area, buf);
+            self.logs.render(" Message Stream ", logs_area, buf);
+            return;
+        }
+
+        // Inspecting on a wide enough screen gets the selected task in a panel on the right
+        let inspecting = matches!(
+            self.view_state,
+            ViewState::Inspect
+                | ViewState::Filter
+                | ViewState::Negotiate
+                | ViewState::Menu
+                | ViewState::Note
+                | ViewState::Columns
+        );
+        if inspecting && internal_area.width >= SIDE_PANEL_MIN_WIDTH {
+            let [left, panel_area] =
+                Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                    .areas(internal_area);
+            // Table's state first, so the panel shows the task the highlight is actually on
+            self.task_table.resolve(&self.tasks);
+            if let Some(task) = self
+                .selected_index()
+                .and_then(|selected| self.tasks.get(selected))
+            {
+                TaskPanel(task, self.vs_avg(task)).render(panel_area, buf);
+            }
+            internal_area = left;
+        }
+
+        let table_height =
+            split::table_height(internal_area.height, self.tasks.len(), self.config.ui.split);
+        // If there's leftovers, logger gets it
+        let [table_area, logger_area] = split_with_min(internal_area, table_height, 0);
+        self.task_table.now = self.clock.now_instant();
+        // Render the TaskTable inside the main block's inner area
+        // Pass the task data required by the TaskTable widget's render method
+        StatefulWidget::render(
+            &mut self.task_table,
+            table_area,
+            buf,
+            &mut &self.tasks, // We don't mutate but the trait wants a mut ref
+        );
+        let menu_anchor = self
+            .task_table
+            .highlight_y(table_area)
+            .map(|y| (table_area.x.saturating_add(MENU_INDENT), y));
+
+        // Render the TuiLogger in remaining space
+        if logger_area.area() > 0 {
+            self.logs.render(" Message Stream ", logger_area, buf);
+        }
+
+        // We want to draw our modal over if we're in add state
+        // TODO: Put all this inside render() if it gets more complicated
+        if let ViewState::TaskAdd | ViewState::Duration = self.view_state {
+            let modal_area = self.picker_area(area);
+            trace!("rendering modal with {:?}", modal_area);
+            self.picker.render(modal_area, buf);
+        }
+        if let ViewState::Pool = self.view_state {
+            let modal_width = (area.width as f32 * 0.85) as u16;
+            let modal_height = self
+                .pool_editor
+                .height(self.picker.pool.len())
+                .min(area.height.saturating_sub(4));
+            let modal_area = centered_rect(area, modal_width, modal_height);
+            StatefulWidget::render(
+                &mut self.pool_editor,
+                modal_area,
+                buf,
+                &mut self.picker.pool.as_slice(),
+            );
+        }
+        if let Some(negotiation) = &mut self.negotiation {
+            let modal_width = 50.min(area.width);
+            let modal_height = Negotiation::height().min(area.height);
+            let modal_area = centered_rect(area, modal_width, modal_height);
+            negotiation.render(modal_area, buf);
+        }
+        if let Some(prompt) = &self.duration_prompt {
+            let modal_width = 60.min(area.width);
+            let modal_height = DurationPrompt::height().min(area.height);
+            let modal_area = centered_rect(area, modal_width, modal_height);
+            prompt.render(modal_area, buf);
+        }
+        if let Some(editor) = &self.note_editor {
+            let modal_width = 60.min(area.width);
+            let modal_height = NoteEditor::height().min(area.height);
+            let modal_area = centered_rect(area, modal_width, modal_height);
+            editor.render(modal_area, buf);
+        }
+        if let Some(batch) = &self.batch {
+            let modal_width = 60.min(area.width);
+            let modal_height = batch.height(modal_width).min(area.height);
+            let modal_area = centered_rect(area, modal_width, modal_height);
+            batch.render(modal_area, buf);
+        }
+        if let Some(prompt) = &self.guard_popup {
+            let modal_width = 50.min(area.width);
+            let modal_height = prompt.height(modal_width).min(area.height);
+            let modal_area = centered_rect(area, modal_width, modal_height);
+            prompt.render(modal_area, buf);
+        }
+        if let Some(menu) = &mut self.column_menu {
+            let modal_width = 44.min(area.width);
+            let modal_height = menu.height().min(area.height);
+            let modal_area = centered_rect(area, modal_width, modal_height);
+            menu.render(modal_area, buf);
+        }
+        if let Some(menu) = &mut self.menu {
+            let (width, height) = menu.size();
+            let (width, height) = (width.min(area.width), height.min(area.height));
+            // Just under the row it's for, or just over it if that would run off the bottom
+            let (x, y) = match menu_anchor {
+                Some((x, row)) if row + 1 + height <= area.bottom() => (x, row + 1),
+                Some((x, row)) => (x, row.saturating_sub(height)),
+                None => {
+                    let middle = centered_rect(area, width, height);
+                    (middle.x, middle.y)
+                }
+            };
+            let modal_area = Rect {
+                x: x.min(area.right().saturating_sub(width)),
+                y,
+                width,
+                height,
+            };
+            menu.render(modal_area, buf);
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! The architecture screen, from `a` in the help: the plumbing as it is right now. App at the
+//! top, the one mpsc channel every worker reports back on, and a box per running task with its
+//! own control channel and the last thing it said. There's no broadcast channel, telling everyone
+//! is each control channel in turn, so that's what's counted. Half teaching aid, half a way to
+//! see what's backed up. Too small for the boxes and it's a plain list instead
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    text::{Line, Span},
+    widgets::{Block, Padding, Paragraph, Widget},
+};
+use tokio::sync::mpsc;
+
+use crate::{
+    layout_util::{centered_rect, split_with_min},
+    tasks::{Id, Task},
+    theme,
+};
+
+/// A task's box, borders included. Room for what it said, when, and its channel
+pub const BOX_WIDTH: u16 = 20;
+pub const BOX_HEIGHT: u16 = 5;
+/// Columns between boxes side by side. Rows of them touch
+pub const GAP: u16 = 1;
+/// App's box and the two channel lines under it, plus a row before the tasks
+const HEADER_HEIGHT: u16 = 6;
+const APP_WIDTH: u16 = 24;
+
+/// How full a channel is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Channel {
+    pub used: usize,
+    pub capacity: usize,
+}
+
+impl Channel {
+    /// As the sending half sees it: sent and not taken off yet
+    pub fn of<T>(tx: &mpsc::Sender<T>) -> Self {
+        Self {
+            used: tx.max_capacity() - tx.capacity(),
+            capacity: tx.max_capacity(),
+        }
+    }
+
+    fn fill(&self) -> String {
+        format!("{}/{}", self.used, self.capacity)
+    }
+}
+
+/// A running task as the diagram shows it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Worker {
+    pub id: Id,
+    pub control: Channel,
+    /// Which message it last sent, and how long ago
+    pub last: Option<(&'static str, Duration)>,
+}
+
+/// Everything the screen draws, gathered up once a frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plumbing {
+    /// Workers to App, shared by all of them
+    pub inbound: Channel,
+    pub workers: Vec<Worker>,
+}
+
+impl Plumbing {
+    /// Only tasks with a worker still on the other end of their channel
+    pub fn of(tasks: &[Task], inbound: Channel, now: Instant) -> Self {
+        let workers = tasks
+            .iter()
+            .filter(|task| task.completion.is_running())
+            .map(|task| Worker {
+                id: task.id,
+                control: Channel::of(&task.control),
+                last: task
+                    .last_heard
+                    .map(|(kind, at)| (kind, now.saturating_duration_since(at))),
+            })
+            .collect();
+        Self { inbound, workers }
+    }
+
+    fn inbound_line(&self) -> String {
+        format!("▲ mpsc {}, every worker → App", self.inbound.fill())
+    }
+
+    fn outbound_line(&self) -> String {
+        match self.workers.len() {
+            1 => "▼ 1 control channel, App → its task".to_string(),
+            n => format!("▼ {n} control channels, App → one task each"),
+        }
+    }
+
+    /// The same again without any boxes, for when they won't fit
+    fn lines(&self) -> Vec<Line<'static>> {
+        let theme = theme::current();
+        let mut lines = vec![
+            Line::styled("App", theme.accent),
+            Line::from(format!("  {}", self.inbound_line())),
+            Line::from(format!("  {}", self.outbound_line())),
+        ];
+        lines.extend(self.workers.iter().map(|worker| {
+            Line::from(vec![
+                Span::raw(format!("  task {}: ", worker.id)),
+                Span::styled(last_said(worker), theme.dim),
+                Span::raw(format!(", control {}", worker.control.fill())),
+            ])
+        }));
+        lines
+    }
+}
+
+/// "Progress 3s ago"
+fn last_said(worker: &Worker) -> String {
+    match worker.last {
+        Some((kind, ago)) => format!("{kind} {}s ago", ago.as_secs()),
+        None => "nothing yet".to_string(),
+    }
+}
+
+/// Where `count` boxes go in `area`: as many across as fit, centered, filling rows top down.
+/// `None` if they don't all fit
+pub fn grid(area: Rect, count: usize) -> Option<Vec<Rect>> {
+    if count == 0 {
+        return Some(vec![]);
+    }
+    let across = ((area.width as usize + GAP as usize) / (BOX_WIDTH + GAP) as usize).min(count);
+    if across == 0 {
+        return None;
+    }
+    let rows = count.div_ceil(across);
+    if rows * BOX_HEIGHT as usize > area.height as usize {
+        return None;
+    }
+    let used = across as u16 * (BOX_WIDTH + GAP) - GAP;
+    let left = area.x + (area.width - used) / 2;
+    Some(
+        (0..count)
+            .map(|n| Rect {
+                x: left + (n % across) as u16 * (BOX_WIDTH + GAP),
+                y: area.y + (n / across) as u16 * BOX_HEIGHT,
+                width: BOX_WIDTH,
+                height: BOX_HEIGHT,
+            })
+            .collect(),
+    )
+}
+
+impl Widget for &Plumbing {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = theme::current();
+        let block = Block::bordered()
+            .title(" Architecture ")
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [header_area, tasks_area] = split_with_min(inner, HEADER_HEIGHT, 0);
+        let boxes = (header_area.height == HEADER_HEIGHT && inner.width >= APP_WIDTH)
+            .then(|| grid(tasks_area, self.workers.len()))
+            .flatten();
+        let Some(boxes) = boxes else {
+            Paragraph::new(self.lines()).render(inner, buf);
+            return;
+        };
+
+        let [app_area, channels_area] = split_with_min(header_area, 3, 0);
+        let app_area = centered_rect(app_area, APP_WIDTH, 3);
+        Paragraph::new("App")
+            .alignment(Alignment::Center)
+            .block(Block::bordered().border_style(theme.accent))
+            .render(app_area, buf);
+        let channels = vec![
+            Line::from(self.inbound_line()),
+            Line::from(self.outbound_line()),
+        ];
+        let channels_area = centered_rect(channels_area, channels_area.width, 2);
+        Paragraph::new(channels)
+            .alignment(Alignment::Center)
+            .render(channels_area, buf);
+
+        for (worker, at) in self.workers.iter().zip(boxes) {
+            let (said, ago) = match worker.last {
+                Some((kind, ago)) => (kind.to_string(), format!("{}s ago", ago.as_secs())),
+                None => ("nothing yet".to_string(), String::new()),
+            };
+            let lines = vec![
+                Line::from(said),
+                Line::styled(ago, theme.dim),
+                Line::from(format!("control {}", worker.control.fill())),
+            ];
+            Paragraph::new(lines)
+                .block(Block::bordered().title(format!(" task {} ", worker.id)))
+                .render(at, buf);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Finished tasks moved out of the way without being deleted. They're still tasks, so the report
+//! and history still have them and they keep their IDs. The table draws them under one summary
+//! row at the bottom, which opens up to show them. The summary isn't a task and can't be selected,
+//! so selection works in positions among the tasks shown (`order`) and `row`/`unrow` step over
+//! the summary to get to table rows and back
+
+use std::collections::HashSet;
+
+use crate::tasks::Id;
+
+#[derive(Debug, Clone, Default)]
+pub struct Archive {
+    ids: HashSet<Id>,
+    /// Whether the archived rows show under the summary
+    open: bool,
+}
+
+impl Archive {
+    pub fn contains(&self, id: Id) -> bool {
+        self.ids.contains(&id)
+    }
+
+    /// Moves it in, or back out
+    pub fn set(&mut self, id: Id, archived: bool) {
+        if archived {
+            self.ids.insert(id);
+        } else {
+            self.ids.remove(&id);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn toggle_open(&mut self) {
+        self.open = !self.open;
+    }
+
+    /// "▸ 7 archived tasks — press A to expand", or the other way up when it's open. `shown` is
+    /// how many the filter lets through
+    pub fn summary(&self, shown: usize, ascii: bool) -> String {
+        let (arrow, verb) = match (self.open, ascii) {
+            (false, false) => ("▸", "expand"),
+            (true, false) => ("▾", "collapse"),
+            (false, true) => (">", "expand"),
+            (true, true) => ("v", "collapse"),
+        };
+        let tasks = if shown == 1 { "task" } else { "tasks" };
+        format!("{arrow} {shown} archived {tasks} — press A to {verb}")
+    }
+}
+
+/// Table row for position `at` among the tasks shown, one further down if it's past the summary.
+/// `summary` is the position the summary row takes, if there is one
+pub fn row(at: usize, summary: Option<usize>) -> usize {
+    match summary {
+        Some(summary) if at >= summary => at + 1,
+        _ => at,
+    }
+}
+
+/// The other way round from `row`. The summary's own row comes out as the task after it
+pub fn unrow(row: usize, summary: Option<usize>) -> usize {
+    match summary {
+        Some(summary) if row > summary => row - 1,
+        _ => row,
+    }
+}
@@ -0,0 +1,25 @@
+//! A line of text that's only on screen for a little while: the warning banner, toasts and hints
+
+use std::time::{Duration, Instant};
+
+use crate::clock::Clock;
+
+#[derive(Debug)]
+pub struct Banner {
+    pub text: String,
+    until: Instant,
+}
+
+impl Banner {
+    /// Shows for `duration` from now, going by `clock`
+    pub fn new(text: String, duration: Duration, clock: &dyn Clock) -> Self {
+        Self {
+            text,
+            until: clock.now_instant() + duration,
+        }
+    }
+
+    pub fn is_expired(&self, clock: &dyn Clock) -> bool {
+        clock.now_instant() >= self.until
+    }
+}
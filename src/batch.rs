@@ -0,0 +1,257 @@
+//! A batch file of tasks to add all at once, and the popup that asks before doing it. Entries are
+//! plain candidates - no `command` here, those only ever come from the tasks file. Guards are
+//! checked as it's loaded, so the popup's own confirmation covers the ones that want asking.
+//! An entry can be `after` an earlier one, and then it waits for all of that one's copies to
+//! finish before it starts
+use std::{fs, path::Path, time::Duration};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+use serde::Deserialize;
+
+use crate::{
+    guards::{GuardAction, Guards, Verdict},
+    layout_util::saturating_height,
+    theme, CandidateTask,
+};
+
+/// Entries listed in the popup before it just says "and N more"
+const SHOWN_ENTRIES: usize = 8;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchFile {
+    #[serde(default)]
+    task: Vec<BatchEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchEntry {
+    name: String,
+    description: String,
+    delay_secs: Option<u64>,
+    count: Option<usize>,
+    /// The name of an earlier entry this one waits on
+    after: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct BatchItem {
+    pub candidate: CandidateTask,
+    pub delay: Option<Duration>,
+    pub count: usize,
+    /// Which earlier item it waits on, by index
+    pub after: Option<usize>,
+    /// What a guard had to say about it, if one matched
+    pub guard: Option<(GuardAction, String)>,
+}
+
+impl BatchItem {
+    pub fn is_blocked(&self) -> bool {
+        matches!(self.guard, Some((GuardAction::Block, _)))
+    }
+}
+
+/// What came out of reading the file, good or bad. Nothing is added until it's confirmed
+#[derive(Debug)]
+pub struct Batch {
+    file_name: String,
+    pub items: Result<Vec<BatchItem>, String>,
+}
+
+impl Batch {
+    /// Reads and checks the whole file up front. Errors are kept for the popup, not returned
+    pub fn load(path: &Path, guards: &Guards) -> Self {
+        let file_name = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |n| n.to_string_lossy().into(),
+        );
+        let items = fs::read_to_string(path)
+            .map_err(|e| format!("couldn't read {}: {e}", path.display()))
+            .and_then(|text| toml::from_str::<BatchFile>(&text).map_err(|e| e.to_string()))
+            .and_then(|file| to_items(file.task))
+            .map(|mut items: Vec<BatchItem>| {
+                for at in 0..items.len() {
+                    let item = &items[at];
+                    let guard = match guards.check(&item.candidate) {
+                        Verdict::Allow => None,
+                        Verdict::Confirm(guard) => Some((GuardAction::Confirm, guard.reason())),
+                        Verdict::Block(guard) => Some((GuardAction::Block, guard.reason())),
+                    };
+                    // Waiting on something that's never going to be there is waiting forever
+                    let upstream = item.after.map(|after| &items[after]);
+                    items[at].guard = match upstream.filter(|upstream| upstream.is_blocked()) {
+                        Some(upstream) => Some((
+                            GuardAction::Block,
+                            format!("it waits on `{}`, which won't be", upstream.candidate.name),
+                        )),
+                        None => guard,
+                    };
+                }
+                items
+            });
+        Self { file_name, items }
+    }
+
+    /// Tasks this adds once every count is multiplied out. Blocked ones don't count
+    pub fn total(&self) -> usize {
+        self.items.as_ref().map_or(0, |items| {
+            items
+                .iter()
+                .filter(|item| !item.is_blocked())
+                .map(|item| item.count)
+                .sum()
+        })
+    }
+
+    /// Height the popup needs at this width, including borders. Error messages wrap, so
+    /// every line is counted for however many rows it'll take
+    pub fn height(&self, width: u16) -> u16 {
+        let inner = width.saturating_sub(2).max(1) as usize;
+        let rows: usize = self
+            .lines()
+            .iter()
+            .map(|line| line.width().div_ceil(inner).max(1))
+            .sum();
+        saturating_height(rows + 2)
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        match &self.items {
+            Ok(items) if items.is_empty() => {
+                vec![Line::from(format!("No tasks in {}", self.file_name))]
+            }
+            Ok(items) => {
+                let mut lines = vec![
+                    Line::from(format!("{} tasks from {}", self.total(), self.file_name)).bold(),
+                    Line::from(""),
+                ];
+                let theme = theme::current();
+                for item in items.iter().take(SHOWN_ENTRIES) {
+                    let delay = match item.delay {
+                        Some(delay) => format!(", in {}s", delay.as_secs()),
+                        None => String::new(),
+                    };
+                    let after = match item.after {
+                        Some(after) => format!(", after {}", items[after].candidate.name),
+                        None => String::new(),
+                    };
+                    lines.push(Line::from(format!(
+                        "{}× {}{delay}{after}",
+                        item.count, item.candidate.name
+                    )));
+                    match &item.guard {
+                        Some((GuardAction::Confirm, reason)) => lines
+                            .push(Line::from(format!("   careful: {reason}")).style(theme.notice)),
+                        Some((GuardAction::Block, reason)) => lines.push(
+                            Line::from(format!("   won't be added: {reason}")).style(theme.alert),
+                        ),
+                        None => {}
+                    }
+                }
+                if items.len() > SHOWN_ENTRIES {
+                    lines.push(
+                        Line::from(format!("...and {} more", items.len() - SHOWN_ENTRIES))
+                            .style(theme::current().dim),
+                    );
+                }
+                lines
+            }
+            Err(e) => {
+                let mut lines = vec![
+                    Line::from(format!("{} has problems, nothing added:", self.file_name)).bold(),
+                    Line::from(""),
+                ];
+                lines.extend(e.lines().map(|line| Line::from(line.to_string())));
+                lines
+            }
+        }
+    }
+}
+
+fn to_items(entries: Vec<BatchEntry>) -> Result<Vec<BatchItem>, String> {
+    let mut items = vec![];
+    for (idx, entry) in entries.into_iter().enumerate() {
+        let item = to_item(idx, entry, &items)?;
+        items.push(item);
+    }
+    Ok(items)
+}
+
+/// Only checks what toml can't. `after` can only name something in `earlier`, so there's no
+/// going round in circles
+fn to_item(idx: usize, entry: BatchEntry, earlier: &[BatchItem]) -> Result<BatchItem, String> {
+    let problem = |what: String| format!("task {} (`{}`): {what}", idx + 1, entry.name);
+    let count = entry.count.unwrap_or(1);
+    if count == 0 {
+        return Err(problem("count must be at least 1".to_string()));
+    }
+    let after = match &entry.after {
+        None => None,
+        Some(_) if entry.delay_secs.is_some() => {
+            return Err(problem(
+                "`after` and `delay_secs` don't go together".to_string(),
+            ))
+        }
+        Some(name) => match earlier
+            .iter()
+            .rposition(|item| item.candidate.name == *name)
+        {
+            Some(at) => Some(at),
+            None => {
+                return Err(problem(format!(
+                    "`after = \"{name}\"` has to name a task earlier in the file"
+                )))
+            }
+        },
+    };
+    Ok(BatchItem {
+        candidate: CandidateTask::new(entry.name, entry.description),
+        delay: entry.delay_secs.map(Duration::from_secs),
+        count,
+        after,
+        guard: None,
+    })
+}
+
+impl Widget for &Batch {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let theme = theme::current();
+        let (controls, border) = match &self.items {
+            Ok(items) if !items.is_empty() => (
+                Line::from(vec![
+                    " Add ".into(),
+                    theme::key("<ENTER>"),
+                    " Cancel ".into(),
+                    theme::key("<ESC> "),
+                ]),
+                theme.text,
+            ),
+            // Nothing to add, so nothing to confirm
+            Ok(_) => (
+                Line::from(vec![" Close ".into(), theme::key("<ESC> ")]),
+                theme.text,
+            ),
+            Err(_) => (
+                Line::from(vec![" Close ".into(), theme::key("<ESC> ")]),
+                theme.alert,
+            ),
+        };
+        let block = Block::new()
+            .title(" Batch Import ")
+            .borders(Borders::ALL)
+            .border_style(border)
+            .title_bottom(controls.centered());
+        Paragraph::new(self.lines())
+            .wrap(Wrap { trim: false })
+            .block(block)
+            .render(area, buf);
+    }
+}
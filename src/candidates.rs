@@ -0,0 +1,141 @@
+//! What a task is made from: a name, some flavor text, and maybe a real command. The built-in pool
+//! lives with the picker, this is just the type and the tasks file that can add to it (or, once
+//! the pool's been edited in the app, replace it)
+
+use core::fmt;
+use std::{fs, path::Path};
+
+use color_eyre::eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::retry::RetryPolicy;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CandidateTask {
+    pub name: String,
+    pub description: String,
+    /// If set, this is a real shell command rather than pretend work. Only ever comes from the
+    /// tasks file - nothing typed into the app ends up here
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Disabled ones stay in the pool but the picker never offers them
+    #[serde(default = "yes", skip_serializing_if = "is_true")]
+    pub enabled: bool,
+    /// Its own retry policy, instead of the config's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+}
+
+impl CandidateTask {
+    /// Pretend work, enabled
+    pub fn new(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            command: None,
+            enabled: true,
+            retry: None,
+        }
+    }
+}
+
+/// A tasks file: `[[task]]` entries, and whether they go with the built-in ones or instead of
+/// them. Hand-written files usually want the built-ins too, saved pools already have them
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TaskFile {
+    #[serde(default = "yes")]
+    pub include_builtins: bool,
+    #[serde(default)]
+    pub task: Vec<CandidateTask>,
+}
+
+impl TaskFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// The whole pool as it is, to be read back instead of the built-ins
+    pub fn save(path: &Path, pool: &[CandidateTask]) -> Result<()> {
+        let file = TaskFile {
+            include_builtins: false,
+            task: pool.to_vec(),
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(&file)?)?;
+        Ok(())
+    }
+}
+
+/// Why a change to the pool was turned down
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PoolError {
+    /// It would leave the picker with nothing to offer
+    LastEnabled,
+    /// Names have to be unique, recent picks are matched on them
+    Duplicate(String),
+    NoName,
+}
+
+impl fmt::Display for PoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PoolError::LastEnabled => write!(f, "at least one candidate has to stay enabled"),
+            PoolError::Duplicate(name) => write!(f, "there's already a candidate called {name}"),
+            PoolError::NoName => write!(f, "candidates need a name"),
+        }
+    }
+}
+
+impl std::error::Error for PoolError {}
+
+/// Adds `ct` to the end of the pool, if it has a name nobody else does
+pub fn add(pool: &mut Vec<CandidateTask>, ct: CandidateTask) -> Result<(), PoolError> {
+    if ct.name.trim().is_empty() {
+        return Err(PoolError::NoName);
+    }
+    if pool.iter().any(|other| other.name == ct.name) {
+        return Err(PoolError::Duplicate(ct.name));
+    }
+    pool.push(ct);
+    Ok(())
+}
+
+/// Flips whether `pool[idx]` is enabled and says which it is now. Won't turn off the last one
+pub fn toggle(pool: &mut [CandidateTask], idx: usize) -> Result<bool, PoolError> {
+    if pool[idx].enabled && enabled_besides(pool, idx) == 0 {
+        return Err(PoolError::LastEnabled);
+    }
+    pool[idx].enabled = !pool[idx].enabled;
+    Ok(pool[idx].enabled)
+}
+
+/// Takes `pool[idx]` out, unless nothing enabled would be left behind
+pub fn remove(pool: &mut Vec<CandidateTask>, idx: usize) -> Result<CandidateTask, PoolError> {
+    if enabled_besides(pool, idx) == 0 {
+        return Err(PoolError::LastEnabled);
+    }
+    Ok(pool.remove(idx))
+}
+
+fn enabled_besides(pool: &[CandidateTask], idx: usize) -> usize {
+    pool.iter()
+        .enumerate()
+        .filter(|&(i, ct)| i != idx && ct.enabled)
+        .count()
+}
+
+fn yes() -> bool {
+    true
+}
+
+fn is_true(b: &bool) -> bool {
+    *b
+}
+
+impl fmt::Display for CandidateTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}): {}", self.name, self.description)
+    }
+}
@@ -0,0 +1,86 @@
+//! Tasks that wait on each other, taken as one chain so the whole pipeline can be shown at once.
+//! Links only go one way (a task says what it depends on), so finding everything a task is
+//! connected to means walking them backwards too. A link to a task that's gone is kept track of
+//! rather than followed. Dependencies come from `after` in a batch file, so a chain is a batch's
+//! worth at most
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::tasks::Id;
+
+/// Between the IDs in `#9 ⇢ #12`
+pub const ARROW: &str = "⇢";
+/// Goes before what a table row's waiting on, as in `4 ⇠#2`
+pub const WAITS: &str = "⇠";
+
+/// Everything connected to one task, either way round
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Chain {
+    /// Oldest first, the task it was found from included. Dependencies can only be on tasks
+    /// that already exist, so that's upstream first too
+    pub members: Vec<Id>,
+    /// Tasks depended on that aren't there anymore, oldest first
+    pub dangling: Vec<Id>,
+}
+
+impl Chain {
+    /// Walks out from `from` along `links`, which are each task's ID and what it depends on. Goes
+    /// round in circles at most once, if anything got linked up that way
+    pub fn find<'a>(links: impl IntoIterator<Item = (Id, &'a [Id])>, from: Id) -> Chain {
+        let upstream: HashMap<Id, &[Id]> = links.into_iter().collect();
+        let mut downstream: HashMap<Id, Vec<Id>> = HashMap::new();
+        for (&id, deps) in &upstream {
+            for &dep in deps.iter() {
+                downstream.entry(dep).or_default().push(id);
+            }
+        }
+        let mut members = BTreeSet::new();
+        let mut dangling = BTreeSet::new();
+        let mut todo = vec![from];
+        while let Some(id) = todo.pop() {
+            if !upstream.contains_key(&id) {
+                dangling.insert(id);
+                continue;
+            }
+            if !members.insert(id) {
+                continue;
+            }
+            todo.extend(upstream[&id].iter().copied());
+            todo.extend(downstream.get(&id).into_iter().flatten().copied());
+        }
+        Chain {
+            members: members.into_iter().collect(),
+            dangling: dangling.into_iter().collect(),
+        }
+    }
+
+    /// A task on its own isn't much of a chain
+    pub fn is_lone(&self) -> bool {
+        self.members.len() < 2 && self.dangling.is_empty()
+    }
+
+    /// `#9 ⇢ #12 ⇢ #15`, then whatever's gone, like `(#3 gone)`
+    pub fn describe(&self) -> String {
+        let mut text = self
+            .members
+            .iter()
+            .map(|id| format!("#{id}"))
+            .collect::<Vec<_>>()
+            .join(&format!(" {ARROW} "));
+        if !self.dangling.is_empty() {
+            let gone: Vec<String> = self.dangling.iter().map(|id| format!("#{id}")).collect();
+            text.push_str(&format!(" ({} gone)", gone.join(", ")));
+        }
+        text
+    }
+}
+
+/// What goes after a row's ID when it depends on something, like `⇠#9` or `⇠#9,#10`. Empty when
+/// it doesn't
+pub fn waits_on(deps: &[Id]) -> String {
+    if deps.is_empty() {
+        return String::new();
+    }
+    let ids: Vec<String> = deps.iter().map(|id| format!("#{id}")).collect();
+    format!("{WAITS}{}", ids.join(","))
+}
@@ -0,0 +1,69 @@
+//! Faults on purpose, for showing off what happens when workers misbehave. Everything a worker
+//! might get wrong is a chance in here, so the app can turn it all up at once and tests can turn
+//! on exactly one thing with a chance of 1. Anything injected is logged under `TARGET`, which is
+//! how it's told apart from the real bugs
+
+use rand::Rng;
+
+/// Log target for every injected fault
+pub const TARGET: &str = "clustrctrl::chaos";
+/// Injected panics always say this, so a panic hook can tell them from real ones
+pub const PANIC_MESSAGE: &str = "panicked for chaos";
+
+/// Chances are per opportunity: per run report, per work block, per stop request
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChaosConfig {
+    /// A run report is never sent
+    pub drop_report: f64,
+    /// The worker panics after a work block
+    pub panic: f64,
+    /// The worker acts like it never got a `PleaseStop`. Only ever the first one, so asking
+    /// again always works
+    pub ignore_stop: f64,
+    /// Room in the task's control channel, instead of the usual. Small enough and App's sends
+    /// start failing whenever the worker falls behind
+    pub control_capacity: Option<usize>,
+}
+
+impl Default for ChaosConfig {
+    /// No chaos at all
+    fn default() -> Self {
+        Self {
+            drop_report: 0.0,
+            panic: 0.0,
+            ignore_stop: 0.0,
+            control_capacity: None,
+        }
+    }
+}
+
+impl ChaosConfig {
+    /// What the app uses while chaos is switched on. Enough to see every kind of fault in a few
+    /// minutes, not so much that nothing ever finishes
+    pub fn demo() -> Self {
+        Self {
+            drop_report: 0.05,
+            panic: 0.03,
+            ignore_stop: 0.3,
+            control_capacity: Some(2),
+        }
+    }
+
+    pub fn is_off(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// True `chance` of the time. Anything past 0..=1 is treated as the nearest end of it
+pub fn roll(rng: &mut impl Rng, chance: f64) -> bool {
+    chance > 0.0 && rng.random_bool(chance.min(1.0))
+}
+
+/// Whether a panic's payload is one of ours
+pub fn is_injected(payload: &(dyn std::any::Any + Send)) -> bool {
+    let message = payload
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .or_else(|| payload.downcast_ref::<&str>().copied());
+    message.is_some_and(|m| m.ends_with(PANIC_MESSAGE))
+}
@@ -0,0 +1,71 @@
+//! Where the time comes from. App reads it from a `Clock` instead of asking the system, so
+//! anything that waits for time to pass (toasts, the thread watchdog, start and end stamps) can
+//! be tested with a `MockClock` that only moves when it's told to
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Wall clock time, for anything shown or written down
+    fn now_local(&self) -> DateTime<Local>;
+    /// Monotonic time, for measuring how long something took
+    fn now_instant(&self) -> Instant;
+}
+
+/// The real time, straight from the system
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_local(&self) -> DateTime<Local> {
+        Local::now()
+    }
+
+    fn now_instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Stands still until `advance`d. Clones share the one time, so a test can keep a handle on the
+/// clock it gave away
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<(DateTime<Local>, Instant)>>,
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockClock {
+    /// Starts at whenever it was made
+    pub fn new() -> Self {
+        Self {
+            now: Arc::new(Mutex::new((Local::now(), Instant::now()))),
+        }
+    }
+
+    /// Moves both times on by `by`
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().expect("nobody panics holding it");
+        now.0 += by;
+        now.1 += by;
+    }
+}
+
+impl Clock for MockClock {
+    fn now_local(&self) -> DateTime<Local> {
+        self.now.lock().expect("nobody panics holding it").0
+    }
+
+    fn now_instant(&self) -> Instant {
+        self.now.lock().expect("nobody panics holding it").1
+    }
+}
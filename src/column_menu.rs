@@ -0,0 +1,106 @@
+//! Popup for picking which table columns show and shuffling their order. Works on a copy, so
+//! nothing moves in the table until it's applied and Esc really does mean never mind
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::Line,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::{columns::Columns, layout_util::saturating_height, theme};
+
+#[derive(Debug)]
+pub struct ColumnMenu {
+    /// What the table will get on apply
+    pub draft: Columns,
+    pub state: ListState,
+}
+
+impl ColumnMenu {
+    pub fn new(columns: &Columns) -> Self {
+        Self {
+            draft: columns.clone(),
+            state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    /// Height the popup wants, including borders
+    pub fn height(&self) -> u16 {
+        saturating_height(self.draft.settings().len() + 2)
+    }
+
+    fn selected(&self) -> usize {
+        self.state.selected().unwrap_or(0)
+    }
+
+    /// Wraps list down
+    pub fn next(&mut self) {
+        let idx = (self.selected() + 1) % self.draft.settings().len();
+        self.state.select(Some(idx));
+    }
+
+    /// Wraps list up
+    pub fn previous(&mut self) {
+        let len = self.draft.settings().len();
+        self.state.select(Some((self.selected() + len - 1) % len));
+    }
+
+    /// Returns false if it's the one that has to stay
+    pub fn toggle(&mut self) -> bool {
+        self.draft.toggle(self.selected()).is_some()
+    }
+
+    /// Moves the highlighted column, and the highlight with it. These don't wrap, since jumping
+    /// from first to last would be a much bigger move than the key suggests
+    pub fn move_up(&mut self) {
+        let idx = self.draft.move_up(self.selected());
+        self.state.select(Some(idx));
+    }
+
+    pub fn move_down(&mut self) {
+        let idx = self.draft.move_down(self.selected());
+        self.state.select(Some(idx));
+    }
+}
+
+impl Widget for &mut ColumnMenu {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let theme = theme::current();
+        let items: Vec<ListItem> = self
+            .draft
+            .settings()
+            .iter()
+            .map(|setting| {
+                let mark = match (setting.visible, setting.column.hideable()) {
+                    (_, false) => "[=] ",
+                    (true, true) => "[x] ",
+                    (false, true) => "[ ] ",
+                };
+                let item = ListItem::from(format!("{mark}{}", setting.column.title()));
+                if setting.visible {
+                    item.style(theme.text)
+                } else {
+                    item.style(theme.dim)
+                }
+            })
+            .collect();
+        let controls = Line::from(vec![
+            " Show ".into(),
+            theme::key("<SPACE>"),
+            " Move ".into(),
+            theme::key("<S-J/K>"),
+            " Apply ".into(),
+            theme::key("<ENTER> "),
+        ]);
+        let block = Block::new()
+            .title(" Columns ")
+            .borders(Borders::ALL)
+            .border_style(theme.text)
+            .title_bottom(controls.centered());
+        let list = List::new(items)
+            .block(block)
+            .highlight_symbol(theme::current().highlight_symbol());
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
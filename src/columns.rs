@@ -0,0 +1,168 @@
+//! Which of the task table's columns show, and in what order. The table turns each one into cells,
+//! this is only the list and the rules for shuffling it, so the config side can be checked without
+//! drawing anything
+
+use serde::{Deserialize, Serialize};
+
+use crate::strings;
+
+/// Everything the table can show, apart from the glyph that always leads the row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Id,
+    Name,
+    Status,
+    Halt,
+    Progress,
+    Tired,
+    Cpu,
+    Eta,
+    Start,
+    End,
+    Description,
+}
+
+impl Column {
+    /// The order they come in out of the box
+    pub const ALL: [Column; 11] = [
+        Column::Id,
+        Column::Name,
+        Column::Status,
+        Column::Halt,
+        Column::Progress,
+        Column::Tired,
+        Column::Cpu,
+        Column::Eta,
+        Column::Start,
+        Column::End,
+        Column::Description,
+    ];
+
+    /// Its name in `strings`, without the group
+    fn key(self) -> &'static str {
+        match self {
+            Column::Id => "id",
+            Column::Name => "name",
+            Column::Status => "status",
+            Column::Halt => "halt",
+            Column::Progress => "progress",
+            Column::Tired => "tired",
+            Column::Cpu => "cpu",
+            Column::Eta => "eta",
+            Column::Start => "start",
+            Column::End => "end",
+            Column::Description => "description",
+        }
+    }
+
+    /// Header text
+    pub fn title(self) -> &'static str {
+        strings::get(&format!("column.{}", self.key()))
+    }
+
+    /// Header text when there's only room for a letter. `?` says what they stand for
+    pub fn letter(self) -> &'static str {
+        strings::get(&format!("column_letter.{}", self.key()))
+    }
+
+    /// The ID is how rows get talked about everywhere else, so it can move but not go
+    pub fn hideable(self) -> bool {
+        self != Column::Id
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnSetting {
+    pub column: Column,
+    #[serde(default = "yes", skip_serializing_if = "is_true")]
+    pub visible: bool,
+}
+
+/// Every column exactly once, in the order they're drawn. Whatever's read from the config is
+/// tidied into that shape: repeats dropped, missing ones (new in a later version, say) added
+/// visible on the end, and the ID shown whatever the file says
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<ColumnSetting>", into = "Vec<ColumnSetting>")]
+pub struct Columns(Vec<ColumnSetting>);
+
+impl Default for Columns {
+    fn default() -> Self {
+        Self::from(vec![])
+    }
+}
+
+impl From<Vec<ColumnSetting>> for Columns {
+    fn from(settings: Vec<ColumnSetting>) -> Self {
+        let mut tidy: Vec<ColumnSetting> = vec![];
+        for mut setting in settings {
+            if tidy.iter().any(|s| s.column == setting.column) {
+                continue;
+            }
+            setting.visible |= !setting.column.hideable();
+            tidy.push(setting);
+        }
+        for column in Column::ALL {
+            if !tidy.iter().any(|s| s.column == column) {
+                tidy.push(ColumnSetting {
+                    column,
+                    visible: true,
+                });
+            }
+        }
+        Self(tidy)
+    }
+}
+
+impl From<Columns> for Vec<ColumnSetting> {
+    fn from(columns: Columns) -> Self {
+        columns.0
+    }
+}
+
+impl Columns {
+    /// All of them, hidden ones included
+    pub fn settings(&self) -> &[ColumnSetting] {
+        &self.0
+    }
+
+    /// Just the ones to draw, in order
+    pub fn visible(&self) -> impl Iterator<Item = Column> + '_ {
+        self.0.iter().filter(|s| s.visible).map(|s| s.column)
+    }
+
+    /// Shows or hides the one at `idx`. Returns whether it's visible now, or None if it's the ID
+    /// (or there's nothing there)
+    pub fn toggle(&mut self, idx: usize) -> Option<bool> {
+        let setting = self.0.get_mut(idx).filter(|s| s.column.hideable())?;
+        setting.visible = !setting.visible;
+        Some(setting.visible)
+    }
+
+    /// Swaps the one at `idx` with the one before it, so it's drawn further left. Returns where
+    /// it ended up, which is `idx` again if it was already first
+    pub fn move_up(&mut self, idx: usize) -> usize {
+        if idx == 0 || idx >= self.0.len() {
+            return idx;
+        }
+        self.0.swap(idx, idx - 1);
+        idx - 1
+    }
+
+    /// Same as `move_up`, the other way
+    pub fn move_down(&mut self, idx: usize) -> usize {
+        if idx + 1 >= self.0.len() {
+            return idx;
+        }
+        self.0.swap(idx, idx + 1);
+        idx + 1
+    }
+}
+
+fn yes() -> bool {
+    true
+}
+
+fn is_true(b: &bool) -> bool {
+    *b
+}
@@ -0,0 +1,79 @@
+//! Terminals that get things wrong, mostly on Windows. Some take the F-keys for themselves, so
+//! `Ctrl+N`, `Ctrl+I` and `Ctrl+Q` do what F1, F2 and F3 do everywhere (and the controls say so
+//! on Windows). Some send a key again for as long as it's held, which is fine for moving the
+//! cursor and not for Enter. And the old console host draws thick borders and blinking as
+//! garbage, which the theme leaves out when it's told this is a legacy terminal
+use std::env;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+/// What's been worked out about the terminal, once, at startup
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Compat {
+    /// Put the `Ctrl` stand-ins next to F1 to F3 in the controls. They work either way
+    pub show_alt_keys: bool,
+    /// Plain borders and nothing blinking
+    pub legacy: bool,
+}
+
+impl Compat {
+    /// This platform and terminal, unless `ui.legacy_terminal` says otherwise
+    pub fn detect(configured: Option<bool>) -> Compat {
+        // Windows Terminal sets this for everything it runs. The old console host doesn't
+        let windows_terminal = env::var_os("WT_SESSION").is_some();
+        Compat::for_platform(cfg!(windows), windows_terminal, configured)
+    }
+
+    /// `detect`, with what it found handed in
+    pub fn for_platform(windows: bool, windows_terminal: bool, configured: Option<bool>) -> Compat {
+        Compat {
+            show_alt_keys: windows,
+            legacy: configured.unwrap_or(windows && !windows_terminal),
+        }
+    }
+}
+
+/// Whether a key event is worth acting on. Releases never are, and a held key only keeps going
+/// if it moves something, so holding Enter doesn't terminate task after task
+pub fn accepts(event: &KeyEvent) -> bool {
+    match event.kind {
+        KeyEventKind::Press => true,
+        KeyEventKind::Repeat => repeats(event.code),
+        KeyEventKind::Release => false,
+    }
+}
+
+/// Keys that are meant to be held down
+fn repeats(code: KeyCode) -> bool {
+    matches!(
+        code,
+        KeyCode::Up
+            | KeyCode::Down
+            | KeyCode::Left
+            | KeyCode::Right
+            | KeyCode::PageUp
+            | KeyCode::PageDown
+            | KeyCode::Home
+            | KeyCode::End
+            | KeyCode::Backspace
+            | KeyCode::Char('j' | 'k')
+    )
+}
+
+/// The F-key a `Ctrl` stand-in means, or the event as it was
+pub fn alternate(event: KeyEvent) -> KeyEvent {
+    if !event.modifiers.contains(KeyModifiers::CONTROL) {
+        return event;
+    }
+    let code = match event.code {
+        KeyCode::Char('n') => KeyCode::F(1),
+        KeyCode::Char('i') => KeyCode::F(2),
+        KeyCode::Char('q') => KeyCode::F(3),
+        _ => return event,
+    };
+    KeyEvent {
+        code,
+        modifiers: event.modifiers - KeyModifiers::CONTROL,
+        ..event
+    }
+}
@@ -0,0 +1,149 @@
+//! Who's holding a task's worker handle. There's nothing to hold before `activate`, then the
+//! handle until the worker's returned, then only what it returned. Harvesting moves it from the
+//! second to the third and awaits the handle on the way, so there's no handle to hand out and
+//! forget about, and nothing left to take a second time
+use std::{any::Any, fmt};
+
+use tokio::task::{JoinError, JoinHandle};
+use tracing::error;
+
+/// What a worker gives back: its sum, or `None` if it was stopped or the sum overflowed
+pub type Returned = Option<i128>;
+
+/// How a worker came back, kept on the task once it's harvested
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Returned(Returned),
+    /// With whatever it panicked with, if that was text
+    Panicked(String),
+    /// Cut off by `abort` (a shutdown that gave up waiting) before it could return
+    Aborted,
+}
+
+/// Why there was nothing to harvest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HarvestError {
+    /// No worker's been started
+    NotStarted,
+    /// Its worker's still at it
+    StillRunning,
+    /// Somebody got here first
+    AlreadyHarvested,
+}
+
+#[derive(Debug, Default)]
+enum State {
+    #[default]
+    Pending,
+    Harvestable(JoinHandle<Returned>),
+    Harvested {
+        result: Outcome,
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct Completion {
+    state: State,
+}
+
+impl Completion {
+    /// Takes the handle of a worker that's just been started. There's only ever one worker per
+    /// task, so a second is a bug: it's kept from taking over, and the handle's dropped (which
+    /// leaves that worker running, detached)
+    pub fn start(&mut self, handle: JoinHandle<Returned>) {
+        match self.state {
+            State::Pending => self.state = State::Harvestable(handle),
+            _ => {
+                error!("a worker was started for a task that already had one");
+                debug_assert!(false, "a worker was started twice for the same task");
+            }
+        }
+    }
+
+    /// There's a worker on the other end, finished or not, that nobody's harvested yet
+    pub fn is_running(&self) -> bool {
+        matches!(self.state, State::Harvestable(_))
+    }
+
+    /// The worker's returned (or panicked), so `harvest` won't have to wait
+    pub fn is_finished(&self) -> bool {
+        match &self.state {
+            State::Harvestable(handle) => handle.is_finished(),
+            _ => false,
+        }
+    }
+
+    /// What it came back with, once harvested
+    pub fn outcome(&self) -> Option<&Outcome> {
+        match &self.state {
+            State::Harvested { result } => Some(result),
+            _ => None,
+        }
+    }
+
+    /// Cuts the worker off, if there's one to cut off. Only does anything to async ones, a
+    /// blocking thread can't be interrupted. True if there was a handle to abort
+    pub fn abort(&self) -> bool {
+        match &self.state {
+            State::Harvestable(handle) => {
+                handle.abort();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Awaits a finished worker's handle and keeps what it came back with. A worker that isn't
+    /// finished is left alone, so this never waits on one that's still working
+    pub async fn harvest(&mut self) -> Result<&Outcome, HarvestError> {
+        match &self.state {
+            State::Pending => return Err(HarvestError::NotStarted),
+            State::Harvested { .. } => return Err(HarvestError::AlreadyHarvested),
+            State::Harvestable(handle) if !handle.is_finished() => {
+                return Err(HarvestError::StillRunning)
+            }
+            State::Harvestable(_) => {}
+        }
+        let State::Harvestable(handle) = std::mem::take(&mut self.state) else {
+            unreachable!("just matched it");
+        };
+        let result = match handle.await {
+            Ok(returned) => Outcome::Returned(returned),
+            Err(e) => Outcome::from(e),
+        };
+        self.state = State::Harvested { result };
+        Ok(self.outcome().expect("just harvested"))
+    }
+}
+
+impl From<JoinError> for Outcome {
+    fn from(e: JoinError) -> Self {
+        match e.try_into_panic() {
+            Ok(payload) => Outcome::Panicked(panic_message(payload)),
+            Err(_) => Outcome::Aborted,
+        }
+    }
+}
+
+/// `panic!` with a message leaves a `&str` or a `String`, anything else is anyone's guess
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(text) => *text,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(text) => text.to_string(),
+            Err(_) => "something that wasn't text".to_string(),
+        },
+    }
+}
+
+impl fmt::Display for HarvestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HarvestError::NotStarted => write!(f, "no worker's been started"),
+            HarvestError::StillRunning => write!(f, "its worker's still going"),
+            HarvestError::AlreadyHarvested => write!(f, "its worker's already been harvested"),
+        }
+    }
+}
+
+impl std::error::Error for HarvestError {}
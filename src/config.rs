@@ -0,0 +1,446 @@
+//! Command line flags and the (optional) config file. CLI wins over the file, the file wins over
+//! defaults. Nothing here should ever stop the app from starting - a bad config file is a warning
+
+use std::{ffi::OsString, fs, path::PathBuf};
+
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    columns::Columns,
+    demo::DemoConfig,
+    guards::{GuardConfig, Guards},
+    history::PaceConfig,
+    inbox, journal,
+    momentum::MomentumConfig,
+    retry::RetryPolicy,
+    session,
+    split::SplitMode,
+    status_server::HttpConfig,
+    tasks::{TaskStatus, MAX_INTENSITY, MIN_INTENSITY},
+    templates::TemplateConfig,
+};
+
+use crate::theme::ColorChoice;
+
+const DEFAULT_CONFIG_PATH: &str = "./clustrctrl.toml";
+
+#[derive(Debug, Parser)]
+#[command(version, about = "A 'workflow engine' that doesn't do any real work")]
+pub struct Cli {
+    /// Config file to read (and write back to, for settings changed at runtime)
+    #[arg(long, default_value = DEFAULT_CONFIG_PATH)]
+    pub config: PathBuf,
+    /// Where to write the trace log. Parent directories are created as needed
+    #[arg(long)]
+    pub log_file: Option<PathBuf>,
+    /// Don't write a log file at all, only the in-app Message Stream
+    #[arg(long)]
+    pub no_log_file: bool,
+    /// Write a JSON report of every task (and the messages we traded with it) here on exit
+    #[arg(long)]
+    pub report: Option<PathBuf>,
+    /// How long to wait for a key each pass, in milliseconds
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub poll_ms: Option<u64>,
+    /// How often animations (spinners, flashes) move along, in milliseconds
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub tick_ms: Option<u64>,
+    /// Never redraw more often than this
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_fps: Option<u32>,
+    /// Start with the table unsorted and unfiltered, and leave the saved UI state alone this run
+    #[arg(long)]
+    pub reset_ui: bool,
+    /// Color, or only bold/reversed/underline. Auto goes without if `NO_COLOR` is set or
+    /// `TERM=dumb`
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+    /// Write every key handled (and every task message) to this file as it happens, to
+    /// `--replay` later. Starts the file over
+    #[arg(long, conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+    /// Play a `--record`ing back: its keys go in at the pace they were pressed, and once they run
+    /// out (or you press one yourself) it's all yours
+    #[arg(long)]
+    pub replay: Option<PathBuf>,
+    /// How much faster than recorded the replayed keys come. Tasks still take as long as they take
+    #[arg(long, default_value_t = 1.0, requires = "replay", value_parser = positive)]
+    pub replay_speed: f64,
+    /// What to call this run, in the title and on the end of the log and report file names.
+    /// Made up (like `wobbly-otter-42`) if not given
+    #[arg(long, value_parser = session::parse)]
+    pub session: Option<String>,
+    /// A TOML file of the UI's words to use instead of the English ones, like `de.toml`. Any it
+    /// leaves out stay English
+    #[arg(long)]
+    pub strings: Option<PathBuf>,
+    /// Start even as root. ROOT stays in the title the whole time
+    #[arg(long)]
+    pub allow_root: bool,
+}
+
+impl Cli {
+    /// Recording and replaying start from the same place, not wherever the UI was last left
+    pub fn fresh_ui(&self) -> bool {
+        self.reset_ui || self.record.is_some() || self.replay.is_some()
+    }
+}
+
+fn positive(text: &str) -> Result<f64, String> {
+    match text.parse::<f64>() {
+        Ok(value) if value > 0.0 && value.is_finite() => Ok(value),
+        Ok(_) => Err("has to be more than 0".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Extra candidates (including shell commands) to offer in the picker
+    pub tasks_file: Option<PathBuf>,
+    /// Where `F7` loads a batch of tasks from. `./batch.toml` if not set
+    pub batch_file: Option<PathBuf>,
+    /// Gets a POST whenever a task starts, finishes, fails or is stopped
+    pub webhook_url: Option<String>,
+    /// Every task that ends gets a line here, and the picker says how each candidate did before
+    pub history_file: Option<PathBuf>,
+    /// Kept up to date with a JSON blob of counts, for status bars to read. Removed on exit
+    pub status_file: Option<PathBuf>,
+    /// A CSV line per task every time its progress moves, for plotting. Named for the session
+    pub progress_csv: Option<PathBuf>,
+    /// The same and more over HTTP, for dashboards
+    pub http: HttpConfig,
+    /// When a running task is shown as slower than its history says it should be
+    pub pace: PaceConfig,
+    /// When a working task's Progress goes from green to yellow, and (running, not sleeping) to red
+    pub momentum: MomentumConfig,
+    /// Failed tasks tried again this many times, backing off in between. A candidate can have
+    /// its own in the tasks file instead
+    pub retry: RetryPolicy,
+    pub log: LogConfig,
+    /// Every task made and everything that happens to it, so a crash doesn't lose them
+    pub journal: JournalConfig,
+    pub picker: PickerConfig,
+    pub ui: UiConfig,
+    pub work: WorkConfig,
+    pub timing: TimingConfig,
+    /// What F10 plays, and any macros of your own for it to
+    pub demo: DemoConfig,
+    /// Checked against every task before it's added. See `guards::Guards::check`
+    pub guards: Vec<GuardConfig>,
+    /// `guards`, compiled once on load
+    #[serde(skip)]
+    pub compiled_guards: Guards,
+    /// `[[template]]`s: `T` and a key adds a few of a candidate at once. See `templates`
+    #[serde(rename = "template")]
+    pub templates: Vec<TemplateConfig>,
+    /// Where runtime changes are saved back to. None if the file's there but wouldn't parse, so
+    /// a toggle doesn't replace what they wrote with the defaults
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    pub enabled: bool,
+    pub path: PathBuf,
+    /// Repeats of a Message Stream line within this long of the first are collapsed into a count.
+    /// The file gets all of them
+    pub collapse_window_ms: u64,
+    /// Most lines collapsed into one count. 1 collapses nothing
+    pub collapse_max: u32,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: PathBuf::from("./log"),
+            collapse_window_ms: 2000,
+            collapse_max: 100,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JournalConfig {
+    pub enabled: bool,
+    /// Started over every run. What's left in it from a run that didn't close is shown as
+    /// abandoned rows first
+    pub path: PathBuf,
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: PathBuf::from(journal::DEFAULT_PATH),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PickerConfig {
+    /// If the last chosen candidate didn't make it into a fresh sample, put it in anyway
+    pub keep_last: bool,
+    /// How long after one of a candidate's tasks finishes before it's offered again. 0 never holds
+    /// anything back
+    pub cooldown_secs: u64,
+}
+
+impl Default for PickerConfig {
+    fn default() -> Self {
+        Self {
+            keep_last: false,
+            cooldown_secs: 60,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// Highlight rows for a couple seconds after their status changes
+    pub flash: bool,
+    /// Plain ASCII for the table's glyph column, for fonts that don't have the fancy ones
+    pub ascii_glyphs: bool,
+    /// Fill in progress between reports instead of jumping a block at a time
+    pub smooth_progress: bool,
+    /// Keep a row for tasks that couldn't be started, instead of just a toast about it
+    pub keep_failed_spawns: bool,
+    /// Enter in inspect mode opens the action menu instead of terminating straight away
+    pub enter_opens_menu: bool,
+    /// Moving past the last row of the table or the picker goes back to the first
+    pub wrap_lists: bool,
+    /// Whether F12 shows the debug overlay at all. Off for demos, so nobody sees frame times
+    pub debug_overlay: bool,
+    /// Who wins when the table and the message stream both want the room. `=` changes it
+    pub split: SplitMode,
+    /// Order and visibility of the table's columns. `C` while inspecting changes them
+    pub columns: Columns,
+    /// No blinking, flashing or spinning, and toasts stay up until a key's pressed
+    pub reduce_motion: bool,
+    /// Statuses in words only, with no glyph column and nothing told apart by color alone.
+    /// Meant for screen readers
+    pub plain_output: bool,
+    /// What `]` and `[` jump between while inspecting. Anything of the same sort counts, so
+    /// Failed covers DOA too
+    pub jump_statuses: Vec<TaskStatus>,
+    /// Rows with these statuses are left out of the table from the start, like `h` does for
+    /// Finished and Canceled. They're still in the summary and the report
+    pub hidden_statuses: Vec<TaskStatus>,
+    /// No padding, a thin border, one-letter column headers and shorter controls, for small
+    /// terminals. `z` flips it
+    pub compact: bool,
+    /// Thin borders and no blinking, for consoles that draw them as garbage. Left out, it's on
+    /// in the old Windows console host and off everywhere else
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legacy_terminal: Option<bool>,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            flash: true,
+            ascii_glyphs: false,
+            smooth_progress: false,
+            keep_failed_spawns: false,
+            enter_opens_menu: false,
+            wrap_lists: true,
+            debug_overlay: true,
+            split: SplitMode::default(),
+            columns: Columns::default(),
+            reduce_motion: false,
+            plain_output: false,
+            jump_statuses: vec![
+                TaskStatus::OnStrike,
+                TaskStatus::Failed,
+                TaskStatus::KnownUnknown,
+            ],
+            hidden_statuses: vec![],
+            compact: false,
+            legacy_terminal: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkConfig {
+    /// Multiplier on how much pretend work a task does per block
+    pub intensity: f32,
+    /// Answer every strike with the full break it asked for, no negotiating
+    pub auto_grant_breaks: bool,
+    /// New tasks drop reports, panic and ignore stops now and then, for demos. `Ctrl+K` flips it
+    pub chaos: bool,
+    /// Tokio's blocking pool size. Every dummy task holds one of these threads for as long as it
+    /// runs, so this is how many can run at once. Read at startup only
+    pub max_blocking_threads: usize,
+    /// A dummy that hasn't said anything this long after starting is assumed to be queued for
+    /// one of those threads
+    pub thread_wait_secs: u64,
+    /// Pause every dummy while the terminal's out of focus, and carry on when it's back. Only
+    /// for terminals that say when that happens
+    pub pause_unfocused: bool,
+    /// Pause every dummy before suspending (Ctrl+Z), and carry on after `fg`. They're stopped
+    /// along with everything else either way, this is so they pick up where they were
+    pub pause_suspended: bool,
+    /// Dummies report progress every few seconds at most and don't trace every block, and the
+    /// UI ticks slower with nothing animated. For laptops on battery. `b` flips it
+    pub low_power: bool,
+    /// Put dummies' heartbeats in their audit trail too. They come every few seconds, so they
+    /// push everything else out
+    pub audit_heartbeats: bool,
+}
+
+impl Default for WorkConfig {
+    fn default() -> Self {
+        Self {
+            intensity: 1.0,
+            auto_grant_breaks: false,
+            chaos: false,
+            // Tokio's own default
+            max_blocking_threads: 512,
+            thread_wait_secs: 3,
+            pause_unfocused: false,
+            pause_suspended: false,
+            low_power: false,
+            audit_heartbeats: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimingConfig {
+    pub poll_ms: u64,
+    pub tick_ms: u64,
+    pub max_fps: u32,
+    /// Most worker messages handled in a frame. The rest wait for the next one
+    pub message_budget: usize,
+}
+
+impl Default for TimingConfig {
+    fn default() -> Self {
+        Self {
+            poll_ms: 50,
+            tick_ms: 200,
+            max_fps: 30,
+            message_budget: inbox::DEFAULT_BUDGET,
+        }
+    }
+}
+
+impl LogConfig {
+    /// The file settings with `--log-file`/`--no-log-file` applied
+    pub fn with_overrides(&self, cli: &Cli) -> Self {
+        Self {
+            enabled: self.enabled && !cli.no_log_file,
+            path: cli.log_file.clone().unwrap_or_else(|| self.path.clone()),
+            ..*self
+        }
+    }
+}
+
+impl TimingConfig {
+    /// The file settings with `--poll-ms`/`--tick-ms`/`--max-fps` applied
+    pub fn with_overrides(&self, cli: &Cli) -> Self {
+        Self {
+            poll_ms: cli.poll_ms.unwrap_or(self.poll_ms),
+            tick_ms: cli.tick_ms.unwrap_or(self.tick_ms),
+            max_fps: cli.max_fps.unwrap_or(self.max_fps),
+            message_budget: self.message_budget,
+        }
+    }
+
+    /// Zeroes would mean spinning flat out (or dividing by zero), so they're bumped to 1
+    fn fix_zeroes(&mut self, problems: &mut Vec<String>) {
+        for (name, value) in [
+            ("poll_ms", &mut self.poll_ms),
+            ("tick_ms", &mut self.tick_ms),
+        ] {
+            if *value == 0 {
+                problems.push(format!("timing.{name} can't be 0, using 1"));
+                *value = 1;
+            }
+        }
+        if self.max_fps == 0 {
+            problems.push("timing.max_fps can't be 0, using 1".to_string());
+            self.max_fps = 1;
+        }
+        if self.message_budget == 0 {
+            problems.push("timing.message_budget can't be 0, using 1".to_string());
+            self.message_budget = 1;
+        }
+    }
+}
+
+impl Config {
+    /// Reads the config file (if any). Returns the config plus any complaints we had, since
+    /// tracing isn't set up yet when this runs. CLI overrides are applied by whoever needs them,
+    /// so they don't get saved back into the file
+    pub fn load(cli: &Cli) -> (Self, Vec<String>) {
+        let mut problems = vec![];
+        let saved = Some(cli.config.clone());
+        let (mut config, path) = match fs::read_to_string(&cli.config) {
+            Ok(text) => match toml::from_str::<Config>(&text) {
+                Ok(config) => (config, saved),
+                Err(e) => {
+                    problems.push(format!(
+                        "config file {} is invalid, using defaults and leaving it alone: {e}",
+                        cli.config.display()
+                    ));
+                    (Config::default(), None)
+                }
+            },
+            // No file is the normal case, don't complain about it
+            Err(_) => (Config::default(), saved),
+        };
+        config.path = path;
+        let intensity = config.work.intensity;
+        if !(MIN_INTENSITY..=MAX_INTENSITY).contains(&intensity) {
+            problems.push(format!(
+                "work.intensity {intensity} outside {MIN_INTENSITY}..={MAX_INTENSITY}, clamping"
+            ));
+            // NaN survives clamp, so it gets the default instead
+            config.work.intensity = if intensity.is_nan() {
+                WorkConfig::default().intensity
+            } else {
+                intensity.clamp(MIN_INTENSITY, MAX_INTENSITY)
+            };
+        }
+        if config.work.max_blocking_threads == 0 {
+            problems.push("work.max_blocking_threads can't be 0, using 1".to_string());
+            config.work.max_blocking_threads = 1;
+        }
+        config.timing.fix_zeroes(&mut problems);
+        config.compiled_guards = Guards::compile(&config.guards, &mut problems);
+        problems.extend(config.demo.problems());
+        (config, problems)
+    }
+
+    /// Writes the current settings back to where they came from, by way of a temp file so a
+    /// crash halfway doesn't leave half a config. Nowhere to (see `path`) and it's skipped, the
+    /// load already said why. Failure is just logged
+    pub fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let text = match toml::to_string_pretty(self) {
+            Ok(text) => text,
+            Err(e) => return warn!("couldn't serialize config: {e}"),
+        };
+        let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+        name.push(".tmp");
+        let temp = path.with_file_name(name);
+        if let Err(e) = fs::write(&temp, text).and_then(|()| fs::rename(&temp, path)) {
+            warn!("couldn't save config to {}: {e}", path.display());
+        }
+    }
+}
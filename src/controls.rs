@@ -0,0 +1,207 @@
+//! What the controls line along the bottom offers. Mostly it's down to the screen, but while
+//! inspecting it also depends on the highlighted task, since there's no point offering to stop
+//! something that's already finished. Just labels and keys here, the app does the styling
+
+use crate::{strings, tasks::TaskStatus, view::ViewState};
+
+/// Put on the end when some controls don't fit
+pub const ELLIPSIS: &str = "…";
+
+/// One "Label <KEY>" on the line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Control {
+    pub label: &'static str,
+    pub key: &'static str,
+}
+
+impl Control {
+    /// The label's first word, for when there's no room for the rest
+    pub fn short(&self) -> &'static str {
+        self.label.split(' ').next().unwrap_or(self.label)
+    }
+}
+
+/// Settings and state, other than the screen and the task, that change what's on offer
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Toggles {
+    /// `ui.enter_opens_menu`, so Enter is the menu and not a terminate
+    pub enter_opens_menu: bool,
+    /// A filter's hiding rows, so Esc clears it before it goes back
+    pub filtering: bool,
+    /// The highlighted task is archived, so all it offers is a look and a way back out
+    pub archived: bool,
+    /// The highlight's on a group header, and whether that group's folded. Folding's all a
+    /// header does
+    pub header: Option<bool>,
+    /// Something's been added this session, so `n` can add another
+    pub repeat: bool,
+    /// There are templates for `T` to add from
+    pub templates: bool,
+    /// Say which `Ctrl` key stands in for F1 to F3, for terminals that keep those (see `compat`)
+    pub alt_keys: bool,
+}
+
+/// `name` is the label's key in `strings`, under `control.`
+fn c(name: &str, key: &'static str) -> Control {
+    Control {
+        label: strings::get(&format!("control.{name}")),
+        key,
+    }
+}
+
+fn quit() -> Control {
+    c("quit", "F3")
+}
+
+/// Everything that should be on the line, in order. `selected` is the highlighted task's status,
+/// and only matters while inspecting
+pub fn controls(view: ViewState, selected: Option<TaskStatus>, toggles: Toggles) -> Vec<Control> {
+    let mut controls = offered(view, selected, toggles);
+    if toggles.alt_keys {
+        for control in &mut controls {
+            control.key = with_alt(control.key);
+        }
+    }
+    controls
+}
+
+/// F1 to F3 with their stand-ins
+fn with_alt(key: &'static str) -> &'static str {
+    match key {
+        "F1" => "F1/^N",
+        "F2" => "F2/^I",
+        "F3" => "F3/^Q",
+        _ => key,
+    }
+}
+
+fn offered(view: ViewState, selected: Option<TaskStatus>, toggles: Toggles) -> Vec<Control> {
+    match view {
+        ViewState::Monitor => {
+            let mut controls = vec![c("new_task", "F1")];
+            if toggles.repeat {
+                controls.push(c("again", "N"));
+            }
+            if toggles.templates {
+                controls.push(c("templates", "T"));
+            }
+            controls.extend([
+                c("manage_tasks", "F2"),
+                c("batch", "F7"),
+                c("timeline", "F8"),
+                c("minimize", "F9"),
+                c("logs", "F"),
+                c("intensity", "</>"),
+                c("sort", "S"),
+                c("help", "?"),
+                quit(),
+            ]);
+            controls
+        }
+        ViewState::Batch => vec![c("add", "ENTER"), c("cancel", "ESC"), quit()],
+        ViewState::Guard => vec![c("add_anyway", "ENTER"), c("cancel", "ESC"), quit()],
+        ViewState::TaskAdd => vec![
+            c("back", "ESC"),
+            c("set_duration", "TAB"),
+            c("edit_pool", "E"),
+            quit(),
+        ],
+        ViewState::Duration => vec![c("start", "ENTER"), c("back", "ESC"), quit()],
+        ViewState::Inspect => inspect(selected, toggles),
+        ViewState::Filter => vec![c("keep", "ENTER"), c("clear", "ESC"), quit()],
+        ViewState::Columns => vec![c("cancel", "ESC"), c("choose", "J/K"), quit()],
+        ViewState::Pool => vec![c("choose", "J/K"), quit()],
+        ViewState::Note => vec![
+            c("save", "C-S"),
+            c("new_line", "ENTER"),
+            c("cancel", "ESC"),
+            quit(),
+        ],
+        ViewState::Menu => vec![
+            c("back", "ESC"),
+            c("choose", "J/K"),
+            c("do", "ENTER"),
+            quit(),
+        ],
+        ViewState::Negotiate => vec![c("back", "ESC"), c("choose", "J/K"), quit()],
+        ViewState::Logs => vec![
+            c("back", "ESC"),
+            c("scroll", "PGUP/PGDN"),
+            c("follow", "END"),
+            quit(),
+        ],
+        ViewState::Detail | ViewState::Timeline => {
+            vec![c("back", "ESC"), c("scroll", "J/K"), quit()]
+        }
+        ViewState::Architecture => vec![c("back", "ESC"), quit()],
+    }
+}
+
+fn inspect(selected: Option<TaskStatus>, toggles: Toggles) -> Vec<Control> {
+    let mut controls = vec![if toggles.filtering {
+        c("clear_filter", "ESC")
+    } else {
+        c("back", "ESC")
+    }];
+    if let Some(folded) = toggles.header {
+        controls.push(match folded {
+            true => c("unfold", "ENTER"),
+            false => c("fold", "ENTER"),
+        });
+        controls.extend([c("sort", "S"), c("filter", "/"), quit()]);
+        return controls;
+    }
+    if toggles.archived {
+        controls.extend([c("details", "D"), c("unarchive", "A"), c("remove", "DEL")]);
+        controls.extend([c("sort", "S"), c("filter", "/"), quit()]);
+        return controls;
+    }
+    if toggles.enter_opens_menu {
+        controls.push(c("actions", "ENTER"));
+    } else if selected.is_some_and(|status| status.is_stoppable()) {
+        controls.push(c("terminate_task", "ENTER"));
+    }
+    controls.extend([c("menu", "M"), c("details", "D"), c("expand", "O")]);
+    if selected == Some(TaskStatus::OnStrike) {
+        controls.push(c("negotiate", "N"));
+    }
+    controls.push(c("note", "S-N"));
+    if selected.is_some_and(|status| status.is_terminal()) {
+        controls.extend([c("archive", "A"), c("remove", "DEL")]);
+    }
+    controls.extend([c("sort", "S"), c("filter", "/"), quit()]);
+    controls
+}
+
+/// Columns the line takes as drawn: " Label <KEY>" for each, plus a space at the end
+pub fn width(controls: &[Control]) -> usize {
+    let each: usize = controls
+        .iter()
+        .map(|c| c.label.chars().count() + c.key.chars().count() + 4)
+        .sum();
+    each + 1
+}
+
+/// Columns the compact line takes: " KEY Short" for each, plus a space at the end
+pub fn terse_width(controls: &[Control]) -> usize {
+    let each: usize = controls
+        .iter()
+        .map(|c| c.short().chars().count() + c.key.chars().count() + 2)
+        .sum();
+    each + 1
+}
+
+/// As many controls as fit in `room` columns, dropping from the end. True if any were dropped, in
+/// which case there's room left for " …" after the ones kept. `terse` measures them the compact
+/// way
+pub fn fit(mut controls: Vec<Control>, room: usize, terse: bool) -> (Vec<Control>, bool) {
+    let width = if terse { terse_width } else { width };
+    if width(&controls) <= room {
+        return (controls, false);
+    }
+    let ellipsis = ELLIPSIS.chars().count() + 1;
+    while !controls.is_empty() && width(&controls) + ellipsis > room {
+        controls.pop();
+    }
+    (controls, true)
+}
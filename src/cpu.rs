@@ -0,0 +1,95 @@
+//! How much CPU each dummy's worker has burned. Workers read their own thread's CPU clock around
+//! each block of work and send the running total with their progress reports. Where there's no
+//! such clock the wall time of the work is summed instead, which overcounts whenever the worker
+//! had to wait for a core, so those are marked as estimates. Shell tasks run in a child process
+//! nobody measures, so they don't have any
+
+use std::{fmt, time::Duration};
+
+/// CPU time used by the calling thread so far. None if the platform can't say
+#[cfg(unix)]
+pub fn thread_time() -> Option<Duration> {
+    let mut now = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // Safe: it only writes to `now`, which lives long enough
+    let ok = unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut now) } == 0;
+    ok.then(|| Duration::new(now.tv_sec as u64, now.tv_nsec as u32))
+}
+
+#[cfg(not(unix))]
+pub fn thread_time() -> Option<Duration> {
+    None
+}
+
+/// A worker's total so far, as sent in its progress reports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CpuTime {
+    pub ms: u64,
+    /// Summed from how long the work took, not read off a CPU clock
+    pub estimated: bool,
+}
+
+/// `12.4s`, or `~12.4s` for an estimate
+impl fmt::Display for CpuTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tilde = if self.estimated { "~" } else { "" };
+        write!(f, "{tilde}{:.1}s", self.ms as f64 / 1000.0)
+    }
+}
+
+/// Keeps a worker's total between blocks. Doesn't read any clocks itself, so it can be fed
+/// made-up samples
+#[derive(Debug, Clone, Default)]
+pub struct CpuMeter {
+    /// The thread's CPU clock when the first block started. It counts from whenever the thread
+    /// did, and blocking threads get reused
+    baseline: Option<Duration>,
+    /// The work's wall time, summed, for when the clock's no good
+    wall: Duration,
+    /// The clock's last reading, so it can never go backwards
+    last: Duration,
+}
+
+impl CpuMeter {
+    /// `sample` is the thread's CPU clock before any work, if it has one
+    pub fn new(sample: Option<Duration>) -> Self {
+        Self {
+            baseline: sample,
+            ..Self::default()
+        }
+    }
+
+    /// A block of work that took `took` by the wall clock just finished, and the CPU clock reads
+    /// `sample` now. The total so far, from the clock if it's been readable every time
+    pub fn record(&mut self, sample: Option<Duration>, took: Duration) -> CpuTime {
+        self.wall += took;
+        let used = self.baseline.zip(sample).map(|(start, now)| {
+            self.last = self.last.max(now.saturating_sub(start));
+            self.last
+        });
+        if used.is_none() {
+            // Lost the clock partway, so nothing it said before can be trusted to add up
+            self.baseline = None;
+        }
+        match used {
+            Some(used) => CpuTime {
+                ms: used.as_millis() as u64,
+                estimated: false,
+            },
+            None => CpuTime {
+                ms: self.wall.as_millis() as u64,
+                estimated: true,
+            },
+        }
+    }
+}
+
+/// All of them added up, an estimate if any of them were. None if none of them had any
+pub fn total(times: impl IntoIterator<Item = CpuTime>) -> Option<CpuTime> {
+    times.into_iter().reduce(|sum, time| CpuTime {
+        ms: sum.ms + time.ms,
+        estimated: sum.estimated || time.estimated,
+    })
+}
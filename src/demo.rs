@@ -0,0 +1,154 @@
+//! F10's demo macros: a list of keys put through `App::handle_key_event` one at a time, slowly
+//! enough for an audience to follow along. There's one built in, and the config file can add
+//! more as key names (the same ones `--record` writes). Pressing any key yourself stops it
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use serde::{Deserialize, Serialize};
+
+use crate::recording;
+
+/// What `DemoConfig::play` is when it isn't set
+pub const BUILTIN_NAME: &str = "demo";
+
+/// Picks three at random, a little apart, then cancels the second. Starts from the main screen
+const BUILTIN: [&str; 13] = [
+    "f1", "r", "pause", "f1", "r", "pause", "f1", "r", "pause", "f2", "down", "m", "enter",
+];
+
+/// Holds things up for another step instead of pressing anything
+const PAUSE: &str = "pause";
+
+/// The key that starts and stops them, so it can't be in one
+pub const KEY: KeyCode = KeyCode::F(10);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DemoConfig {
+    /// Which macro F10 plays. One in `macros` with the built-in's name goes instead of it
+    pub play: String,
+    /// Between one key and the next
+    pub step_ms: u64,
+    pub macros: Vec<MacroConfig>,
+}
+
+impl Default for DemoConfig {
+    fn default() -> Self {
+        Self {
+            play: BUILTIN_NAME.to_string(),
+            step_ms: 300,
+            macros: vec![],
+        }
+    }
+}
+
+/// A macro of your own, like `keys = ["f1", "enter", "pause", "f2"]`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MacroConfig {
+    pub name: String,
+    pub keys: Vec<String>,
+}
+
+impl DemoConfig {
+    /// The keys of the macro called `play`, each with how long to wait before it
+    pub fn steps(&self) -> Result<Vec<(Duration, KeyEvent)>, String> {
+        match self.macros.iter().find(|custom| custom.name == self.play) {
+            Some(custom) => custom.compile(self.step()),
+            None if self.play == BUILTIN_NAME => compile(&BUILTIN, self.step()),
+            None => Err(format!("there's no demo macro called `{}`", self.play)),
+        }
+    }
+
+    /// Everything wrong with the macros in the file, for the startup warnings
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems: Vec<String> = self
+            .macros
+            .iter()
+            .filter_map(|custom| custom.compile(self.step()).err())
+            .collect();
+        let known =
+            self.play == BUILTIN_NAME || self.macros.iter().any(|custom| custom.name == self.play);
+        if !known {
+            problems.push(format!("there's no demo macro called `{}`", self.play));
+        }
+        problems
+    }
+
+    fn step(&self) -> Duration {
+        Duration::from_millis(self.step_ms)
+    }
+}
+
+impl MacroConfig {
+    fn compile(&self, step: Duration) -> Result<Vec<(Duration, KeyEvent)>, String> {
+        compile(&self.keys, step).map_err(|e| format!("demo macro `{}` {e}", self.name))
+    }
+}
+
+/// Key names into keys, `pause`s folded into the wait before the next one
+pub fn compile(
+    names: &[impl AsRef<str>],
+    step: Duration,
+) -> Result<Vec<(Duration, KeyEvent)>, String> {
+    let mut steps = vec![];
+    let mut wait = Duration::ZERO;
+    for name in names {
+        let name = name.as_ref();
+        wait += step;
+        if name == PAUSE {
+            continue;
+        }
+        let key = recording::parse_key(name)
+            .ok_or_else(|| format!("has a key we don't know: `{name}`"))?;
+        if key.code == KEY {
+            return Err("can't press F10, that's what stops it".to_string());
+        }
+        steps.push((wait, key));
+        wait = Duration::ZERO;
+    }
+    if steps.is_empty() {
+        return Err("has no keys to press".to_string());
+    }
+    Ok(steps)
+}
+
+/// A macro partway through. Each key's wait starts from when the one before went in
+#[derive(Debug)]
+pub struct Player {
+    steps: VecDeque<(Duration, KeyEvent)>,
+    /// When the last key went in, or when we started
+    last: Instant,
+}
+
+impl Player {
+    pub fn new(steps: Vec<(Duration, KeyEvent)>, now: Instant) -> Self {
+        Self {
+            steps: steps.into(),
+            last: now,
+        }
+    }
+
+    /// When the next key's meant to go in
+    pub fn next_at(&self) -> Option<Instant> {
+        let &(wait, _) = self.steps.front()?;
+        Some(self.last + wait)
+    }
+
+    /// The next key, if it's time for it. Only one per call, however far behind we are, so
+    /// every key still gets its moment on screen
+    pub fn due(&mut self, now: Instant) -> Option<KeyEvent> {
+        if self.next_at()? > now {
+            return None;
+        }
+        self.last = now;
+        self.steps.pop_front().map(|(_, key)| key)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
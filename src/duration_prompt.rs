@@ -0,0 +1,118 @@
+//! Popup for choosing how long a dummy should take, opened with Tab from the picker instead of
+//! Enter. Holds the candidate until it's answered, same as the guard popup
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::{tasks::TARGET_SECS, theme, CandidateTask};
+
+/// What's filled in to start with. About as long as an average roll
+const DEFAULT_SECS: u64 = 15;
+/// More digits than this can't be in range anyway
+const MAX_DIGITS: usize = 4;
+
+#[derive(Debug)]
+pub struct DurationPrompt {
+    pub candidate: CandidateTask,
+    /// Whatever's been typed, digits only. Left/Right write over it
+    text: String,
+    /// Why the last Enter didn't take, until the next edit
+    error: Option<String>,
+}
+
+impl DurationPrompt {
+    pub fn new(candidate: CandidateTask) -> Self {
+        Self {
+            candidate,
+            text: DEFAULT_SECS.to_string(),
+            error: None,
+        }
+    }
+
+    /// Height the modal needs, including borders
+    pub fn height() -> u16 {
+        4
+    }
+
+    /// Anything but a digit is ignored
+    pub fn push(&mut self, ch: char) {
+        if ch.is_ascii_digit() && self.text.len() < MAX_DIGITS {
+            self.text.push(ch);
+        }
+        self.error = None;
+    }
+
+    pub fn pop(&mut self) {
+        self.text.pop();
+        self.error = None;
+    }
+
+    /// Nudges it by `by` seconds, kept in range. Starts from the default if what's typed is
+    /// nonsense
+    pub fn step(&mut self, by: i64) {
+        let now = self.text.parse::<u64>().unwrap_or(DEFAULT_SECS);
+        let stepped = now.saturating_add_signed(by);
+        self.text = stepped
+            .clamp(*TARGET_SECS.start(), *TARGET_SECS.end())
+            .to_string();
+        self.error = None;
+    }
+
+    /// The seconds asked for, if they're any good. If not, says why on the popup
+    pub fn value(&mut self) -> Option<u64> {
+        match self.text.parse::<u64>() {
+            Ok(secs) if TARGET_SECS.contains(&secs) => return Some(secs),
+            Ok(_) => {
+                self.error = Some(format!(
+                    "Has to be {} to {} seconds",
+                    TARGET_SECS.start(),
+                    TARGET_SECS.end()
+                ))
+            }
+            Err(_) => self.error = Some("Type a number of seconds".to_string()),
+        }
+        None
+    }
+}
+
+impl Widget for &DurationPrompt {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let theme = theme::current();
+        let controls = Line::from(vec![
+            " Start ".into(),
+            theme::key("<ENTER>"),
+            " Nudge ".into(),
+            theme::key("<←/→>"),
+            " Back ".into(),
+            theme::key("<ESC> "),
+        ]);
+        let block = Block::new()
+            .title(format!(" How long should {} take? ", self.candidate.name))
+            .borders(Borders::ALL)
+            .title_bottom(controls.centered());
+        let hint = match &self.error {
+            Some(error) => Span::styled(error.as_str(), theme.alert),
+            None => Span::styled(
+                format!(
+                    "{} to {} seconds of sleep, work blocks on top",
+                    TARGET_SECS.start(),
+                    TARGET_SECS.end()
+                ),
+                theme.dim,
+            ),
+        };
+        let lines = vec![
+            Line::from(vec![
+                Span::raw(format!(" {}", self.text)),
+                Span::styled("▏", theme.accent),
+                " sec".into(),
+            ]),
+            Line::from(vec![" ".into(), hint]),
+        ];
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
@@ -0,0 +1,122 @@
+//! Guesses how long a task has left from how fast its progress has been moving. The workers don't
+//! say - they rolled a random total and keep it to themselves - so that's usually all we've got.
+//! When the total was chosen up front, progress says exactly how much of it is left instead
+
+use std::{collections::VecDeque, time::Duration, time::Instant};
+
+/// Progress reports kept for the rate fit
+const SAMPLES: usize = 10;
+/// Past this we stop pretending to know
+pub const ETA_CAP: Duration = Duration::from_secs(10 * 60);
+/// How much of each new estimate makes it into the displayed one. Lower is calmer
+const SMOOTHING: f64 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Eta {
+    /// No progress yet, so no rate to go on
+    Unknown,
+    /// Best guess at what's left
+    Remaining(Duration),
+    /// Longer than `ETA_CAP`, or not moving at all
+    Stalled,
+}
+
+#[derive(Debug, Default)]
+pub struct EtaEstimator {
+    /// The very first report, for when the recent window has nothing to say
+    origin: Option<(Instant, u8)>,
+    samples: VecDeque<(Instant, u8)>,
+    /// Seconds, smoothed across updates so the column doesn't flicker
+    smoothed: Option<f64>,
+    /// The whole sleep, if it was chosen rather than rolled
+    target: Option<Duration>,
+}
+
+impl EtaEstimator {
+    /// Progress counts sleep done out of `total`, so from here on that's what's left goes by
+    pub fn set_target(&mut self, total: Duration) {
+        self.target = Some(total);
+    }
+
+    pub fn record(&mut self, at: Instant, progress: u8) {
+        self.origin.get_or_insert((at, progress));
+        if self.samples.len() >= SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((at, progress));
+    }
+
+    /// Folds the current raw estimate into the smoothed one. Call once per update, not per frame
+    pub fn update(&mut self, now: Instant) {
+        match self.raw_secs(now) {
+            Some(raw) => {
+                let smoothed = self
+                    .smoothed
+                    .map_or(raw, |old| old + SMOOTHING * (raw - old));
+                self.smoothed = Some(smoothed);
+            }
+            None => self.smoothed = None,
+        }
+    }
+
+    pub fn eta(&self) -> Eta {
+        match self.smoothed {
+            None => Eta::Unknown,
+            Some(secs) if secs >= ETA_CAP.as_secs_f64() => Eta::Stalled,
+            Some(secs) => Eta::Remaining(Duration::from_secs_f64(secs.max(0.0))),
+        }
+    }
+
+    /// Remaining seconds from progress per second. The rate runs up to `now` rather than the last
+    /// report, so a stall makes the estimate grow instead of freezing at its old value. If the
+    /// recent window made no progress at all we fall back to the rate since the first report
+    fn raw_secs(&self, now: Instant) -> Option<f64> {
+        let &(latest_at, latest) = self.samples.back()?;
+        if let Some(total) = self.target {
+            // Counts down between reports. Strikes and work blocks don't, so it runs a bit short
+            let left = total.as_secs_f64() * (100 - latest.min(100)) as f64 / 100.0
+                - now.saturating_duration_since(latest_at).as_secs_f64();
+            return Some(left.clamp(0.0, ETA_CAP.as_secs_f64()));
+        }
+        let &(window_start, window_progress) = self.samples.front()?;
+        let (start, start_progress) = if latest > window_progress {
+            (window_start, window_progress)
+        } else {
+            self.origin?
+        };
+        let gained = latest.saturating_sub(start_progress) as f64;
+        let elapsed = now.saturating_duration_since(start).as_secs_f64();
+        if gained == 0.0 {
+            // Never moved. If we've been at it a while that's a stall, otherwise we just don't know
+            return (latest > 0).then_some(ETA_CAP.as_secs_f64());
+        }
+        if elapsed == 0.0 {
+            return None;
+        }
+        let rate = gained / elapsed;
+        let remaining = (100.0 - latest as f64) / rate;
+        Some(remaining.min(ETA_CAP.as_secs_f64()))
+    }
+}
+
+impl Eta {
+    /// Sort key, soonest first, with the ones we know nothing about at the back
+    pub fn sort_key(&self) -> (u8, Duration) {
+        match self {
+            Eta::Remaining(left) => (0, *left),
+            Eta::Stalled => (1, Duration::ZERO),
+            Eta::Unknown => (2, Duration::ZERO),
+        }
+    }
+}
+
+impl std::fmt::Display for Eta {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Eta::Unknown => write!(f, "?"),
+            Eta::Stalled => write!(f, ">10m"),
+            Eta::Remaining(left) if left.as_secs() < 60 => write!(f, "~{}s", left.as_secs()),
+            Eta::Remaining(left) => write!(f, "~{}m", left.as_secs().div_ceil(60)),
+        }
+    }
+}
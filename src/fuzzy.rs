@@ -0,0 +1,57 @@
+//! fzf-ish subsequence matching for the table filter. No scoring, no crate: either every character
+//! of the query shows up in order or it doesn't
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+
+use crate::theme;
+
+/// Char positions in `text` that matched, or None if it doesn't. Case doesn't matter, and an empty
+/// query matches everything with nothing to highlight
+pub fn fuzzy_match(query: &str, text: &str) -> Option<Vec<usize>> {
+    let mut positions = Vec::new();
+    let mut wanted = query.chars().flat_map(char::to_lowercase).peekable();
+    for (pos, ch) in text.chars().enumerate() {
+        let Some(&next) = wanted.peek() else {
+            break;
+        };
+        // Good enough for the names we've got, multi-char lowercases just won't match
+        if ch.to_lowercase().eq(std::iter::once(next)) {
+            positions.push(pos);
+            wanted.next();
+        }
+    }
+    wanted.peek().is_none().then_some(positions)
+}
+
+/// `text` with the matched characters picked out, everything else left to the row style
+pub fn highlight(text: &str, positions: &[usize]) -> Line<'static> {
+    if positions.is_empty() {
+        return Line::from(text.to_string());
+    }
+    let matched = theme::current().matched;
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (pos, ch) in text.chars().enumerate() {
+        let is_match = positions.contains(&pos);
+        if is_match != run_matched && !run.is_empty() {
+            spans.push(styled(std::mem::take(&mut run), run_matched, matched));
+        }
+        run_matched = is_match;
+        run.push(ch);
+    }
+    if !run.is_empty() {
+        spans.push(styled(run, run_matched, matched));
+    }
+    Line::from(spans)
+}
+
+fn styled(run: String, is_match: bool, style: Style) -> Span<'static> {
+    if is_match {
+        Span::styled(run, style)
+    } else {
+        Span::raw(run)
+    }
+}
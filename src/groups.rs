@@ -0,0 +1,85 @@
+//! Sorted by status, the table splits into a group per sort of status with a header row over
+//! each, like `▾ Working (4)`. Headers aren't tasks, but Enter on one folds the group away (or
+//! back out), so the highlight lands on them as well as on tasks. What's folded is kept by status
+//! rather than by row, so it stays folded however many tasks come and go
+
+use std::collections::HashSet;
+
+use crate::tasks::StatusClass;
+
+/// Groups top to bottom: whatever's going on first, the ways things end up last
+pub const ORDER: [StatusClass; 7] = [
+    StatusClass::Working,
+    StatusClass::Striking,
+    StatusClass::Unknown,
+    StatusClass::Waiting,
+    StatusClass::Failed,
+    StatusClass::Cancelled,
+    StatusClass::Done,
+];
+
+/// A row the highlight can be on, once the groups are in. `Task` is a position among the tasks
+/// it was made from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entry {
+    Task(usize),
+    Header(StatusClass),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Groups {
+    folded: HashSet<StatusClass>,
+}
+
+impl Groups {
+    pub fn is_folded(&self, class: StatusClass) -> bool {
+        self.folded.contains(&class)
+    }
+
+    /// Folds it, or opens it back up. True if it's folded now
+    pub fn toggle(&mut self, class: StatusClass) -> bool {
+        if !self.folded.remove(&class) {
+            self.folded.insert(class);
+        }
+        self.is_folded(class)
+    }
+
+    /// Everything back out
+    pub fn unfold_all(&mut self) {
+        self.folded.clear();
+    }
+
+    /// Headers and tasks for `classes`, each task's class in the order they're shown (already
+    /// sorted by `rank`, so each class is in one piece). Folded groups are just their header
+    pub fn entries(&self, classes: &[StatusClass]) -> Vec<Entry> {
+        let mut entries = Vec::with_capacity(classes.len() + ORDER.len());
+        for (at, &class) in classes.iter().enumerate() {
+            if at == 0 || classes[at - 1] != class {
+                entries.push(Entry::Header(class));
+            }
+            if !self.is_folded(class) {
+                entries.push(Entry::Task(at));
+            }
+        }
+        entries
+    }
+
+    /// "▾ Working (4)", or "▸ Done (7)" folded. `count` is how many the filter lets through
+    pub fn header(&self, class: StatusClass, count: usize, ascii: bool) -> String {
+        let arrow = match (self.is_folded(class), ascii) {
+            (false, false) => "▾",
+            (true, false) => "▸",
+            (false, true) => "v",
+            (true, true) => ">",
+        };
+        format!("{arrow} {class} ({count})")
+    }
+}
+
+/// Where `class` goes in `ORDER`, for sorting by
+pub fn rank(class: StatusClass) -> usize {
+    ORDER
+        .iter()
+        .position(|&c| c == class)
+        .unwrap_or(ORDER.len())
+}
@@ -0,0 +1,73 @@
+//! Popup asking whether to add a task anyway, after a `confirm` guard matched it. Holds the
+//! candidate until it's answered, since nothing's been made from it yet
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::{guards::Guard, layout_util::saturating_height, theme, CandidateTask};
+
+#[derive(Debug)]
+pub struct GuardPopup {
+    pub candidate: CandidateTask,
+    /// Carried through for `add_task`, if a duration was chosen before the guard spoke up
+    pub target_secs: Option<u64>,
+    reason: String,
+    pattern: String,
+}
+
+impl GuardPopup {
+    pub fn new(candidate: CandidateTask, guard: &Guard, target_secs: Option<u64>) -> Self {
+        Self {
+            candidate,
+            target_secs,
+            reason: guard.reason(),
+            pattern: guard.pattern().to_string(),
+        }
+    }
+
+    /// Height the popup needs at this width, including borders. The reason can be long, so it
+    /// wraps
+    pub fn height(&self, width: u16) -> u16 {
+        let inner = width.saturating_sub(2).max(1) as usize;
+        let rows: usize = self
+            .lines()
+            .iter()
+            .map(|line| line.width().div_ceil(inner).max(1))
+            .sum();
+        saturating_height(rows + 2)
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from(self.candidate.name.clone()).bold(),
+            Line::from(""),
+            Line::from(self.reason.clone()),
+            Line::from(format!("(guard `{}`)", self.pattern)).style(theme::current().dim),
+        ]
+    }
+}
+
+impl Widget for &GuardPopup {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let controls = Line::from(vec![
+            " Add Anyway ".into(),
+            theme::key("<ENTER>"),
+            " Cancel ".into(),
+            theme::key("<ESC> "),
+        ]);
+        let block = Block::new()
+            .title(" Are you sure? ")
+            .borders(Borders::ALL)
+            .border_style(theme::current().notice)
+            .title_bottom(controls.centered());
+        Paragraph::new(self.lines())
+            .wrap(Wrap { trim: false })
+            .block(block)
+            .render(area, buf);
+    }
+}
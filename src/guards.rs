@@ -0,0 +1,125 @@
+//! Patterns from the config that a new task's name and description are checked against before
+//! it's added, so a candidate known to go badly gets a second look (or doesn't get added at
+//! all). Every way of adding a task asks `Guards::check`, so they can't disagree
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::CandidateTask;
+
+/// What happens to a task that matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GuardAction {
+    /// Ask first, with the guard's message
+    Confirm,
+    /// Don't add it, and say why
+    Block,
+}
+
+/// One `[[guards]]` entry, as written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuardConfig {
+    pub pattern: String,
+    pub action: GuardAction,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub message: String,
+}
+
+/// A guard with its pattern compiled
+#[derive(Debug, Clone)]
+pub struct Guard {
+    pattern: Regex,
+    pub action: GuardAction,
+    message: String,
+}
+
+/// All of them, in the order the config had them
+#[derive(Debug, Clone, Default)]
+pub struct Guards(Vec<Guard>);
+
+/// What a guard thinks of a task
+#[derive(Debug, Clone, Copy)]
+pub enum Verdict<'a> {
+    Allow,
+    Confirm(&'a Guard),
+    Block(&'a Guard),
+}
+
+impl Guard {
+    pub fn pattern(&self) -> &str {
+        self.pattern.as_str()
+    }
+
+    /// The message, or which pattern it was if there isn't one
+    pub fn reason(&self) -> String {
+        if self.message.is_empty() {
+            format!("matches `{}`", self.pattern())
+        } else {
+            self.message.clone()
+        }
+    }
+
+    fn matches(&self, ct: &CandidateTask) -> bool {
+        self.pattern.is_match(&ct.name) || self.pattern.is_match(&ct.description)
+    }
+}
+
+impl Guards {
+    /// Compiles every pattern, ignoring case. One that won't compile is left out with a complaint
+    /// of its own, the rest still work
+    pub fn compile(configs: &[GuardConfig], problems: &mut Vec<String>) -> Self {
+        let guards = configs
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, config)| {
+                match RegexBuilder::new(&config.pattern)
+                    .case_insensitive(true)
+                    .build()
+                {
+                    Ok(pattern) => Some(Guard {
+                        pattern,
+                        action: config.action,
+                        message: config.message.clone(),
+                    }),
+                    Err(e) => {
+                        // Syntax errors draw a little diagram first, the last line's the point
+                        let e = e.to_string();
+                        problems.push(format!(
+                            "guard {} (`{}`) left out, its pattern doesn't compile: {}",
+                            idx + 1,
+                            config.pattern,
+                            e.lines().last().unwrap_or_default()
+                        ));
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self(guards)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether `ct` can be added. A block anywhere wins over any number of confirms, otherwise
+    /// it's the first guard that matched
+    pub fn check(&self, ct: &CandidateTask) -> Verdict<'_> {
+        let mut verdict = Verdict::Allow;
+        for guard in self.0.iter().filter(|guard| guard.matches(ct)) {
+            match guard.action {
+                GuardAction::Block => return Verdict::Block(guard),
+                GuardAction::Confirm if matches!(verdict, Verdict::Allow) => {
+                    verdict = Verdict::Confirm(guard)
+                }
+                GuardAction::Confirm => {}
+            }
+        }
+        verdict
+    }
+}
@@ -0,0 +1,92 @@
+//! Everything startup noticed that isn't quite right, in one list: a log file that can't be
+//! written, config fields put back to defaults, no color, a small thread pool and so on. Each
+//! step that sets something up adds to it (and logs it) instead of only logging, and anything
+//! Warning or worse gets the list shown before the main screen. It stays in the help after
+use tracing::{error, info, warn};
+
+/// Fewer blocking threads than this and the dummies will be queueing for them soon enough
+pub const SMALL_BLOCKING_POOL: usize = 4;
+
+/// Worst last, so they sort
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Worth knowing, nothing to fix
+    Info,
+    /// Something's been worked around, but probably not how you wanted
+    Warning,
+    /// Something's not going to work this run
+    Error,
+}
+
+impl Severity {
+    /// Its word in `strings`
+    pub fn key(self) -> &'static str {
+        match self {
+            Severity::Info => "health.info",
+            Severity::Warning => "health.warning",
+            Severity::Error => "health.error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthItem {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// The list as it's gathered, in the order things were found
+#[derive(Debug, Clone, Default)]
+pub struct Health {
+    items: Vec<HealthItem>,
+}
+
+impl Health {
+    /// Adds and logs it at the level it deserves
+    pub fn add(&mut self, severity: Severity, message: impl Into<String>) {
+        let message = message.into();
+        match severity {
+            Severity::Info => info!("{message}"),
+            Severity::Warning => warn!("{message}"),
+            Severity::Error => error!("{message}"),
+        }
+        self.items.push(HealthItem { severity, message });
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.add(Severity::Info, message);
+    }
+
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.add(Severity::Warning, message);
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.add(Severity::Error, message);
+    }
+
+    /// Another list's items after this one's
+    pub fn merge(&mut self, other: Health) {
+        self.items.extend(other.items);
+    }
+
+    /// Worst first, and in the order they were found within a severity
+    pub fn sorted(&self) -> Vec<&HealthItem> {
+        let mut items: Vec<&HealthItem> = self.items.iter().collect();
+        items.sort_by_key(|item| std::cmp::Reverse(item.severity));
+        items
+    }
+
+    /// Whether it's worth stopping to show them before the main screen
+    pub fn needs_attention(&self) -> bool {
+        self.worst().is_some_and(|worst| worst >= Severity::Warning)
+    }
+
+    pub fn worst(&self) -> Option<Severity> {
+        self.items.iter().map(|item| item.severity).max()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
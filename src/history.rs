@@ -0,0 +1,239 @@
+//! How tasks went in earlier sessions, one JSON line per task that ended, so the picker can say
+//! how a candidate usually does (and a running task can be held up against that). Lines are only
+//! ever appended, and ones that don't parse are skipped instead of losing the whole file
+
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+};
+
+use color_eyre::eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::tasks::{Task, TaskStatus};
+
+/// One task that ended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    /// As shown, so possibly with a " (2)" on the end
+    pub name: String,
+    /// Which candidate it came from. Older lines might not have it, see `candidate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidate: Option<String>,
+    pub status: TaskStatus,
+    /// Start to end, if it ever started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secs: Option<u64>,
+    /// RFC 3339, for anyone reading the file themselves
+    #[serde(default)]
+    pub ended: String,
+    /// Which run it was in. The file's shared between sessions, so this is how to pick one out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+}
+
+/// What a candidate's history comes down to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CandidateStats {
+    pub runs: usize,
+    /// Failed, panicked or never started
+    pub failures: usize,
+    /// Only runs that have a duration count towards the average
+    timed: usize,
+    total_secs: u64,
+}
+
+/// When a run starts looking slow, in multiples of its candidate's average
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaceConfig {
+    pub slow: f64,
+    pub very_slow: f64,
+}
+
+impl Default for PaceConfig {
+    fn default() -> Self {
+        Self {
+            slow: 1.5,
+            very_slow: 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pace {
+    Usual,
+    /// Past `PaceConfig::slow`
+    Slow,
+    /// Past `PaceConfig::very_slow`
+    VerySlow,
+}
+
+/// A run so far next to how long its candidate usually takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Comparison {
+    pub elapsed_secs: u64,
+    pub avg_secs: u64,
+    pub pace: Pace,
+}
+
+impl Record {
+    /// The candidate this is history for. Lines without one go by the name, minus any copy number
+    pub fn candidate(&self) -> &str {
+        self.candidate
+            .as_deref()
+            .unwrap_or_else(|| base_name(&self.name))
+    }
+}
+
+impl From<&Task> for Record {
+    fn from(task: &Task) -> Self {
+        let end = task.end.unwrap_or_else(chrono::Local::now);
+        Self {
+            name: task.name.clone(),
+            candidate: Some(task.candidate.clone()),
+            status: task.status,
+            secs: task
+                .start
+                .map(|start| (end - start).num_seconds().max(0) as u64),
+            ended: end.to_rfc3339(),
+            session: None,
+        }
+    }
+}
+
+impl CandidateStats {
+    pub fn add(&mut self, record: &Record) {
+        self.runs += 1;
+        if matches!(
+            record.status,
+            TaskStatus::Failed | TaskStatus::FailedToStart
+        ) {
+            self.failures += 1;
+        }
+        if let Some(secs) = record.secs {
+            self.timed += 1;
+            self.total_secs += secs;
+        }
+    }
+
+    /// Average run in whole seconds, if any had a duration
+    pub fn avg_secs(&self) -> Option<u64> {
+        (self.timed > 0).then(|| self.total_secs / self.timed as u64)
+    }
+}
+
+impl fmt::Display for CandidateStats {
+    /// "run 4 times, avg 38s, 1 failure"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.runs {
+            0 => return write!(f, "never run"),
+            1 => write!(f, "run once")?,
+            runs => write!(f, "run {runs} times")?,
+        }
+        if let Some(avg) = self.avg_secs() {
+            write!(f, ", avg {avg}s")?;
+        }
+        match self.failures {
+            0 => Ok(()),
+            1 => write!(f, ", 1 failure"),
+            failures => write!(f, ", {failures} failures"),
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    /// "38s elapsed vs 31s avg"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}s elapsed vs {}s avg",
+            self.elapsed_secs, self.avg_secs
+        )
+    }
+}
+
+/// How `elapsed_secs` measures up to `stats`. Nothing without a timed run to compare against.
+/// Averages are whole seconds, so a 0s one was really under a second and counts as one
+pub fn compare(
+    elapsed_secs: u64,
+    stats: Option<&CandidateStats>,
+    pace: &PaceConfig,
+) -> Option<Comparison> {
+    let avg_secs = stats?.avg_secs()?;
+    let ratio = elapsed_secs as f64 / avg_secs.max(1) as f64;
+    let pace = if ratio > pace.very_slow {
+        Pace::VerySlow
+    } else if ratio > pace.slow {
+        Pace::Slow
+    } else {
+        Pace::Usual
+    };
+    Some(Comparison {
+        elapsed_secs,
+        avg_secs,
+        pace,
+    })
+}
+
+/// `name` without the " (2)" that copies after the first get
+pub fn base_name(name: &str) -> &str {
+    let Some((base, copy)) = name
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once(" ("))
+    else {
+        return name;
+    };
+    if !copy.is_empty() && copy.chars().all(|c| c.is_ascii_digit()) {
+        base
+    } else {
+        name
+    }
+}
+
+/// Everything per candidate, keyed by `Record::candidate`
+pub fn aggregate<'a>(
+    records: impl IntoIterator<Item = &'a Record>,
+) -> HashMap<String, CandidateStats> {
+    let mut stats: HashMap<String, CandidateStats> = HashMap::new();
+    for record in records {
+        stats
+            .entry(record.candidate().to_string())
+            .or_default()
+            .add(record);
+    }
+    stats
+}
+
+/// What the picker says about a candidate
+pub fn describe(stats: Option<&CandidateStats>) -> String {
+    stats.copied().unwrap_or_default().to_string()
+}
+
+/// Every line in `path` that parses, and how many didn't. No file is no history, not an error
+pub fn load(path: &Path) -> Result<(Vec<Record>, usize)> {
+    if !path.exists() {
+        return Ok((vec![], 0));
+    }
+    let text = fs::read_to_string(path)?;
+    let mut bad = 0;
+    let records = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).inspect_err(|_| bad += 1).ok())
+        .collect();
+    Ok((records, bad))
+}
+
+/// Adds `record` to the end of `path`, making it (and its directory) if needed
+pub fn append(path: &Path, record: &Record) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
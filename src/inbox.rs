@@ -0,0 +1,97 @@
+//! Messages from the workers that have been taken off the channel but not handled yet. Each
+//! frame handles `timing.message_budget` of them at most, so a storm (lots of tasks at once, or
+//! a backlog after being suspended) can't hold up keys and drawing, and the rest wait for the
+//! next frame. When there's more than that, anything that says how a task ended goes first,
+//! ahead of the progress reports and output that only say how it's going. It only takes a
+//! channel's worth more than a frame handles, so a storm that outruns the frames fills the
+//! channel again and the workers wait on it, instead of this growing without end
+use std::collections::VecDeque;
+
+use crate::{
+    latency::Stamped,
+    tasks::{Id, TaskTxMsg},
+};
+
+/// How many messages a frame handles unless `timing.message_budget` says
+pub const DEFAULT_BUDGET: usize = 500;
+
+/// How many messages the workers' channel holds before they have to wait
+pub const CHANNEL_CAPACITY: usize = 100;
+
+/// Whether `msg` gets handled ahead of the others when they don't all fit
+pub fn is_urgent(msg: &TaskTxMsg) -> bool {
+    match msg {
+        TaskTxMsg::StatusChange { .. }
+        | TaskTxMsg::CancelReport(_)
+        | TaskTxMsg::Fault { .. }
+        | TaskTxMsg::Resignation { .. }
+        | TaskTxMsg::ExitReport { .. } => true,
+        TaskTxMsg::Progress { .. }
+        | TaskTxMsg::Output { .. }
+        | TaskTxMsg::Milestone { .. }
+        | TaskTxMsg::Heartbeat(_) => false,
+    }
+}
+
+/// In the order they were received
+#[derive(Debug, Default)]
+pub struct Inbox {
+    waiting: VecDeque<Stamped<TaskTxMsg>>,
+}
+
+impl Inbox {
+    pub fn push(&mut self, stamped: Stamped<TaskTxMsg>) {
+        self.waiting.push_back(stamped);
+    }
+
+    /// Up to `budget` of them, in the order they came. Everything if it fits, otherwise the
+    /// urgent ones first and the oldest of the rest to fill up. What's left keeps its order
+    pub fn take(&mut self, budget: usize) -> Vec<Stamped<TaskTxMsg>> {
+        if self.waiting.len() <= budget {
+            return self.waiting.drain(..).collect();
+        }
+        let urgent = self
+            .waiting
+            .iter()
+            .filter(|stamped| is_urgent(&stamped.msg))
+            .count()
+            .min(budget);
+        let mut routine = budget - urgent;
+        let mut urgent = urgent;
+        let mut taken = vec![];
+        let mut left = VecDeque::new();
+        for stamped in self.waiting.drain(..) {
+            let slot = match is_urgent(&stamped.msg) {
+                true => &mut urgent,
+                false => &mut routine,
+            };
+            if *slot > 0 {
+                *slot -= 1;
+                taken.push(stamped);
+            } else {
+                left.push_back(stamped);
+            }
+        }
+        self.waiting = left;
+        taken
+    }
+
+    /// How many more it takes off the channel, with `budget` handled a frame
+    pub fn room(&self, budget: usize) -> usize {
+        (budget + CHANNEL_CAPACITY).saturating_sub(self.waiting.len())
+    }
+
+    /// Deferred to a later frame
+    pub fn len(&self) -> usize {
+        self.waiting.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.waiting.is_empty()
+    }
+
+    /// Whether anything from task `id` is still waiting, so it's not done being heard from
+    pub fn holds(&self, id: Id) -> bool {
+        self.waiting.iter().any(|stamped| stamped.msg.id() == id)
+    }
+}
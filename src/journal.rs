@@ -0,0 +1,272 @@
+//! The journal: a line of JSON for every task made, every status it goes through and every stop
+//! asked for, so a session that dies without closing still leaves a record of what it had going.
+//! Started over every run. Buffered and flushed every second or so, except a task ending and the
+//! session closing, which go to disk straight away. Next time round, a journal with no `closed`
+//! at the end means the last session never got that far, and `recover` says what it had
+//!
+//! A crash can cut the last line off halfway, so lines that don't parse are skipped rather than
+//! taken as a reason to give up on the rest
+
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    tasks::{CancelState, Id, Task, TaskStatus},
+    CandidateTask,
+};
+
+pub const DEFAULT_PATH: &str = "./clustrctrl-journal.log";
+
+/// Lines sit in the buffer at most about this long, unless they're about something ending
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// One line of the journal. Times are RFC 3339, like the report's
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Entry {
+    Opened {
+        session: Option<String>,
+        at: String,
+    },
+    Created {
+        id: Id,
+        candidate: String,
+        copy: usize,
+        description: String,
+        at: String,
+    },
+    Status {
+        id: Id,
+        status: TaskStatus,
+        progress: u8,
+        at: String,
+    },
+    CancelRequested {
+        id: Id,
+        at: String,
+    },
+    /// Only ever the last line, and only if the session got to the end of `run`
+    Closed {
+        at: String,
+    },
+}
+
+impl Entry {
+    /// Worth waiting on the disk for
+    fn is_final(&self) -> bool {
+        match self {
+            Entry::Status { status, .. } => status.is_terminal(),
+            Entry::Closed { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    out: BufWriter<File>,
+    flushed: Instant,
+}
+
+impl Journal {
+    /// Starts `path` over with an `opened` line
+    pub fn create(
+        path: &Path,
+        session: Option<&str>,
+        at: DateTime<Local>,
+        now: Instant,
+    ) -> io::Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let mut journal = Self {
+            path: path.to_path_buf(),
+            out: BufWriter::new(File::create(path)?),
+            flushed: now,
+        };
+        journal.write(&Entry::Opened {
+            session: session.map(str::to_string),
+            at: at.to_rfc3339(),
+        })?;
+        Ok(journal)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Into the buffer, or all the way to the disk if it's something ending
+    pub fn write(&mut self, entry: &Entry) -> io::Result<()> {
+        serde_json::to_writer(&mut self.out, entry)?;
+        writeln!(self.out)?;
+        if entry.is_final() {
+            self.out.flush()?;
+            self.out.get_ref().sync_data()?;
+        }
+        Ok(())
+    }
+
+    /// Flushes if the last one was `FLUSH_INTERVAL` ago. True if it did
+    pub fn tick(&mut self, now: Instant) -> io::Result<bool> {
+        if now.saturating_duration_since(self.flushed) < FLUSH_INTERVAL {
+            return Ok(false);
+        }
+        self.flushed = now;
+        self.out.flush()?;
+        Ok(true)
+    }
+
+    /// The `closed` line, which is what makes it a clean shutdown
+    pub fn close(mut self, at: DateTime<Local>) -> io::Result<()> {
+        self.write(&Entry::Closed {
+            at: at.to_rfc3339(),
+        })
+    }
+}
+
+impl Drop for Journal {
+    /// Everything so far, but no `closed`: dropping it without `close` is the unclean way out
+    fn drop(&mut self) {
+        if let Err(e) = self.out.flush() {
+            warn!("couldn't finish {}: {e}", self.path.display());
+        }
+    }
+}
+
+/// A task as the journal last had it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recovered {
+    pub id: Id,
+    pub candidate: String,
+    pub copy: usize,
+    pub description: String,
+    pub status: TaskStatus,
+    pub progress: u8,
+    pub cancel_requested: bool,
+    pub created: Option<DateTime<Local>>,
+    /// The last the journal heard of it
+    pub last: Option<DateTime<Local>>,
+}
+
+impl Recovered {
+    /// A row for it as task `id`, for the archive. Anything that hadn't ended was abandoned with
+    /// the session, and says what it was doing at the time
+    pub fn task(&self, id: Id) -> Task {
+        let ct = CandidateTask::new(self.candidate.clone(), self.description.clone());
+        let mut task = Task::pending(&ct, id, self.copy);
+        task.progress = self.progress;
+        task.start = self.created;
+        task.end = self.last;
+        if self.status.is_terminal() {
+            task.status = self.status;
+        } else {
+            task.status = TaskStatus::Canceled;
+            task.cancel = CancelState::Abandoned;
+            task.progress_at_cancel = Some(self.progress);
+            task.cancel_reason = Some(format!(
+                "abandoned when the last session ended without closing ({:?}, {}%{})",
+                self.status,
+                self.progress,
+                if self.cancel_requested {
+                    ", already asked to stop"
+                } else {
+                    ""
+                }
+            ));
+        }
+        task
+    }
+}
+
+/// What a session that never closed left behind
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unclean {
+    pub session: Option<String>,
+    pub tasks: Vec<Recovered>,
+    /// Lines that didn't parse. A crash mid-line leaves one at the end
+    pub skipped: usize,
+}
+
+/// What a journal's text says about how its session ended. `None` if it closed cleanly, or
+/// there's no sign it ever opened
+pub fn read(text: &str) -> Option<Unclean> {
+    let mut unclean = Unclean {
+        session: None,
+        tasks: vec![],
+        skipped: 0,
+    };
+    let mut opened = false;
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let Ok(entry) = serde_json::from_str::<Entry>(line) else {
+            unclean.skipped += 1;
+            continue;
+        };
+        match entry {
+            Entry::Opened { session, .. } => {
+                opened = true;
+                unclean.session = session;
+            }
+            Entry::Created {
+                id,
+                candidate,
+                copy,
+                description,
+                at,
+            } => unclean.tasks.push(Recovered {
+                id,
+                candidate,
+                copy,
+                description,
+                status: TaskStatus::Scheduled,
+                progress: 0,
+                cancel_requested: false,
+                created: when(&at),
+                last: when(&at),
+            }),
+            Entry::Status {
+                id,
+                status,
+                progress,
+                at,
+            } => {
+                if let Some(task) = unclean.tasks.iter_mut().find(|task| task.id == id) {
+                    task.status = status;
+                    task.progress = progress;
+                    task.last = when(&at);
+                }
+            }
+            Entry::CancelRequested { id, at } => {
+                if let Some(task) = unclean.tasks.iter_mut().find(|task| task.id == id) {
+                    task.cancel_requested = true;
+                    task.last = when(&at);
+                }
+            }
+            Entry::Closed { .. } => return None,
+        }
+    }
+    opened.then_some(unclean)
+}
+
+fn when(at: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(at)
+        .ok()
+        .map(|at| at.with_timezone(&Local))
+}
+
+/// `read` for the journal at `path`. No journal at all is a clean start
+pub fn recover(path: &Path) -> io::Result<Option<Unclean>> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(read(&String::from_utf8_lossy(&bytes))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,80 @@
+//! How long messages from tasks sit in the channel before `App::update` gets round to them.
+//! The loop only looks between polls, so this is mostly the poll interval showing through, and
+//! the number to beat for anything that wakes up on messages instead
+
+use std::time::{Duration, Instant};
+
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+/// A message with when it went into the channel
+#[derive(Debug, Clone)]
+pub struct Stamped<T> {
+    pub msg: T,
+    pub sent: Instant,
+}
+
+impl<T> Stamped<T> {
+    /// Stamps it now, so do this right at the send
+    pub fn new(msg: T) -> Self {
+        Self {
+            msg,
+            sent: Instant::now(),
+        }
+    }
+
+    /// How long ago it was sent, as of `now`
+    pub fn age(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.sent)
+    }
+}
+
+/// Upper ends of every bucket but the last, which takes everything from a second on
+const BOUNDS: [Duration; 4] = [
+    Duration::from_millis(10),
+    Duration::from_millis(50),
+    Duration::from_millis(200),
+    Duration::from_secs(1),
+];
+
+pub const LABELS: [&str; 5] = ["<10ms", "<50ms", "<200ms", "<1s", "≥1s"];
+
+/// Counts of delivery times, in the buckets in `LABELS`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Histogram {
+    counts: [u64; 5],
+}
+
+impl Histogram {
+    pub fn record(&mut self, latency: Duration) {
+        let bucket = BOUNDS
+            .iter()
+            .position(|&bound| latency < bound)
+            .unwrap_or(BOUNDS.len());
+        self.counts[bucket] += 1;
+    }
+
+    /// One count per label, in the same order
+    pub fn counts(&self) -> [u64; 5] {
+        self.counts
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Label and count for each bucket, quickest first
+    pub fn buckets(&self) -> impl Iterator<Item = (&'static str, u64)> + '_ {
+        LABELS.into_iter().zip(self.counts)
+    }
+}
+
+/// `{"<10ms": 120, "<50ms": 3, ...}`, in bucket order rather than sorted
+impl Serialize for Histogram {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(LABELS.len()))?;
+        for (label, count) in self.buckets() {
+            map.serialize_entry(label, &count)?;
+        }
+        map.end()
+    }
+}
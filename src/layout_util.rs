@@ -0,0 +1,39 @@
+//! `Rect` arithmetic that can't go wrong on a tiny terminal. Everything here stays inside the
+//! parent it's given and never overflows or underflows, so a 0x0 screen just gets empty rects
+//! instead of a panic. Dividing the screen up with `Layout` is already safe, this is for the
+//! places that work out a rect by hand
+use ratatui::layout::Rect;
+
+/// `width` by `height` in the middle of `parent`, cut down to fit
+pub fn centered_rect(parent: Rect, width: u16, height: u16) -> Rect {
+    let (width, height) = (width.min(parent.width), height.min(parent.height));
+    Rect {
+        x: parent.x.saturating_add((parent.width - width) / 2),
+        y: parent.y.saturating_add((parent.height - height) / 2),
+        width,
+        height,
+    }
+}
+
+/// `parent` cut across into a top `primary_min` rows high and a bottom with the rest. The bottom
+/// keeps `secondary_min` of them first if there isn't room for both, and anything spare goes to
+/// it too
+pub fn split_with_min(parent: Rect, primary_min: u16, secondary_min: u16) -> [Rect; 2] {
+    let secondary_min = secondary_min.min(parent.height);
+    let primary = primary_min.min(parent.height - secondary_min);
+    let top = Rect {
+        height: primary,
+        ..parent
+    };
+    let bottom = Rect {
+        y: parent.y.saturating_add(primary),
+        height: parent.height - primary,
+        ..parent
+    };
+    [top, bottom]
+}
+
+/// `rows` as a height, stopping at the tallest there is instead of wrapping round
+pub fn saturating_height(rows: usize) -> u16 {
+    rows.min(u16::MAX as usize) as u16
+}
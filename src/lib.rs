@@ -0,0 +1,79 @@
+//! Everything but `main`. Underneath, the task machinery: spawning workers, the messages they
+//! trade with whoever spawned them, and what gets tracked about each one, none of which needs a
+//! terminal. Over that, the app that shows it all, which only needs one to be launched. It draws
+//! into a plain buffer like any widget, so screens can be checked against a `TestBackend`
+
+pub mod action_menu;
+pub mod app;
+pub mod architecture;
+pub mod archive;
+pub mod banner;
+pub mod batch;
+pub mod candidates;
+pub mod chain;
+pub mod chaos;
+pub mod clock;
+pub mod column_menu;
+pub mod columns;
+pub mod compat;
+pub mod completion;
+pub mod config;
+pub mod controls;
+pub mod cpu;
+pub mod demo;
+pub mod duration_prompt;
+pub mod eta;
+pub mod fuzzy;
+pub mod groups;
+pub mod guard_popup;
+pub mod guards;
+pub mod health;
+pub mod history;
+pub mod inbox;
+pub mod journal;
+pub mod latency;
+pub mod layout_util;
+pub mod log_dedup;
+pub mod log_file;
+pub mod log_pane;
+pub mod momentum;
+pub mod nav;
+pub mod negotiation;
+pub mod note_editor;
+pub mod pool_editor;
+pub mod preflight;
+pub mod progress_csv;
+pub mod recording;
+pub mod removals;
+pub mod report;
+pub mod retry;
+pub mod session;
+pub mod shutdown_summary;
+pub mod smooth;
+pub mod split;
+pub mod stats;
+pub mod status_file;
+pub mod status_server;
+pub mod strings;
+pub mod summary;
+pub mod suspend;
+pub mod tally;
+pub mod task_detail;
+pub mod task_picker;
+pub mod task_table;
+pub mod tasks;
+pub mod templates;
+pub mod theme;
+pub mod timeline;
+pub mod timing;
+pub mod tombstones;
+pub mod tour;
+pub mod ui_state;
+pub mod view;
+pub mod view_spec;
+pub mod visibility;
+pub mod webhook;
+pub mod wiring;
+pub mod wrap;
+
+pub use candidates::CandidateTask;
@@ -0,0 +1,244 @@
+//! Runs of the same line in the message stream, collapsed so a chatty worker doesn't bury
+//! everything else. tui-logger can't change a line once it has it, so the newest run is held
+//! back here and the pane draws it at the bottom itself, like `sent a progress report ×23` with
+//! the count going up in place. Once the run's over (something else comes along, its window's up
+//! or it's hit the cap) tui-logger gets it as the one line it ended up as, stamped then rather
+//! than when it started. Only the widget goes through here, the log file gets every line
+use std::{
+    fmt::{self, Write},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+
+use tracing::{
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, Layer};
+
+/// Between the text and the count
+pub const TIMES: &str = "×";
+
+/// One line as the widget would show it. Lines are only the same if all of it is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub target: String,
+    pub level: Level,
+    pub text: String,
+}
+
+#[derive(Debug)]
+struct Run {
+    line: Line,
+    /// The first one included
+    count: u32,
+    started: Instant,
+}
+
+impl Run {
+    /// The one line it comes to, with the count if there's more than one
+    fn shown(&self) -> Line {
+        match self.count {
+            1 => self.line.clone(),
+            count => Line {
+                text: format!("{} {TIMES}{count}", self.line.text),
+                ..self.line.clone()
+            },
+        }
+    }
+}
+
+/// Which lines get through to the widget, and when. Doesn't know about tracing or the clock, so
+/// it can be driven by hand
+#[derive(Debug)]
+pub struct Collapser {
+    window: Duration,
+    max: u32,
+    run: Option<Run>,
+}
+
+impl Collapser {
+    /// Repeats within `window` of the first are counted, up to `max` in a run. A `max` of 1 (or
+    /// 0) collapses nothing
+    pub fn new(window: Duration, max: u32) -> Self {
+        Self {
+            window,
+            max,
+            run: None,
+        }
+    }
+
+    /// `line` came in at `now`. Whatever's done with and can go to the widget for good, oldest
+    /// first: nothing if it's a repeat, otherwise the run before (if there was one). This one's
+    /// held as the start of a run, unless runs are off
+    pub fn push(&mut self, line: Line, now: Instant) -> Vec<Line> {
+        if let Some(run) = &mut self.run {
+            let open = now.saturating_duration_since(run.started) < self.window;
+            if run.line == line && open && run.count < self.max {
+                run.count += 1;
+                return vec![];
+            }
+        }
+        let mut done: Vec<Line> = self.close().into_iter().collect();
+        match self.max > 1 {
+            true => {
+                self.run = Some(Run {
+                    line,
+                    count: 1,
+                    started: now,
+                })
+            }
+            false => done.push(line),
+        }
+        done
+    }
+
+    /// The run, done with if its window's up by `now`. Nothing else would end it if it was the
+    /// last thing logged for a while
+    pub fn flush(&mut self, now: Instant) -> Option<Line> {
+        let run = self.run.as_ref()?;
+        if now.saturating_duration_since(run.started) < self.window {
+            return None;
+        }
+        self.close()
+    }
+
+    /// The run still going, as it should look right now
+    pub fn held(&self) -> Option<Line> {
+        self.run.as_ref().map(Run::shown)
+    }
+
+    fn close(&mut self) -> Option<Line> {
+        self.run.take().as_ref().map(Run::shown)
+    }
+}
+
+/// The `Collapser`, and when its run started by the wall clock for the pane to stamp it with
+#[derive(Debug)]
+struct Shared {
+    collapser: Collapser,
+    since: DateTime<Local>,
+}
+
+/// Only one layer feeds the widget, and `flush` and `held` need to find it without a handle
+static COLLAPSER: Mutex<Option<Shared>> = Mutex::new(None);
+
+/// Sits where `TuiTracingSubscriberLayer` would, and hands tui-logger what the `Collapser` lets
+/// through. It doesn't check tui-logger's own levels, so filter it to match
+#[derive(Debug)]
+pub struct DedupLayer;
+
+impl DedupLayer {
+    pub fn new(window: Duration, max: u32) -> Self {
+        *COLLAPSER.lock().expect("nobody panics holding it") = Some(Shared {
+            collapser: Collapser::new(window, max),
+            since: Local::now(),
+        });
+        DedupLayer
+    }
+}
+
+impl<S: Subscriber> Layer<S> for DedupLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut text = Text::default();
+        event.record(&mut text);
+        let line = Line {
+            target: event.metadata().target().to_string(),
+            level: *event.metadata().level(),
+            text: text.finish(),
+        };
+        let done = match COLLAPSER.lock().expect("nobody panics holding it").as_mut() {
+            Some(shared) => {
+                let done = shared.collapser.push(line, Instant::now());
+                // Only the first of a run starts it
+                if shared
+                    .collapser
+                    .run
+                    .as_ref()
+                    .is_some_and(|run| run.count == 1)
+                {
+                    shared.since = Local::now();
+                }
+                done
+            }
+            None => vec![line],
+        };
+        done.iter().for_each(forward);
+    }
+}
+
+/// Hands over a run whose window's up, if nothing else has since. Once a tick is plenty
+pub fn flush() {
+    let line = COLLAPSER
+        .lock()
+        .expect("nobody panics holding it")
+        .as_mut()
+        .and_then(|shared| shared.collapser.flush(Instant::now()));
+    if let Some(line) = line {
+        forward(&line);
+    }
+}
+
+/// The run being held back, and when it started, for the pane to draw under what tui-logger has
+pub fn held() -> Option<(Line, DateTime<Local>)> {
+    let shared = COLLAPSER.lock().expect("nobody panics holding it");
+    let shared = shared.as_ref()?;
+    Some((shared.collapser.held()?, shared.since))
+}
+
+fn forward(line: &Line) {
+    let level = match line.level {
+        Level::ERROR => log::Level::Error,
+        Level::WARN => log::Level::Warn,
+        Level::INFO => log::Level::Info,
+        Level::DEBUG => log::Level::Debug,
+        Level::TRACE => log::Level::Trace,
+    };
+    tui_logger::Drain::new().log(
+        &log::Record::builder()
+            .args(format_args!("{}", line.text))
+            .level(level)
+            .target(&line.target)
+            .build(),
+    );
+}
+
+/// An event's message and then its other fields, laid out the way tui-logger's own layer does it
+#[derive(Default)]
+struct Text {
+    message: String,
+    fields: String,
+}
+
+impl Text {
+    fn finish(self) -> String {
+        if self.message.is_empty() {
+            return self.fields;
+        }
+        format!(" {}{}", self.message, self.fields)
+    }
+}
+
+impl Visit for Text {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}: {value:?}", field.name());
+        }
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.record_str(field, &value.to_string());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}: {value}", field.name());
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! The log file's appender. Not having one is never fatal: if the file can't be made (a
+//! read-only directory, a path through a regular file, a directory where the file should be) we
+//! carry on logging to the Message Stream only, and the reason comes back as a warning for the
+//! startup panel
+use std::{fs, io, path::Path};
+
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+/// The appender for `path`, making its directory if need be. `rolling::never` panics if it
+/// can't make the file, so this uses the builder to get an error. The error's the warning
+pub fn open(path: &Path) -> Result<RollingFileAppender, String> {
+    try_open(path).map_err(|e| {
+        format!(
+            "can't write log file {}: {e}. Logging to the Message Stream only",
+            path.display()
+        )
+    })
+}
+
+fn try_open(path: &Path) -> io::Result<RollingFileAppender> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("./"),
+    };
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "log path has no file name"))?
+        .to_string_lossy();
+    fs::create_dir_all(dir)?;
+    RollingFileAppender::builder()
+        .rotation(Rotation::NEVER)
+        .filename_prefix(file_name)
+        .build(dir)
+        .map_err(io::Error::other)
+}
@@ -0,0 +1,104 @@
+//! The message stream, under the table or (with `f`) on its own. Both presentations draw from
+//! the one scroll state, so going between them keeps your place in the backlog. While it's
+//! following, the run of repeats `log_dedup` is holding back goes on the bottom line
+use std::{cell::Cell, fmt};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Style,
+    text::Line,
+    widgets::{Block, Padding, Widget},
+};
+use tracing::Level;
+use tui_logger::{TuiLoggerLevelOutput, TuiLoggerWidget, TuiWidgetEvent, TuiWidgetState};
+
+use crate::{log_dedup, theme};
+
+/// How tui-logger's told to stamp lines, and how the held one's stamped to match
+const TIMESTAMP: &str = "%H:%M:%S%.3f ";
+
+#[derive(Default)]
+pub struct LogPane {
+    state: TuiWidgetState,
+    /// Paged back since it was last told to follow. Paging down to the end follows again without
+    /// saying, so the held line only comes back with `End` (or when it's handed over anyway)
+    paged: Cell<bool>,
+}
+
+impl fmt::Debug for LogPane {
+    // TuiWidgetState has nothing worth printing and doesn't implement Debug anyway
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogPane").finish_non_exhaustive()
+    }
+}
+
+impl LogPane {
+    /// A page further back. Pages are however tall the pane was last drawn
+    pub fn page_up(&self) {
+        self.paged.set(true);
+        self.state.transition(TuiWidgetEvent::PrevPageKey);
+    }
+
+    /// A page forward. Paging past the newest line goes back to following
+    pub fn page_down(&self) {
+        self.state.transition(TuiWidgetEvent::NextPageKey);
+    }
+
+    /// Straight back to the newest lines, following as more come in
+    pub fn follow(&self) {
+        self.paged.set(false);
+        self.state.transition(TuiWidgetEvent::EscapeKey);
+    }
+
+    pub fn render(&self, title: &str, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered().title(title).padding(Padding::uniform(1));
+        let inner = block.inner(area);
+        let held = log_dedup::held().filter(|_| !self.paged.get() && inner.height > 1);
+        let Some((line, since)) = held else {
+            return self.logger().block(block).render(area, buf);
+        };
+        block.render(area, buf);
+        let [logs, last] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+        self.logger().render(logs, buf);
+        buf.set_style(last, theme::current().text);
+        let level = line.level.as_str();
+        Line::styled(
+            format!("{}|{level:<5}|{}", since.format(TIMESTAMP), line.text),
+            level_style(line.level),
+        )
+        .render(last, buf);
+    }
+
+    fn logger(&self) -> TuiLoggerWidget<'_> {
+        let theme = theme::current();
+        // Mostly lifted from the example code
+        TuiLoggerWidget::default()
+            .style_error(theme.log_error)
+            .style_debug(theme.log_debug)
+            .style_warn(theme.log_warn)
+            .style_trace(theme.log_trace)
+            .style_info(theme.log_info)
+            .output_separator('|')
+            .output_timestamp(Some(TIMESTAMP.to_string()))
+            .output_level(Some(TuiLoggerLevelOutput::Long))
+            .output_target(false)
+            .output_file(false)
+            .output_line(false)
+            .style(theme.text)
+            .state(&self.state)
+    }
+}
+
+/// The style tui-logger would give a line at `level`
+fn level_style(level: Level) -> Style {
+    let theme = theme::current();
+    match level {
+        Level::ERROR => theme.log_error,
+        Level::WARN => theme.log_warn,
+        Level::INFO => theme.log_info,
+        Level::DEBUG => theme.log_debug,
+        Level::TRACE => theme.log_trace,
+    }
+}
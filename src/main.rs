@@ -1,399 +1,182 @@
-use std::time::Duration;
-
-use color_eyre::eyre::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
-use ratatui::{
-    buffer::Buffer,
-    layout::{Constraint, Layout, Rect},
-    style::{Color, Style, Stylize},
-    symbols::border,
-    text::Line,
-    widgets::{Block, Padding, StatefulWidget, Widget},
-    DefaultTerminal, Frame,
+use std::{
+    io::{self},
+    time::Duration,
 };
-use task_picker::{CandidateTask, TaskPicker};
-use task_table::TaskTable;
-use tasks::{Task, TaskRxMsg, TaskStatus, TaskTxMsg};
-use tokio::{
-    sync::{broadcast, mpsc},
-    task,
+
+use clap::Parser;
+use clustrctrl::{
+    app,
+    compat::Compat,
+    config::{Cli, Config, LogConfig},
+    health::{self, Health},
+    log_dedup::DedupLayer,
+    log_file, preflight,
+    recording::Recording,
+    session,
+    status_server::{self, Shared},
+    strings::{self, Strings},
+    theme::{self, ColorChoice, NoColor, Theme},
 };
-use tracing::{error, info, trace, warn};
+use color_eyre::eyre::{eyre, Result};
+use crossterm::{event::DisableFocusChange, execute};
+use tokio::net::TcpListener;
+use tracing::{error, info, info_span, Instrument};
+use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::{
-    fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer,
+    filter::LevelFilter, fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt,
+    EnvFilter, Layer,
 };
-use tui_logger::{TuiLoggerLevelOutput, TuiLoggerWidget};
-mod task_picker;
-mod task_table;
-mod tasks;
 
-#[tokio::main]
-async fn main() -> Result<()> {
+fn main() -> Result<()> {
     color_eyre::install()?;
-    let appender = tracing_appender::rolling::never("./", "log");
-    let (non_blocking, _guard) = tracing_appender::non_blocking(appender);
-    tracing_subscriber::registry()
-        .with(tui_logger::TuiTracingSubscriberLayer)
-        .with(
-            tracing_subscriber::fmt::layer()
-                .with_writer(non_blocking)
-                .with_thread_ids(true)
-                .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
-                .with_filter(
-                    EnvFilter::try_from_default_env().unwrap_or_else(|_| {
-                        format!("{}=trace,info", env!("CARGO_CRATE_NAME")).into()
-                    }),
-                ),
-        )
-        .init();
-    tui_logger::init_logger(tui_logger::LevelFilter::Info).unwrap();
-    info!("starting application");
-    match tokio::spawn(launch_app()).await? {
-        Ok(_) => {}
-        Err(e) => error!("error during app termination {e}"),
-    };
-    info!("application terminated. restoring");
-    ratatui::restore();
-    //TODO: Skill issue not using collaborative tasks. We could just force stop them probably
-    println!("Goodbye! Any active tasks sent exit signals. This will take time to be heeded.");
-    Ok(())
-}
-
-async fn launch_app() -> Result<()> {
-    let mut terminal = ratatui::init();
-    App::default().run(&mut terminal).await
-}
-
-#[derive(Debug)]
-pub struct App {
-    picker: TaskPicker,
-    task_table: TaskTable,
-    view_state: ViewState,
-    exit: bool,
-    tasks: Vec<tasks::Task>,
-    tasks_created: tasks::Id, // Tokio ID's will be reused. We don't want that!
-    // Tasks send us updates through this
-    mpsc_rx: mpsc::Receiver<TaskTxMsg>,
-    mpsc_tx: mpsc::Sender<TaskTxMsg>,
-    // We send tasks orders through this
-    bcast_tx: broadcast::Sender<TaskRxMsg>,
+    let mut cli = Cli::parse();
+    // Settled before anything's named after it, the app reads it back off `cli`
+    let name = cli
+        .session
+        .get_or_insert_with(|| session::generate(&mut rand::rng()))
+        .clone();
+    let (config, config_problems) = Config::load(&cli);
+    // Built by hand for the blocking pool's size, which every dummy task takes a thread from
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .max_blocking_threads(config.work.max_blocking_threads)
+        .build()?
+        .block_on(run(cli, name, config, config_problems))
 }
 
-#[derive(Debug)]
-enum ViewState {
-    /// Modal should be active, and we can add tasks here
-    TaskAdd,
-    /// Main screen. Can't do anything but enter other modes & watch
-    Monitor,
-    /// Main screen, but we can select tasks on the table and cancel them
-    Inspect,
-}
-
-impl Default for App {
-    fn default() -> Self {
-        // Used by tasks to bubble a message up
-        let (mpsc_tx, mpsc_rx) = mpsc::channel(100);
-        let (bcast_tx, _) = broadcast::channel(16);
-        Self {
-            picker: TaskPicker::default(),
-            task_table: TaskTable::default(),
-            tasks: vec![],
-            tasks_created: 0,
-            view_state: ViewState::Monitor,
-            exit: false,
-            mpsc_rx,
-            mpsc_tx,
-            bcast_tx,
+async fn run(cli: Cli, name: String, config: Config, config_problems: Vec<String>) -> Result<()> {
+    // Before anything's been started as whoever we are
+    let root_warning =
+        preflight::check_root(preflight::euid(), cli.allow_root).map_err(|e| eyre!(e))?;
+    // While there's still a command line to complain on, rather than replaying half of one
+    let recording = cli
+        .replay
+        .as_deref()
+        .map(|path| {
+            Recording::load(path).map_err(|e| eyre!("can't replay {}: {e}", path.display()))
+        })
+        .transpose()?;
+    // And for a strings file that's not there, rather than a demo in the wrong language
+    let strings = cli
+        .strings
+        .as_deref()
+        .map(|path| {
+            Strings::load(path).map_err(|e| eyre!("can't use strings from {}: {e}", path.display()))
+        })
+        .transpose()?;
+    // Same goes for a port that's taken, nobody wants to find out from a banner
+    let status = match config.http.listen {
+        Some(addr) => {
+            let listener = TcpListener::bind(addr)
+                .await
+                .map_err(|e| eyre!("can't listen on {addr} (http.listen in the config): {e}"))?;
+            let shared = Shared::default();
+            status_server::serve(listener, shared.clone());
+            Some(shared)
         }
-    }
-}
-
-impl App {
-    pub async fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
-        while !self.exit {
-            terminal.draw(|frame| self.view(frame))?;
-            self.update().await?;
-            task::yield_now().await;
+        None => None,
+    };
+    let compat = Compat::detect(config.ui.legacy_terminal);
+    theme::init(
+        Theme::pick(cli.color)
+            .with_access(config.ui.reduce_motion, config.ui.plain_output)
+            .with_legacy(compat.legacy),
+    );
+    let strings_problems = match strings {
+        Some((loaded, problems)) => {
+            strings::init(loaded);
+            problems
         }
-        Ok(())
+        None => vec![],
+    };
+    let mut log = config.log.with_overrides(&cli);
+    log.path = session::unique(&log.path, &name);
+    let (_guard, log_warning) = init_tracing(&log);
+    tui_logger::init_logger(tui_logger::LevelFilter::Info).unwrap();
+    info!("starting session {name}");
+    // Everything from here on (and from before, now there's somewhere to log it) goes in the
+    // startup panel too
+    let mut health = Health::default();
+    for warning in log_warning.into_iter().chain(root_warning) {
+        health.warn(warning);
     }
-
-    fn view(&mut self, frame: &mut Frame) {
-        let area = frame.area();
-        frame.render_widget(self, area);
+    for problem in config_problems.into_iter().chain(strings_problems) {
+        health.warn(problem);
     }
-
-    async fn update(&mut self) -> Result<()> {
-        //If I were doing it all over again I'd use a proper event-driven architecture
-        //Like in the templates
-        if event::poll(Duration::from_millis(500))? {
-            match event::read()? {
-                Event::Key(event) if event.kind == KeyEventKind::Press => {
-                    self.handle_key_event(event)
-                }
-                _ => {}
-            };
-        }
-        // Check our messages, and see if any task is done
-        // Legally speaking, this is struct and tokio abuse.
-        while let Ok(msg) = self.mpsc_rx.try_recv() {
-            match msg {
-                //FIXME: We'd panic here if we got a message for an ID that doesn't exist
-                // the logic is pretty tight where we TX but this would be !Ok in a srs project
-                TaskTxMsg::RunReport { id, progress } => {
-                    trace!("got a run report from {id} with progress {progress}%");
-                    self.tasks[id].progress = progress;
-                    self.tasks[id].status = TaskStatus::Running;
-                }
-                TaskTxMsg::SleepReport(id) => {
-                    trace!("got a sleep report from {id}");
-                    self.tasks[id].status = TaskStatus::Sleeping;
-                }
-                //TODO: Implement
-                TaskTxMsg::LaborDispute(id) => {
-                    info!("task {id} refuses to work at this time");
-                    self.tasks[id].status = TaskStatus::OnStrike;
-                }
-                TaskTxMsg::Reconciliation(id) => {
-                    info!("task {id} has reached an agreement, and will resume");
-                    self.tasks[id].status = TaskStatus::Running;
-                }
-                TaskTxMsg::CancelReport(id) => {
-                    info!("task {id} has sent word of termination");
-                    self.tasks[id].status = TaskStatus::Canceled;
-                }
-            };
-        }
-        // Separately, check handles. This is kind of redundant given we have an MPSC channel that
-        // reports doneness. Architectural skill issue, in hindsight.
-        for task in self.tasks.iter_mut() {
-            if let Some(handle) = task.check_done() {
-                match handle.await {
-                    Ok(res) => {
-                        if let Some(sum) = res {
-                            info!("task {} finished and reported: {sum}", task.id)
-                        } else {
-                            warn!(
-                                "task {} finished after termination and reported no sum",
-                                task.id
-                            )
-                        }
-                    }
-                    Err(e) => {
-                        error!(
-                            "problem finishing allegedly completed task {}: {e:?}",
-                            task.id
-                        );
-                    }
-                }
+    if cli.color == ColorChoice::Auto {
+        match Theme::no_color() {
+            Some(NoColor::Asked) => health.info("no color, since NO_COLOR is set"),
+            Some(NoColor::DumbTerminal) => {
+                health.warn("no color, TERM=dumb says this terminal can't do it")
             }
+            None => {}
         }
-        Ok(())
     }
-
-    fn handle_key_event(&mut self, event: KeyEvent) {
-        trace!("key down: {:?}", event);
-        match event.code {
-            KeyCode::Char('k') | KeyCode::Up => match self.view_state {
-                ViewState::TaskAdd => self.picker.previous(),
-                ViewState::Inspect => self.task_table.previous(self.tasks.len()),
-                ViewState::Monitor => {}
-            },
-            KeyCode::Char('j') | KeyCode::Down => match self.view_state {
-                ViewState::TaskAdd => self.picker.next(),
-                ViewState::Inspect => self.task_table.next(self.tasks.len()),
-                ViewState::Monitor => {}
-            },
-
-            KeyCode::Char('r') => {
-                if let ViewState::TaskAdd = self.view_state {
-                    self.add_task(self.picker.select_random());
-                }
-            }
-
-            KeyCode::Enter => match self.view_state {
-                ViewState::TaskAdd => self.add_task(self.picker.select()),
-                ViewState::Inspect => self.cancel_selected_task(),
-                ViewState::Monitor => {}
-            },
-
-            //Go to task add IFF we're at main menu
-            KeyCode::F(1) => {
-                match self.view_state {
-                    ViewState::TaskAdd | ViewState::Inspect => {}
-                    ViewState::Monitor => {
-                        self.view_state = ViewState::TaskAdd;
-                        self.picker.regen(); // Pick fresh pool entries
-                    }
-                };
-            }
-            // Go to inspect mode IFF we're at main menu
-            KeyCode::F(2) => match self.view_state {
-                ViewState::TaskAdd | ViewState::Inspect => {}
-                ViewState::Monitor => {
-                    self.view_state = ViewState::Inspect;
-                    // If table is not empty and nothing selected, select first row
-                    if !self.tasks.is_empty() && self.task_table.state.selected().is_none() {
-                        self.task_table.state.select(Some(0));
-                    }
-                }
-            },
-
-            // We can always exit
-            KeyCode::F(3) => self.exit(),
-
-            // Go back unless we're @ main menu
-            KeyCode::Esc => match self.view_state {
-                ViewState::TaskAdd | ViewState::Inspect => {
-                    self.view_state = ViewState::Monitor;
-                    self.task_table.state.select(None);
-                }
-                ViewState::Monitor => {}
-            },
-            _ => {}
-        }
+    if compat.legacy && config.ui.legacy_terminal.is_none() {
+        health.info(
+            "looks like the old Windows console, so borders are thin and nothing blinks \
+             (ui.legacy_terminal = false if it's wrong)",
+        );
     }
-
-    /// Calls out for the actual task, mostly handles UI juggling
-    fn add_task(&mut self, ct: Option<&'static CandidateTask>) {
-        if let Some(ct) = ct {
-            info!("selected candidate task {:?}", ct);
-            self.view_state = ViewState::Monitor;
-            self.tasks.push(Task::new(
-                ct,
-                self.mpsc_tx.clone(),
-                self.bcast_tx.subscribe(),
-                self.tasks_created, //This counter becomes the unique 'ID'
-            ));
-            self.tasks_created += 1;
-        } else {
-            //Should be recoverable so we'll just ignore it otherwise
-            error!("attempted to select task from picker but got none");
-        }
+    let threads = config.work.max_blocking_threads;
+    if threads < health::SMALL_BLOCKING_POOL {
+        health.warn(format!(
+            "work.max_blocking_threads is {threads}, so only {threads} dummy tasks can run at \
+             once and the rest queue for a thread"
+        ));
     }
-
-    fn cancel_selected_task(&mut self) {
-        // This only works because we don't have sorting TODO: Make less brittle?
-        if let Some(selected) = self.task_table.state.selected() {
-            // Use get_mut to obtain a mutable reference directly
-            if let Some(task) = self.tasks.get_mut(selected) {
-                match self.bcast_tx.send(TaskRxMsg::PleaseStop(task.id)) {
-                    Ok(_) => {
-                        info!("sent a cancel message to task {}", task.id);
-                        task.pending_cancel = true;
-                    }
-                    Err(e) => error!("problem sending cancel message to task {}: {e:?}", task.id),
-                }
-                return;
-            }
-        }
-        warn!("tried to send a cancel message to a task that doesn't exist");
+    if let Some(addr) = config.http.listen {
+        health.info(format!("serving status on http://{addr}"));
     }
-
-    fn exit(&mut self) {
-        //TODO: Worst case this broadcast has a 60 second delay, not great for exiting!
-        match self.bcast_tx.send(TaskRxMsg::EveryoneStopPls) {
-            Ok(_) => info!("sent cancel message to all tasks"),
-            Err(e) => error!("problem sending cancel message to all tasks {e:?}"),
+    let span = info_span!("session", session = %name);
+    let launched = app::launch(cli, config, health, recording, status).instrument(span);
+    let summary = match tokio::spawn(launched).await? {
+        Ok(summary) => Some(summary),
+        Err(e) => {
+            error!("error during app termination {e}");
+            None
         }
-        self.exit = true;
+    };
+    info!("application terminated. restoring");
+    // Nothing to be done about it if this fails, the terminal's on its way out anyway
+    let _ = execute!(io::stdout(), DisableFocusChange);
+    ratatui::restore();
+    //TODO: Skill issue not using collaborative tasks. We could just force stop them probably
+    match summary {
+        Some(summary) => print!("{summary}"),
+        None => println!(
+            "Goodbye! Any active tasks sent exit signals. This will take time to be heeded."
+        ),
     }
+    Ok(())
 }
 
-impl Widget for &mut App {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let title = match self.view_state {
-            ViewState::Monitor => Line::from("  clustrctrl  ".bold()),
-            ViewState::Inspect => Line::from("  clustrctrl ━ [inspect] ".bold()),
-            ViewState::TaskAdd => Line::from("  clustrctrl ━ [task add] ".bold()),
-        };
-        let controls = Line::from(match self.view_state {
-            ViewState::Monitor => vec![
-                " New Task ".into(),
-                "<F1>".blue().bold(),
-                " Manage Tasks ".into(),
-                "<F2>".blue().bold(),
-                " Quit ".into(),
-                "<F3> ".blue().bold(),
-            ],
-            ViewState::TaskAdd => vec![
-                " Back ".into(),
-                "<ESC>".blue().bold(),
-                " Quit ".into(),
-                "<F3> ".blue().bold(),
-            ],
-            ViewState::Inspect => vec![
-                " Back ".into(),
-                "<ESC>".blue().bold(),
-                " Terminate Task ".into(),
-                "<ENTER>".blue().bold(),
-                " Quit ".into(),
-                "<F3> ".blue().bold(),
-            ],
-        });
-
-        let main_block = Block::bordered()
-            .title(title.left_aligned())
-            .title_bottom(controls.centered())
-            .border_set(border::THICK)
-            .padding(Padding::new(2, 2, 1, 4));
-
-        // Render the main block first to draw the borders
-        let internal_area = main_block.inner(area);
-        main_block.render(area, buf);
-
-        // Table fits to tasks + padding, or takes the whole window if we're short on room
-        let table_height = ((self.tasks.len() + 6) as u16).min(internal_area.height);
-        let [table_area, logger_area] = Layout::vertical([
-            Constraint::Length(table_height),
-            Constraint::Min(0), // If there's leftovers, logger gets it
-        ])
-        .areas(internal_area);
-        // Render the TaskTable inside the main block's inner area
-        // Pass the task data required by the TaskTable widget's render method
-        StatefulWidget::render(
-            &mut self.task_table,
-            table_area,
-            buf,
-            &mut &self.tasks, // We don't mutate but the trait wants a mut ref
-        );
-
-        // Render the TuiLogger in remaining space
-        if logger_area.area() > 0 {
-            // Mostly lifted from the example code
-            TuiLoggerWidget::default()
-                .block(
-                    Block::bordered()
-                        .title(" Message Stream ")
-                        .padding(Padding::uniform(1)),
-                )
-                .style_debug(Style::default().fg(Color::Green))
-                .style_warn(Style::default().fg(Color::Yellow))
-                .style_trace(Style::default().fg(Color::Magenta))
-                .style_info(Style::default().fg(Color::Cyan))
-                .output_separator('|')
-                .output_timestamp(Some("%H:%M:%S%.3f ".to_string()))
-                .output_level(Some(TuiLoggerLevelOutput::Long))
-                .output_target(false)
-                .output_file(false)
-                .output_line(false)
-                .style(Style::default().fg(Color::White))
-                .render(logger_area, buf);
-        }
-
-        // We want to draw our modal over if we're in add state
-        // TODO: Put all this inside render() if it gets more complicated
-        if let ViewState::TaskAdd = self.view_state {
-            let modal_width = (area.width as f32 * 0.85) as u16;
-            let modal_height = (task_picker::FETCH_AMOUNT + 2) as u16;
-            let modal_area = Rect {
-                x: (area.width - modal_width) / 2,
-                y: (area.height - modal_height) / 2,
-                width: modal_width,
-                height: modal_height,
-            };
-            trace!("rendering modal with {:?}", modal_area);
-            self.picker.render(modal_area, buf);
+/// Sets up the tui-logger layer and (if we can) the file layer. If the log file can't be made
+/// we carry on without it, and hand back a warning for the UI since nobody reads the log pane
+fn init_tracing(log: &LogConfig) -> (Option<WorkerGuard>, Option<String>) {
+    let (file_layer, guard, warning) = if log.enabled {
+        match log_file::open(&log.path) {
+            Ok(appender) => {
+                let (non_blocking, guard) = tracing_appender::non_blocking(appender);
+                let layer = tracing_subscriber::fmt::layer()
+                    .with_writer(non_blocking)
+                    .with_thread_ids(true)
+                    .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
+                    .with_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+                        format!("{}=trace,info", env!("CARGO_CRATE_NAME")).into()
+                    }));
+                (Some(layer), Some(guard), None)
+            }
+            Err(warning) => (None, None, Some(warning)),
         }
-    }
+    } else {
+        (None, None, None)
+    };
+    let window = Duration::from_millis(log.collapse_window_ms);
+    tracing_subscriber::registry()
+        // The same level tui-logger's given in `run`, which it only checks on its own layer
+        .with(DedupLayer::new(window, log.collapse_max).with_filter(LevelFilter::INFO))
+        .with(file_layer)
+        .init();
+    (guard, warning)
 }
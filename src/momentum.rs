@@ -0,0 +1,69 @@
+//! How recently a task's progress moved, shown as the Progress cell's color: green while it's
+//! moving, yellow once it's sat still a while, red once it's sat still too long for something
+//! that says it's running. Sleeping workers are meant to sit still, so they never go red. A
+//! long block can sit still a good while without anything being wrong, so for workers that
+//! heartbeat it's how long they've been quiet that decides red, not how long progress has sat
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tasks::TaskStatus;
+
+/// When a task's progress stops looking fresh, and when it starts looking stuck
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MomentumConfig {
+    pub idle_ms: u64,
+    pub stalled_ms: u64,
+}
+
+impl Default for MomentumConfig {
+    fn default() -> Self {
+        Self {
+            idle_ms: 5_000,
+            stalled_ms: 20_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Momentum {
+    /// Moved within `MomentumConfig::idle_ms`
+    Moving,
+    Idle,
+    /// Running, and nothing for longer than `MomentumConfig::stalled_ms`
+    Stalled,
+}
+
+/// A task that's `status` and whose progress last moved `still` ago. None for anything that
+/// isn't working on it, since there's nothing to expect of those
+pub fn classify(status: TaskStatus, still: Duration, config: &MomentumConfig) -> Option<Momentum> {
+    let still = still.as_millis() as u64;
+    match status {
+        TaskStatus::Running | TaskStatus::Sleeping if still < config.idle_ms => {
+            Some(Momentum::Moving)
+        }
+        TaskStatus::Running if still >= config.stalled_ms => Some(Momentum::Stalled),
+        TaskStatus::Running | TaskStatus::Sleeping => Some(Momentum::Idle),
+        _ => None,
+    }
+}
+
+/// `classify`, except a worker that's heartbeating isn't stalled however long progress has sat
+/// still, only idle. `quiet` is from `Task::quiet_for`, None for workers that never heartbeat
+pub fn with_heartbeat(
+    status: TaskStatus,
+    still: Duration,
+    quiet: Option<Duration>,
+    config: &MomentumConfig,
+) -> Option<Momentum> {
+    match classify(status, still, config) {
+        Some(Momentum::Stalled)
+            if quiet.is_some_and(|quiet| (quiet.as_millis() as u64) < config.stalled_ms) =>
+        {
+            Some(Momentum::Idle)
+        }
+        momentum => momentum,
+    }
+}
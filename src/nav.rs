@@ -0,0 +1,85 @@
+//! Where the cursor goes in a list when a key moves it. The picker and the table both go
+//! through here, so Home, PageDown and running off the end mean the same thing in each
+
+/// Every way a key can move the cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    Next,
+    Previous,
+    /// Home
+    First,
+    /// End
+    Last,
+    /// A screenful down, however many rows that is right now
+    PageDown,
+    PageUp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListNav {
+    /// Next off the bottom goes back to the top, and the other way round. Paging always stops
+    /// at the ends, so a held PageDown doesn't spin around forever
+    pub wrap: bool,
+}
+
+impl ListNav {
+    pub fn new(wrap: bool) -> Self {
+        Self { wrap }
+    }
+
+    /// The row to select after `to` from `from`, in a list `len` rows long with `page` of them
+    /// on screen. Nothing selected yet starts from the top (or the bottom, for End), and it's
+    /// only ever None when there's nothing to select
+    pub fn apply(&self, from: Option<usize>, len: usize, page: usize, to: Move) -> Option<usize> {
+        let last = len.checked_sub(1)?;
+        // A list too short to see still moves a row at a time
+        let page = page.max(1);
+        let Some(row) = from.map(|row| row.min(last)) else {
+            return Some(if to == Move::Last { last } else { 0 });
+        };
+        Some(match to {
+            Move::Next if row == last => {
+                if self.wrap {
+                    0
+                } else {
+                    last
+                }
+            }
+            Move::Next => row + 1,
+            Move::Previous if row == 0 => {
+                if self.wrap {
+                    last
+                } else {
+                    0
+                }
+            }
+            Move::Previous => row - 1,
+            Move::First => 0,
+            Move::Last => last,
+            Move::PageDown => (row + page).min(last),
+            Move::PageUp => row.saturating_sub(page),
+        })
+    }
+}
+
+/// The nearest row after `from` (or before it, going back) that `matches`, going round the end
+/// if it has to. `from` itself comes last, so a lone match stays put. Nothing selected starts
+/// from the top (or the bottom, going back). None if nothing in the `len` rows matches
+pub fn next_match(
+    from: Option<usize>,
+    len: usize,
+    forward: bool,
+    matches: impl Fn(usize) -> bool,
+) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    // Steps away from where we are, so `from` itself is the last one tried
+    let at = |step: usize| match (from.map(|row| row.min(len - 1)), forward) {
+        (Some(row), true) => (row + step) % len,
+        (Some(row), false) => (row + len * 2 - step) % len,
+        (None, true) => step - 1,
+        (None, false) => len - step,
+    };
+    (1..=len).map(at).find(|&row| matches(row))
+}
@@ -0,0 +1,108 @@
+//! Popup for answering a striking task's demands. Like TaskPicker it holds all its own state, which
+//! is just who we're talking to, what they asked for, and which answer the cursor is on
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::Line,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::{layout_util::saturating_height, tasks::Id, theme};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Offer {
+    /// Everything they asked for
+    Accept,
+    /// Half, take it or leave it
+    Counter,
+    /// Nothing. They might walk
+    Refuse,
+}
+
+impl Offer {
+    const ALL: [Offer; 3] = [Offer::Accept, Offer::Counter, Offer::Refuse];
+
+    /// Break length this offer comes to, zero being a refusal
+    pub fn granted_secs(self, demand_secs: u64) -> u64 {
+        match self {
+            Offer::Accept => demand_secs,
+            Offer::Counter => demand_secs / 2,
+            Offer::Refuse => 0,
+        }
+    }
+
+    fn label(self, demand_secs: u64) -> String {
+        match self {
+            Offer::Accept => format!("Accept: grant the full {demand_secs} sec break"),
+            Offer::Counter => format!("Counter: offer {} sec instead", demand_secs / 2),
+            Offer::Refuse => "Refuse: back to work (they may quit!)".to_string(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Negotiation {
+    pub id: Id,
+    pub demand_secs: u64,
+    pub state: ListState,
+}
+
+impl Negotiation {
+    pub fn new(id: Id, demand_secs: u64) -> Self {
+        Self {
+            id,
+            demand_secs,
+            state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    /// Height the modal needs, including borders
+    pub fn height() -> u16 {
+        saturating_height(Offer::ALL.len() + 2)
+    }
+
+    /// Wraps list down
+    pub fn next(&mut self) {
+        let idx = self
+            .state
+            .selected()
+            .map_or(0, |i| (i + 1) % Offer::ALL.len());
+        self.state.select(Some(idx));
+    }
+
+    /// Wraps list up
+    pub fn previous(&mut self) {
+        let idx = self
+            .state
+            .selected()
+            .map_or(0, |i| (i + Offer::ALL.len() - 1) % Offer::ALL.len());
+        self.state.select(Some(idx));
+    }
+
+    pub fn offer(&self) -> Offer {
+        Offer::ALL[self.state.selected().unwrap_or(0) % Offer::ALL.len()]
+    }
+}
+
+impl Widget for &mut Negotiation {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let items: Vec<ListItem> = Offer::ALL
+            .iter()
+            .map(|offer| ListItem::from(offer.label(self.demand_secs)))
+            .collect();
+        let controls = Line::from(vec![" Answer ".into(), theme::key("<ENTER> ")]);
+        let block = Block::new()
+            .title(format!(
+                " Task {} demands a {} sec break ",
+                self.id, self.demand_secs
+            ))
+            .borders(Borders::ALL)
+            .border_style(theme::current().alert)
+            .title_bottom(controls.centered());
+        let list = List::new(items)
+            .block(block)
+            .highlight_symbol(theme::current().highlight_symbol());
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
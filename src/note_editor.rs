@@ -0,0 +1,86 @@
+//! Popup for writing a note on a task, like "customer asked for this rerun". It edits a copy, so
+//! backing out leaves the old note alone. Enter is just another character here (notes can run to a
+//! few lines), so saving is Ctrl+Enter where the terminal can tell the difference and Ctrl+S
+//! everywhere else
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Widget, Wrap},
+};
+
+use crate::{
+    tasks::{Id, Task},
+    theme,
+};
+
+/// Lines of note shown at once. Longer ones scroll to keep the end in sight
+const VISIBLE_LINES: u16 = 6;
+
+#[derive(Debug)]
+pub struct NoteEditor {
+    pub id: Id,
+    text: String,
+}
+
+impl NoteEditor {
+    pub fn new(task: &Task) -> Self {
+        Self {
+            id: task.id,
+            text: task.note.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Height the popup needs, including borders
+    pub fn height() -> u16 {
+        VISIBLE_LINES + 2
+    }
+
+    pub fn push(&mut self, ch: char) {
+        self.text.push(ch);
+    }
+
+    pub fn pop(&mut self) {
+        self.text.pop();
+    }
+
+    /// What to keep. Saving something blank takes the note off instead of keeping whitespace
+    pub fn note(&self) -> Option<String> {
+        let trimmed = self.text.trim();
+        (!trimmed.is_empty()).then(|| trimmed.to_string())
+    }
+}
+
+impl Widget for &NoteEditor {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let theme = theme::current();
+        let controls = Line::from(vec![
+            " Save ".into(),
+            theme::key("<C-S>"),
+            " Cancel ".into(),
+            theme::key("<ESC> "),
+        ]);
+        let block = Block::new()
+            .title(format!(" Note for task {} ", self.id))
+            .borders(Borders::ALL)
+            .border_style(theme.text)
+            .title_bottom(controls.centered())
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+
+        let mut lines: Vec<Line> = self.text.split('\n').map(Line::from).collect();
+        if let Some(last) = lines.last_mut() {
+            last.push_span(Span::styled("▏", theme.accent));
+        }
+        // Roughly where wrapping leaves the cursor, so it never scrolls out of sight
+        let width = inner.width.max(1) as usize;
+        let rows: usize = lines.iter().map(|l| l.width().max(1).div_ceil(width)).sum();
+        let scroll = rows.saturating_sub(inner.height as usize) as u16;
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0))
+            .block(block)
+            .render(area, buf);
+    }
+}
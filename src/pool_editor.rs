@@ -0,0 +1,172 @@
+//! Screen for curating the candidate pool without recompiling: everything in it, enabled or not,
+//! plus a little form for adding more. Like TaskDetail it doesn't hold what it shows - the pool
+//! stays with the picker, which is how the picker sees changes on its next regen
+use std::path::PathBuf;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget, Widget,
+    },
+};
+
+use crate::{layout_util::saturating_height, theme, CandidateTask};
+
+/// Where the pool gets saved if there's no tasks file to save it to
+pub const DEFAULT_POOL_PATH: &str = "./clustrctrl-candidates.toml";
+/// Rows the add form takes at the bottom, divider included
+const FORM_HEIGHT: u16 = 3;
+
+#[derive(Debug)]
+pub struct PoolEditor {
+    pub state: ListState,
+    /// Set while a new candidate's being typed in
+    pub form: Option<Form>,
+    /// Where every change is written. None if the file was there but wouldn't load, so it
+    /// doesn't get clobbered - changes just last until exit then
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Default)]
+pub struct Form {
+    pub name: String,
+    pub description: String,
+    /// Which one keys go into. Name first
+    pub on_description: bool,
+}
+
+impl Form {
+    fn field(&mut self) -> &mut String {
+        if self.on_description {
+            &mut self.description
+        } else {
+            &mut self.name
+        }
+    }
+
+    pub fn push(&mut self, ch: char) {
+        self.field().push(ch);
+    }
+
+    pub fn pop(&mut self) {
+        self.field().pop();
+    }
+
+    pub fn candidate(&self) -> CandidateTask {
+        CandidateTask::new(self.name.trim(), self.description.trim())
+    }
+}
+
+impl PoolEditor {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            state: ListState::default().with_selected(Some(0)),
+            form: None,
+            path,
+        }
+    }
+
+    /// Height the popup wants for `len` candidates, including borders and the form if it's open
+    pub fn height(&self, len: usize) -> u16 {
+        let form = if self.form.is_some() { FORM_HEIGHT } else { 0 };
+        saturating_height(len + 2).saturating_add(form)
+    }
+
+    pub fn next(&mut self, len: usize) {
+        let idx = self.state.selected().map_or(0, |i| (i + 1) % len.max(1));
+        self.state.select(Some(idx));
+    }
+
+    pub fn previous(&mut self, len: usize) {
+        let len = len.max(1);
+        let idx = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+        self.state.select(Some(idx));
+    }
+
+    /// Keeps the cursor on a real entry after one's been taken out from under it
+    pub fn clamp(&mut self, len: usize) {
+        if let Some(idx) = self.state.selected() {
+            self.state.select(Some(idx.min(len.saturating_sub(1))));
+        }
+    }
+}
+
+impl<'a> StatefulWidget for &'a mut PoolEditor {
+    type State = &'a [CandidateTask];
+
+    fn render(self, area: Rect, buf: &mut Buffer, pool: &mut Self::State) {
+        Clear.render(area, buf);
+        let theme = theme::current();
+        let enabled = pool.iter().filter(|ct| ct.enabled).count();
+        let controls = Line::from(match self.form {
+            Some(_) => vec![
+                " Next Field ".into(),
+                theme::key("<TAB>"),
+                " Add ".into(),
+                theme::key("<ENTER>"),
+                " Cancel ".into(),
+                theme::key("<ESC> "),
+            ],
+            None => vec![
+                " Add ".into(),
+                theme::key("<A>"),
+                " Toggle ".into(),
+                theme::key("<SPACE>"),
+                " Delete ".into(),
+                theme::key("<X>"),
+                " Back ".into(),
+                theme::key("<ESC> "),
+            ],
+        });
+        let block = Block::new()
+            .title(format!(
+                " Candidate Pool ({enabled} of {} enabled) ",
+                pool.len()
+            ))
+            .borders(Borders::ALL)
+            .title_bottom(controls.centered());
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let form_height = if self.form.is_some() { FORM_HEIGHT } else { 0 };
+        let [list_area, form_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(form_height)]).areas(inner);
+        let items: Vec<ListItem> = pool
+            .iter()
+            .map(|ct| {
+                let mark = if ct.enabled { "[x] " } else { "[ ] " };
+                let shell = if ct.command.is_some() { " $" } else { "" };
+                let item = ListItem::from(format!("{mark}{ct}{shell}"));
+                if ct.enabled {
+                    item.style(theme.text)
+                } else {
+                    item.style(theme.dim)
+                }
+            })
+            .collect();
+        let list = List::new(items).highlight_symbol(theme::current().highlight_symbol());
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+
+        if let Some(form) = &self.form {
+            let cursor = |on: bool| Span::styled(if on { "▏" } else { "" }, theme.accent);
+            let divider = "─".repeat(form_area.width as usize);
+            Paragraph::new(vec![
+                Line::from(divider).style(theme.dim),
+                Line::from(vec![
+                    "Name: ".bold(),
+                    form.name.as_str().into(),
+                    cursor(!form.on_description),
+                ]),
+                Line::from(vec![
+                    "Description: ".bold(),
+                    form.description.as_str().into(),
+                    cursor(form.on_description),
+                ]),
+            ])
+            .render(form_area, buf);
+        }
+    }
+}
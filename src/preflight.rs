@@ -0,0 +1,56 @@
+//! Checks made before the terminal's taken over, for things bad enough to not start at all. For
+//! now that's running as root: this spawns CPU burners and whatever shell commands the tasks file
+//! says, and doing that as root on a shared box mid-demo is asking for it. `--allow-root` goes
+//! ahead anyway, with a warning in the health panel (by way of `main`, like the rest of them)
+//! and ROOT in the title the whole time. Who we're running as is passed in, so the deciding can
+//! be tried with any uid
+
+/// What to do about who we're running as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Root {
+    /// Not root, or no way of telling on this platform
+    No,
+    /// Root, and nobody said that's fine
+    Refuse,
+    /// Root, with `--allow-root`
+    Allowed,
+}
+
+/// `euid` is `None` where there's no such thing
+pub fn decide(euid: Option<u32>, allow_root: bool) -> Root {
+    match (euid, allow_root) {
+        (Some(0), false) => Root::Refuse,
+        (Some(0), true) => Root::Allowed,
+        _ => Root::No,
+    }
+}
+
+/// The effective uid we're running as
+#[cfg(unix)]
+pub fn euid() -> Option<u32> {
+    // Can't fail, says POSIX
+    Some(unsafe { libc::geteuid() })
+}
+
+/// Nothing like a uid to go on, so the check doesn't happen
+#[cfg(not(unix))]
+pub fn euid() -> Option<u32> {
+    None
+}
+
+/// `decide`, put into words. An error for the command line if it's not starting, or a warning
+/// for the health panel if it's going ahead as root
+pub fn check_root(euid: Option<u32>, allow_root: bool) -> Result<Option<String>, String> {
+    match decide(euid, allow_root) {
+        Root::No => Ok(None),
+        Root::Refuse => Err(
+            "refusing to run as root, since this starts CPU burners and shell \
+                             commands from the tasks file. Run it as yourself, or pass \
+                             --allow-root if you really mean it"
+                .to_string(),
+        ),
+        Root::Allowed => Ok(Some(
+            "running as root, since --allow-root says so. Shell tasks run as root too".to_string(),
+        )),
+    }
+}
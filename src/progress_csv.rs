@@ -0,0 +1,81 @@
+//! `progress_csv`: a `timestamp,task_id,progress` line for every time a task's progress moves,
+//! for plotting afterwards. Buffered, and flushed every couple of seconds rather than every
+//! line. Closing it (or just dropping it, on the way out however that goes) flushes the rest
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local, SecondsFormat};
+use tracing::warn;
+
+use crate::tasks::Id;
+
+/// The first line of every file
+pub const HEADER: &str = "timestamp,task_id,progress";
+
+/// Lines sit in the buffer at most about this long
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug)]
+pub struct ProgressCsv {
+    path: PathBuf,
+    out: BufWriter<File>,
+    flushed: Instant,
+}
+
+impl ProgressCsv {
+    /// Carries on the end of `path` if it's already there, with the header first if it isn't
+    pub fn create(path: &Path, now: Instant) -> io::Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let fresh = file.metadata()?.len() == 0;
+        let mut out = BufWriter::new(file);
+        if fresh {
+            writeln!(out, "{HEADER}")?;
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            out,
+            flushed: now,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Task `id` got to `progress` at `at`. Only into the buffer
+    pub fn record(&mut self, at: DateTime<Local>, id: Id, progress: u8) -> io::Result<()> {
+        let timestamp = at.to_rfc3339_opts(SecondsFormat::Millis, false);
+        writeln!(self.out, "{timestamp},{id},{progress}")
+    }
+
+    /// Flushes if the last one was `FLUSH_INTERVAL` ago. True if it did
+    pub fn tick(&mut self, now: Instant) -> io::Result<bool> {
+        if now.saturating_duration_since(self.flushed) < FLUSH_INTERVAL {
+            return Ok(false);
+        }
+        self.flushed = now;
+        self.out.flush()?;
+        Ok(true)
+    }
+
+    /// Everything out, and the file closed
+    pub fn close(mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+impl Drop for ProgressCsv {
+    fn drop(&mut self) {
+        if let Err(e) = self.out.flush() {
+            warn!("couldn't finish {}: {e}", self.path.display());
+        }
+    }
+}
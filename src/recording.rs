@@ -0,0 +1,245 @@
+//! `--record` and `--replay`: every key the app handled (and every task message, for the
+//! curious) as JSON lines with a millisecond timestamp, under a header with the format's version
+//! and the seed the workers were given. Replaying feeds the keys back in at the same pace. Unlike
+//! the history file, a line that doesn't parse fails the whole load, since skipping one key
+//! would send the rest somewhere else entirely
+
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, LineWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::tasks::TaskTxMsg;
+
+/// Goes up whenever an old recording wouldn't replay the same any more
+pub const VERSION: u32 = 3;
+
+/// The first line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Header {
+    clustrctrl_recording: u32,
+    seed: u64,
+}
+
+/// Every line after it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Line {
+    /// Since the recorder was made
+    at_ms: u64,
+    #[serde(flatten)]
+    event: Recorded,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Recorded {
+    /// As `key_name` writes it
+    Key(String),
+    /// Only there to read. Workers make their own again from the seed
+    Task(String),
+}
+
+/// Why a recording can't be replayed
+#[derive(Debug)]
+pub enum RecordingError {
+    Io(io::Error),
+    /// The first line isn't a header at all
+    NotARecording,
+    /// Made by a different version of the format
+    Version(u32),
+    /// Line numbers count from 1, the header included
+    BadLine(usize, serde_json::Error),
+    UnknownKey(usize, String),
+}
+
+/// A recording read back, down to what replaying needs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recording {
+    pub seed: u64,
+    /// When each key was pressed, since the start, in order
+    pub keys: Vec<(Duration, KeyEvent)>,
+}
+
+/// Writes a recording as the app goes. Lines go out as they happen, so a crash keeps
+/// everything up to it
+#[derive(Debug)]
+pub struct Recorder {
+    file: LineWriter<File>,
+    started: Instant,
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Io(e) => e.fmt(f),
+            RecordingError::NotARecording => write!(f, "it isn't a clustrctrl recording"),
+            RecordingError::Version(found) => write!(
+                f,
+                "it was recorded in format version {found}, this build only replays {VERSION}"
+            ),
+            RecordingError::BadLine(line, e) => write!(f, "line {line} is invalid: {e}"),
+            RecordingError::UnknownKey(line, key) => {
+                write!(f, "line {line} has a key we don't know: `{key}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RecordingError::Io(e) => Some(e),
+            RecordingError::BadLine(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RecordingError {
+    fn from(e: io::Error) -> Self {
+        RecordingError::Io(e)
+    }
+}
+
+impl Recording {
+    /// The whole of `path`, or the first thing wrong with it
+    pub fn load(path: &Path) -> Result<Self, RecordingError> {
+        let text = fs::read_to_string(path)?;
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| (idx + 1, line))
+            .filter(|(_, line)| !line.trim().is_empty());
+        let header: Header = lines
+            .next()
+            .and_then(|(_, line)| serde_json::from_str(line).ok())
+            .ok_or(RecordingError::NotARecording)?;
+        if header.clustrctrl_recording != VERSION {
+            return Err(RecordingError::Version(header.clustrctrl_recording));
+        }
+        let mut keys = vec![];
+        for (number, text) in lines {
+            let line: Line =
+                serde_json::from_str(text).map_err(|e| RecordingError::BadLine(number, e))?;
+            if let Recorded::Key(name) = line.event {
+                let key = parse_key(&name).ok_or(RecordingError::UnknownKey(number, name))?;
+                keys.push((Duration::from_millis(line.at_ms), key));
+            }
+        }
+        Ok(Self {
+            seed: header.seed,
+            keys,
+        })
+    }
+}
+
+impl Recorder {
+    /// Starts a new recording at `path`, over anything that was there
+    pub fn create(path: &Path, seed: u64) -> io::Result<Self> {
+        let mut file = LineWriter::new(File::create(path)?);
+        let header = Header {
+            clustrctrl_recording: VERSION,
+            seed,
+        };
+        writeln!(file, "{}", serde_json::to_string(&header)?)?;
+        Ok(Self {
+            file,
+            started: Instant::now(),
+        })
+    }
+
+    /// Keys `key_name` can't name aren't ones the app does anything with, so they're left out
+    pub fn key(&mut self, key: &KeyEvent) -> io::Result<()> {
+        match key_name(key) {
+            Some(name) => self.write(Recorded::Key(name)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn task(&mut self, msg: &TaskTxMsg) -> io::Result<()> {
+        self.write(Recorded::Task(format!("{msg:?}")))
+    }
+
+    fn write(&mut self, event: Recorded) -> io::Result<()> {
+        let line = Line {
+            at_ms: self.started.elapsed().as_millis() as u64,
+            event,
+        };
+        writeln!(self.file, "{}", serde_json::to_string(&line)?)
+    }
+}
+
+/// "ctrl+k", "shift+J", "f1", "enter". None for keys nothing in the app listens for
+pub fn key_name(key: &KeyEvent) -> Option<String> {
+    let code = match key.code {
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char(ch) => ch.to_string(),
+        KeyCode::F(n) => format!("f{n}"),
+        code => NAMED
+            .iter()
+            .find(|&&(_, named)| named == code)?
+            .0
+            .to_string(),
+    };
+    let mut name = String::new();
+    for (prefix, modifier) in MODIFIERS {
+        if key.modifiers.contains(modifier) {
+            name.push_str(prefix);
+        }
+    }
+    name.push_str(&code);
+    Some(name)
+}
+
+/// The other way round from `key_name`
+pub fn parse_key(name: &str) -> Option<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = name;
+    // A bare "+" is a key of its own, not a modifier missing its key
+    while let Some((prefix, modifier)) = MODIFIERS
+        .iter()
+        .find(|(prefix, _)| rest.len() > prefix.len() && rest.starts_with(prefix))
+    {
+        modifiers |= *modifier;
+        rest = &rest[prefix.len()..];
+    }
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(ch), None) => KeyCode::Char(ch),
+        _ if rest == "space" => KeyCode::Char(' '),
+        _ => match rest.strip_prefix('f').and_then(|n| n.parse().ok()) {
+            Some(n) => KeyCode::F(n),
+            None => NAMED.iter().find(|&&(named, _)| named == rest)?.1,
+        },
+    };
+    Some(KeyEvent::new(code, modifiers))
+}
+
+const MODIFIERS: [(&str, KeyModifiers); 3] = [
+    ("ctrl+", KeyModifiers::CONTROL),
+    ("alt+", KeyModifiers::ALT),
+    ("shift+", KeyModifiers::SHIFT),
+];
+
+const NAMED: [(&str, KeyCode); 14] = [
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("backspace", KeyCode::Backspace),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("delete", KeyCode::Delete),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+];
@@ -0,0 +1,102 @@
+//! Rows taken out of the table, which can be put back for a little while. IDs are indexes into
+//! the task list, so a removed task doesn't actually leave it: it's hidden, first where `u` can
+//! still bring it back and then for good. It's discarded (can't come back) once it's been waiting
+//! longer than the TTL or enough newer removals have pushed it out. Only discarded tasks are
+//! properly gone, so only messages for those count as strays
+
+use std::{
+    collections::{HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+use crate::tasks::Id;
+
+/// How long `u` can bring a removal back
+pub const UNDO_TTL: Duration = Duration::from_secs(10);
+/// How many removals can be waiting to be undone. The oldest is discarded to make room
+pub const UNDO_DEPTH: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct Removals {
+    /// Removed and still undoable, oldest first, with when they were removed
+    undoable: VecDeque<(Id, Instant)>,
+    /// Removed for good
+    gone: HashSet<Id>,
+    ttl: Duration,
+    depth: usize,
+}
+
+impl Default for Removals {
+    fn default() -> Self {
+        Self::new(UNDO_TTL, UNDO_DEPTH)
+    }
+}
+
+impl Removals {
+    pub fn new(ttl: Duration, depth: usize) -> Self {
+        Self {
+            undoable: VecDeque::new(),
+            gone: HashSet::new(),
+            ttl,
+            depth: depth.max(1),
+        }
+    }
+
+    /// Whether `id` is out of the table, undoable or not
+    pub fn contains(&self, id: Id) -> bool {
+        self.gone.contains(&id) || self.undoable.iter().any(|&(undoable, _)| undoable == id)
+    }
+
+    /// Whether `id` has been discarded, past undoing
+    pub fn is_gone(&self, id: Id) -> bool {
+        self.gone.contains(&id)
+    }
+
+    /// Takes `id` out of the table at `now`. Gives back whatever that pushed out of the undo
+    /// buffer, which is gone for good now
+    pub fn remove(&mut self, id: Id, now: Instant) -> Option<Id> {
+        if self.contains(id) {
+            return None;
+        }
+        self.undoable.push_back((id, now));
+        if self.undoable.len() > self.depth {
+            return self.undoable.pop_front().map(|(id, _)| self.discard(id));
+        }
+        None
+    }
+
+    /// Puts the latest removal back, if there's one that can still come back. Something past the
+    /// TTL stays removed even if `expire` hasn't got round to it
+    pub fn undo(&mut self, now: Instant) -> Option<Id> {
+        let &(id, at) = self.undoable.back()?;
+        if now.saturating_duration_since(at) > self.ttl {
+            return None;
+        }
+        self.undoable.pop_back();
+        Some(id)
+    }
+
+    /// Discards everything that's been undoable longer than the TTL, and says what it was,
+    /// oldest first
+    pub fn expire(&mut self, now: Instant) -> Vec<Id> {
+        let mut expired = vec![];
+        while let Some(&(id, at)) = self.undoable.front() {
+            if now.saturating_duration_since(at) <= self.ttl {
+                break;
+            }
+            self.undoable.pop_front();
+            expired.push(self.discard(id));
+        }
+        expired
+    }
+
+    /// How many removals `u` could bring back
+    pub fn undoable(&self) -> usize {
+        self.undoable.len()
+    }
+
+    fn discard(&mut self, id: Id) -> Id {
+        self.gone.insert(id);
+        id
+    }
+}
@@ -0,0 +1,128 @@
+//! JSON dump of every task at exit, for post-mortems, along with how quickly their messages got
+//! through. Tasks hold handles and channels, so we copy out the parts worth keeping instead of
+//! making Task itself serializable
+
+use std::{fs, path::Path};
+
+use color_eyre::eyre::Result;
+use serde::Serialize;
+
+use crate::{
+    cpu,
+    latency::Histogram,
+    summary::TaskSummary,
+    tasks::{CancelState, Task},
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskReport {
+    /// id, name and status, same as the webhook sends
+    #[serde(flatten)]
+    pub summary: TaskSummary,
+    pub candidate: String,
+    pub description: String,
+    pub progress: u8,
+    /// CPU its worker used, as of its last report. Only dummies have it
+    pub cpu_ms: Option<u64>,
+    /// `cpu_ms` is summed from the work's wall time, the thread had no CPU clock
+    pub cpu_estimated: bool,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub halt_requested: bool,
+    pub cancel_state: CancelState,
+    pub exit_code: Option<i32>,
+    pub cancel_reason: Option<String>,
+    pub progress_at_cancel: Option<u8>,
+    pub cancel_requested_at: Option<String>,
+    pub cancel_acked_at: Option<String>,
+    pub note: Option<String>,
+    pub milestones: Vec<MilestoneEntry>,
+    pub audit: Vec<AuditEntry>,
+}
+
+/// The whole file
+#[derive(Debug, Serialize)]
+pub struct Report<'a> {
+    /// Which run this was, see `session`
+    pub session: Option<&'a str>,
+    pub tasks: Vec<TaskReport>,
+    /// Every task's `cpu_ms` added up, None if none of them had any
+    pub cpu_total_ms: Option<u64>,
+    /// Some of that's estimated
+    pub cpu_estimated: bool,
+    /// How long task messages took to get from the worker to the UI, bucketed
+    pub delivery_latency: &'a Histogram,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MilestoneEntry {
+    pub at: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub at: String,
+    pub message: String,
+}
+
+impl From<&Task> for TaskReport {
+    fn from(task: &Task) -> Self {
+        Self {
+            summary: TaskSummary::from(task),
+            candidate: task.candidate.to_string(),
+            description: task.description.to_string(),
+            progress: task.progress,
+            cpu_ms: task.cpu.map(|cpu| cpu.ms),
+            cpu_estimated: task.cpu.is_some_and(|cpu| cpu.estimated),
+            start: task.start.map(|start| start.to_rfc3339()),
+            end: task.end.map(|end| end.to_rfc3339()),
+            halt_requested: task.cancel != CancelState::None,
+            cancel_state: task.cancel,
+            exit_code: task.exit_code,
+            cancel_reason: task.cancel_reason.clone(),
+            progress_at_cancel: task.progress_at_cancel,
+            cancel_requested_at: task.cancel_requested_at.map(|at| at.to_rfc3339()),
+            cancel_acked_at: task.cancel_acked_at.map(|at| at.to_rfc3339()),
+            note: task.note.clone(),
+            milestones: task
+                .milestones
+                .iter()
+                .map(|(at, label)| MilestoneEntry {
+                    at: at.to_rfc3339(),
+                    label: label.clone(),
+                })
+                .collect(),
+            audit: task
+                .audit
+                .iter()
+                .map(|(at, message)| AuditEntry {
+                    at: at.to_rfc3339(),
+                    message: message.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Writes the report for all tasks, creating parent directories like the log file does
+pub fn write_report(
+    path: &Path,
+    session: Option<&str>,
+    tasks: &[Task],
+    latency: &Histogram,
+) -> Result<()> {
+    let cpu = cpu::total(tasks.iter().filter_map(|task| task.cpu));
+    let report = Report {
+        session,
+        tasks: tasks.iter().map(TaskReport::from).collect(),
+        cpu_total_ms: cpu.map(|cpu| cpu.ms),
+        cpu_estimated: cpu.is_some_and(|cpu| cpu.estimated),
+        delivery_latency: latency,
+    };
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(&report)?)?;
+    Ok(())
+}
@@ -0,0 +1,118 @@
+//! Trying failed tasks again. A retry is a new task with its own ID that points back at the first
+//! attempt, and waits out a backoff as a scheduled row before it starts. Whether to retry, and
+//! when, only depends on the policy, which attempt just failed and the time, so it's all worked
+//! out here away from the app
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{chain::ARROW, tasks::Id};
+
+/// Longest any one backoff gets, however many times it's doubled
+pub const MAX_BACKOFF: Duration = Duration::from_secs(600);
+
+/// How many more tries a failed task gets, and how long to wait before each. `[retry]` in the
+/// config, or `retry = { ... }` on a candidate in the tasks file, which wins
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Tries after the first. 0 is never
+    pub max_retries: u32,
+    /// Wait before the first retry. Doubles for each one after
+    pub backoff_secs: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            backoff_secs: 5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Tries in all, the first included
+    pub fn attempts(&self) -> u32 {
+        self.max_retries.saturating_add(1)
+    }
+
+    /// How long to wait before attempt `n` (2 is the first retry): `backoff_secs`, doubling each
+    /// time, up to `MAX_BACKOFF`
+    pub fn backoff(&self, n: u32) -> Duration {
+        let doublings = n.saturating_sub(2).min(31);
+        let secs = self.backoff_secs.saturating_mul(1 << doublings);
+        Duration::from_secs(secs).min(MAX_BACKOFF)
+    }
+}
+
+/// Which try a task is, out of how many it gets. Only tasks with a policy that retries have one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attempt {
+    pub n: u32,
+    pub of: u32,
+}
+
+impl fmt::Display for Attempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "attempt {}/{}", self.n, self.of)
+    }
+}
+
+/// What to do about a task that's just failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// Nothing, the policy doesn't retry
+    Off,
+    /// Try again as `next`, once it's `at`
+    Retry { next: Attempt, at: Instant },
+    /// That was the last try
+    GiveUp,
+}
+
+/// Attempt `n` failed at `now`. What happens next
+pub fn decide(policy: &RetryPolicy, n: u32, now: Instant) -> Decision {
+    if policy.max_retries == 0 {
+        return Decision::Off;
+    }
+    let of = policy.attempts();
+    if n >= of {
+        return Decision::GiveUp;
+    }
+    let next = Attempt { n: n + 1, of };
+    Decision::Retry {
+        next,
+        at: now + policy.backoff(next.n),
+    }
+}
+
+/// Every attempt at the same thing as `id`, in order, from each task's ID and the first attempt
+/// it's a retry of. Just `id` if it was never retried
+pub fn attempts(links: impl IntoIterator<Item = (Id, Option<Id>)>, id: Id) -> Vec<Id> {
+    let links: Vec<(Id, Option<Id>)> = links.into_iter().collect();
+    let first = links
+        .iter()
+        .find(|&&(task, _)| task == id)
+        .and_then(|&(_, of)| of)
+        .unwrap_or(id);
+    let mut ids: Vec<Id> = links
+        .iter()
+        .filter(|&&(task, of)| task == first || of == Some(first))
+        .map(|&(task, _)| task)
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+/// `#3 ⇢ #7 ⇢ #9`, the way chains are written
+pub fn describe(attempts: &[Id]) -> String {
+    attempts
+        .iter()
+        .map(|id| format!("#{id}"))
+        .collect::<Vec<_>>()
+        .join(&format!(" {ARROW} "))
+}
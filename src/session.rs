@@ -0,0 +1,63 @@
+//! A name for each run, like `wobbly-otter-42`, so several of them (at once, or over a day of
+//! demos) can be told apart. It's in the title and every traced event, and on the end of the log
+//! file's and the report's names. Names come from `--session` or are made up here
+
+use std::path::{Path, PathBuf};
+
+use rand::{seq::IndexedRandom, Rng};
+
+const ADJECTIVES: [&str; 24] = [
+    "wobbly", "sleepy", "brave", "fuzzy", "grumpy", "jolly", "sneaky", "quiet", "bouncy", "dizzy",
+    "plucky", "soggy", "zesty", "lanky", "nimble", "rusty", "shiny", "spiky", "tidy", "wiry",
+    "breezy", "cheeky", "dapper", "frosty",
+];
+
+const ANIMALS: [&str; 24] = [
+    "otter", "badger", "heron", "lemur", "marmot", "newt", "ocelot", "puffin", "quokka", "raven",
+    "stoat", "tapir", "vole", "walrus", "yak", "alpaca", "bison", "dingo", "ferret", "gecko",
+    "ibis", "jackal", "koala", "moose",
+];
+
+/// An adjective, an animal and a number from 10 to 99
+pub fn generate(rng: &mut impl Rng) -> String {
+    let adjective = ADJECTIVES.choose(rng).expect("there are some");
+    let animal = ANIMALS.choose(rng).expect("there are some");
+    format!("{adjective}-{animal}-{}", rng.random_range(10..100))
+}
+
+/// For `--session`: it ends up in file names, so only letters, digits, `-` and `_`
+pub fn parse(text: &str) -> Result<String, String> {
+    let fits = |ch: char| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_';
+    match text {
+        "" => Err("can't be empty".to_string()),
+        _ if text.chars().all(fits) => Ok(text.to_string()),
+        _ => Err("only letters, digits, - and _".to_string()),
+    }
+}
+
+/// `path` with `-session` before its extension: `logs/clustrctrl.log` is
+/// `logs/clustrctrl-wobbly-otter-42.log`
+pub fn suffixed(path: &Path, session: &str) -> PathBuf {
+    with_stem(path, &format!("-{session}"))
+}
+
+/// `suffixed`, or if that's taken already `-2` after it, then `-3` and so on. Nothing's ever
+/// written over
+pub fn unique(path: &Path, session: &str) -> PathBuf {
+    let mut candidate = suffixed(path, session);
+    let mut count = 1;
+    while candidate.exists() {
+        count += 1;
+        candidate = with_stem(path, &format!("-{session}-{count}"));
+    }
+    candidate
+}
+
+fn with_stem(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{stem}{suffix}.{}", extension.to_string_lossy()),
+        None => format!("{stem}{suffix}"),
+    };
+    path.with_file_name(name)
+}
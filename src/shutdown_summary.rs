@@ -0,0 +1,96 @@
+//! What's printed once the terminal's back to normal: every task that hadn't ended by the time
+//! we left, how far it got and how long ago it was asked to stop. Mostly these are dummies that
+//! were still in a work block when the shutdown gave up on them. Plain text, for the scrollback
+
+use std::{fmt, path::PathBuf};
+
+use chrono::{DateTime, Local};
+
+use crate::{summary::TaskSummary, tasks::Task};
+
+/// A task that was still going at exit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Abandoned {
+    pub task: TaskSummary,
+    pub progress: u8,
+    /// How long before exit the stop request went out. None if it never did
+    pub stop_sent_secs: Option<i64>,
+}
+
+/// The tasks as they were when `App::run` finished, for `main` to print after restoring
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShutdownSummary {
+    pub abandoned: Vec<Abandoned>,
+    /// Where `--report` wrote everything else, if it was asked to
+    pub report: Option<PathBuf>,
+}
+
+impl ShutdownSummary {
+    /// Everything in `tasks` that isn't over, as of `now`
+    pub fn of(tasks: &[Task], report: Option<PathBuf>, now: DateTime<Local>) -> Self {
+        let abandoned = tasks
+            .iter()
+            .filter(|task| !task.status.is_terminal())
+            .map(|task| Abandoned {
+                task: TaskSummary::from(task),
+                progress: task.progress,
+                stop_sent_secs: task
+                    .cancel_requested_at
+                    .map(|at| (now - at).num_seconds().max(0)),
+            })
+            .collect();
+        Self { abandoned, report }
+    }
+}
+
+/// "Goodbye!" and nothing else if everything stopped in time, otherwise a table of the ones that
+/// didn't. Ends in a newline either way
+impl fmt::Display for ShutdownSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.abandoned.is_empty() {
+            writeln!(f, "Goodbye! Every task stopped in time.")?;
+        } else {
+            let count = self.abandoned.len();
+            let s = if count == 1 { "" } else { "s" };
+            writeln!(f, "Goodbye! {count} task{s} hadn't stopped when we left:")?;
+            let rows: Vec<[String; 5]> = self
+                .abandoned
+                .iter()
+                .map(|abandoned| {
+                    [
+                        abandoned.task.id.to_string(),
+                        abandoned.task.name.clone(),
+                        abandoned.task.status.clone(),
+                        format!("{}%", abandoned.progress),
+                        match abandoned.stop_sent_secs {
+                            Some(secs) => format!("{secs}s ago"),
+                            None => "never".to_string(),
+                        },
+                    ]
+                })
+                .collect();
+            let header = ["ID", "Name", "Status", "Progress", "Stop sent"].map(String::from);
+            let widths: Vec<usize> = (0..header.len())
+                .map(|col| {
+                    rows.iter()
+                        .chain([&header])
+                        .map(|row| row[col].chars().count())
+                        .max()
+                        .unwrap_or(0)
+                })
+                .collect();
+            for row in [&header].into_iter().chain(&rows) {
+                let cells: Vec<String> = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, &width)| format!("{cell:<width$}"))
+                    .collect();
+                writeln!(f, "  {}", cells.join("  ").trim_end())?;
+            }
+        }
+        if let Some(path) = &self.report {
+            writeln!(f, "Full report: {}", path.display())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+//! Fills in progress between reports, since they only show up once per work block and the
+//! percentage jumps in big ugly steps otherwise. Pure guesswork from the last two reports, same as
+//! the ETA - the worker isn't asked for anything extra
+
+use std::time::Instant;
+
+/// How far past the latest real report we're allowed to get ahead, in percent
+const MAX_LEAD: f64 = 3.0;
+/// Reports this many gaps overdue are stale, and we stop guessing past them
+const STALE_GAPS: f64 = 2.0;
+/// Only a real finish gets to 100
+const CEILING: f64 = 99.0;
+
+#[derive(Debug, Default)]
+pub struct SmoothProgress {
+    previous: Option<(Instant, u8)>,
+    latest: Option<(Instant, u8)>,
+    /// What's been shown so far. Only ever goes up
+    shown: f64,
+}
+
+impl SmoothProgress {
+    pub fn record(&mut self, at: Instant, progress: u8) {
+        self.previous = self.latest.replace((at, progress));
+        self.shown = self.shown.max(progress as f64);
+    }
+
+    /// Moves the shown value along. Only call it while the task is actually running - anything
+    /// else leaves it frozen where it was
+    pub fn update(&mut self, now: Instant) {
+        if let (Some(previous), Some(latest)) = (self.previous, self.latest) {
+            self.shown = self.shown.max(interpolate(previous, latest, now));
+        }
+    }
+
+    pub fn value(&self) -> u8 {
+        self.shown as u8
+    }
+
+    /// The last real report, no guessing
+    pub fn reported(&self) -> Option<u8> {
+        self.latest.map(|(_, progress)| progress)
+    }
+}
+
+/// Where progress probably is at `now`, carrying on at the rate between the two reports. Never
+/// more than `MAX_LEAD` past the latest one, and just the latest one if the next is overdue or
+/// there's no rate to go on. Clock values are passed in so it can be checked without waiting
+pub fn interpolate(previous: (Instant, u8), latest: (Instant, u8), now: Instant) -> f64 {
+    let (then, from) = previous;
+    let (last, to) = latest;
+    let gap = last.saturating_duration_since(then).as_secs_f64();
+    let raw = to as f64;
+    if gap == 0.0 || to <= from {
+        return raw;
+    }
+    let since = now.saturating_duration_since(last).as_secs_f64();
+    if since > gap * STALE_GAPS {
+        return raw;
+    }
+    let rate = (to - from) as f64 / gap;
+    (raw + rate * since)
+        .min(raw + MAX_LEAD)
+        .min(CEILING.max(raw))
+}
@@ -0,0 +1,57 @@
+//! How the main screen's height is shared between the task table and the message stream. Only
+//! arithmetic, no terminal involved, so it can be checked at the awkward sizes without drawing
+
+use serde::{Deserialize, Serialize};
+
+use crate::layout_util::saturating_height;
+
+/// Rows the table wants on top of one per task: borders, header, and a bit of breathing room
+pub const TABLE_CHROME: u16 = 6;
+/// The logger gets at least this many rows whenever there's room for a table too
+pub const LOGGER_MIN: u16 = 8;
+/// Borders, header and one row. Anything less isn't a table
+pub const TABLE_MIN: u16 = 4;
+
+/// Which side gets the room when there isn't enough for both. `=` on the main screen cycles them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitMode {
+    /// The table gets what it needs, up to 60%
+    #[default]
+    Table,
+    /// Up to half each
+    Even,
+    /// Mirror image of `Table`: the table stops at 40%
+    Logger,
+}
+
+impl SplitMode {
+    pub fn next(self) -> Self {
+        match self {
+            SplitMode::Table => SplitMode::Even,
+            SplitMode::Even => SplitMode::Logger,
+            SplitMode::Logger => SplitMode::Table,
+        }
+    }
+
+    /// Most of the height the table is allowed, in percent
+    fn table_share(self) -> u32 {
+        match self {
+            SplitMode::Table => 60,
+            SplitMode::Even => 50,
+            SplitMode::Logger => 40,
+        }
+    }
+}
+
+/// Rows for the table out of `height`. The logger gets the rest. The table never takes more than
+/// it needs or its share, and always leaves the logger `LOGGER_MIN` - unless the screen is so
+/// short that would squeeze the table below `TABLE_MIN`, then the table just gets what fits
+pub fn table_height(height: u16, tasks: usize, mode: SplitMode) -> u16 {
+    let need = saturating_height(tasks.saturating_add(TABLE_CHROME as usize));
+    if height < TABLE_MIN + LOGGER_MIN {
+        return need.min(height);
+    }
+    let share = (height as u32 * mode.table_share() / 100) as u16;
+    need.min(share).min(height - LOGGER_MIN).max(TABLE_MIN)
+}
@@ -0,0 +1,61 @@
+//! The last so-many samples of something, and the usual questions about them. Made for frame
+//! times, but it doesn't care what the numbers are
+
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone)]
+pub struct RollingStats {
+    capacity: usize,
+    samples: VecDeque<f64>,
+}
+
+impl RollingStats {
+    /// Keeps at most `capacity` samples (at least one), dropping the oldest to make room
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, sample: f64) {
+        if self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Oldest first
+    pub fn samples(&self) -> impl DoubleEndedIterator<Item = f64> + ExactSizeIterator + '_ {
+        self.samples.iter().copied()
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.samples().reduce(f64::max)
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (!self.is_empty()).then(|| self.samples().sum::<f64>() / self.len() as f64)
+    }
+
+    /// Nearest-rank percentile, `p` from 0 to 1. So 0.5 is the median and always one of the
+    /// samples, never something in between. Sorts a copy, which is nothing at a few hundred samples
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<f64> = self.samples().collect();
+        sorted.sort_by(f64::total_cmp);
+        let rank = (p.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.saturating_sub(1)])
+    }
+}
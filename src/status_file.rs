@@ -0,0 +1,122 @@
+//! A small JSON file of counts for status bars (tmux, polybar...) to read whenever they like. It's
+//! written to a temporary file beside it and renamed over the top, so a reader never catches it
+//! half written. Only rewritten when the counts change, and not more than once a second however
+//! much they do
+
+use std::{
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::{clock::Clock, tally::Tally};
+
+/// However much changes, no more writes than this
+pub const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What goes in the file
+#[derive(Debug, Serialize)]
+struct Status {
+    running: usize,
+    sleeping: usize,
+    done: usize,
+    failed: usize,
+    overall_pct: Option<u8>,
+    /// RFC 3339
+    updated_at: String,
+}
+
+#[derive(Debug)]
+pub struct StatusFile {
+    path: PathBuf,
+    /// What's in the file now
+    written: Option<Tally>,
+    /// When the last write was, whether it worked or not
+    tried: Option<Instant>,
+    /// A write's failed since the last one that worked, and it's been logged
+    failing: bool,
+}
+
+impl StatusFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            written: None,
+            tried: None,
+            failing: false,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes `tally` out if it's changed and the last write was long enough ago. Otherwise it
+    /// waits for a later call. True if it wrote
+    pub fn update(&mut self, tally: Tally, clock: &dyn Clock) -> bool {
+        let now = clock.now_instant();
+        let too_soon = self
+            .tried
+            .is_some_and(|at| now.saturating_duration_since(at) < MIN_INTERVAL);
+        if self.written == Some(tally) || too_soon {
+            return false;
+        }
+        self.tried = Some(now);
+        let status = Status {
+            running: tally.running,
+            sleeping: tally.sleeping,
+            done: tally.done,
+            failed: tally.failed,
+            overall_pct: tally.overall_pct,
+            updated_at: clock.now_local().to_rfc3339(),
+        };
+        match self.write(&status) {
+            Ok(()) => {
+                self.written = Some(tally);
+                if self.failing {
+                    info!("status file {} is writable again", self.path.display());
+                    self.failing = false;
+                }
+                true
+            }
+            Err(e) => {
+                if !self.failing {
+                    warn!("couldn't write status file {}: {e}", self.path.display());
+                    self.failing = true;
+                }
+                false
+            }
+        }
+    }
+
+    /// The file next to it that's written first
+    pub fn temp_path(&self) -> PathBuf {
+        let mut name = self
+            .path
+            .file_name()
+            .map(OsString::from)
+            .unwrap_or_default();
+        name.push(".tmp");
+        self.path.with_file_name(name)
+    }
+
+    fn write(&self, status: &Status) -> io::Result<()> {
+        let temp = self.temp_path();
+        fs::write(&temp, serde_json::to_string(status)?)?;
+        fs::rename(&temp, &self.path)
+    }
+
+    /// Takes the file away on the way out, so nothing reads stale counts off it. Already gone is
+    /// fine
+    pub fn remove(&self) {
+        match fs::remove_file(&self.path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => warn!("couldn't remove status file {}: {e}", self.path.display()),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! An HTTP listener for dashboards to scrape, off unless `http.listen` is set: `/tasks` is every
+//! task as the report has it, `/stats` the counts, and `/metrics` the counts again for
+//! Prometheus. It serves a copy the main loop refreshes each pass, so a slow scraper never holds
+//! up the UI (or sees it halfway through changing)
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+};
+
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Json, Router};
+use serde::{Deserialize, Serialize};
+use tokio::{net::TcpListener, task::JoinHandle};
+use tracing::error;
+
+use crate::{report::TaskReport, tally::Tally, tasks::Task};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Like `127.0.0.1:8090`. Nothing listens if it isn't set
+    pub listen: Option<SocketAddr>,
+}
+
+/// The tasks as of the main loop's last pass
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub tasks: Vec<TaskReport>,
+    /// Over every task, removed ones too, so the finished and cancelled counts only go up
+    pub tally: Tally,
+}
+
+/// Written by the main loop, read by the server
+pub type Shared = Arc<RwLock<Snapshot>>;
+
+/// What `/stats` says
+#[derive(Debug, Serialize)]
+struct Stats {
+    tasks: usize,
+    #[serde(flatten)]
+    tally: Tally,
+}
+
+impl Snapshot {
+    pub fn of(tasks: &[Task]) -> Self {
+        Self {
+            tasks: tasks.iter().map(TaskReport::from).collect(),
+            tally: Tally::of(tasks),
+        }
+    }
+}
+
+/// Swaps in a fresh snapshot of `tasks`
+pub fn refresh(shared: &Shared, tasks: &[Task]) {
+    let snapshot = Snapshot::of(tasks);
+    // A reader that panicked can't have left it half written, it only ever reads
+    *shared
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = snapshot;
+}
+
+/// Serves `shared` on `listener` until the runtime goes away
+pub fn serve(listener: TcpListener, shared: Shared) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router(shared)).await {
+            error!("status server stopped: {e}");
+        }
+    })
+}
+
+pub fn router(shared: Shared) -> Router {
+    Router::new()
+        .route("/tasks", get(tasks))
+        .route("/stats", get(stats))
+        .route("/metrics", get(metrics))
+        .with_state(shared)
+}
+
+fn read(shared: &Shared) -> Snapshot {
+    shared
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+async fn tasks(State(shared): State<Shared>) -> Json<Vec<TaskReport>> {
+    Json(read(&shared).tasks)
+}
+
+async fn stats(State(shared): State<Shared>) -> Json<Stats> {
+    let snapshot = read(&shared);
+    Json(Stats {
+        tasks: snapshot.tasks.len(),
+        tally: snapshot.tally,
+    })
+}
+
+async fn metrics(State(shared): State<Shared>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        prometheus(&read(&shared).tally),
+    )
+}
+
+/// The Prometheus text format. No progress line with nothing working, since there's no average
+/// to give
+pub fn prometheus(tally: &Tally) -> String {
+    let mut lines = vec![
+        "# HELP clustrctrl_tasks Tasks in each status right now".to_string(),
+        "# TYPE clustrctrl_tasks gauge".to_string(),
+    ];
+    for (status, count) in [
+        ("running", tally.running),
+        ("sleeping", tally.sleeping),
+        ("waiting", tally.waiting),
+        ("striking", tally.striking),
+        ("done", tally.done),
+        ("cancelled", tally.cancelled),
+        ("failed", tally.failed),
+    ] {
+        lines.push(format!("clustrctrl_tasks{{status=\"{status}\"}} {count}"));
+    }
+    for (name, help, count) in [
+        ("completed", "Tasks that finished", tally.done),
+        ("cancelled", "Tasks that were stopped", tally.cancelled),
+    ] {
+        lines.push(format!("# HELP clustrctrl_tasks_{name}_total {help}"));
+        lines.push(format!("# TYPE clustrctrl_tasks_{name}_total counter"));
+        lines.push(format!("clustrctrl_tasks_{name}_total {count}"));
+    }
+    if let Some(pct) = tally.overall_pct {
+        lines.push(
+            "# HELP clustrctrl_progress_percent Average progress of the tasks working".to_string(),
+        );
+        lines.push("# TYPE clustrctrl_progress_percent gauge".to_string());
+        lines.push(format!("clustrctrl_progress_percent {pct}"));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
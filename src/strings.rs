@@ -0,0 +1,403 @@
+//! Every word the UI shows, looked up by name so `--strings de.toml` can swap them out: status
+//! words, column headers, the controls line, hints, toasts and the help. English is built in, and
+//! a file only has to have the ones it changes. Keys are dotted, so `[status] on_strike = "Streik"`
+//! and `"status.on_strike" = "Streik"` are the same thing. `{name}` bits get filled in by `fill`.
+//! Reports, webhooks and the log stay in English, they're read by scripts and not people
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::Path,
+    sync::{LazyLock, OnceLock},
+};
+
+static STRINGS: OnceLock<Strings> = OnceLock::new();
+
+static BUILT_IN: LazyLock<Strings> = LazyLock::new(Strings::english);
+
+/// The defaults, and the list of everything there is
+pub const ENGLISH: &[(&str, &str)] = &[
+    ("status.scheduled", "Waiting"),
+    ("status.running", "Running"),
+    ("status.sleeping", "Sleeping"),
+    ("status.finalizing", "Finalizing"),
+    ("status.on_strike", "Strike!"),
+    ("status.known_unknown", "???"),
+    ("status.waiting_for_thread", "Queued"),
+    ("status.finished", "Done"),
+    ("status.canceled", "Cancelled"),
+    ("status.failed", "Failed"),
+    ("status.failed_to_start", "DOA"),
+    ("column.id", "ID"),
+    ("column.name", "Name"),
+    ("column.status", "Status"),
+    ("column.halt", "Halt?"),
+    ("column.progress", "Progress"),
+    ("column.tired", "Tired"),
+    ("column.cpu", "CPU"),
+    ("column.eta", "ETA"),
+    ("column.start", "Start Time"),
+    ("column.end", "End Time"),
+    ("column.description", "Description"),
+    ("column_letter.id", "#"),
+    ("column_letter.name", "N"),
+    ("column_letter.status", "S"),
+    ("column_letter.halt", "H"),
+    ("column_letter.progress", "P"),
+    ("column_letter.tired", "T"),
+    ("column_letter.cpu", "C"),
+    ("column_letter.eta", "E"),
+    ("column_letter.start", "B"),
+    ("column_letter.end", "F"),
+    ("column_letter.description", "D"),
+    ("glyph.working", "working"),
+    ("glyph.sleeping", "sleeping"),
+    ("glyph.finishing", "finishing"),
+    ("glyph.shell", "shell"),
+    ("glyph.strike", "strike"),
+    ("glyph.waiting", "waiting"),
+    ("glyph.starting", "starting"),
+    ("glyph.no_thread", "no thread"),
+    ("glyph.done", "done"),
+    ("glyph.failed", "failed"),
+    ("glyph.stopped", "stopped"),
+    ("glyph.never_started", "never started"),
+    ("control.actions", "Actions"),
+    ("control.add", "Add"),
+    ("control.add_anyway", "Add Anyway"),
+    ("control.again", "Again"),
+    ("control.archive", "Archive"),
+    ("control.back", "Back"),
+    ("control.batch", "Batch"),
+    ("control.cancel", "Cancel"),
+    ("control.choose", "Choose"),
+    ("control.clear", "Clear"),
+    ("control.clear_filter", "Clear Filter"),
+    ("control.details", "Details"),
+    ("control.do", "Do"),
+    ("control.edit_pool", "Edit Pool"),
+    ("control.expand", "Expand"),
+    ("control.filter", "Filter"),
+    ("control.fold", "Fold"),
+    ("control.follow", "Follow"),
+    ("control.help", "Help"),
+    ("control.intensity", "Intensity"),
+    ("control.keep", "Keep"),
+    ("control.logs", "Logs"),
+    ("control.manage_tasks", "Manage Tasks"),
+    ("control.menu", "Menu"),
+    ("control.minimize", "Minimize"),
+    ("control.negotiate", "Negotiate"),
+    ("control.new_line", "New Line"),
+    ("control.new_task", "New Task"),
+    ("control.note", "Note"),
+    ("control.quit", "Quit"),
+    ("control.remove", "Remove"),
+    ("control.save", "Save"),
+    ("control.scroll", "Scroll"),
+    ("control.set_duration", "Set Duration"),
+    ("control.sort", "Sort"),
+    ("control.start", "Start"),
+    ("control.templates", "Templates"),
+    ("control.terminate_task", "Terminate Task"),
+    ("control.timeline", "Timeline"),
+    ("control.unarchive", "Unarchive"),
+    ("control.unfold", "Unfold"),
+    ("help.title", " help "),
+    ("help.close", " any key to close "),
+    ("help.headers", "Column headers when compact (z)"),
+    ("help.glyphs", "Glyphs"),
+    ("help.startup", "Found at startup"),
+    ("help.architecture", "How the channels fit together, live"),
+    (
+        "help.templates",
+        "Templates (T then the key, T here lists them)",
+    ),
+    ("low_power.label", "(low power)"),
+    ("templates.title", " templates "),
+    ("health.title", " startup "),
+    ("health.continue", " Enter to continue "),
+    ("health.info", "info"),
+    ("health.warning", "warning"),
+    ("health.error", "error"),
+    (
+        "hint.health_continue",
+        "Enter to carry on to the main screen",
+    ),
+    (
+        "hint.main_screen_only",
+        "{key} only works from the main screen — press Esc first",
+    ),
+    (
+        "hint.while_inspecting",
+        "{key} only works while inspecting — {how}",
+    ),
+    ("hint.from_main_screen", "press F2 first"),
+    ("hint.from_elsewhere", "press Esc, then F2"),
+    ("hint.no_suspend", "Ctrl+Z has nothing to suspend to here"),
+    (
+        "hint.table_only",
+        "{key} only works where the table is — press Esc first",
+    ),
+    (
+        "hint.picker_only",
+        "{key} only works in the new task picker (F1)",
+    ),
+    ("hint.unbound", "'{key}' isn't bound to anything"),
+    ("hint.nothing_to_expand", "Nothing highlighted to expand"),
+    (
+        "hint.no_debug_overlay",
+        "F12 is turned off in the config (ui.debug_overlay)",
+    ),
+    ("hint.no_demo", "Can't play the demo: {error}"),
+    (
+        "hint.shell_duration",
+        "{name} runs a command, so it takes as long as that does",
+    ),
+    (
+        "hint.nothing_to_repeat",
+        "Nothing to repeat — add a task with F1 first",
+    ),
+    (
+        "hint.no_templates",
+        "No templates — add some with [[template]] in the config",
+    ),
+    (
+        "hint.no_template",
+        "No template on {key} — T then ? lists them",
+    ),
+    (
+        "hint.archived_read_only",
+        "Archived tasks are read-only — press a to unarchive first",
+    ),
+    (
+        "hint.archive_unfinished",
+        "Task {id} is still going — only finished tasks can be archived",
+    ),
+    (
+        "hint.remove_unfinished",
+        "Task {id} is still going — only finished tasks can be removed",
+    ),
+    (
+        "hint.nothing_to_undo",
+        "Nothing removed recently enough to undo",
+    ),
+    (
+        "hint.back_but_hidden",
+        "Task {id} is back, but not showing right now",
+    ),
+    (
+        "hint.nothing_archived",
+        "Nothing's archived yet — press a on a finished task",
+    ),
+    (
+        "hint.nothing_to_jump_to",
+        "Nothing showing to jump to ({statuses})",
+    ),
+    ("hint.no_jump_statuses", "(none set)"),
+    (
+        "hint.view_default",
+        "The table's already sorted and shown the default way",
+    ),
+    (
+        "toast.strike_over",
+        "task {id} isn't on strike anymore, nothing to negotiate",
+    ),
+    ("toast.not_on_strike", "task {id} isn't on strike"),
+    ("toast.replay_stopped", "Replay stopped, you're driving now"),
+    ("toast.replay_over", "Replay's over, it's all yours"),
+    ("toast.demo_stopped", "Demo stopped, you're driving now"),
+    (
+        "toast.nothing_to_inspect",
+        "Nothing to inspect yet — add a task with F1 first",
+    ),
+    (
+        "toast.id_column_stays",
+        "The ID column can move, but it stays",
+    ),
+    ("toast.add_failed", "Couldn't add it: {error}"),
+    ("toast.disable_failed", "Can't turn that off: {error}"),
+    ("toast.delete_failed", "Can't delete that: {error}"),
+    (
+        "toast.pool_not_saved",
+        "Not saved, the tasks file didn't load. Changes last until you quit",
+    ),
+    ("toast.pool_save_failed", "Couldn't save the pool: {error}"),
+    ("toast.guard_blocked", "Not adding {name}: {reason}"),
+    ("toast.added_again", "Added {name} (#{id})"),
+    (
+        "toast.choose_template",
+        "Template: its key, or ? to list them",
+    ),
+    ("toast.template_added", "{name}: added {count}"),
+    (
+        "toast.template_guarded",
+        "{name} needs confirming first — add it with F1",
+    ),
+    ("toast.start_failed", "Couldn't start {name}: {error}"),
+    (
+        "toast.batch_blocked",
+        "Added {added}, left out {blocked} that guards blocked",
+    ),
+    (
+        "toast.batch_delayed",
+        "Added {added} tasks, {later} more on a delay",
+    ),
+    (
+        "toast.retry_called_off",
+        "Called off the retry of task {id} ({retries})",
+    ),
+    ("toast.cant_stop", "Can't stop that: {error}"),
+    ("toast.stop_failed", "Couldn't stop it: {error}"),
+    (
+        "toast.chaos_on",
+        "Chaos on — new tasks will misbehave on purpose",
+    ),
+    ("toast.chaos_off", "Chaos off"),
+    (
+        "toast.low_power_on",
+        "Low power — sparse reports, slow ticks, nothing animated",
+    ),
+    ("toast.low_power_off", "Full power"),
+    ("toast.compact_on", "Compact — ? says what the headers mean"),
+    ("toast.compact_off", "Roomy again"),
+    ("toast.layout", "Layout: {layout}"),
+    ("toast.layout_table", "table first"),
+    ("toast.layout_even", "half and half"),
+    ("toast.layout_logger", "messages first"),
+    (
+        "toast.shutting_down",
+        "Shutting down, not accepting new tasks",
+    ),
+    ("toast.removed", "removed task {id} — press u to undo"),
+];
+
+/// English with whatever a file changed on top
+#[derive(Debug, Clone)]
+pub struct Strings {
+    table: HashMap<&'static str, String>,
+}
+
+impl Strings {
+    pub fn english() -> Self {
+        Self {
+            table: ENGLISH
+                .iter()
+                .map(|&(key, text)| (key, text.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Reads an override file. Anything it leaves out stays English, and the problems (a single
+    /// line listing what's missing, another for keys we don't have) are for the startup warnings
+    pub fn load(path: &Path) -> Result<(Self, Vec<String>), String> {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<(Self, Vec<String>), String> {
+        let file: toml::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+        let mut given = vec![];
+        flatten("", &file, &mut given)?;
+        let mut strings = Self::english();
+        let mut set = vec![];
+        let mut unknown = vec![];
+        for (key, text) in given {
+            match ENGLISH.iter().find(|&&(known, _)| known == key) {
+                Some(&(known, _)) => {
+                    strings.table.insert(known, text);
+                    set.push(known);
+                }
+                None => unknown.push(key),
+            }
+        }
+        let missing: Vec<&str> = ENGLISH
+            .iter()
+            .map(|&(key, _)| key)
+            .filter(|key| !set.contains(key))
+            .collect();
+        let mut problems = vec![];
+        if !missing.is_empty() {
+            problems.push(format!(
+                "the strings file leaves out {}, so they're in English: {}",
+                plural(missing.len()),
+                missing.join(", ")
+            ));
+        }
+        if !unknown.is_empty() {
+            unknown.sort();
+            problems.push(format!(
+                "the strings file has {} we don't use: {}",
+                plural(unknown.len()),
+                unknown.join(", ")
+            ));
+        }
+        Ok((strings, problems))
+    }
+
+    pub fn get(&self, key: &str) -> &str {
+        self.table
+            .get(key)
+            .map(String::as_str)
+            .unwrap_or_else(|| english(key))
+    }
+}
+
+fn plural(n: usize) -> String {
+    match n {
+        1 => "1 key".to_string(),
+        n => format!("{n} keys"),
+    }
+}
+
+/// `[status] on_strike = ".."` into `("status.on_strike", "..")`
+fn flatten(
+    prefix: &str,
+    table: &toml::Table,
+    out: &mut Vec<(String, String)>,
+) -> Result<(), String> {
+    for (key, value) in table {
+        let key = match prefix {
+            "" => key.clone(),
+            _ => format!("{prefix}.{key}"),
+        };
+        match value {
+            toml::Value::String(text) => out.push((key, text.clone())),
+            toml::Value::Table(inner) => flatten(&key, inner, out)?,
+            _ => return Err(format!("`{key}` should be text")),
+        }
+    }
+    Ok(())
+}
+
+/// Only the first call does anything, they don't change once we're going
+pub fn init(strings: Strings) {
+    let _ = STRINGS.set(strings);
+}
+
+/// English until `init` says otherwise
+pub fn current() -> &'static Strings {
+    STRINGS.get().unwrap_or(&BUILT_IN)
+}
+
+/// The current text for `key`
+pub fn get(key: &str) -> &'static str {
+    current().get(key)
+}
+
+/// The current text for `key` with each `{name}` in it swapped for its value
+pub fn fill(key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let mut text = get(key).to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    text
+}
+
+/// What `key` is out of the box, whatever's been loaded. A key that isn't one is a `?`, so a typo
+/// shows up on screen rather than as a blank
+pub fn english(key: &str) -> &'static str {
+    ENGLISH
+        .iter()
+        .find(|&&(known, _)| known == key)
+        .map_or("?", |&(_, text)| text)
+}
@@ -0,0 +1,24 @@
+//! The few facts about a task that everything sent or written about it starts with, so the exit
+//! report and the webhook can't drift apart on what a task's name or status is called
+
+use serde::Serialize;
+
+use crate::tasks::{Id, Task};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TaskSummary {
+    pub id: Id,
+    pub name: String,
+    /// As shown in the table, "Done" and all
+    pub status: String,
+}
+
+impl From<&Task> for TaskSummary {
+    fn from(task: &Task) -> Self {
+        Self {
+            id: task.id,
+            name: task.name.clone(),
+            status: task.status.to_string(),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Ctrl+Z. In raw mode the terminal hands it over as a key instead of stopping us, and a SIGTSTP
+//! from anywhere else would stop us with the terminal still raw. Either way the loop's asked to
+//! suspend, gives the terminal back, stops, and sets the terminal up again once it's continued,
+//! before anything else is drawn. Dummies are our own threads, so they stop along with the rest
+//! of us. Shell tasks' children have process groups of their own and carry on
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Whether there's job control to suspend to
+pub const SUPPORTED: bool = cfg!(unix);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Phase {
+    #[default]
+    Awake,
+    /// Asked to, and the loop will before its next draw
+    Requested,
+    /// The terminal's been given back, and nothing can be drawn until it's set up again
+    Suspended,
+}
+
+/// Set by the signal listeners, taken by the loop
+#[derive(Debug, Default)]
+pub struct Signals {
+    stop: AtomicBool,
+    continued: AtomicBool,
+}
+
+impl Signals {
+    /// SIGTSTP: somebody wants us suspended
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// SIGCONT: we were stopped, whoever did it, and the screen might not be ours any more
+    pub fn continued(&self) {
+        self.continued.store(true, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Suspend {
+    phase: Phase,
+    signals: Arc<Signals>,
+}
+
+impl Suspend {
+    /// Starts listening for SIGTSTP and SIGCONT, so they reach the loop. Needs to be in a
+    /// runtime. Without it (or off Unix), only the key asks
+    pub fn listen(&self) {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            for (kind, on) in [
+                (
+                    SignalKind::from_raw(libc::SIGTSTP),
+                    Signals::stop as fn(&Signals),
+                ),
+                (SignalKind::from_raw(libc::SIGCONT), Signals::continued),
+            ] {
+                let signals = Arc::clone(&self.signals);
+                match signal(kind) {
+                    Ok(mut stream) => {
+                        tokio::spawn(async move {
+                            while stream.recv().await.is_some() {
+                                on(&signals);
+                            }
+                        });
+                    }
+                    Err(e) => tracing::warn!("can't listen for {kind:?}: {e}"),
+                }
+            }
+        }
+    }
+
+    /// What the listeners set, for poking without a real signal
+    pub fn signals(&self) -> Arc<Signals> {
+        Arc::clone(&self.signals)
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// Ctrl+Z. False if we're already on the way
+    pub fn request(&mut self) -> bool {
+        if self.phase != Phase::Awake {
+            return false;
+        }
+        self.phase = Phase::Requested;
+        true
+    }
+
+    /// Whether it's time to give the terminal back, counting a SIGTSTP as asking. Only ever true
+    /// once per suspend, and it's `Suspended` from here until `resumed`
+    pub fn due(&mut self) -> bool {
+        if self.signals.stop.swap(false, Ordering::Relaxed) {
+            self.request();
+        }
+        if self.phase != Phase::Requested {
+            return false;
+        }
+        self.phase = Phase::Suspended;
+        true
+    }
+
+    /// Back, with the terminal set up again. The SIGCONT that woke us is already dealt with
+    pub fn resumed(&mut self) {
+        if self.phase == Phase::Suspended {
+            self.phase = Phase::Awake;
+            self.signals.continued.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// A SIGCONT since we last looked, from somebody else's stop. Everything needs drawing
+    /// again from scratch
+    pub fn take_continued(&mut self) -> bool {
+        self.phase == Phase::Awake && self.signals.continued.swap(false, Ordering::Relaxed)
+    }
+
+    /// Never into a terminal that's been given back and not set up again
+    pub fn can_draw(&self) -> bool {
+        self.phase != Phase::Suspended
+    }
+}
+
+/// Ctrl+Z as the terminal sends it in raw mode
+pub fn is_suspend_key(event: &KeyEvent) -> bool {
+    event.code == KeyCode::Char('z') && event.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// Stops the whole process, and returns once it's been continued (`fg`). SIGSTOP rather than
+/// SIGTSTP, since that one's ours now
+pub fn stop() {
+    // SAFETY: just a syscall, at ourselves
+    #[cfg(unix)]
+    unsafe {
+        libc::raise(libc::SIGSTOP)
+    };
+}
@@ -0,0 +1,53 @@
+//! How many tasks are where, counted once for everything that sums them up: the strip over the
+//! logs, the minimized line and the status file. They can't disagree if none of them counts
+
+use serde::Serialize;
+
+use crate::tasks::{Task, TaskStatus};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Tally {
+    /// Running or finalizing
+    pub running: usize,
+    pub sleeping: usize,
+    /// Scheduled or queued for a thread
+    pub waiting: usize,
+    pub striking: usize,
+    pub done: usize,
+    pub cancelled: usize,
+    /// Failed or never started
+    pub failed: usize,
+    /// Average progress of everything working. None with nothing working
+    pub overall_pct: Option<u8>,
+}
+
+impl Tally {
+    pub fn of<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> Tally {
+        let mut tally = Tally::default();
+        let mut progress = 0;
+        for task in tasks {
+            match task.status {
+                TaskStatus::Running | TaskStatus::Finalizing => tally.running += 1,
+                TaskStatus::Sleeping => tally.sleeping += 1,
+                TaskStatus::Scheduled | TaskStatus::WaitingForThread => tally.waiting += 1,
+                TaskStatus::OnStrike => tally.striking += 1,
+                TaskStatus::Finished => tally.done += 1,
+                TaskStatus::Canceled => tally.cancelled += 1,
+                TaskStatus::Failed | TaskStatus::FailedToStart => tally.failed += 1,
+                // Heard nothing yet, so it's not anywhere in particular
+                TaskStatus::KnownUnknown => {}
+            }
+            if task.status.is_working() {
+                progress += task.shown_progress() as usize;
+            }
+        }
+        let working = tally.working();
+        tally.overall_pct = (working > 0).then(|| (progress / working) as u8);
+        tally
+    }
+
+    /// Running, sleeping or finalizing: everything that's properly getting on with it
+    pub fn working(&self) -> usize {
+        self.running + self.sleeping
+    }
+}
@@ -0,0 +1,323 @@
+//! Widgets for the 'everything about one task' view, and the side panel version of it in inspect
+//! mode. Like TaskTable they don't hold the task, the full view just remembers which one we're
+//! looking at and how far the message history is scrolled. Both get their facts from
+//! `summary_lines` so they can't disagree
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, Padding, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use crate::{
+    completion::Outcome,
+    history::{Comparison, Pace},
+    layout_util::saturating_height,
+    task_table::MARKER,
+    tasks::{Id, Task, TaskKind, TaskStatus},
+    theme,
+};
+
+#[derive(Debug, Default)]
+pub struct TaskDetail {
+    pub id: Id,
+    /// Cursor into the audit trail, for scrolling
+    pub state: ListState,
+    /// How the run so far compares to its candidate's history. App sets it fresh every frame
+    pub vs_avg: Option<Comparison>,
+    /// `#9 ⇢ #12 ⇢ #15` if it's part of a chain. Also set by App every frame
+    pub chain: Option<String>,
+    /// `#3 ⇢ #7 ⇢ #9` if it's been tried more than once. Same again
+    pub attempts: Option<String>,
+}
+
+impl TaskDetail {
+    /// Points the view at a task, starting from the newest message
+    pub fn open(&mut self, task: &Task) {
+        self.id = task.id;
+        self.state.select(Some(task.audit.len().saturating_sub(1)));
+    }
+
+    pub fn next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn previous(&mut self) {
+        self.state.select_previous();
+    }
+}
+
+/// " ▎ Task 3: Alpha ", the marker in the task's own color if it has one
+fn title(task: &Task) -> Line<'static> {
+    let name = format!(" Task {}: {} ", task.id, task.name);
+    match marker(task) {
+        Some(mark) => Line::from(vec![" ".into(), mark, name.trim_start().to_string().into()]),
+        None => Line::from(name),
+    }
+}
+
+/// The table's marker for the task, and a space after it
+fn marker(task: &Task) -> Option<Span<'static>> {
+    theme::current()
+        .task_color(task.id)
+        .map(|color| Span::styled(format!("{MARKER} "), Style::new().fg(color)))
+}
+
+impl<'a> StatefulWidget for &'a mut TaskDetail {
+    type State = &'a Task;
+
+    fn render(self, area: Rect, buf: &mut Buffer, task: &mut Self::State) {
+        let block = Block::bordered()
+            .title(title(task))
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let mut summary = summary_lines(task, self.vs_avg);
+        if let Some(chain) = &self.chain {
+            // Under the times, over the description
+            summary.insert(2, Line::from(vec!["Chain: ".bold(), chain.clone().into()]));
+        }
+        if let Some(attempts) = &self.attempts {
+            summary.insert(
+                2,
+                Line::from(vec!["Attempts: ".bold(), attempts.clone().into()]),
+            );
+        }
+        // A spare line for the description wrapping, plus one to breathe
+        let summary_height = saturating_height(summary.len() + 2);
+        // None at all and there's no box for them either
+        let milestones_height = match task.milestones.len() {
+            0 => 0,
+            n => saturating_height(n + 2),
+        };
+        let [summary_area, milestones_area, audit_area] = Layout::vertical([
+            Constraint::Length(summary_height),
+            Constraint::Length(milestones_height),
+            Constraint::Min(0),
+        ])
+        .areas(inner);
+        Paragraph::new(summary)
+            .wrap(Wrap { trim: true })
+            .render(summary_area, buf);
+
+        if !task.milestones.is_empty() {
+            let lines: Vec<Line> = task
+                .milestones
+                .iter()
+                .map(|(at, label)| {
+                    Line::from(vec![
+                        Span::styled(at.format("%H:%M:%S%.3f ").to_string(), theme::current().dim),
+                        label.as_str().into(),
+                    ])
+                })
+                .collect();
+            Paragraph::new(lines)
+                .block(Block::bordered().title(" Milestones "))
+                .render(milestones_area, buf);
+        }
+
+        // Its messages carry its color, same as its row
+        let mark = marker(task);
+        let items: Vec<ListItem> = task
+            .audit
+            .iter()
+            .map(|(at, msg)| {
+                ListItem::from(Line::from_iter(mark.clone().into_iter().chain([
+                    Span::styled(at.format("%H:%M:%S%.3f ").to_string(), theme::current().dim),
+                    msg.into(),
+                ])))
+            })
+            .collect();
+        let list =
+            theme::current().highlight_quietly(List::new(items).block(
+                Block::bordered().title(format!(" Control Messages ({}) ", task.audit.len())),
+            ));
+        StatefulWidget::render(list, audit_area, buf, &mut self.state);
+    }
+}
+
+/// Side panel next to the table. Whatever's selected, minus the message history but plus
+/// whatever it printed lately. Second half is the same as `TaskDetail::vs_avg`
+pub struct TaskPanel<'a>(pub &'a Task, pub Option<Comparison>);
+
+impl Widget for TaskPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (task, vs_avg) = (self.0, self.1);
+        let block = Block::bordered()
+            .title(title(task))
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let summary = summary_lines(task, vs_avg);
+        // Narrow, so the description is going to wrap. Guess generously
+        let summary_height = saturating_height(summary.len() + 3);
+        let [summary_area, output_area] =
+            Layout::vertical([Constraint::Length(summary_height), Constraint::Min(0)]).areas(inner);
+        Paragraph::new(summary)
+            .wrap(Wrap { trim: true })
+            .render(summary_area, buf);
+
+        let shown = output_area.height.saturating_sub(2) as usize;
+        let lines: Vec<Line> = if task.output.is_empty() {
+            vec![Line::from("nothing yet").style(theme::current().dim)]
+        } else {
+            let skip = task.output.len().saturating_sub(shown);
+            task.output
+                .iter()
+                .skip(skip)
+                .map(|line| Line::from(line.as_str()))
+                .collect()
+        };
+        Paragraph::new(lines)
+            .block(Block::bordered().title(" Recent Output "))
+            .render(output_area, buf);
+    }
+}
+
+/// The facts about a task, a label and value per span pair
+fn summary_lines(task: &Task, vs_avg: Option<Comparison>) -> Vec<Line<'_>> {
+    let end = match task.end {
+        Some(time) => time.format("%I:%M:%S %P").to_string(),
+        None => "-".to_string(),
+    };
+    let mut lines = vec![
+        Line::from(vec![
+            "Status: ".bold(),
+            task.status.label().into(),
+            "  Progress: ".bold(),
+            match task.progress_at_cancel {
+                Some(at) => format!("{at}% (stopped)"),
+                None => format!("{}%", task.shown_progress()),
+            }
+            .into(),
+            "  Halt requested: ".bold(),
+            task.cancel.to_string().into(),
+        ]),
+        Line::from(vec![
+            "Started: ".bold(),
+            match task.start {
+                Some(start) => start.format("%I:%M:%S %P").to_string(),
+                None => "-".to_string(),
+            }
+            .into(),
+            "  Ended: ".bold(),
+            end.into(),
+            "  Took: ".bold(),
+            duration(task).into(),
+        ]),
+    ];
+    if let Some(secs) = task.target_secs {
+        lines.push(Line::from(vec![
+            "Duration: ".bold(),
+            format!("{secs}s of sleep, chosen").into(),
+        ]));
+    }
+    if let Some(vs_avg) = vs_avg {
+        lines.push(Line::from(vec!["Pace: ".bold(), pace_span(vs_avg)]));
+    }
+    if task.backwards > 0 {
+        lines.push(Line::from(vec![
+            "Went backwards: ".bold(),
+            Span::styled(
+                format!("{} reports, kept the higher", task.backwards),
+                theme::current().notice,
+            ),
+        ]));
+    }
+    lines.extend([Line::from(vec![
+        "Description: ".bold(),
+        task.description.as_str().into(),
+    ])]);
+    if let Some(result) = result(task) {
+        lines.push(Line::from(vec!["Result: ".bold(), result.into()]));
+    }
+    if let Some(demand) = task.demand_secs {
+        lines.push(Line::from(vec![
+            "On strike: ".bold(),
+            Span::styled(
+                format!("wants a {demand} sec break"),
+                theme::current().alert,
+            ),
+        ]));
+    }
+    if let Some(requested) = task.cancel_requested_at {
+        let acked = match task.cancel_acked_at {
+            Some(time) => time.format("%H:%M:%S%.3f").to_string(),
+            None => "not yet".to_string(),
+        };
+        lines.push(Line::from(vec![
+            "Stop requested: ".bold(),
+            requested.format("%H:%M:%S%.3f").to_string().into(),
+            "  Acknowledged: ".bold(),
+            acked.into(),
+        ]));
+    }
+    if let Some(reason) = &task.cancel_reason {
+        lines.push(Line::from(vec!["Stopped: ".bold(), reason.as_str().into()]));
+    }
+    // Last, since it's the one that can go on for a while
+    if let Some(note) = &task.note {
+        let mut note_lines = note.split('\n');
+        lines.push(Line::from(vec![
+            "Note: ".bold(),
+            note_lines.next().unwrap_or_default().into(),
+        ]));
+        lines.extend(note_lines.map(Line::from));
+    }
+    lines
+}
+
+/// Yellow once it's slow, red once it's very slow. Plain output says so too
+fn pace_span(vs_avg: Comparison) -> Span<'static> {
+    let theme = theme::current();
+    let (style, word) = match vs_avg.pace {
+        Pace::Usual => (theme.text, ""),
+        Pace::Slow => (theme.notice, " (slow)"),
+        Pace::VerySlow => (theme.alert, " (very slow)"),
+    };
+    let word = if theme.markers { word } else { "" };
+    Span::styled(format!("{vs_avg}{word}"), style)
+}
+
+/// Start to end, or to now if it's still going
+fn duration(task: &Task) -> String {
+    let Some(start) = task.start else {
+        return "-".to_string();
+    };
+    let end = task.end.unwrap_or_else(chrono::Local::now);
+    let secs = (end - start).num_seconds().max(0);
+    if secs < 60 {
+        format!("{secs}s")
+    } else {
+        format!("{}m {}s", secs / 60, secs % 60)
+    }
+}
+
+/// How it ended, once it has. Why it stopped is its own line, so that's not repeated here
+fn result(task: &Task) -> Option<String> {
+    match task.completion.outcome() {
+        Some(Outcome::Panicked(e)) => return Some(format!("worker panicked: {e}")),
+        Some(Outcome::Aborted) => return Some("worker cut off".to_string()),
+        _ => {}
+    }
+    match (task.status, task.kind) {
+        (TaskStatus::Finished, TaskKind::Shell) => Some("exited cleanly".to_string()),
+        (TaskStatus::Finished, TaskKind::Dummy) => match task.completion.outcome() {
+            Some(Outcome::Returned(Some(sum))) => Some(format!("all work done, summed to {sum}")),
+            _ => Some("all work done".to_string()),
+        },
+        (TaskStatus::Failed, _) => Some(match task.exit_code {
+            Some(code) => format!("exit code {code}"),
+            None => "killed by a signal".to_string(),
+        }),
+        (TaskStatus::Canceled, _) if task.start.is_none() => {
+            Some("called off before it started".to_string())
+        }
+        (TaskStatus::Canceled, _) => Some("stopped early".to_string()),
+        (TaskStatus::FailedToStart, _) => Some("never started".to_string()),
+        _ => None,
+    }
+}
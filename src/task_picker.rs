@@ -1,190 +1,428 @@
 //! Widget for generating candidate tasks from a big list. Keeps full Ratatui list state, but
 //! we only care about the cursor, really . Not responsible for actually making tasks
 
-use core::fmt;
+use std::{collections::HashMap, path::Path, time::Duration};
 
-use rand::seq::IndexedRandom;
+use crate::{
+    candidates::TaskFile,
+    health::Health,
+    history::{self, CandidateStats},
+    nav::{ListNav, Move},
+    CandidateTask,
+};
+use chrono::{DateTime, Local};
+use rand::{rngs::StdRng, seq::IndexedRandom, SeedableRng};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Color, Stylize},
-    text::Line,
+    text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, ListState, StatefulWidget, Widget},
 };
 
+use crate::theme;
+
 /// How many entries to pick out for the menu
 pub const FETCH_AMOUNT: usize = 6;
+/// How many distinct past choices get their own section above the random picks
+pub const RECENT_AMOUNT: usize = 3;
+/// How long a candidate's held back after one of its tasks finishes, unless the config says
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(60);
 
 #[derive(Debug)]
 pub struct TaskPicker {
-    items: Vec<&'static CandidateTask>,
+    /// Everything we can sample from: the built-ins plus whatever the tasks file added, or just
+    /// the tasks file if it says so. The pool editor changes it in place
+    pub pool: Vec<CandidateTask>,
+    items: Vec<CandidateTask>,
+    /// Most recent first, distinct by name. Survives regen so it's never rerolled away
+    recent: Vec<CandidateTask>,
+    /// Pin the last choice to the top of the random picks if the sample didn't have it
+    keep_last: bool,
+    /// How each candidate did before, by candidate name. `None` without a history file, so
+    /// there's nothing to say at all rather than "never run" on everything
+    pub stats: Option<HashMap<String, CandidateStats>>,
+    /// Candidates whose tasks finished less than this ago are only offered to fill the page
+    pub cooldown: Duration,
+    /// When each candidate's last task finished, by candidate name
+    finished: HashMap<String, DateTime<Local>>,
+    /// What was still cooling down at the last regen, and for how much longer
+    cooling: HashMap<String, Duration>,
+    nav: ListNav,
+    /// Rows inside the border as of the last render, for paging
+    page: usize,
+    /// Everything random the picker does comes from here, so a replay offers the same picks
+    rng: StdRng,
+    /// A pick's been added and the picker's on its way out. Nothing else gets added from it
+    /// until it's opened again
+    committed: bool,
     pub state: ListState,
 }
 
-#[derive(Debug)]
-pub struct CandidateTask {
-    pub name: &'static str,
-    pub description: &'static str,
-}
+impl TaskPicker {
+    pub fn new(keep_last: bool, pool: Vec<CandidateTask>, nav: ListNav) -> Self {
+        let mut rng = StdRng::from_os_rng();
+        Self {
+            items: gen_list(&pool, &HashMap::new(), &mut rng),
+            pool,
+            recent: vec![],
+            keep_last,
+            stats: None,
+            cooldown: DEFAULT_COOLDOWN,
+            finished: HashMap::new(),
+            cooling: HashMap::new(),
+            nav,
+            page: FETCH_AMOUNT,
+            rng,
+            committed: false,
+            state: ListState::default().with_selected(Some((FETCH_AMOUNT / 2) - 1)),
+        }
+    }
 
-impl fmt::Display for CandidateTask {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}): {}", self.name, self.description)
+    /// Height the modal needs, including borders and the recent section if there is one
+    pub fn height(&self) -> usize {
+        self.len() + 2
     }
-}
 
-impl Default for TaskPicker {
-    fn default() -> Self {
-        Self {
-            items: gen_list(),
-            state: ListState::default().with_selected(Some((FETCH_AMOUNT / 2) - 1)),
+    /// Rows in the list: recent picks, a divider if there were any, then the random picks
+    fn len(&self) -> usize {
+        self.items.len() + self.picks_offset()
+    }
+
+    /// Index into the list where the random picks start
+    fn picks_offset(&self) -> usize {
+        if self.recent.is_empty() {
+            0
+        } else {
+            self.recent.len() + 1
+        }
+    }
+
+    fn is_divider(&self, idx: usize) -> bool {
+        !self.recent.is_empty() && idx == self.recent.len()
+    }
+
+    fn get(&self, idx: usize) -> Option<&CandidateTask> {
+        if idx < self.recent.len() {
+            Some(&self.recent[idx])
+        } else {
+            self.items.get(idx.checked_sub(self.picks_offset())?)
         }
     }
-}
 
-impl TaskPicker {
-    /// Wraps list down
     pub fn next(&mut self) {
-        self.state.select_next();
+        self.navigate(Move::Next);
     }
 
-    /// Wraps list up
     pub fn previous(&mut self) {
-        self.state.select_previous();
+        self.navigate(Move::Previous);
+    }
+
+    /// Same moves as the table. The divider can't be selected, so landing on it carries on
+    /// past it the way the cursor was going
+    pub fn navigate(&mut self, to: Move) {
+        let Some(idx) = self
+            .nav
+            .apply(self.state.selected(), self.len(), self.page, to)
+        else {
+            self.state.select(None);
+            return;
+        };
+        let idx = if !self.is_divider(idx) {
+            idx
+        } else if matches!(to, Move::Previous | Move::PageUp) {
+            idx - 1
+        } else {
+            idx + 1
+        };
+        self.state.select(Some(idx));
     }
 
     /// Should be called on state change FROM modal, to get candidate for creation in main
-    pub fn select(&self) -> Option<&'static CandidateTask> {
+    pub fn select(&self) -> Option<CandidateTask> {
         // This SHOULD always have something selected, but we will handle the possibility back in main
         // Return None if there's no selection. Return None if there is and no item @ selection
-        if let Some(idx) = self.state.selected() {
-            self.items.get(idx).map(|ct| Some(*ct))?
-        } else {
-            None
+        self.get(self.state.selected()?).cloned()
+    }
+
+    /// For when the user wants a random option. Convenient for us both! Never one that's cooling
+    /// down, unless that's all there is
+    pub fn select_random(&mut self) -> Option<CandidateTask> {
+        let ready: Vec<&CandidateTask> = self
+            .items
+            .iter()
+            .filter(|ct| !self.cooling.contains_key(&ct.name))
+            .collect();
+        match ready.choose(&mut self.rng) {
+            Some(&ct) => Some(ct.clone()),
+            None => self.items.choose(&mut self.rng).cloned(),
         }
     }
 
-    /// For when the user wants a random option. Convenient for us both!
-    pub fn select_random(&self) -> Option<&'static CandidateTask> {
-        self.items.choose(&mut rand::rng()).copied()
+    /// Picks from `seed` from here on, starting with a fresh sample
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.items = gen_list(&self.pool, &self.cooling, &mut self.rng);
+    }
+
+    pub fn is_committed(&self) -> bool {
+        self.committed
+    }
+
+    /// Should be called once a candidate actually became a task, so we can offer it again
+    pub fn confirm(&mut self, ct: &CandidateTask) {
+        self.committed = true;
+        self.recent.retain(|r| r.name != ct.name);
+        self.recent.insert(0, ct.clone());
+        self.recent.truncate(RECENT_AMOUNT);
+    }
+
+    /// Should be called when a candidate's task finishes, so it can cool down
+    pub fn finished(&mut self, candidate: &str, at: DateTime<Local>) {
+        self.finished.insert(candidate.to_string(), at);
     }
 
     /// Should be called every time the modal is 'opened' (state change in main). Picks from the
-    /// pool and rebuilds list again
-    pub fn regen(&mut self) {
-        self.items = COOL_TASKS
-            .choose_multiple(&mut rand::rng(), FETCH_AMOUNT)
-            .collect()
+    /// pool and rebuilds list again, then puts the cursor back on the last choice if it can.
+    /// Recent picks that have since been disabled or deleted are dropped, and anything that
+    /// finished within the cooldown of `now` is only there if there's room to spare
+    pub fn regen(&mut self, now: DateTime<Local>) {
+        self.committed = false;
+        let pool = &self.pool;
+        self.recent
+            .retain(|r| pool.iter().any(|ct| ct.enabled && ct.name == r.name));
+        self.cooling = cooling_down(&self.finished, now, self.cooldown);
+        self.items = gen_list(&self.pool, &self.cooling, &mut self.rng);
+        let Some(last) = self.recent.first() else {
+            self.state.select(Some((FETCH_AMOUNT / 2) - 1));
+            return;
+        };
+        let found = self.items.iter().position(|ct| ct.name == last.name);
+        let idx = match found {
+            Some(idx) => idx,
+            None if self.keep_last && !self.cooling.contains_key(&last.name) => {
+                self.items.pop();
+                self.items.insert(0, last.clone());
+                0
+            }
+            None => (FETCH_AMOUNT / 2) - 1,
+        };
+        self.state.select(Some(self.picks_offset() + idx));
+    }
+
+    /// Counts another run for its candidate, if there's history being kept
+    pub fn record(&mut self, record: &history::Record) {
+        if let Some(stats) = &mut self.stats {
+            stats
+                .entry(record.candidate().to_string())
+                .or_default()
+                .add(record);
+        }
+    }
+
+    /// "  · run 4 times, avg 38s" after an entry, or nothing without history
+    fn annotation(&self, ct: &CandidateTask) -> Span<'static> {
+        match &self.stats {
+            Some(stats) => Span::styled(
+                format!("  · {}", history::describe(stats.get(&ct.name))),
+                theme::current().dim,
+            ),
+            None => Span::default(),
+        }
+    }
+
+    /// "(cooling down, 42s)" after a candidate that's only there to fill the page
+    fn cooling_note(&self, ct: &CandidateTask) -> Option<Span<'static>> {
+        let left = self.cooling.get(&ct.name)?;
+        let secs = (left.as_secs_f64().ceil() as u64).max(1);
+        Some(Span::styled(
+            format!(" (cooling down, {secs}s)"),
+            theme::current().dim,
+        ))
     }
 }
 
 impl Widget for &mut TaskPicker {
     fn render(self, area: Rect, buf: &mut Buffer) {
         Clear.render(area, buf);
+        let theme = theme::current();
         let mut ctr = 0;
-        let styled_items: Vec<ListItem> = self
-            .items
+        let mut styled_items: Vec<ListItem> = self
+            .recent
             .iter()
             .map(|item| {
-                ctr += 1;
-                style_item(item, ctr)
+                let mut spans = vec![format!("↺ {item}").into()];
+                spans.extend(self.cooling_note(item));
+                spans.push(self.annotation(item));
+                ListItem::from(Line::from(spans)).style(theme.accent)
             })
             .collect();
+        if !self.recent.is_empty() {
+            let divider = "─".repeat(area.width.saturating_sub(4) as usize);
+            styled_items.push(ListItem::from(divider).style(theme.dim));
+        }
+        styled_items.extend(self.items.iter().map(|item| {
+            ctr += 1;
+            match self.cooling_note(item) {
+                Some(note) => ListItem::from(Line::from(vec![
+                    item.to_string().into(),
+                    note,
+                    self.annotation(item),
+                ]))
+                .style(theme.dim),
+                None => style_item(item, ctr, self.annotation(item)),
+            }
+        }));
         let controls = Line::from(vec![
             " Pick for Me! ".into(),
-            "<R>".blue().bold(),
+            theme::key("<R>"),
             " Pick Selected ".into(),
-            "<ENTER>".blue().bold(),
+            theme::key("<ENTER>"),
+            " Choose Duration ".into(),
+            theme::key("<TAB> "),
         ]);
         let block = Block::new()
             .title(" New Task ")
             .borders(Borders::ALL)
             .title_bottom(controls.centered());
 
-        let list = List::new(styled_items).block(block).highlight_symbol("> ");
+        self.page = block.inner(area).height as usize;
+        let list = List::new(styled_items)
+            .block(block)
+            .highlight_symbol(theme::current().highlight_symbol());
         StatefulWidget::render(list, area, buf, &mut self.state);
     }
 }
 
 /// Enforces alternating color with external counter
-fn style_item(ct: &CandidateTask, ctr: i32) -> ListItem {
-    let item = ListItem::from(ct.to_string());
+fn style_item<'a>(ct: &CandidateTask, ctr: i32, annotation: Span<'a>) -> ListItem<'a> {
+    let item = ListItem::from(Line::from(vec![ct.to_string().into(), annotation]));
     if ctr % 2 == 0 {
-        item.style(Color::White)
+        item.style(theme::current().text)
+    } else {
+        item.style(theme::current().muted)
+    }
+}
+
+/// A page of enabled candidates, none of them `cooling` if there are enough others to fill it.
+/// Any that are go after the rest, so the page is only ever short if the pool is
+pub fn gen_list(
+    pool: &[CandidateTask],
+    cooling: &HashMap<String, Duration>,
+    rng: &mut StdRng,
+) -> Vec<CandidateTask> {
+    let (ready, cooled): (Vec<&CandidateTask>, Vec<&CandidateTask>) = pool
+        .iter()
+        .filter(|ct| ct.enabled)
+        .partition(|ct| !cooling.contains_key(&ct.name));
+    let mut picks: Vec<CandidateTask> = ready
+        .choose_multiple(rng, FETCH_AMOUNT)
+        .map(|&ct| ct.clone())
+        .collect();
+    let short = FETCH_AMOUNT - picks.len();
+    picks.extend(cooled.choose_multiple(rng, short).map(|&ct| ct.clone()));
+    picks
+}
+
+/// Which candidates finished less than `cooldown` before `now`, and how long they've got left.
+/// One that somehow finished after `now` has the whole cooldown to go
+pub fn cooling_down(
+    finished: &HashMap<String, DateTime<Local>>,
+    now: DateTime<Local>,
+    cooldown: Duration,
+) -> HashMap<String, Duration> {
+    finished
+        .iter()
+        .filter_map(|(name, &at)| {
+            let since = (now - at).to_std().unwrap_or_default();
+            let left = cooldown.checked_sub(since).filter(|left| !left.is_zero())?;
+            Some((name.clone(), left))
+        })
+        .collect()
+}
+
+/// The pool from `path`, or the built-in one if there's no file there and there didn't have to
+/// be. Second half is whether `path` is safe to save over: not if it was there but broken.
+/// Anything wrong with it goes in `health`
+pub fn load_pool(path: &Path, required: bool, health: &mut Health) -> (Vec<CandidateTask>, bool) {
+    if !required && !path.exists() {
+        return (builtin_pool(), true);
+    }
+    let file = match TaskFile::load(path) {
+        Ok(file) => file,
+        Err(e) => {
+            health.error(format!(
+                "couldn't load tasks file {}, using the built-in pool: {e}",
+                path.display()
+            ));
+            return (builtin_pool(), false);
+        }
+    };
+    let pool = if file.include_builtins {
+        builtin_pool().into_iter().chain(file.task).collect()
     } else {
-        item.style(Color::Gray)
+        file.task
+    };
+    if pool.iter().any(|ct| ct.enabled) {
+        (pool, true)
+    } else {
+        health.warn(format!(
+            "tasks file {} has nothing enabled, using the built-in pool",
+            path.display()
+        ));
+        (builtin_pool(), false)
+    }
+}
+
+/// Stats for every candidate in the history file at `path`. A broken file goes in `health` and
+/// is treated as no history so far, since new lines still get added to the end of it
+pub fn load_history(path: &Path, health: &mut Health) -> HashMap<String, CandidateStats> {
+    match history::load(path) {
+        Ok((records, bad)) => {
+            if bad > 0 {
+                health.warn(format!(
+                    "skipped {bad} lines in history file {} that didn't parse",
+                    path.display()
+                ));
+            }
+            history::aggregate(&records)
+        }
+        Err(e) => {
+            health.error(format!(
+                "couldn't load history file {}, so there are no stats: {e}",
+                path.display()
+            ));
+            HashMap::new()
+        }
     }
 }
 
-fn gen_list() -> Vec<&'static CandidateTask> {
+/// A fresh copy of the built-in pool
+pub fn builtin_pool() -> Vec<CandidateTask> {
     COOL_TASKS
-        .choose_multiple(&mut rand::rng(), FETCH_AMOUNT)
+        .iter()
+        .map(|&(name, description)| CandidateTask::new(name, description))
         .collect()
 }
 
-const COOL_TASKS: &[CandidateTask] = &[
-    CandidateTask {
-        name: "Bobson Dugnutt",
-        description: "Wait for Pokemon cards",
-    },
-    CandidateTask {
-        name: "Sleve McDichael",
-        description: "Re-attach turbo encabulator",
-    },
-    CandidateTask {
-        name: "Onson Sweemey",
-        description: "Repaint fence",
-    },
-    CandidateTask {
-        name: "Anatoli Smorin",
-        description: "Revandalize fence",
-    },
-    CandidateTask {
-        name: "Rey McSriff",
-        description: "help im trapped in a binary an",
-    },
-    CandidateTask {
-        name: "Glenallen Mixon",
-        description: "Rehydrate the PDF files",
-    },
-    CandidateTask {
-        name: "Mario McRlwain",
-        description: "Defragment rubber duck collection",
-    },
-    CandidateTask {
-        name: "Todd Bonzalez",
-        description: "Uninstall gravity temporarily",
-    },
-    CandidateTask {
-        name: "Dwigt Rortugal",
-        description: "Calibrate the hydrospanner flux matrix",
-    },
-    CandidateTask {
-        name: "Karl Dandleton",
-        description: "Reverse-engineer cafeteria meatloaf",
-    },
-    CandidateTask {
-        name: "Mike Truk",
-        description: "Overclock the toaster (bagels only)",
-    },
-    CandidateTask {
-        name: "Dean Wesrey",
-        description: "Re-enact fax machine error codes via mime",
-    },
-    CandidateTask {
-        name: "Raul Chamgerlain",
-        description: "Translate whale songs into Excel formulas",
-    },
-    CandidateTask {
-        name: "Tony Smellme",
-        description: "Teach office plants about blockchain",
-    },
-    CandidateTask {
-        name: "Jeromy Gride",
-        description: "Recycle the same oxygen molecule 17 times",
-    },
-    CandidateTask {
-        name: "Bingus",
-        description: "<REDACTED>",
-    },
+/// Name and description of the pool everyone starts with
+const COOL_TASKS: &[(&str, &str)] = &[
+    ("Bobson Dugnutt", "Wait for Pokemon cards"),
+    ("Sleve McDichael", "Re-attach turbo encabulator"),
+    ("Onson Sweemey", "Repaint fence"),
+    ("Anatoli Smorin", "Revandalize fence"),
+    ("Rey McSriff", "help im trapped in a binary an"),
+    ("Glenallen Mixon", "Rehydrate the PDF files"),
+    ("Mario McRlwain", "Defragment rubber duck collection"),
+    ("Todd Bonzalez", "Uninstall gravity temporarily"),
+    ("Dwigt Rortugal", "Calibrate the hydrospanner flux matrix"),
+    ("Karl Dandleton", "Reverse-engineer cafeteria meatloaf"),
+    ("Mike Truk", "Overclock the toaster (bagels only)"),
+    ("Dean Wesrey", "Re-enact fax machine error codes via mime"),
+    (
+        "Raul Chamgerlain",
+        "Translate whale songs into Excel formulas",
+    ),
+    ("Tony Smellme", "Teach office plants about blockchain"),
+    ("Jeromy Gride", "Recycle the same oxygen molecule 17 times"),
+    ("Bingus", "<REDACTED>"),
 ];
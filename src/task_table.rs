@@ -1,65 +1,532 @@
 //! Widget that forms the 'main view' of tasks and their status. Doesn't hold the tasks(!) because
 //! then we'd have to move a bunch of business logic from the app - unlike TaskPicker which holds
 //! all its state
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Rect},
-    style::{Color, Style, Stylize},
-    widgets::{Block, Cell, Padding, Row, StatefulWidget, Table, TableState},
+    layout::Alignment,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, Cell, Paragraph, Row, StatefulWidget, Table, TableState, Widget, Wrap},
+};
+
+use crate::{
+    archive::{self, Archive},
+    chain::{self, Chain},
+    columns::{Column, Columns},
+    groups::{self, Entry, Groups},
+    layout_util::centered_rect,
+    momentum::{self, Momentum, MomentumConfig},
+    nav::{self, ListNav, Move},
+    removals::Removals,
+    strings,
+    tasks::{CancelState, Id, StatusClass, Task, TaskKind, TaskStatus, FATIGUE_LIMIT},
+    theme::{self, Chrome},
+    ui_state::UiState,
+    view_spec::{self, ViewSpec},
+    visibility::Visibility,
+    wrap::{truncate, wrap},
 };
 
-use crate::tasks::{Task, TaskStatus};
+use crate::fuzzy::{fuzzy_match, highlight};
+
+const EMPTY_MESSAGE: &str = "No tasks yet — press F1 to add one";
+
+/// What `S` goes round
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortBy {
+    /// The order they were added
+    #[default]
+    Added,
+    /// Soonest-to-finish first
+    Eta,
+    /// A group per sort of status, under a header each
+    Status,
+}
+
+/// What the highlight's on. Either way it goes by what it is, not which row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cursor {
+    Task(Id),
+    Header(StatusClass),
+}
+
+impl Cursor {
+    /// `entry` is a task, by its index
+    fn of(entry: Entry, tasks: &[Task]) -> Self {
+        match entry {
+            Entry::Task(idx) => Cursor::Task(tasks[idx].id),
+            Entry::Header(class) => Cursor::Header(class),
+        }
+    }
+
+    fn is(self, entry: Entry, tasks: &[Task]) -> bool {
+        self == Cursor::of(entry, tasks)
+    }
+}
 
 #[derive(Debug)]
 pub struct TaskTable {
+    /// Only the row half of the selection. `cursor` is the real one, see `resolve`
     pub state: TableState,
+    cursor: Option<Cursor>,
+    /// Some people don't like things blinking at them
+    flash: bool,
+    /// Glyph column in plain ASCII
+    ascii: bool,
+    sort: SortBy,
+    /// Which groups are folded away, while sorted by status
+    pub groups: Groups,
+    /// Only rows fuzzy-matching this are shown. Empty shows everything
+    filter: String,
+    /// Whether keys are going into the filter prompt right now
+    pub editing_filter: bool,
+    /// What's drawn after the glyph, and in what order
+    pub columns: Columns,
+    nav: ListNav,
+    /// Lines that fit under the header as of the last render, for paging
+    page: usize,
+    /// `O`: tasks whose description (and why they ended) is wrapped over as many lines as it takes
+    expanded: HashSet<Id>,
+    /// How tall each row was drawn last time, in row order. Mostly 1
+    heights: Vec<u16>,
+    /// `a`: finished tasks moved under a summary row at the bottom, out of the way
+    pub archive: Archive,
+    /// Delete: rows out of the table, and the last few that `u` can put back
+    pub removals: Removals,
+    /// `h` and `ui.hidden_statuses`: rows left out by status
+    pub visibility: Visibility,
+    /// `ui.compact`: no padding and one-letter headers
+    pub compact: bool,
+    /// The terminal's out of focus: spinners stop where they are and nothing flashes
+    pub still: bool,
+    /// When Progress turns yellow, then red
+    pub momentum: MomentumConfig,
+    /// The app clock's time as of this frame, for how long progress has sat still
+    pub now: Instant,
 }
 
-impl Default for TaskTable {
-    fn default() -> Self {
+impl TaskTable {
+    pub fn new(flash: bool, ascii: bool, columns: Columns, nav: ListNav) -> Self {
         Self {
-            state: TableState::default().with_selected(0),
+            state: TableState::default(),
+            cursor: None,
+            flash,
+            ascii,
+            sort: SortBy::Added,
+            groups: Groups::default(),
+            filter: String::new(),
+            editing_filter: false,
+            columns,
+            nav,
+            page: 1,
+            expanded: HashSet::new(),
+            heights: vec![],
+            archive: Archive::default(),
+            removals: Removals::default(),
+            visibility: Visibility::default(),
+            compact: false,
+            still: false,
+            momentum: MomentumConfig::default(),
+            now: Instant::now(),
         }
     }
-}
 
-impl TaskTable {
-    /// Selects the next item in the table, wrapping around.
-    pub fn next(&mut self, num_rows: usize) {
-        if num_rows == 0 {
-            self.state.select(None);
-            return;
+    /// One entry per glyph in the table right now, in a fixed order so it doesn't shuffle about.
+    /// Nothing at all if the theme's gone without glyphs
+    pub fn legend(&self, tasks: &[Task]) -> Line<'static> {
+        let theme = theme::current();
+        if !theme.glyphs() {
+            return Line::default();
         }
-        let new_sel = match self.state.selected() {
-            Some(old_sel) => {
-                if old_sel >= num_rows - 1 {
-                    0
-                } else {
-                    old_sel + 1
-                }
+        let mut present: Vec<(char, &str, Style)> = self
+            .kept(tasks)
+            .map(|task| {
+                let glyph = glyph(task.kind, task.status, self.ascii);
+                (
+                    glyph,
+                    glyph_meaning(task.kind, task.status),
+                    theme.status(&task.status),
+                )
+            })
+            .collect();
+        present.sort_by_key(|&(_, meaning, _)| GLYPH_ORDER.iter().position(|&m| m == meaning));
+        present.dedup_by_key(|&mut (glyph, _, _)| glyph);
+        let mut spans = vec![];
+        for (glyph, meaning, style) in present {
+            spans.push(Span::styled(glyph.to_string(), style));
+            let meaning = strings::get(meaning);
+            spans.push(Span::styled(format!(" {meaning}  "), theme.dim));
+        }
+        Line::from(spans)
+    }
+
+    /// Added, then soonest first, then by status, then round again
+    pub fn toggle_sort(&mut self) {
+        self.sort = match self.sort {
+            SortBy::Added => SortBy::Eta,
+            SortBy::Eta => SortBy::Status,
+            SortBy::Status => SortBy::Added,
+        };
+    }
+
+    pub fn sort(&self) -> SortBy {
+        self.sort
+    }
+
+    /// The parts of the table worth having back next time
+    pub fn ui_state(&self) -> UiState {
+        UiState {
+            sort_by_eta: self.sort == SortBy::Eta,
+            sort_by_status: self.sort == SortBy::Status,
+            filter: self.filter.clone(),
+            hide_finished: self.visibility.is_quick(),
+            ..UiState::default()
+        }
+    }
+
+    pub fn restore(&mut self, state: UiState) {
+        self.sort = match (state.sort_by_status, state.sort_by_eta) {
+            (true, _) => SortBy::Status,
+            (false, true) => SortBy::Eta,
+            (false, false) => SortBy::Added,
+        };
+        self.filter = state.filter;
+        self.visibility.set_quick(state.hide_finished);
+    }
+
+    /// What the line over the header says about it
+    pub fn view_spec(&self) -> ViewSpec {
+        ViewSpec {
+            sort: self.sort,
+            filter: self.filter.clone(),
+            folded: view_spec::folded(&self.groups),
+            expanded: self.expanded.len(),
+        }
+    }
+
+    /// `V`: the order they were added, no filter, nothing folded or expanded. False if that's
+    /// how it already was
+    pub fn reset_view(&mut self) -> bool {
+        // Folds count even when they're not showing, they'd be back with the next S
+        let changed = !self.view_spec().is_default() || !view_spec::folded(&self.groups).is_empty();
+        self.sort = SortBy::Added;
+        self.clear_filter();
+        self.groups.unfold_all();
+        self.expanded.clear();
+        changed
+    }
+
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    pub fn push_filter(&mut self, ch: char) {
+        self.filter.push(ch);
+    }
+
+    pub fn pop_filter(&mut self) {
+        self.filter.pop();
+    }
+
+    pub fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.editing_filter = false;
+    }
+
+    /// Kept rows that `visibility` leaves out, filter or no filter
+    pub fn hidden(&self, tasks: &[Task]) -> usize {
+        self.kept(tasks)
+            .filter(|task| self.visibility.hides(task))
+            .count()
+    }
+
+    /// Indexes into `tasks` in the order they're shown, minus whatever the filter hides, anything
+    /// removed or hidden by status, folded groups, and the archive while it's closed. Headers and the archive summary
+    /// aren't tasks, so they're not in here. Anything mapping a row back to a task has to go
+    /// through here or `entries`
+    pub fn order(&self, tasks: &[Task]) -> Vec<usize> {
+        self.entries(tasks)
+            .0
+            .into_iter()
+            .filter_map(|entry| match entry {
+                Entry::Task(idx) => Some(idx),
+                Entry::Header(_) => None,
+            })
+            .collect()
+    }
+
+    /// What the highlight can land on, in the order it's shown: `order` with the group headers in
+    /// (tasks by index). Then where the archive summary goes among them, straight after the live
+    /// rows, if there are any archived rows to sum up. `row` goes from here to a table row
+    fn entries(&self, tasks: &[Task]) -> (Vec<Entry>, Option<usize>) {
+        let (live, archived) = self.sections(tasks);
+        let mut entries: Vec<Entry> = match self.sort {
+            SortBy::Status => {
+                let classes: Vec<StatusClass> =
+                    live.iter().map(|&idx| tasks[idx].status.class()).collect();
+                self.groups
+                    .entries(&classes)
+                    .into_iter()
+                    .map(|entry| match entry {
+                        Entry::Task(at) => Entry::Task(live[at]),
+                        header => header,
+                    })
+                    .collect()
             }
-            None => 0, // Select the first item if nothing is selected
+            SortBy::Added | SortBy::Eta => live.into_iter().map(Entry::Task).collect(),
         };
-        self.state.select(Some(new_sel));
+        let summary = (!archived.is_empty()).then_some(entries.len());
+        if self.archive.is_open() {
+            entries.extend(archived.into_iter().map(Entry::Task));
+        }
+        (entries, summary)
+    }
+
+    /// Everything the filter lets through: the live rows, then the archived ones, each sorted.
+    /// Archived rows never get headers, so sorting by status only puts them in group order
+    fn sections(&self, tasks: &[Task]) -> (Vec<usize>, Vec<usize>) {
+        let (mut live, mut archived): (Vec<usize>, Vec<usize>) = (0..tasks.len())
+            .filter(|&idx| self.shows(&tasks[idx]))
+            .partition(|&idx| !self.archive.contains(tasks[idx].id));
+        // Stable, so ties stay in the order they were added
+        match self.sort {
+            SortBy::Added => {}
+            SortBy::Eta => {
+                live.sort_by_key(|&idx| eta_sort_key(&tasks[idx]));
+                archived.sort_by_key(|&idx| eta_sort_key(&tasks[idx]));
+            }
+            SortBy::Status => {
+                live.sort_by_key(|&idx| groups::rank(tasks[idx].status.class()));
+                archived.sort_by_key(|&idx| groups::rank(tasks[idx].status.class()));
+            }
+        }
+        (live, archived)
+    }
+
+    /// Whether it gets a row at all, before the archive
+    fn shows(&self, task: &Task) -> bool {
+        self.matches(task) && !self.removals.contains(task.id) && !self.visibility.hides(task)
+    }
+
+    fn matches(&self, task: &Task) -> bool {
+        fuzzy_match(&self.filter, &task.name).is_some()
+            || fuzzy_match(&self.filter, &task.description).is_some()
+    }
+
+    /// The highlighted task, as long as it's showing. Goes by ID, not row. None while the
+    /// highlight's on a group header
+    pub fn selected(&self, tasks: &[Task]) -> Option<Id> {
+        let Some(Cursor::Task(id)) = self.cursor else {
+            return None;
+        };
+        self.order(tasks)
+            .into_iter()
+            .any(|idx| tasks[idx].id == id)
+            .then_some(id)
+    }
+
+    /// The group whose header's highlighted, if it's showing
+    pub fn selected_group(&self, tasks: &[Task]) -> Option<StatusClass> {
+        let Some(Cursor::Header(class)) = self.cursor else {
+            return None;
+        };
+        self.entries(tasks)
+            .0
+            .contains(&Entry::Header(class))
+            .then_some(class)
+    }
+
+    /// Folds the highlighted group, or unfolds it. False if the highlight isn't on a header
+    pub fn toggle_fold(&mut self, tasks: &[Task]) -> bool {
+        let Some(class) = self.selected_group(tasks) else {
+            return false;
+        };
+        self.groups.toggle(class);
+        true
+    }
+
+    /// Screen row the highlight was on when last rendered into `area`, for popups that want to
+    /// sit next to it. None if it's scrolled out of sight or there isn't one
+    pub fn highlight_y(&self, area: Rect) -> Option<u16> {
+        self.row_y(area, self.state.selected()?)
+    }
+
+    /// Screen row table row `row` was on when last rendered into `area`, if it was in sight
+    fn row_y(&self, area: Rect, row: usize) -> Option<u16> {
+        let offset = self.state.offset();
+        let row = row.checked_sub(offset)?;
+        // Expanded rows above it push it down by more than one
+        let above: u16 = (offset..offset + row).map(|row| self.height(row)).sum();
+        let inner = self.block(String::new()).inner(area);
+        // Under the header
+        let y = inner.y + 1 + above;
+        (y < inner.bottom()).then_some(y)
     }
 
-    /// Selects the previous item in the table, wrapping around.
-    pub fn previous(&mut self, num_rows: usize) {
-        if num_rows == 0 {
+    /// The border round the table, padded however much the chrome allows
+    fn block(&self, title: String) -> Block<'static> {
+        let mut padding = Chrome::pick(self.compact).table_padding;
+        // A line over the header for the view spec, when there's one to show
+        padding.top += !self.view_spec().is_default() as u16;
+        Block::bordered().title(title).padding(padding)
+    }
+
+    /// Highlights the top row, if there is one
+    pub fn select_first(&mut self, tasks: &[Task]) {
+        if let Some(&entry) = self.entries(tasks).0.first() {
+            self.cursor = Some(Cursor::of(entry, tasks));
+            self.resolve(tasks);
+        }
+    }
+
+    /// Everything that hasn't been removed, shown or not
+    pub fn kept<'a>(&'a self, tasks: &'a [Task]) -> impl Iterator<Item = &'a Task> + 'a {
+        tasks.iter().filter(|task| !self.removals.contains(task.id))
+    }
+
+    /// What `Chain::find` walks. Removed tasks aren't in it, so links to them come out dangling
+    pub fn links<'a>(&'a self, tasks: &'a [Task]) -> impl Iterator<Item = (Id, &'a [Id])> + 'a {
+        self.kept(tasks)
+            .map(|task| (task.id, task.depends_on.as_slice()))
+    }
+
+    /// Highlights `id`, if it's showing
+    pub fn select(&mut self, tasks: &[Task], id: Id) {
+        if self.order(tasks).iter().any(|&idx| tasks[idx].id == id) {
+            self.cursor = Some(Cursor::Task(id));
+            self.resolve(tasks);
+        }
+    }
+
+    /// Moves the highlight to the next task (or the previous, going back) that `is_match`, going
+    /// round the end if it has to. Only rows that are showing count, and from a header it's as if
+    /// the highlight was on nothing. False if none of them match
+    pub fn jump_to(
+        &mut self,
+        tasks: &[Task],
+        forward: bool,
+        is_match: impl Fn(&Task) -> bool,
+    ) -> bool {
+        let order = self.order(tasks);
+        let from = self
+            .selected(tasks)
+            .and_then(|id| order.iter().position(|&idx| tasks[idx].id == id));
+        let Some(at) =
+            nav::next_match(from, order.len(), forward, |at| is_match(&tasks[order[at]]))
+        else {
+            return false;
+        };
+        self.cursor = Some(Cursor::Task(tasks[order[at]].id));
+        self.resolve(tasks);
+        true
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.cursor = None;
+        self.state.select(None);
+    }
+
+    /// Works out which row the selected task (or header) is on now. Rows move whenever tasks are
+    /// sorted, filtered, folded or removed, and the highlight has to stay on the task, not the
+    /// row. If it's not showing anymore whatever slid into its row takes over (the one below,
+    /// usually), or the last row if it was at the bottom
+    pub fn resolve(&mut self, tasks: &[Task]) {
+        self.visibility.watch(tasks);
+        let Some(cursor) = self.cursor else {
             self.state.select(None);
             return;
+        };
+        let (entries, summary) = self.entries(tasks);
+        if let Some(at) = entries.iter().position(|&entry| cursor.is(entry, tasks)) {
+            self.state.select(Some(archive::row(at, summary)));
+            return;
         }
-        let new_sel = match self.state.selected() {
-            Some(old_sel) => {
-                if old_sel == 0 {
-                    num_rows - 1
-                } else {
-                    old_sel - 1
-                }
+        let old = self
+            .state
+            .selected()
+            .map_or(0, |row| archive::unrow(row, summary));
+        let at = old.min(entries.len().saturating_sub(1));
+        match entries.get(at) {
+            Some(&entry) => {
+                self.cursor = Some(Cursor::of(entry, tasks));
+                self.state.select(Some(archive::row(at, summary)));
+            }
+            // Nothing showing at all. Hang on to the ID in case it comes back (filter cleared)
+            None => self.state.select(None),
+        }
+    }
+
+    pub fn next(&mut self, tasks: &[Task]) {
+        self.navigate(tasks, Move::Next);
+    }
+
+    pub fn previous(&mut self, tasks: &[Task]) {
+        self.navigate(tasks, Move::Previous);
+    }
+
+    /// Expands the highlighted task's row, or collapses it if it already was. False if there's
+    /// nothing highlighted
+    pub fn toggle_expanded(&mut self, tasks: &[Task]) -> bool {
+        let Some(id) = self.selected(tasks) else {
+            return false;
+        };
+        if !self.expanded.remove(&id) {
+            self.expanded.insert(id);
+        }
+        true
+    }
+
+    /// Lines `row` took last render
+    fn height(&self, row: usize) -> u16 {
+        self.heights.get(row).copied().unwrap_or(1)
+    }
+
+    /// How many rows a page is from `row`, going down (or up): as many as fit in the lines there
+    /// are, however tall they are, but always at least one
+    fn page_from(&self, row: usize, down: bool) -> usize {
+        let mut lines = 0;
+        let mut rows = 0;
+        let mut next = Some(row);
+        while let Some(row) = next {
+            lines += self.height(row) as usize;
+            if lines > self.page {
+                break;
             }
-            None => 0, // Select the first item if nothing is selected
+            rows += 1;
+            next = if down {
+                (row + 1 < self.heights.len()).then_some(row + 1)
+            } else {
+                row.checked_sub(1)
+            };
+        }
+        rows.max(1)
+    }
+
+    /// Moves the highlight by row, whatever the rows are sorted and filtered down to right now
+    pub fn navigate(&mut self, tasks: &[Task], to: Move) {
+        self.resolve(tasks);
+        let (entries, summary) = self.entries(tasks);
+        let from = self.state.selected().unwrap_or(0);
+        let page = match to {
+            Move::PageUp => self.page_from(from, false),
+            _ => self.page_from(from, true),
         };
-        self.state.select(Some(new_sel));
+        let at = self
+            .state
+            .selected()
+            .map(|row| archive::unrow(row, summary));
+        let Some(at) = self.nav.apply(at, entries.len(), page, to) else {
+            self.clear_selection();
+            return;
+        };
+        self.cursor = Some(Cursor::of(entries[at], tasks));
+        self.state.select(Some(archive::row(at, summary)));
     }
 }
 
@@ -70,98 +537,584 @@ impl<'a> StatefulWidget for &'a mut TaskTable {
     type State = &'a Vec<Task>;
 
     fn render(self, area: Rect, buf: &mut Buffer, tasks: &mut Self::State) {
-        let header = Row::new(vec![
-            "ID",
-            "Name",
-            "Status",
-            "Halt?",
-            "Progress",
-            "Start Time",
-            "End Time",
-            "Description",
-        ])
+        self.resolve(tasks);
+        let order = self.order(tasks);
+        let mut title = " Task Table".to_string();
+        match self.sort {
+            SortBy::Added => {}
+            SortBy::Eta => title.push_str(" (soonest first)"),
+            SortBy::Status => title.push_str(" (by status)"),
+        }
+        let hidden = self.hidden(tasks);
+        if hidden > 0 {
+            title.push_str(&format!(" (+{hidden} hidden)"));
+        }
+        // Say so when rows are hidden, or someone's going to think their tasks vanished
+        if self.editing_filter || !self.filter.is_empty() {
+            let cursor = if self.editing_filter { "▏" } else { "" };
+            title.push_str(&format!(
+                " [/{}{cursor} {} of {}]",
+                self.filter,
+                order.len(),
+                self.kept(tasks).count()
+            ));
+        }
+        title.push(' ');
+        let block = self.block(title);
+        self.page = block.inner(area).height.saturating_sub(1) as usize;
+        let inner = block.inner(area);
+        if let Some(spec) = view_spec::line(&self.view_spec(), inner.width, self.ascii) {
+            spec.render(
+                Rect {
+                    y: inner.y.saturating_sub(1),
+                    height: 1,
+                    ..inner
+                },
+                buf,
+            );
+        }
+
+        // A header over nothing just looks broken, so say something instead
+        if self.kept(tasks).next().is_none() {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            // Rough wrap estimate to center vertically, close enough for one short sentence
+            let lines = (EMPTY_MESSAGE.chars().count() as u16).div_ceil(inner.width.max(1));
+            let message_area = centered_rect(inner, inner.width, lines);
+            Paragraph::new(EMPTY_MESSAGE)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true })
+                .style(theme::current().dim)
+                .render(message_area, buf);
+            return;
+        }
+
+        let columns: Vec<Column> = self.columns.visible().collect();
+        // The rest of the selected task's chain gets marked, if it's in one
+        let linked = self
+            .selected(tasks)
+            .map(|id| Chain::find(self.links(tasks), id))
+            .filter(|chain| !chain.is_lone())
+            .map_or(vec![], |chain| chain.members);
+        let id_width = order
+            .iter()
+            .map(|&idx| {
+                let marker = linked.contains(&tasks[idx].id) as usize * LINKED.chars().count();
+                (id_text(&tasks[idx]).chars().count() + marker) as u16
+            })
+            .max()
+            .unwrap_or(0);
+        // Plain output goes without the glyphs, the status column already says it in words
+        let glyphs = theme::current().glyphs();
+        let room = block.inner(area).width.saturating_sub(self.symbol_width());
+        let columns = fitting(columns, room, id_width, glyphs);
+        let header = Row::new(glyphs.then_some("").into_iter().chain(columns.iter().map(
+            |&column| match self.compact {
+                true => column.letter(),
+                false => column.title(),
+            },
+        )))
         .style(Style::new().bold()) // Example style
         .height(1);
 
-        let mut row_ctr = 0;
-        let rows: Vec<Row> = tasks // Use the state variable name `tasks`
+        let widths: Vec<Constraint> = glyphs
+            .then_some(Constraint::Length(1))
+            .into_iter()
+            .chain(columns.iter().map(|&c| width(c, id_width)))
+            .collect();
+        let wrap_width = columns
             .iter()
-            .map(|task| {
-                row_ctr += 1;
-                row_style(
-                    Row::new(vec![
-                        Cell::from(task.id.to_string()),
-                        Cell::from(task.name),
-                        status_cell_style(&task.status),
-                        abort_cell_style(&task.status, task.pending_cancel),
-                        Cell::from(format!("{}%", task.progress)),
-                        Cell::from(task.start.format("%I:%M:%S %P").to_string()),
-                        Cell::from(match task.end {
-                            Some(time) => time.format("%I:%M:%S %P").to_string(),
-                            None => "-".to_string(),
-                        }),
-                        Cell::from(task.description),
-                    ]),
-                    row_ctr,
-                )
+            .position(|&column| column == Column::Description)
+            .map(|idx| {
+                self.description_width(block.inner(area).width, &widths, idx + glyphs as usize)
             })
-            .collect();
+            .unwrap_or(0);
 
-        let widths = [
-            //TODO: These could be made dynamic
-            Constraint::Max(4),
-            Constraint::Length(16),
-            Constraint::Length(10),
-            Constraint::Length(7),
-            Constraint::Length(12),
-            Constraint::Length(14),
-            Constraint::Length(14),
-            Constraint::Min(42), // Use Min for the last one to fill space
-        ];
+        let mut row_ctr = 0;
+        let now = Instant::now();
+        let spin = match self.still {
+            true => 0,
+            false => chrono::Local::now().timestamp_millis(),
+        };
+        self.heights.clear();
+        let (live, archived) = self.sections(tasks);
+        // Headers and the summary get rows of their own, drawn over once the table's down since
+        // they're wider than any column
+        let (entries, summary) = self.entries(tasks);
+        let mut headers: Vec<(usize, StatusClass)> = vec![];
+        let mut rows: Vec<Row> = Vec::with_capacity(entries.len() + 1);
+        // Which row each task went in, for its color
+        let mut tinted: Vec<(usize, Id, u16)> = vec![];
+        for (at, entry) in entries.into_iter().enumerate() {
+            if summary == Some(at) {
+                rows.push(Row::default().height(1));
+                self.heights.push(1);
+            }
+            let task = match entry {
+                Entry::Task(idx) => &tasks[idx],
+                Entry::Header(class) => {
+                    headers.push((rows.len(), class));
+                    rows.push(Row::default().height(1));
+                    self.heights.push(1);
+                    continue;
+                }
+            };
+            row_ctr += 1;
+            let flash = match task.status_changed_at {
+                Some(at) if self.flash && !self.still => {
+                    theme::current().flash(now.duration_since(at))
+                }
+                _ => Modifier::empty(),
+            };
+            let expanded = wrap_width > 0 && self.expanded.contains(&task.id);
+            let mut height = 1;
+            let momentum = task.still_for(self.now).and_then(|still| {
+                let quiet = task.quiet_for(self.now);
+                momentum::with_heartbeat(task.status, still, quiet, &self.momentum)
+            });
+            let is_linked = self.cursor != Some(Cursor::Task(task.id)) && linked.contains(&task.id);
+            let cells = columns.iter().map(|&column| match column {
+                Column::Id if is_linked => Cell::from(Line::from(vec![
+                    Span::styled(LINKED, theme::current().accent),
+                    Span::styled(id_text(task), theme::current().accent),
+                ])),
+                Column::Description if expanded => {
+                    let text = expanded_description(task, wrap_width);
+                    // Any taller than the table and `Table` wouldn't draw it at all
+                    height = text.height().clamp(1, self.page.max(1)) as u16;
+                    Cell::from(text)
+                }
+                _ => cell_for(column, task, &self.filter, spin, momentum),
+            });
+            let row = Row::new(
+                glyphs
+                    .then(|| glyph_cell(task, self.ascii))
+                    .into_iter()
+                    .chain(cells),
+            )
+            .height(height);
+            self.heights.push(height);
+            // Archived rows are only there to look at
+            let row = match summary {
+                Some(summary) if rows.len() > summary => row.style(theme::current().dim),
+                _ => row_style(row, row_ctr).add_modifier(flash),
+            };
+            tinted.push((rows.len(), task.id, height));
+            rows.push(row);
+        }
+        if summary == Some(rows.len()) {
+            rows.push(Row::default().height(1));
+            self.heights.push(1);
+        }
 
+        let inner = block.inner(area);
         // The block is now rendered by the App, we only render the table itself
         let table = Table::new(rows, widths)
             .header(header)
-            .style(Color::White)
-            .highlight_symbol("> ")
-            .block(
-                Block::bordered()
-                    .title(" Task Table ")
-                    .padding(Padding::new(1, 1, 0, 1)),
-            );
+            .style(theme::current().text)
+            .highlight_symbol(theme::current().highlight_symbol())
+            .block(block);
 
         // Use StatefulWidget's render method
         StatefulWidget::render(table, area, buf, &mut self.state);
+
+        // Past the highlight symbol, like the cells, so it's not drawn over on the highlighted one
+        let symbol = match self.state.selected() {
+            Some(_) => theme::current().highlight_symbol().chars().count() as u16,
+            None => 0,
+        };
+        for (row, class) in headers {
+            let Some(y) = self.row_y(area, row) else {
+                continue;
+            };
+            let count = live
+                .iter()
+                .filter(|&&idx| tasks[idx].status.class() == class)
+                .count();
+            Line::styled(
+                self.groups.header(class, count, self.ascii),
+                Style::new().bold(),
+            )
+            .render(
+                Rect {
+                    x: inner.x + symbol,
+                    y,
+                    width: inner.width.saturating_sub(symbol),
+                    height: 1,
+                },
+                buf,
+            );
+        }
+        // Each task's own color down the left of its row. In the padding, or on the border if
+        // there isn't any, so it doesn't take room from the columns
+        let mark = if self.ascii { MARKER_ASCII } else { MARKER };
+        if theme::current().tints_tasks() && inner.x > area.x {
+            let x = inner.x - 1;
+            for (row, id, height) in tinted {
+                let (Some(y), Some(color)) =
+                    (self.row_y(area, row), theme::current().task_color(id))
+                else {
+                    continue;
+                };
+                for y in y..y.saturating_add(height).min(inner.bottom()) {
+                    let cell = &mut buf[(x, y)];
+                    if cell.symbol() == " " {
+                        cell.set_symbol(mark);
+                    }
+                    cell.set_fg(color);
+                }
+            }
+        }
+        if let Some(y) = summary.and_then(|at| self.row_y(area, at)) {
+            Line::styled(
+                self.archive.summary(archived.len(), self.ascii),
+                theme::current().muted,
+            )
+            .render(
+                Rect {
+                    y,
+                    height: 1,
+                    ..inner
+                },
+                buf,
+            );
+        }
+    }
+}
+
+impl TaskTable {
+    /// Columns the description gets when the table's drawn `width` wide, worked out the way
+    /// `Table` itself will: the highlight symbol's room (if something's highlighted) comes off
+    /// first, then the columns are laid out one space apart
+    fn description_width(&self, width: u16, widths: &[Constraint], idx: usize) -> usize {
+        let area = Rect::new(0, 0, width.saturating_sub(self.symbol_width()), 1);
+        Layout::horizontal(widths.iter().copied())
+            .flex(Flex::Start)
+            .spacing(1)
+            .split(area)
+            .get(idx)
+            .map_or(0, |column| column.width as usize)
+    }
+
+    /// What the highlight symbol takes off the front of every row, while something's highlighted
+    fn symbol_width(&self) -> u16 {
+        match self.state.selected() {
+            Some(_) => theme::current().highlight_symbol().chars().count() as u16,
+            None => 0,
+        }
+    }
+}
+
+/// The whole description wrapped to `width`, then why the task ended if it said. Filter matches
+/// aren't picked out here, wrapping moves them about
+fn expanded_description(task: &Task, width: usize) -> Text<'static> {
+    let theme = theme::current();
+    let mut lines: Vec<Line> = wrap(&task.description, width)
+        .into_iter()
+        .map(Line::from)
+        .collect();
+    if let Some(reason) = &task.cancel_reason {
+        let style = match task.status {
+            TaskStatus::Failed => theme.alert,
+            _ => theme.dim,
+        };
+        lines.extend(
+            wrap(reason, width)
+                .into_iter()
+                .map(|line| Line::styled(line, style)),
+        );
+    }
+    Text::from(lines)
+}
+
+/// What `task` shows under `column`. Filter matches get picked out in the text ones. `spin` is
+/// how far round spinners are, in milliseconds
+fn cell_for<'a>(
+    column: Column,
+    task: &'a Task,
+    filter: &str,
+    spin: i64,
+    momentum: Option<Momentum>,
+) -> Cell<'a> {
+    match column {
+        Column::Id => Cell::from(id_text(task)),
+        Column::Name => name_cell(filter, task),
+        Column::Status => status_cell(task),
+        Column::Halt => halt_cell(task),
+        Column::Progress => progress_cell(task, spin, momentum),
+        Column::Tired => fatigue_cell(task),
+        Column::Cpu => cpu_cell(task),
+        Column::Eta => Cell::from(eta_text(task)),
+        Column::Start => Cell::from(match task.start {
+            Some(time) => time.format("%I:%M:%S %P").to_string(),
+            None => "-".to_string(),
+        }),
+        Column::End => Cell::from(match task.end {
+            Some(time) => time.format("%I:%M:%S %P").to_string(),
+            None => "-".to_string(),
+        }),
+        Column::Description => highlight_cell(filter, &task.description),
     }
 }
 
-fn status_cell_style(status: &TaskStatus) -> Cell {
-    let cell = Cell::from(status.to_string());
-    match status {
-        TaskStatus::Sleeping => cell.style(Color::Gray),
-        TaskStatus::Finished => cell.style(Color::Green),
-        TaskStatus::OnStrike => cell.style(Color::Red).slow_blink(),
-        TaskStatus::Running => cell.style(Color::White),
-        _ => cell,
+/// Room for a status and a short milestone after it
+const STATUS_WIDTH: u16 = 24;
+
+/// The ID, then what it's waiting on if anything: `12 ⇠#9`
+fn id_text(task: &Task) -> String {
+    match task.depends_on.is_empty() {
+        true => task.id.to_string(),
+        false => format!("{} {}", task.id, chain::waits_on(&task.depends_on)),
     }
 }
 
-fn abort_cell_style(status: &TaskStatus, cancel: bool) -> Cell {
-    if cancel {
-        match status {
-            TaskStatus::Canceled => Cell::from("Done").style(Color::Green),
-            _ => Cell::from("Req").style(Color::Yellow),
+/// Marks the rows in the same chain as the selected one, in front of the ID
+const LINKED: &str = "·";
+/// The task's own color down the left of its row
+pub const MARKER: &str = "▎";
+const MARKER_ASCII: &str = "|";
+
+//TODO: These could be made dynamic
+/// `id_width` is however wide the widest ID cell needs to be
+fn width(column: Column, id_width: u16) -> Constraint {
+    match column {
+        Column::Id => Constraint::Length(id_width.max(4)),
+        Column::Name => Constraint::Length(20), // Room for a " (2)" on the end of the longer names
+        Column::Status => Constraint::Length(STATUS_WIDTH), // "Sleeping (past halfway)"
+        Column::Halt => Constraint::Length(9),  // "abandoned"
+        Column::Progress => Constraint::Length(14), // "100% (stopped)"
+        Column::Tired => Constraint::Length(5),
+        Column::Cpu => Constraint::Length(7), // "~612.3s"
+        Column::Eta => Constraint::Length(6),
+        Column::Start | Column::End => Constraint::Length(14),
+        Column::Description => Constraint::Fill(1), // Soaks up the spare room, and only that
+    }
+}
+
+/// What goes when the table's too narrow for every column, least missed first, down to the
+/// description if it can't have a readable width. The rest always stay, squeezed if they have to
+const DROP_ORDER: [Column; 7] = [
+    Column::End,
+    Column::Start,
+    Column::Cpu,
+    Column::Tired,
+    Column::Halt,
+    Column::Eta,
+    Column::Description,
+];
+/// Room the description's kept before anything else is dropped to make it
+const DESCRIPTION_ROOM: u16 = 16;
+
+/// `columns`, less whatever in `DROP_ORDER` has to go for them all to fit in `room` (with the
+/// description, while it's there, getting `DESCRIPTION_ROOM` of it)
+fn fitting(mut columns: Vec<Column>, room: u16, id_width: u16, glyphs: bool) -> Vec<Column> {
+    let needs = |columns: &[Column]| {
+        let cells: u16 = columns
+            .iter()
+            .map(|&column| match width(column, id_width) {
+                Constraint::Length(length) => length,
+                _ => DESCRIPTION_ROOM,
+            })
+            .sum();
+        let gaps = (columns.len() + glyphs as usize).saturating_sub(1) as u16;
+        cells + gaps + glyphs as u16
+    };
+    for column in DROP_ORDER {
+        if needs(&columns) <= room {
+            break;
         }
+        columns.retain(|&shown| shown != column);
+    }
+    columns
+}
+
+/// How the legend is ordered: working first, then the ways things end up
+const GLYPH_ORDER: [&str; 12] = [
+    "glyph.working",
+    "glyph.sleeping",
+    "glyph.finishing",
+    "glyph.shell",
+    "glyph.strike",
+    "glyph.waiting",
+    "glyph.starting",
+    "glyph.no_thread",
+    "glyph.done",
+    "glyph.failed",
+    "glyph.stopped",
+    "glyph.never_started",
+];
+
+/// One character for what a task is and how it's doing. Finished-ish statuses look the same
+/// whatever the kind is, it's only while working that the kind is worth telling apart
+fn glyph(kind: TaskKind, status: TaskStatus, ascii: bool) -> char {
+    let (fancy, plain) = match (kind, status) {
+        (TaskKind::Dummy, TaskStatus::Running) => ('◆', '*'),
+        (TaskKind::Dummy, TaskStatus::Sleeping) => ('◇', 'z'),
+        (_, TaskStatus::Finalizing) => ('◈', '~'),
+        (TaskKind::Shell, TaskStatus::Running | TaskStatus::Sleeping) => ('❯', '$'),
+        (_, TaskStatus::OnStrike) => ('⚑', '!'),
+        (_, TaskStatus::Scheduled) => ('◷', '@'),
+        (_, TaskStatus::KnownUnknown) => ('…', '.'),
+        (_, TaskStatus::WaitingForThread) => ('⧗', 'q'),
+        (_, TaskStatus::Finished) => ('✔', '+'),
+        (_, TaskStatus::Failed) => ('✘', 'x'),
+        (_, TaskStatus::Canceled) => ('■', '-'),
+        (_, TaskStatus::FailedToStart) => ('⊘', '0'),
+    };
+    if ascii {
+        plain
     } else {
-        Cell::from(" ")
+        fancy
     }
 }
 
+/// The key in `strings` for what the legend says next to a glyph. Same cases as `glyph`
+fn glyph_meaning(kind: TaskKind, status: TaskStatus) -> &'static str {
+    match (kind, status) {
+        (TaskKind::Dummy, TaskStatus::Running) => "glyph.working",
+        (TaskKind::Dummy, TaskStatus::Sleeping) => "glyph.sleeping",
+        (_, TaskStatus::Finalizing) => "glyph.finishing",
+        (TaskKind::Shell, TaskStatus::Running | TaskStatus::Sleeping) => "glyph.shell",
+        (_, TaskStatus::OnStrike) => "glyph.strike",
+        (_, TaskStatus::Scheduled) => "glyph.waiting",
+        (_, TaskStatus::KnownUnknown) => "glyph.starting",
+        (_, TaskStatus::WaitingForThread) => "glyph.no_thread",
+        (_, TaskStatus::Finished) => "glyph.done",
+        (_, TaskStatus::Failed) => "glyph.failed",
+        (_, TaskStatus::Canceled) => "glyph.stopped",
+        (_, TaskStatus::FailedToStart) => "glyph.never_started",
+    }
+}
+
+fn glyph_cell(task: &Task, ascii: bool) -> Cell<'static> {
+    Cell::from(glyph(task.kind, task.status, ascii).to_string())
+        .style(theme::current().status(&task.status))
+}
+
+/// Picks out the characters the filter matched, if it matched this cell at all
+fn highlight_cell(filter: &str, text: &str) -> Cell<'static> {
+    let positions = fuzzy_match(filter, text).unwrap_or_default();
+    Cell::from(highlight(text, &positions))
+}
+
+/// The name, then which try it is if it's been tried more than once
+fn name_cell(filter: &str, task: &Task) -> Cell<'static> {
+    let positions = fuzzy_match(filter, &task.name).unwrap_or_default();
+    let mut line = highlight(&task.name, &positions);
+    if let Some(attempt) = task.attempt {
+        line.push_span(Span::styled(format!(" {attempt}"), theme::current().dim));
+    }
+    Cell::from(line)
+}
+
+/// Only running work has an ETA worth showing
+fn eta_text(task: &Task) -> String {
+    if task.status.is_working() {
+        task.eta.eta().to_string()
+    } else {
+        "—".to_string()
+    }
+}
+
+fn eta_sort_key(task: &Task) -> (u8, Duration) {
+    if task.status.is_working() {
+        task.eta.eta().sort_key()
+    } else {
+        (3, Duration::ZERO) // Done (or worse) goes to the bottom
+    }
+}
+
+/// `momentum` is how the percentage is colored, if it's still going
+fn progress_cell(task: &Task, spin: i64, momentum: Option<Momentum>) -> Cell<'_> {
+    let theme = theme::current();
+    let style = match momentum {
+        Some(Momentum::Moving) => theme.good,
+        Some(Momentum::Idle) => theme.notice,
+        Some(Momentum::Stalled) => theme.alert,
+        None => Style::new(),
+    };
+    match (task.kind, &task.status) {
+        (TaskKind::Shell, TaskStatus::Running) => Cell::from(theme::current().spinner(spin)),
+        (TaskKind::Shell, TaskStatus::Failed) => match task.exit_code {
+            Some(code) => Cell::from(format!("exit {code}")),
+            None => Cell::from("killed"),
+        },
+        // Never had a percentage to stop at
+        (TaskKind::Shell, TaskStatus::Canceled) => Cell::from("stopped"),
+        (_, TaskStatus::Scheduled | TaskStatus::FailedToStart) => Cell::from("-"),
+        (_, TaskStatus::Canceled) => Cell::from(format!(
+            "{}% (stopped)",
+            task.progress_at_cancel.unwrap_or(task.progress)
+        )),
+        _ => Cell::from(format!("{}%", task.shown_progress())).style(style),
+    }
+}
+
+/// Shell tasks don't get tired, and nobody cares how tired the dead ones were
+fn fatigue_cell(task: &Task) -> Cell<'_> {
+    let awake = task.status.is_working() || task.status == TaskStatus::OnStrike;
+    if task.kind == TaskKind::Shell || !awake {
+        return Cell::from("-");
+    }
+    let theme = theme::current();
+    let style = match task.fatigue {
+        0..50 => theme.good,
+        50..FATIGUE_LIMIT => theme.notice,
+        _ => theme.alert,
+    };
+    Cell::from(task.fatigue.to_string()).style(style)
+}
+
+/// Nothing to show until its first report, and shell tasks never have one
+fn cpu_cell(task: &Task) -> Cell<'static> {
+    match task.cpu {
+        Some(cpu) if cpu.estimated => Cell::from(cpu.to_string()).style(theme::current().dim),
+        Some(cpu) => Cell::from(cpu.to_string()),
+        None => Cell::from("—"),
+    }
+}
+
+/// The status, and while it's working whatever it last said it had got to, cut to fit
+fn status_cell(task: &Task) -> Cell<'static> {
+    let theme = theme::current();
+    let status = theme.status_text(&task.status);
+    if task.out_of_retries {
+        return Cell::from(Line::from(vec![
+            status,
+            Span::styled(" (final)", theme.alert),
+        ]));
+    }
+    let Some(label) = task.latest_milestone().filter(|_| task.status.is_working()) else {
+        return Cell::from(status);
+    };
+    // Brackets and the space before them
+    let room = (STATUS_WIDTH as usize).saturating_sub(status.content.chars().count() + 3);
+    if room == 0 {
+        return Cell::from(status);
+    }
+    Cell::from(Line::from(vec![
+        status,
+        Span::styled(format!(" ({})", truncate(label, room)), theme.dim),
+    ]))
+}
+
+fn halt_cell(task: &Task) -> Cell<'static> {
+    let theme = theme::current();
+    let style = match task.cancel {
+        CancelState::None => theme.dim,
+        CancelState::Requested => theme.notice,
+        CancelState::Acked => theme.good,
+        CancelState::Forced => theme.alert,
+        CancelState::Abandoned => theme.muted,
+    };
+    Cell::from(task.halt_label(chrono::Local::now())).style(style)
+}
+
 // Could do more, but enforces alternating color
 fn row_style(row: Row, ctr: i32) -> Row {
     if ctr % 2 == 0 {
         row
     } else {
-        row.style(Color::Gray)
+        row.style(theme::current().alt_row)
     }
 }
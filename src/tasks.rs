@@ -1,118 +1,1181 @@
-use crate::task_picker::CandidateTask;
+use crate::candidates::CandidateTask;
+use crate::chaos::{self, ChaosConfig};
+use crate::clock::{Clock, SystemClock};
+use crate::completion::{Completion, HarvestError, Outcome};
+use crate::cpu::{self, CpuMeter, CpuTime};
+use crate::eta::EtaEstimator;
+use crate::latency::Stamped;
+use crate::retry::{Attempt, RetryPolicy};
+use crate::smooth::SmoothProgress;
+use crate::strings;
 use chrono::{DateTime, Local};
-use std::thread::sleep;
-use std::time::Duration;
-use std::{fmt, mem};
-use tokio::sync::broadcast::error::TryRecvError;
-use tokio::sync::{broadcast, mpsc};
-use tokio::task::{self, JoinHandle};
-use tracing::{error, info, instrument, trace, warn};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::process::Stdio;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::runtime::{Handle, TryCurrentError};
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::{TryRecvError, TrySendError};
+use tracing::{debug, error, info, instrument, trace, warn, Instrument, Span};
 
 const MAX_SLEEPYTIME: u64 = 30;
+/// What a chosen duration has to be in, in seconds
+pub const TARGET_SECS: RangeInclusive<u64> = 1..=600;
+/// Set for replays (and the recordings they come from), so workers roll the same dice each time
+static WORKER_SEED: OnceLock<u64> = OnceLock::new();
+/// Random numbers summed per work block at 1x intensity
+const WORK_PER_BLOCK: f32 = 11333777.0;
+/// Each number's folded under this before it's added
+const WORK_MODULUS: i128 = 500;
+/// Range for the work intensity multiplier. Anything outside gets clamped
+pub const MIN_INTENSITY: f32 = 0.1;
+pub const MAX_INTENSITY: f32 = 2.0;
+/// Chance per work block that a task decides it's had enough
+const STRIKE_CHANCE: f64 = 0.1;
+/// Fatigue piled on per work block at 1x intensity
+const FATIGUE_PER_BLOCK: f32 = 25.0;
+/// Fatigue that wears off per second of sleep
+const REST_PER_SEC: u64 = 5;
+/// At or past this much fatigue a task strikes for sure, no dice involved
+pub const FATIGUE_LIMIT: u8 = 80;
+/// Control messages a task can have queued before App's sends start failing
+const CONTROL_CAPACITY: usize = 16;
+/// How often `join` looks to see if the worker's done
+const JOIN_POLL: Duration = Duration::from_millis(5);
+/// How long a shell task's child gets after SIGTERM before we stop asking nicely
+const SHELL_GRACE_PERIOD: Duration = Duration::from_secs(5);
+/// Output lines kept per task. Older ones fall off the front
+pub const OUTPUT_LINES: usize = 200;
+/// Control messages kept per task. Same deal, oldest go first
+pub const AUDIT_LEN: usize = 100;
+/// Milestones kept per task. Same again
+pub const MILESTONES: usize = 20;
+/// Characters of a milestone label kept. Anything past it is the worker rambling
+pub const MILESTONE_LABEL: usize = 40;
+/// Closest two progress reports get in `ReportingMode::Sparse`, bar the last block's
+pub const SPARSE_INTERVAL: Duration = Duration::from_secs(5);
+/// Longest a dummy goes without a heartbeat while it's crunching
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+/// Numbers crunched between looks at the clock for whether a heartbeat's due
+pub const HEARTBEAT_CHUNK: usize = 100_000;
 pub type Id = usize;
 
 #[derive(Debug)]
 pub struct Task {
     pub id: Id,
-    pub name: &'static str,
+    /// What we show. Same as `candidate` unless there's already one of those, then it's numbered
+    pub name: String,
+    /// The name of the candidate this came from, for anything that needs to match on it
+    pub candidate: String,
+    /// Which copy of its candidate this is, from 1. Only shown from 2 up
+    pub copy: usize,
     pub status: TaskStatus,
-    pub start: DateTime<Local>,
+    /// When the status last changed in a way worth pointing out (see `set_status`)
+    pub status_changed_at: Option<Instant>,
+    /// When the worker was put on the runtime. `None` for anything that never got one
+    pub start: Option<DateTime<Local>>,
+    /// Same moment as `start`, but for measuring how long it's been
+    activated_at: Option<Instant>,
+    /// How long after `activate` the worker first said anything. A dummy's worker needs a
+    /// blocking thread before it can, so this is mostly time spent queued for one
+    pub thread_wait: Option<Duration>,
+    /// Which message the worker last sent (see `TaskTxMsg::kind`) and when it was heard
+    pub last_heard: Option<(&'static str, Instant)>,
     pub end: Option<DateTime<Local>>,
-    pub description: &'static str,
-    pub handle: Option<JoinHandle<Option<i128>>>,
+    pub description: String,
+    /// The worker's handle while it's going, what it came back with after
+    pub completion: Completion,
     pub progress: u8, // This is the part where I regretted not just sharing the struct w/ task
-    pub pending_cancel: bool,
+    /// When a report last moved `progress`. Reports saying the same again don't count
+    pub progress_changed_at: Option<Instant>,
+    /// When its worker last said it was still alive, see `TaskTxMsg::Heartbeat`
+    pub heartbeat_at: Option<Instant>,
+    /// Reports lower than one it had already sent, which left `progress` where it was. Every try
+    /// is a task of its own (see `retry`), so a new one starts again from 0 with none of these
+    pub backwards: u32,
+    /// How worn out it said it was, last it reported. 0 to 100
+    pub fatigue: u8,
+    /// CPU its worker's used, as of its last report. None until then, and always for shell tasks
+    pub cpu: Option<CpuTime>,
+    /// Whatever the user wanted to say about it. Can run to several lines
+    pub note: Option<String>,
+    /// How far along being stopped it is. The times below say when it got there
+    pub cancel: CancelState,
+    pub kind: TaskKind,
+    /// What a shell task runs, kept until it's activated
+    pub command: Option<String>,
+    /// How long a dummy should sleep in all, if someone picked. Otherwise its worker rolls one.
+    /// Only read by `activate`
+    pub target_secs: Option<u64>,
+    /// Its own work intensity instead of the config's, from a template. Only read by `activate`,
+    /// and `<`/`>` changes it along with everyone else's
+    pub intensity: Option<f32>,
+    /// Stopped once it's been running this long, from a template
+    pub time_limit: Option<Duration>,
+    /// Only shell tasks have one. `None` after exit means a signal got it
+    pub exit_code: Option<i32>,
+    /// Whatever the task printed, newest last
+    pub output: VecDeque<String>,
+    /// Every message we sent it or got from it, newest last
+    pub audit: VecDeque<(DateTime<Local>, String)>,
+    /// Whatever it announced it had got to, newest last
+    pub milestones: VecDeque<(DateTime<Local>, String)>,
+    /// How its worker does its pretend work. Only read by `activate`, and only ever not the
+    /// default in tests
+    pub profile: WorkProfile,
+    /// Tasks it waits on, see `chain`. Only a batch file's `after` sets these
+    pub depends_on: Vec<Id>,
+    /// The candidate's own retry policy, if it had one. Otherwise the config's goes
+    pub retry: Option<RetryPolicy>,
+    /// The first attempt, if this is a retry of it
+    pub retry_of: Option<Id>,
+    /// Which try this is, once there's more than one. The first gets one when it's retried
+    pub attempt: Option<Attempt>,
+    /// Failed on its last try, so nothing's coming after it
+    pub out_of_retries: bool,
+    /// How long a break it wants, while it's on strike
+    pub demand_secs: Option<u64>,
+    /// Why it stopped, if it wasn't just us asking
+    pub cancel_reason: Option<String>,
+    /// Where progress was when it stopped. Anything showing up after that doesn't count
+    pub progress_at_cancel: Option<u8>,
+    /// When we asked it to stop...
+    pub cancel_requested_at: Option<DateTime<Local>>,
+    /// ...and when it told us it had
+    pub cancel_acked_at: Option<DateTime<Local>>,
+    pub eta: EtaEstimator,
+    /// Progress filled in between reports, if App bothers to update it
+    pub smooth: SmoothProgress,
+    /// This task's end of its own control channel. Nobody else hears what goes down it
+    pub control: mpsc::Sender<TaskRxMsg>,
 }
 
+/// Whatever a worker needs to remember about messages between checks
 #[derive(Debug)]
+struct WorkerState {
+    intensity: f32,
+    /// A bargain addressed to us that hasn't been dealt with yet
+    bargain: Option<u64>,
+    fatigue: u8,
+    chaos: ChaosConfig,
+    /// Chaos only gets to swallow one stop request, this is whether it has
+    ignored_stop: bool,
+    /// Every roll the worker makes comes from here, see `seed_workers`
+    rng: StdRng,
+    profile: WorkProfile,
+    /// Told to `Pause` and not let go yet
+    paused: bool,
+    /// What App was last told we're up to, so it's only told when that changes
+    said: Option<WorkerStatus>,
+    reporting: ReportingMode,
+}
+
+impl WorkerState {
+    /// How a dummy starts out, before it's heard anything
+    fn new(
+        id: Id,
+        intensity: f32,
+        chaos: ChaosConfig,
+        profile: WorkProfile,
+        reporting: ReportingMode,
+    ) -> Self {
+        Self {
+            intensity,
+            bargain: None,
+            fatigue: 0,
+            chaos,
+            ignored_stop: false,
+            rng: worker_rng(id),
+            profile,
+            paused: false,
+            said: None,
+            reporting,
+        }
+    }
+}
+
+/// What a dummy's work block is made of. Every real one is the default
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkProfile {
+    /// Random numbers summed per block at 1x intensity
+    pub per_block: f32,
+    /// Each is folded under this before it's added
+    pub modulus: i128,
+}
+
+impl Default for WorkProfile {
+    fn default() -> Self {
+        Self {
+            per_block: WORK_PER_BLOCK,
+            modulus: WORK_MODULUS,
+        }
+    }
+}
+
+impl WorkProfile {
+    /// A tiny modulus and more numbers a block than can be counted, for tests. It overflows in
+    /// the first block, whatever the intensity
+    pub fn overflowing() -> Self {
+        Self {
+            per_block: f32::MAX,
+            modulus: 2,
+        }
+    }
+
+    /// How many numbers a block sums at `intensity`. None if that's too many to count, which is
+    /// an overflow like the sum's and goes the same way
+    pub fn block_len(&self, intensity: f32) -> Option<usize> {
+        let len = self.per_block * intensity;
+        // `as` would quietly make a usize::MAX out of anything past it
+        (len.is_finite() && (0.0..usize::MAX as f32).contains(&len)).then_some(len as usize)
+    }
+}
+
+/// `sum` plus each of `samples` folded under `modulus` (by size, so never negative). None if
+/// that doesn't fit in an i128
+pub fn crunch(sum: i128, samples: impl IntoIterator<Item = i32>, modulus: i128) -> Option<i128> {
+    samples.into_iter().try_fold(sum, |acc, num| {
+        acc.checked_add((num as i128 % modulus).abs())
+    })
+}
+
+/// When a worker last heartbeat, so a long block can say it's alive partway through
+#[derive(Debug, Clone, Copy)]
+pub struct Heartbeat {
+    every: Duration,
+    /// Numbers between looks at the clock
+    chunk: usize,
+    last: Instant,
+}
+
+impl Heartbeat {
+    pub fn new(every: Duration, chunk: usize, now: Instant) -> Self {
+        Self {
+            every,
+            chunk: chunk.max(1),
+            last: now,
+        }
+    }
+
+    /// Whether one's due at `now`. Counts as sent if it is
+    pub fn due(&mut self, now: Instant) -> bool {
+        let due = now.saturating_duration_since(self.last) >= self.every;
+        if due {
+            self.last = now;
+        }
+        due
+    }
+}
+
+/// `crunch`, a chunk at a time, calling `beat` after any chunk that finishes with a heartbeat
+/// due by `clock`
+pub fn crunch_chunked(
+    mut sum: i128,
+    samples: impl IntoIterator<Item = i32>,
+    modulus: i128,
+    heartbeat: &mut Heartbeat,
+    clock: &dyn Clock,
+    mut beat: impl FnMut(),
+) -> Option<i128> {
+    let mut samples = samples.into_iter().peekable();
+    while samples.peek().is_some() {
+        sum = crunch(sum, samples.by_ref().take(heartbeat.chunk), modulus)?;
+        if heartbeat.due(clock.now_instant()) {
+            beat();
+        }
+    }
+    Some(sum)
+}
+
+/// Most a dummy could sum with the default profile: every number as big as it gets, at the
+/// highest intensity, in as many blocks as the longest run has seconds
+const MAX_SUM: i128 =
+    (WORK_MODULUS - 1) * (WORK_PER_BLOCK * MAX_INTENSITY) as i128 * *TARGET_SECS.end() as i128;
+
+/// Whether a dummy could have come up with `sum`. Anything else is a bug in the worker
+pub fn is_sane_sum(sum: i128) -> bool {
+    (0..=MAX_SUM).contains(&sum)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TaskKind {
+    /// Pretend work on the blocking pool
+    Dummy,
+    /// A real subprocess, from the tasks file. Progress is anyone's guess
+    Shell,
+}
+
+/// Statuses that mean about the same thing to someone scanning the table, like Failed and DOA
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusClass {
+    Waiting,
+    /// Spawned but not heard from, or queued for a thread
+    Unknown,
+    Working,
+    Striking,
+    Done,
+    Cancelled,
+    Failed,
+}
+
+/// What a group of them is called over the table
+impl fmt::Display for StatusClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StatusClass::Waiting => "Waiting",
+            StatusClass::Unknown => "Starting",
+            StatusClass::Working => "Working",
+            StatusClass::Striking => "On strike",
+            StatusClass::Done => "Done",
+            StatusClass::Cancelled => "Cancelled",
+            StatusClass::Failed => "Failed",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TaskStatus {
+    /// Made but not started yet, waiting on its batch delay. There's no worker to talk to
+    Scheduled,
     Running,
     Sleeping,
+    /// In its last block, so it's only a matter of time
+    Finalizing,
     OnStrike,
     KnownUnknown,
+    /// Spawned, but silent for so long it's probably queued behind a full blocking pool
+    WaitingForThread,
     Finished,
     Canceled,
+    Failed,
+    /// Never got a worker at all. Only kept around if the config asks for it
+    FailedToStart,
+}
+
+/// How far along being stopped a task is. Only ever moves forward, see `next`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CancelState {
+    /// Nobody's asked
+    #[default]
+    None,
+    /// Asked, and waiting to hear back
+    Requested,
+    /// It sent a CancelReport, so the worker's on its way out
+    Acked,
+    /// Didn't stop in time, so its worker was aborted out from under it
+    Forced,
+    /// Didn't stop in time and couldn't be aborted either (nothing can interrupt a blocking
+    /// thread), so it was left to it
+    Abandoned,
+}
+
+/// What can happen to a task's `CancelState`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelEvent {
+    /// We asked it to stop, on its own or along with everyone
+    Request,
+    /// Its CancelReport came in
+    Ack,
+    /// Time's up and its worker could be aborted
+    Force,
+    /// Time's up and it couldn't
+    Abandon,
+}
+
+/// Why a task couldn't be spawned
+#[derive(Debug, thiserror::Error)]
+pub enum SpawnError {
+    /// There's no runtime to put the worker on
+    #[error("no runtime to run it on ({0})")]
+    NoRuntime(TryCurrentError),
+}
+
+/// Anything asked of a task (or of a bunch of them) that didn't happen. Nothing in here logs on
+/// its own, so callers can decide whether it's a toast, a log line or somebody else's problem
+#[derive(Debug, thiserror::Error)]
+pub enum TaskError {
+    /// Its control channel is full, or nobody's listening on it any more
+    #[error("couldn't reach task {0}: {1}")]
+    SendFailed(Id, #[source] TrySendError<TaskRxMsg>),
+    #[error("there's no task {0}")]
+    TaskNotFound(Id),
+    /// It's over already, so there's nothing left to stop
+    #[error("task {0} has already ended ({1})")]
+    AlreadyTerminal(Id, TaskStatus),
+    #[error("{0}")]
+    SpawnFailed(#[from] SpawnError),
+    /// Still going after being given this long
+    #[error("task {0} still wasn't done after {secs}s", secs = .1.as_secs())]
+    Timeout(Id, Duration),
 }
 
 /// Sent from tasks via mpsc to App
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum TaskTxMsg {
-    /// Conditions were untenable and the task refuses to work
-    LaborDispute(Id),
-    /// Work resumes after a bargain was struck
-    Reconciliation(Id),
-    /// Updates the table with percentage (progress is 0..100)
-    RunReport {
-        id: Id,
-        progress: u8,
+    /// How far along it is (0..100), and how tired (likewise). Says nothing about its status, a
+    /// late one can't pull a stopped or striking task back to Running. Sent once the block's
+    /// work is done, with the CPU it's used so far
+    Progress {
+        id: Id,
+        pct: u8,
+        fatigue: u8,
+        cpu_ms: u64,
+        /// `cpu_ms` is from the work's wall time, see `cpu`
+        cpu_estimated: bool,
+    },
+    /// The worker's moved on to something else. App only believes it if `TaskStatus::can_become`
+    /// says it's allowed from wherever the task is now
+    StatusChange {
+        id: Id,
+        status: WorkerStatus,
     },
-    SleepReport(Id),
     CancelReport(Id),
+    /// The worker hit something it can't carry on from and gave up, so the task's failed
+    Fault {
+        id: Id,
+        error: String,
+    },
+    /// The task walked off the job on its own
+    Resignation {
+        id: Id,
+        reason: String,
+    },
+    /// A line of stdout/stderr from a shell task
+    Output {
+        id: Id,
+        line: String,
+    },
+    /// Got somewhere worth naming, like "warmup done". How far along it is still comes in
+    /// `Progress`
+    Milestone {
+        id: Id,
+        label: String,
+    },
+    /// A shell task's child exited on its own. `None` if it never started or a signal got it
+    ExitReport {
+        id: Id,
+        code: Option<i32>,
+    },
+    /// Still alive, even if progress hasn't moved. Sent from the middle of a dummy's work block
+    /// every `HEARTBEAT_INTERVAL` or so, and dropped if the channel's full
+    Heartbeat(Id),
+}
+
+/// The statuses a worker can put itself in. Everything else is App's doing, or comes with a
+/// message of its own (stopping, quitting, exiting)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkerStatus {
+    Running,
+    Sleeping,
+    /// The last block has started, the next thing to hear is the handle finishing
+    Finalizing,
+    /// Conditions were untenable and the task refuses to work until it gets a break this long.
+    /// Running again means a bargain was struck
+    OnStrike {
+        demand_secs: u64,
+    },
 }
 
-/// Sent by App to all tasks via broadcast (tasks check if it's for them)
+/// Sent by App down a task's own control channel, so there's no need to say who it's for
 #[derive(Debug, Clone, Copy)]
 pub enum TaskRxMsg {
-    PleaseStop(Id), // Abort handles don't work on sync spawns
+    PleaseStop, // Abort handles don't work on sync spawns
     EveryoneStopPls,
+    /// Scale work blocks by this much from the next block on (blocks in progress are committed)
+    SetIntensity(f32),
+    /// Answer to a striking task's demand, in seconds. Zero is a refusal
+    Bargain(u64),
+    /// Hold still from the next block (or the rest of this nap) on, until told `false`. Stops
+    /// still get through meanwhile. Subprocesses carry on regardless
+    Pause(bool),
+    /// How chatty to be about it from the next block on
+    SetReporting(ReportingMode),
+}
+
+/// How often a dummy reports progress, and whether it traces every block
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReportingMode {
+    /// Every block, traced
+    #[default]
+    Normal,
+    /// Once every `SPARSE_INTERVAL` at most (and always the last block, so the end's not
+    /// missed), with nothing traced per block. For low power
+    Sparse,
 }
 
+/// When a worker last reported progress, so `Sparse` knows when the next is due
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReportGate {
+    last: Option<Instant>,
+}
+
+impl ReportGate {
+    /// Whether a block finishing at `now` gets to report. Counts as reported if it does
+    pub fn due(&mut self, mode: ReportingMode, now: Instant, last_block: bool) -> bool {
+        let due = match (mode, self.last) {
+            (ReportingMode::Normal, _) | (_, None) => true,
+            (ReportingMode::Sparse, Some(at)) => {
+                last_block || now.saturating_duration_since(at) >= SPARSE_INTERVAL
+            }
+        };
+        if due {
+            self.last = Some(now);
+        }
+        due
+    }
+}
+
+/// What every worker's been told that one starting now has to hear too, or it'd carry on as if
+/// it had missed the memo
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Orders {
+    /// Everyone's been told `Pause(true)`
+    pub paused: bool,
+    /// Everyone's been told `EveryoneStopPls`
+    pub stopping: bool,
+    /// Everyone's been told `SetReporting` this. A new dummy starts out with it instead of
+    /// hearing it, so it's never a block behind
+    pub reporting: ReportingMode,
+}
+
+impl Orders {
+    /// What a new worker hears before anything else. Stopping makes pausing moot
+    pub fn first_word(self) -> Option<TaskRxMsg> {
+        match self {
+            Orders { stopping: true, .. } => Some(TaskRxMsg::EveryoneStopPls),
+            Orders { paused: true, .. } => Some(TaskRxMsg::Pause(true)),
+            _ => None,
+        }
+    }
+}
+
+/// Always the English word, since reports and webhooks get it. The UI goes by `label`
 impl fmt::Display for TaskStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(strings::english(self.key()))
+    }
+}
+
+impl TaskTxMsg {
+    /// Who sent it. Every message has one, they're just in different places
+    pub fn id(&self) -> Id {
+        match self {
+            TaskTxMsg::CancelReport(id)
+            | TaskTxMsg::Heartbeat(id)
+            | TaskTxMsg::Progress { id, .. }
+            | TaskTxMsg::StatusChange { id, .. }
+            | TaskTxMsg::Fault { id, .. }
+            | TaskTxMsg::Resignation { id, .. }
+            | TaskTxMsg::Output { id, .. }
+            | TaskTxMsg::Milestone { id, .. }
+            | TaskTxMsg::ExitReport { id, .. } => *id,
+        }
+    }
+
+    /// Which message it is, without the fields
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TaskTxMsg::Progress { .. } => "Progress",
+            TaskTxMsg::StatusChange { .. } => "StatusChange",
+            TaskTxMsg::CancelReport(_) => "CancelReport",
+            TaskTxMsg::Fault { .. } => "Fault",
+            TaskTxMsg::Resignation { .. } => "Resignation",
+            TaskTxMsg::Output { .. } => "Output",
+            TaskTxMsg::Milestone { .. } => "Milestone",
+            TaskTxMsg::ExitReport { .. } => "ExitReport",
+            TaskTxMsg::Heartbeat(_) => "Heartbeat",
+        }
+    }
+}
+
+impl From<WorkerStatus> for TaskStatus {
+    fn from(status: WorkerStatus) -> Self {
+        match status {
+            WorkerStatus::Running => TaskStatus::Running,
+            WorkerStatus::Sleeping => TaskStatus::Sleeping,
+            WorkerStatus::Finalizing => TaskStatus::Finalizing,
+            WorkerStatus::OnStrike { .. } => TaskStatus::OnStrike,
+        }
+    }
+}
+
+impl CancelState {
+    /// Where `event` leaves it. Asking again doesn't undo an answer, and an answer that turns up
+    /// late (the worker can finish before its CancelReport is read, or without ever sending one)
+    /// still counts. Forced and abandoned are final, whatever arrives after
+    pub fn next(self, event: CancelEvent) -> Self {
+        match (self, event) {
+            (CancelState::Forced | CancelState::Abandoned, _) => self,
+            (CancelState::None, CancelEvent::Request) => CancelState::Requested,
+            (_, CancelEvent::Request) => self,
+            (_, CancelEvent::Ack) => CancelState::Acked,
+            (_, CancelEvent::Force) => CancelState::Forced,
+            (_, CancelEvent::Abandon) => CancelState::Abandoned,
+        }
+    }
+}
+
+impl fmt::Display for CancelState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CancelState::None => write!(f, "no"),
+            CancelState::Requested => write!(f, "requested"),
+            CancelState::Acked => write!(f, "acknowledged"),
+            CancelState::Forced => write!(f, "forced"),
+            CancelState::Abandoned => write!(f, "abandoned"),
+        }
+    }
+}
+
+impl TaskStatus {
+    /// Its word in `strings`
+    pub fn key(&self) -> &'static str {
+        match self {
+            TaskStatus::Scheduled => "status.scheduled",
+            TaskStatus::Running => "status.running",
+            TaskStatus::Sleeping => "status.sleeping",
+            TaskStatus::Finalizing => "status.finalizing",
+            TaskStatus::OnStrike => "status.on_strike",
+            TaskStatus::KnownUnknown => "status.known_unknown",
+            TaskStatus::WaitingForThread => "status.waiting_for_thread",
+            TaskStatus::Finished => "status.finished",
+            TaskStatus::Canceled => "status.canceled",
+            TaskStatus::Failed => "status.failed",
+            TaskStatus::FailedToStart => "status.failed_to_start",
+        }
+    }
+
+    /// What it's called on screen, in whatever language the strings are in
+    pub fn label(&self) -> &'static str {
+        strings::get(self.key())
+    }
+
+    /// The statuses a healthy task bounces between, until its last block
+    pub fn is_working(&self) -> bool {
+        matches!(
+            self,
+            TaskStatus::Running | TaskStatus::Sleeping | TaskStatus::Finalizing
+        )
+    }
+
+    /// Which sort of status it is, for anything that doesn't care about the difference
+    pub fn class(&self) -> StatusClass {
         match self {
-            TaskStatus::Running => write!(f, "Running"),
-            TaskStatus::Sleeping => write!(f, "Sleeping"),
-            TaskStatus::OnStrike => write!(f, "Strike!"),
-            TaskStatus::KnownUnknown => write!(f, "???"),
-            TaskStatus::Finished => write!(f, "Done"),
-            TaskStatus::Canceled => write!(f, "Cancelled"),
+            TaskStatus::Scheduled => StatusClass::Waiting,
+            TaskStatus::KnownUnknown | TaskStatus::WaitingForThread => StatusClass::Unknown,
+            TaskStatus::Running | TaskStatus::Sleeping | TaskStatus::Finalizing => {
+                StatusClass::Working
+            }
+            TaskStatus::OnStrike => StatusClass::Striking,
+            TaskStatus::Finished => StatusClass::Done,
+            TaskStatus::Canceled => StatusClass::Cancelled,
+            TaskStatus::Failed | TaskStatus::FailedToStart => StatusClass::Failed,
         }
     }
+
+    /// Over and done with, one way or another. Nothing about it changes from here
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            TaskStatus::Finished
+                | TaskStatus::Canceled
+                | TaskStatus::Failed
+                | TaskStatus::FailedToStart
+        )
+    }
+
+    /// Whether a worker saying it's `to` (or its handle finishing, for Finished) should be
+    /// believed. Messages are only drained once an update and the handle's checked separately,
+    /// so news can turn up late or after something that settled it already. Ending is for good,
+    /// and the last block doesn't go back to being an ordinary one, though it can still strike.
+    /// Saying what it already is is always fine, and changes nothing
+    pub fn can_become(self, to: TaskStatus) -> bool {
+        if self == to {
+            return true;
+        }
+        match (self, to) {
+            (from, _) if from.is_terminal() => false,
+            // No worker yet, so nobody to be hearing from
+            (TaskStatus::Scheduled, _) => false,
+            // These are App's guesses and decisions, never a worker's
+            (
+                _,
+                TaskStatus::Scheduled
+                | TaskStatus::KnownUnknown
+                | TaskStatus::WaitingForThread
+                | TaskStatus::FailedToStart,
+            ) => false,
+            (TaskStatus::Finalizing, TaskStatus::Running | TaskStatus::Sleeping) => false,
+            _ => true,
+        }
+    }
+
+    /// Still has a worker that a stop request could reach, or hasn't got one yet and can just
+    /// be called off
+    pub fn is_stoppable(&self) -> bool {
+        self.is_working()
+            || matches!(
+                self,
+                TaskStatus::OnStrike
+                    | TaskStatus::KnownUnknown
+                    | TaskStatus::WaitingForThread
+                    | TaskStatus::Scheduled
+            )
+    }
 }
 
 impl Task {
-    pub fn new(
+    /// Starts a worker for `ct` on the current runtime. Errors mean nothing was started, and
+    /// there's no task to show for it unless the caller makes one with `failed_to_start`
+    pub fn spawn(
+        ct: &CandidateTask,
+        tx: mpsc::Sender<Stamped<TaskTxMsg>>,
+        id: Id,
+        intensity: f32,
+        copy: usize,
+        chaos: ChaosConfig,
+        clock: &dyn Clock,
+    ) -> Result<Self, TaskError> {
+        let mut task = Task::pending(ct, id, copy);
+        task.activate(tx, intensity, chaos, Orders::default(), clock)?;
+        Ok(task)
+    }
+
+    /// A row for something that'll start later, with `activate`. Until then there's no worker
+    /// and nothing on the end of its channel
+    pub fn pending(ct: &CandidateTask, id: Id, copy: usize) -> Self {
+        let (mut task, _) = Task::blank(ct, id, copy, CONTROL_CAPACITY);
+        task.status = TaskStatus::Scheduled;
+        task
+    }
+
+    /// A row for trying this again as `attempt`, to `activate` later. Same candidate, name and
+    /// target, but a new ID
+    pub fn retry(&self, id: Id, attempt: Attempt) -> Self {
+        let ct = CandidateTask {
+            command: self.command.clone(),
+            retry: self.retry,
+            ..CandidateTask::new(self.candidate.clone(), self.description.clone())
+        };
+        let mut task = Task::pending(&ct, id, self.copy);
+        task.target_secs = self.target_secs;
+        task.intensity = self.intensity;
+        task.time_limit = self.time_limit;
+        task.profile = self.profile;
+        task.retry_of = Some(self.retry_of.unwrap_or(self.id));
+        task.attempt = Some(attempt);
+        task
+    }
+
+    /// Puts the worker on the current runtime. Starting time is from here, not from when the
+    /// row was made. `orders` are waiting in its channel before it starts, so it hears them
+    /// before its first block. On error the task's left as it was
+    pub fn activate(
+        &mut self,
+        tx: mpsc::Sender<Stamped<TaskTxMsg>>,
+        intensity: f32,
+        chaos: ChaosConfig,
+        orders: Orders,
+        clock: &dyn Clock,
+    ) -> Result<(), TaskError> {
+        let runtime = Handle::try_current().map_err(SpawnError::NoRuntime)?;
+        let id = self.id;
+        let (control, rx) = mpsc::channel(chaos.control_capacity.unwrap_or(CONTROL_CAPACITY));
+        if !chaos.is_off() {
+            info!(target: chaos::TARGET, "task {id}: spawned with chaos on");
+        }
+        self.control = control;
+        if let Some(msg) = orders.first_word() {
+            self.tell(msg, clock);
+        }
+        let target = self.target_secs;
+        let intensity = self.intensity.unwrap_or(intensity);
+        let state = WorkerState::new(id, intensity, chaos, self.profile, orders.reporting);
+        // Workers trace under whatever session span we're in, even off on their own threads
+        let span = Span::current();
+        let handle = match self.command.clone() {
+            Some(command) => runtime.spawn(Task::shell_task(id, command, tx, rx).instrument(span)),
+            None => runtime.spawn_blocking(move || {
+                span.in_scope(|| Task::blocking_dummy_task(id, tx, rx, target, state))
+            }),
+        };
+        if let (Some(secs), None) = (target, &self.command) {
+            self.eta.set_target(Duration::from_secs(secs));
+        }
+        self.start = Some(clock.now_local());
+        self.activated_at = Some(clock.now_instant());
+        self.set_status(TaskStatus::KnownUnknown, clock);
+        self.completion.start(handle);
+        Ok(())
+    }
+
+    /// A row for a task that never got going, so there's something to point at. Its channel
+    /// has nobody on the other end, so telling it things just fails
+    pub fn failed_to_start(
         ct: &CandidateTask,
-        tx: mpsc::Sender<TaskTxMsg>,
-        rx: broadcast::Receiver<TaskRxMsg>,
         id: Id,
+        copy: usize,
+        reason: String,
+        clock: &dyn Clock,
     ) -> Self {
+        let mut task = Task::pending(ct, id, copy);
+        task.mark_failed_to_start(reason, clock);
+        task
+    }
+
+    /// For a pending task whose `activate` didn't work out
+    pub fn mark_failed_to_start(&mut self, reason: String, clock: &dyn Clock) {
+        self.set_status(TaskStatus::FailedToStart, clock);
+        self.end = Some(clock.now_local());
+        self.cancel_reason = Some(reason);
+    }
+
+    /// Calls off a pending task. There's no worker to tell, so it's over as soon as it's asked
+    pub fn cancel_pending(&mut self, clock: &dyn Clock) {
+        let now = clock.now_local();
+        self.set_status(TaskStatus::Canceled, clock);
+        self.end = Some(now);
+        self.stop_requested(now);
+        self.stop_acked(now);
+    }
+
+    /// Notes that we've asked it to stop. Only the first ask gets a time
+    pub fn stop_requested(&mut self, now: DateTime<Local>) {
+        self.cancel = self.cancel.next(CancelEvent::Request);
+        self.cancel_requested_at.get_or_insert(now);
+    }
+
+    /// Notes its CancelReport
+    pub fn stop_acked(&mut self, now: DateTime<Local>) {
+        self.cancel = self.cancel.next(CancelEvent::Ack);
+        self.cancel_acked_at.get_or_insert(now);
+    }
+
+    /// What the Halt column shows: "—", "req 5s", "ack", "forced" or "abandoned". A request's
+    /// clock stops when the task ends, answered or not
+    pub fn halt_label(&self, now: DateTime<Local>) -> String {
+        match self.cancel {
+            CancelState::None => "—".to_string(),
+            CancelState::Requested => {
+                let since = self.cancel_requested_at.unwrap_or(now);
+                let waited = (self.end.unwrap_or(now) - since).num_seconds().max(0);
+                match waited {
+                    0..100 => format!("req {waited}s"),
+                    100..6000 => format!("req {}m", waited / 60),
+                    _ => format!("req {}h", waited / 3600),
+                }
+            }
+            CancelState::Acked => "ack".to_string(),
+            CancelState::Forced => "forced".to_string(),
+            CancelState::Abandoned => "abandoned".to_string(),
+        }
+    }
+
+    /// Everything but the worker
+    fn blank(
+        ct: &CandidateTask,
+        id: Id,
+        copy: usize,
+        capacity: usize,
+    ) -> (Self, mpsc::Receiver<TaskRxMsg>) {
         // This is write once read never nonsense because I only wanted so much effort
         // into the 'pretend to work' code
-        let start = Local::now();
-        let mut proto_self = Self {
+        let (control, rx) = mpsc::channel(capacity);
+        let proto_self = Self {
             id,
-            name: ct.name,
+            name: display_name(&ct.name, copy),
+            candidate: ct.name.clone(),
+            copy,
             status: TaskStatus::KnownUnknown,
-            start,
+            status_changed_at: None,
+            start: None,
+            activated_at: None,
+            thread_wait: None,
+            last_heard: None,
             end: None,
-            description: ct.description,
-            handle: None,
+            description: ct.description.clone(),
+            completion: Completion::default(),
             progress: 0,
-            pending_cancel: false,
+            progress_changed_at: None,
+            heartbeat_at: None,
+            backwards: 0,
+            fatigue: 0,
+            cpu: None,
+            note: None,
+            cancel: CancelState::None,
+            kind: match ct.command {
+                Some(_) => TaskKind::Shell,
+                None => TaskKind::Dummy,
+            },
+            command: ct.command.clone(),
+            target_secs: None,
+            intensity: None,
+            time_limit: None,
+            exit_code: None,
+            output: VecDeque::new(),
+            audit: VecDeque::new(),
+            milestones: VecDeque::new(),
+            profile: WorkProfile::default(),
+            depends_on: vec![],
+            retry: ct.retry,
+            retry_of: None,
+            attempt: None,
+            out_of_retries: false,
+            demand_secs: None,
+            cancel_reason: None,
+            progress_at_cancel: None,
+            cancel_requested_at: None,
+            cancel_acked_at: None,
+            eta: EtaEstimator::default(),
+            smooth: SmoothProgress::default(),
+            control,
         };
-        let handle = task::spawn_blocking(move || Task::blocking_dummy_task(id, tx, rx));
-        proto_self.handle = Some(handle);
-        proto_self
-    }
-    pub fn check_done(&mut self) -> Option<JoinHandle<Option<i128>>> {
-        if self.handle.as_ref().map_or(false, |h| h.is_finished()) {
-            if !matches!(self.status, TaskStatus::Canceled) {
-                // Cancel message will usually arrive first - don't let this over-write it!
-                // This was fun to debug... Architectural skill issue
-                self.status = TaskStatus::Finished;
-            }
-            self.end = Some(chrono::Local::now());
-            self.progress = 100;
-            // This is feels messy but the point is we want to lose ownership of the handle
-            // We don't need any useful value stored in self.handle anymore since it's done
-            let handle = mem::take(&mut self.handle).unwrap();
-            self.handle = None;
-            Some(handle)
+        (proto_self, rx)
+    }
+
+    /// Every status change should come through here. Running <-> Sleeping happens every work
+    /// block, so that doesn't count as a change anyone needs to notice
+    pub fn set_status(&mut self, status: TaskStatus, clock: &dyn Clock) {
+        if self.status == status {
+            return;
+        }
+        if !(self.status.is_working() && status.is_working()) {
+            self.status_changed_at = Some(clock.now_instant());
+        }
+        if status == TaskStatus::Canceled {
+            self.progress_at_cancel = Some(self.progress);
+        }
+        self.status = status;
+    }
+
+    /// What the worker (or its handle finishing) says its status is now. Only goes through if
+    /// `can_become` allows it from here, so late news doesn't undo anything. True if it did
+    pub fn report(&mut self, status: TaskStatus, clock: &dyn Clock) -> bool {
+        if !self.status.can_become(status) {
+            debug!(
+                "task {}: ignoring {status:?}, it's {:?} already",
+                self.id, self.status
+            );
+            return false;
+        }
+        self.set_status(status, clock);
+        true
+    }
+
+    /// A `Progress` from the worker. Once it's over, how far it got is settled. It only ever goes
+    /// up: one lower than the last is counted in `backwards` and the higher one kept, so the ETA
+    /// and the smoothing never see it. False if that's what this one was
+    pub fn progressed(&mut self, pct: u8, fatigue: u8, now: Instant) -> bool {
+        if self.status.is_terminal() {
+            return true;
+        }
+        self.fatigue = fatigue;
+        if pct < self.progress {
+            self.backwards += 1;
+            return false;
+        }
+        if pct != self.progress {
+            self.progress_changed_at = Some(now);
+        }
+        self.progress = pct;
+        self.eta.record(now, pct);
+        self.smooth.record(now, pct);
+        true
+    }
+
+    /// How long its progress has sat where it is, counting from when it was started if it's
+    /// never moved. None if it's never been started
+    pub fn still_for(&self, now: Instant) -> Option<Duration> {
+        let since = self.progress_changed_at.or(self.activated_at)?;
+        Some(now.saturating_duration_since(since))
+    }
+
+    /// How long since its worker last seemed alive: a heartbeat, progress moving, or being
+    /// started. None if it's never heartbeat, since then there's only progress to go on
+    pub fn quiet_for(&self, now: Instant) -> Option<Duration> {
+        let heartbeat = self.heartbeat_at?;
+        let since = [self.progress_changed_at, self.activated_at]
+            .into_iter()
+            .flatten()
+            .fold(heartbeat, Instant::max);
+        Some(now.saturating_duration_since(since))
+    }
+
+    /// Past its `time_limit` and not asked to stop yet
+    pub fn out_of_time(&self, now: Instant) -> bool {
+        let (Some(limit), Some(at)) = (self.time_limit, self.activated_at) else {
+            return false;
+        };
+        self.completion.is_running()
+            && self.status.is_stoppable()
+            && self.cancel == CancelState::None
+            && now.saturating_duration_since(at) >= limit
+    }
+
+    /// Any message from the worker at all. The first one says it's been given a thread
+    pub fn heard_from(&mut self, now: Instant) {
+        if self.thread_wait.is_none() {
+            self.thread_wait = self.activated_at.map(|at| now.duration_since(at));
+        }
+    }
+
+    /// Flips a dummy that's been starting for longer than `patience` without a word to
+    /// `WaitingForThread`. Tokio queues `spawn_blocking` work quietly once every thread's taken,
+    /// so this is the only sign of it. True if it flipped just now
+    pub fn check_thread_wait(&mut self, patience: Duration, clock: &dyn Clock) -> bool {
+        let now = clock.now_instant();
+        let late = self
+            .activated_at
+            .is_some_and(|at| now.duration_since(at) >= patience);
+        if self.kind != TaskKind::Dummy
+            || self.status != TaskStatus::KnownUnknown
+            || self.thread_wait.is_some()
+            || !late
+        {
+            return false;
+        }
+        self.set_status(TaskStatus::WaitingForThread, clock);
+        true
+    }
+
+    /// A worker that panicked never said how far it got, so the table shouldn't claim it
+    /// finished. Back to the last progress it did report. Its handle's been harvested by now,
+    /// so this is the one way back out of Finished
+    pub fn mark_panicked(&mut self, reason: String, clock: &dyn Clock) {
+        self.set_status(TaskStatus::Failed, clock);
+        self.progress = self.smooth.reported().unwrap_or(0);
+        self.cancel_reason = Some(reason);
+    }
+
+    /// Progress as it should be shown. Smoothed while there's still work going on (which is just
+    /// the raw value if nobody's been updating it), the real thing once it's over
+    pub fn shown_progress(&self) -> u8 {
+        if self.status.is_working() || self.status == TaskStatus::OnStrike {
+            self.smooth.value().max(self.progress)
         } else {
-            // There is no handle or it isn't done
-            None
+            self.progress
+        }
+    }
+
+    /// Notes down a message for the audit trail, dropping the oldest if we're full
+    pub fn record(&mut self, entry: String, clock: &dyn Clock) {
+        if self.audit.len() >= AUDIT_LEN {
+            self.audit.pop_front();
+        }
+        self.audit.push_back((clock.now_local(), entry));
+    }
+
+    /// Notes down a milestone, cut to `MILESTONE_LABEL` characters. Bounded like the audit trail
+    pub fn milestone(&mut self, label: &str, clock: &dyn Clock) {
+        if self.milestones.len() >= MILESTONES {
+            self.milestones.pop_front();
+        }
+        let label = label.chars().take(MILESTONE_LABEL).collect();
+        self.milestones.push_back((clock.now_local(), label));
+    }
+
+    /// The newest milestone's label, if it's announced any
+    pub fn latest_milestone(&self) -> Option<&str> {
+        self.milestones.back().map(|(_, label)| label.as_str())
+    }
+
+    /// Keeps the output buffer bounded, oldest lines go first
+    pub fn push_output(&mut self, line: String) {
+        if self.output.len() >= OUTPUT_LINES {
+            self.output.pop_front();
+        }
+        self.output.push_back(line);
+    }
+
+    /// Sends a control message to this task and notes it down if it went. Dead tasks have dropped
+    /// their receiver, so this fails for them
+    pub fn tell(&mut self, msg: TaskRxMsg, clock: &dyn Clock) -> bool {
+        self.try_tell(msg, clock)
+            .inspect_err(|e| error!("problem sending {msg:?}: {e}"))
+            .is_ok()
+    }
+
+    /// `tell` for callers that want to know why not, and won't have it logged for them
+    pub fn try_tell(&mut self, msg: TaskRxMsg, clock: &dyn Clock) -> Result<(), TaskError> {
+        self.control
+            .try_send(msg)
+            .map_err(|e| TaskError::SendFailed(self.id, e))?;
+        self.record(format!("→ {msg:?}"), clock);
+        Ok(())
+    }
+
+    /// Asks it to stop. One that's only scheduled never gets a worker at all, so that's the end
+    /// of it straight away. Asking twice just asks again
+    pub fn request_stop(&mut self, clock: &dyn Clock) -> Result<(), TaskError> {
+        if self.status == TaskStatus::Scheduled {
+            self.cancel_pending(clock);
+            return Ok(());
+        }
+        if !self.status.is_stoppable() {
+            return Err(TaskError::AlreadyTerminal(self.id, self.status));
+        }
+        self.try_tell(TaskRxMsg::PleaseStop, clock)?;
+        self.stop_requested(clock.now_local());
+        Ok(())
+    }
+
+    /// `check_done`, waiting up to `patience` for it. Only for headless callers and tests, App
+    /// can't be sitting around waiting. Actually waits, so it goes by the system clock
+    pub async fn join(&mut self, patience: Duration) -> Result<Outcome, TaskError> {
+        if !self.completion.is_running() {
+            return Err(TaskError::AlreadyTerminal(self.id, self.status));
+        }
+        let deadline = Instant::now() + patience;
+        loop {
+            if let Some(outcome) = self.check_done(&SystemClock).await {
+                return Ok(outcome);
+            }
+            if Instant::now() >= deadline {
+                return Err(TaskError::Timeout(self.id, patience));
+            }
+            tokio::time::sleep(JOIN_POLL).await;
+        }
+    }
+
+    /// The worker's returned (or panicked), and its handle's waiting for `check_done`. Anything
+    /// it sent before that is already in the channel
+    pub fn is_done(&self) -> bool {
+        self.completion.is_finished()
+    }
+
+    /// Harvests the worker once it's done with, stamping the end with `clock`'s time. How it
+    /// came back is kept on the task, and handed back too. Nothing if it isn't done (or never
+    /// started), and a warning as well if it's been harvested already
+    pub async fn check_done(&mut self, clock: &dyn Clock) -> Option<Outcome> {
+        let outcome = match self.completion.harvest().await {
+            Ok(outcome) => outcome.clone(),
+            Err(e @ HarvestError::AlreadyHarvested) => {
+                warn!("task {} can't be harvested again: {e}", self.id);
+                return None;
+            }
+            Err(HarvestError::NotStarted | HarvestError::StillRunning) => return None,
+        };
+        self.end = Some(clock.now_local());
+        match outcome {
+            // Looked finished from the outside, but it never got there
+            Outcome::Panicked(_) => self.mark_panicked("worker panicked".to_string(), clock),
+            // Stopped or failed already says how it ended, and that sticks
+            _ => {
+                self.report(TaskStatus::Finished, clock);
+                // Stopped or failed tasks didn't get there, so leave them where they were
+                if self.status == TaskStatus::Finished {
+                    self.progress = 100;
+                }
+            }
         }
+        Some(outcome)
     }
 
     // The fact that these are static methods is symptomatic of undercooked architecture - I'm just
@@ -120,102 +1183,570 @@ impl Task {
 
     /// This is the actual task we spawn
     /// Panics: Maybe
-    /// Returns: Some(i128) if completed, or None if aborted by message
-    #[instrument(skip(tx, rx))]
+    /// Returns: Some(i128) if completed, or None if aborted by message or the sum overflowed
+    #[instrument(skip(tx, rx, state), fields(intensity = state.intensity))]
     fn blocking_dummy_task(
         id: Id,
-        tx: mpsc::Sender<TaskTxMsg>,
-        mut rx: broadcast::Receiver<TaskRxMsg>,
+        tx: mpsc::Sender<Stamped<TaskTxMsg>>,
+        mut rx: mpsc::Receiver<TaskRxMsg>,
+        target: Option<u64>,
+        mut state: WorkerState,
     ) -> Option<i128> {
-        // The game was rigged all along
-        let time_to_sleep = rand::random_range(2..MAX_SLEEPYTIME);
+        // The game was rigged all along, unless somebody picked
+        let time_to_sleep = match target {
+            Some(secs) => secs,
+            None => state.rng.random_range(2..MAX_SLEEPYTIME),
+        };
         let mut remaining_time = time_to_sleep;
         // These id's are redundant in the log file, but the tui_tracer won't show spans
         info!(
             "task {}: total sleep scheduled: {:?} sec",
             id, time_to_sleep
         );
-        let mut sum: i128 = 0;
+        let mut sum = 0;
+        let mut blocks = 0;
+        let mut meter = CpuMeter::new(cpu::thread_time());
+        let (mut warmed_up, mut halfway) = (false, false);
+        let mut gate = ReportGate::default();
+        let mut heartbeat = Heartbeat::new(HEARTBEAT_INTERVAL, HEARTBEAT_CHUNK, Instant::now());
         while remaining_time > 0 {
-            if Task::check_for_term_message(id, &mut rx, &tx) {
+            if Task::check_messages(id, &mut rx, &tx, &mut state)
+                || Task::hold(id, &mut rx, &tx, &mut state)
+            {
                 return None;
             }
+            let chatty = state.reporting == ReportingMode::Normal;
             // Do some really hecking important work
-            trace!("sum: {:?}", sum);
-            if let Err(some) = tx.blocking_send(TaskTxMsg::RunReport {
+            if chatty {
+                trace!("sum: {:?}", sum);
+            }
+            let microsleep = state.rng.random_range(1..(remaining_time + 1));
+            // The last block would otherwise sit at wherever the one before it left off
+            let last = microsleep == remaining_time;
+            let progress = if last {
+                99
+            } else {
+                (((time_to_sleep - remaining_time) as f64 / time_to_sleep as f64) * 100.0) as u8
+            };
+            if last {
+                Task::last_block(id, &tx, &mut state);
+            } else {
+                Task::say(id, &tx, &mut state, WorkerStatus::Running);
+            }
+            let started = Instant::now();
+            blocks += 1;
+            let Some(len) = state.profile.block_len(state.intensity) else {
+                return Task::overflowed(id, &tx, blocks);
+            };
+            let samples = (&mut state.rng)
+                .random_iter::<i32>()
+                // Imagine being an electron and someone makes you do this
+                .take(len);
+            let beat = || Task::beat(id, &tx);
+            let modulus = state.profile.modulus;
+            let Some(next) =
+                crunch_chunked(sum, samples, modulus, &mut heartbeat, &SystemClock, beat)
+            else {
+                return Task::overflowed(id, &tx, blocks);
+            };
+            sum = next;
+            let used = meter.record(cpu::thread_time(), started.elapsed());
+            if !gate.due(state.reporting, Instant::now(), last) {
+                // Soon enough, with a newer one
+            } else if chaos::roll(&mut state.rng, state.chaos.drop_report) {
+                warn!(target: chaos::TARGET, "task {id}: dropped a progress report ({progress}%)");
+            } else if let Err(some) = tx.blocking_send(Stamped::new(TaskTxMsg::Progress {
                 id,
                 //Sub-optimal casts but they keep us from rounding progress into 0%
-                progress: (((time_to_sleep - remaining_time) as f64 / time_to_sleep as f64) * 100.0)
-                    as u8,
-            }) {
+                pct: progress,
+                fatigue: state.fatigue,
+                cpu_ms: used.ms,
+                cpu_estimated: used.estimated,
+            })) {
                 error!("task {}: problem sending to App: {:?}", id, some);
-            } else {
-                trace!("sent a run report");
+            } else if chatty {
+                trace!("sent a progress report");
+            }
+            if chaos::roll(&mut state.rng, state.chaos.panic) {
+                warn!(target: chaos::TARGET, "task {id}: panicking on purpose");
+                panic!("task {id} {}", chaos::PANIC_MESSAGE);
+            }
+            // Somewhere to have got to besides a percentage, at most one a block
+            if last {
+                Task::announce(id, &tx, "flushing");
+            } else if !warmed_up {
+                Task::announce(id, &tx, "warmup done");
+            } else if !halfway && progress >= 50 {
+                halfway = true;
+                Task::announce(id, &tx, "past halfway");
+            }
+            warmed_up = true;
+            state.fatigue = tire(state.fatigue, state.intensity);
+            let overworked = state.fatigue >= FATIGUE_LIMIT;
+            if overworked || state.rng.random_bool(STRIKE_CHANCE) {
+                if !Task::strike(id, &mut rx, &tx, &mut state) {
+                    return None;
+                }
+                // Making up put it back to Running, but it's still the last block
+                if last {
+                    Task::last_block(id, &tx, &mut state);
+                }
             }
-            sum = rand::random_iter::<i32>()
-                // Imagine being an electron and someone makes you do this
-                .take(11333777)
-                .fold(sum, |acc, num| acc + ((num as i128 % 500).abs()));
-            let microsleep = rand::random_range(1..(remaining_time + 1));
             remaining_time -= microsleep;
-            if Task::check_for_term_message(id, &mut rx, &tx) {
+            if Task::check_messages(id, &mut rx, &tx, &mut state) {
                 return None;
             }
-            info!(
-                "id {}: sleep block for {:?} sec with {:?} sec remaining after",
-                id, microsleep, remaining_time
-            );
-            if let Err(some) = tx.blocking_send(TaskTxMsg::SleepReport(id)) {
-                error!("id {}: problem sending to App: {:?}", id, some);
-            } else {
-                trace!("sent a sleep report")
+            if chatty {
+                info!(
+                    "id {}: sleep block for {:?} sec with {:?} sec remaining after",
+                    id, microsleep, remaining_time
+                );
+            }
+            // Finalizing already says what the last nap is for
+            if !last {
+                Task::say(id, &tx, &mut state, WorkerStatus::Sleeping);
+            }
+            if Task::nap(
+                id,
+                &mut rx,
+                &tx,
+                &mut state,
+                Duration::from_secs(microsleep),
+            ) {
+                return None;
             }
-            sleep(Duration::from_secs(microsleep));
+            state.fatigue = rest(state.fatigue, Duration::from_secs(microsleep));
         }
         trace!("done with sum {:?}", sum);
         Some(sum)
     }
 
-    /// Reads all messages. If any are relevant, sends bool so the blocking task can terminate
+    /// The shell flavor of task. Unlike the dummy this one is properly async, so it can just
+    /// wait on the child, its output, and our messages all at once
+    /// Returns: Some(exit code) if the child exited on its own, or None if stopped/never started
     #[instrument(skip(tx, rx))]
-    fn check_for_term_message(
+    async fn shell_task(
+        id: Id,
+        command: String,
+        tx: mpsc::Sender<Stamped<TaskTxMsg>>,
+        mut rx: mpsc::Receiver<TaskRxMsg>,
+    ) -> Option<i128> {
+        let mut child = match shell_command(&command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                error!("task {id}: couldn't start `{command}`: {e}");
+                Task::send(&tx, TaskTxMsg::ExitReport { id, code: None }).await;
+                return None;
+            }
+        };
+        info!("task {id}: started `{command}`");
+        Task::send(
+            &tx,
+            TaskTxMsg::StatusChange {
+                id,
+                status: WorkerStatus::Running,
+            },
+        )
+        .await;
+        // Both were piped just above, they're definitely there
+        let mut stdout = BufReader::new(child.stdout.take().unwrap()).lines();
+        let mut stderr = BufReader::new(child.stderr.take().unwrap()).lines();
+        let (mut stdout_open, mut stderr_open) = (true, true);
+        let status = loop {
+            tokio::select! {
+                line = stdout.next_line(), if stdout_open => match line {
+                    Ok(Some(line)) => Task::send(&tx, TaskTxMsg::Output { id, line }).await,
+                    _ => stdout_open = false,
+                },
+                line = stderr.next_line(), if stderr_open => match line {
+                    Ok(Some(line)) => Task::send(&tx, TaskTxMsg::Output { id, line }).await,
+                    _ => stderr_open = false,
+                },
+                status = child.wait() => break status,
+                msg = rx.recv() => match msg {
+                    Some(TaskRxMsg::PleaseStop) => {
+                        trace!("recieved strong suggestion to terminate, passing it on");
+                        terminate(id, &mut child).await;
+                        Task::send(&tx, TaskTxMsg::CancelReport(id)).await;
+                        return None;
+                    }
+                    // Intensity means nothing to a subprocess
+                    // Nor pausing, it'd need signals and it's not ours to freeze
+                    // And it never reports progress to be sparing with
+                    Some(
+                        TaskRxMsg::SetIntensity(_)
+                        | TaskRxMsg::Bargain(_)
+                        | TaskRxMsg::Pause(_)
+                        | TaskRxMsg::SetReporting(_),
+                    ) => {}
+                    Some(TaskRxMsg::EveryoneStopPls) | None => {
+                        info!("id {id}: told to stop with everyone else (or App is gone)");
+                        terminate(id, &mut child).await;
+                        return None;
+                    }
+                },
+            }
+        };
+        // The child can exit with output still sitting in the pipes
+        while let Ok(Some(line)) = stdout.next_line().await {
+            Task::send(&tx, TaskTxMsg::Output { id, line }).await;
+        }
+        while let Ok(Some(line)) = stderr.next_line().await {
+            Task::send(&tx, TaskTxMsg::Output { id, line }).await;
+        }
+        let code = match status {
+            Ok(status) => status.code(),
+            Err(e) => {
+                error!("task {id}: lost track of child: {e}");
+                None
+            }
+        };
+        info!("task {id}: `{command}` exited with {code:?}");
+        Task::send(&tx, TaskTxMsg::ExitReport { id, code }).await;
+        code.map(i128::from)
+    }
+
+    async fn send(tx: &mpsc::Sender<Stamped<TaskTxMsg>>, msg: TaskTxMsg) {
+        if let Err(some) = tx.send(Stamped::new(msg)).await {
+            error!("problem sending to App: {:?}", some);
+        }
+    }
+
+    /// Lets App know this is the last block, so it shows as almost done and not stuck at 99%
+    fn last_block(id: Id, tx: &mpsc::Sender<Stamped<TaskTxMsg>>, state: &mut WorkerState) {
+        info!("task {id}: last block");
+        Task::say(id, tx, state, WorkerStatus::Finalizing);
+    }
+
+    /// Tells App we're `status` now, unless it's what we said last
+    fn say(
+        id: Id,
+        tx: &mpsc::Sender<Stamped<TaskTxMsg>>,
+        state: &mut WorkerState,
+        status: WorkerStatus,
+    ) {
+        if state.said == Some(status) {
+            return;
+        }
+        state.said = Some(status);
+        if let Err(some) = tx.blocking_send(Stamped::new(TaskTxMsg::StatusChange { id, status })) {
+            error!("task {}: problem sending to App: {:?}", id, some);
+        } else {
+            trace!("said we're {status:?}");
+        }
+    }
+
+    /// Tells App the numbers didn't fit after `blocks`, which is the end of the task
+    fn overflowed(id: Id, tx: &mpsc::Sender<Stamped<TaskTxMsg>>, blocks: usize) -> Option<i128> {
+        let error = format!("arithmetic overflow after {blocks} blocks");
+        error!("task {id}: {error}");
+        if let Err(some) = tx.blocking_send(Stamped::new(TaskTxMsg::Fault { id, error })) {
+            error!("task {}: problem sending to App: {:?}", id, some);
+        }
+        None
+    }
+
+    /// Never waits for room, a heartbeat that doesn't fit isn't worth holding the block up for
+    fn beat(id: Id, tx: &mpsc::Sender<Stamped<TaskTxMsg>>) {
+        if let Err(e) = tx.try_send(Stamped::new(TaskTxMsg::Heartbeat(id))) {
+            trace!("task {id}: heartbeat dropped: {e}");
+        }
+    }
+
+    /// Tells App the worker got to `label`
+    fn announce(id: Id, tx: &mpsc::Sender<Stamped<TaskTxMsg>>, label: &str) {
+        info!("task {id}: {label}");
+        let label = label.to_string();
+        if let Err(some) = tx.blocking_send(Stamped::new(TaskTxMsg::Milestone { id, label })) {
+            error!("task {}: problem sending to App: {:?}", id, some);
+        }
+    }
+
+    /// Files a labor dispute and refuses to budge until App answers it
+    /// Returns: true if work goes on, false if the task quit or was stopped while striking
+    #[instrument(skip(tx, rx, state))]
+    fn strike(
+        id: Id,
+        rx: &mut mpsc::Receiver<TaskRxMsg>,
+        tx: &mpsc::Sender<Stamped<TaskTxMsg>>,
+        state: &mut WorkerState,
+    ) -> bool {
+        let demand_secs = state.rng.random_range(3..=15);
+        info!("task {id}: on strike until granted a {demand_secs} sec break");
+        state.bargain = None; // Anything older wasn't an answer to this
+        Task::say(id, tx, state, WorkerStatus::OnStrike { demand_secs });
+        // Nothing to do but wait for an answer, so block on the channel until one shows up
+        let granted_secs = loop {
+            let Some(msg) = rx.blocking_recv() else {
+                warn!("id {id}: App is gone mid-strike(?). terminating");
+                return false;
+            };
+            if Task::handle_message(id, msg, tx, state) {
+                return false;
+            }
+            if let Some(granted) = state.bargain.take() {
+                break granted;
+            }
+        };
+        if granted_secs == 0 && state.rng.random_bool(0.5) {
+            info!("task {id}: demands refused, quitting in protest");
+            if let Err(some) = tx.blocking_send(Stamped::new(TaskTxMsg::Resignation {
+                id,
+                reason: "quit in protest".to_string(),
+            })) {
+                error!("task {}: problem sending to App: {:?}", id, some);
+            }
+            return false;
+        }
+        info!("task {id}: got {granted_secs} of {demand_secs} sec, back to work");
+        Task::say(id, tx, state, WorkerStatus::Running);
+        if granted_secs > 0 {
+            // Any break at all and they come back fresh. They're easy to please, really
+            state.fatigue = 0;
+            Task::say(id, tx, state, WorkerStatus::Sleeping);
+            if Task::nap(id, rx, tx, state, Duration::from_secs(granted_secs)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Reads all messages. If any are relevant, sends bool so the blocking task can terminate.
+    /// Intensity changes and bargains are stashed in `state` as they come in
+    #[instrument(skip(tx, rx, state))]
+    fn check_messages(
         id: Id,
-        rx: &mut broadcast::Receiver<TaskRxMsg>,
-        tx: &mpsc::Sender<TaskTxMsg>,
+        rx: &mut mpsc::Receiver<TaskRxMsg>,
+        tx: &mpsc::Sender<Stamped<TaskTxMsg>>,
+        state: &mut WorkerState,
     ) -> bool {
         loop {
             match rx.try_recv() {
-                Ok(TaskRxMsg::PleaseStop(addr_to)) => {
-                    if addr_to == id {
-                        trace!("recieved strong suggestion to terminate, doing so");
-                        if let Err(some) = tx.blocking_send(TaskTxMsg::CancelReport(id)) {
-                            error!("id {}: problem sending cancel report to App {:?}", id, some)
-                        } else {
-                            trace!("cancel report sent off to App")
-                        }
+                Ok(msg) => {
+                    if Task::handle_message(id, msg, tx, state) {
                         return true;
-                    } // Else we keep checking messages
-                }
-                Ok(TaskRxMsg::EveryoneStopPls) => {
-                    info!(
-                        "id {}: recieved terminate-all message, joining the club",
-                        id
-                    );
-                    return true;
+                    }
                 }
-                Err(TryRecvError::Closed) => {
+                Err(TryRecvError::Disconnected) => {
                     warn!(
                         "id {}: recived no message, but App is gone(?). terminating",
                         id
                     );
                     return true;
                 }
-                Err(TryRecvError::Lagged(by)) => {
-                    warn!("task {id} reports lag of {by} messages")
-                    // And keep checking messages
-                }
                 Err(TryRecvError::Empty) => return false,
             };
         }
     }
+
+    /// Sleeps for `duration`, except it's really waiting on the channel so a stop gets through
+    /// right away instead of after the nap. Same return as `check_messages`
+    #[instrument(skip(tx, rx, state))]
+    fn nap(
+        id: Id,
+        rx: &mut mpsc::Receiver<TaskRxMsg>,
+        tx: &mpsc::Sender<Stamped<TaskTxMsg>>,
+        state: &mut WorkerState,
+        duration: Duration,
+    ) -> bool {
+        // Blocking pool threads still know their runtime, they just can't await on their own
+        let runtime = Handle::current();
+        let mut wake_at = Instant::now() + duration;
+        loop {
+            if state.paused {
+                // The nap stops too, or it'd be over the moment we're let go
+                let left = wake_at.saturating_duration_since(Instant::now());
+                if Task::hold(id, rx, tx, state) {
+                    return true;
+                }
+                wake_at = Instant::now() + left;
+            }
+            let left = wake_at.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return false;
+            }
+            match runtime.block_on(tokio::time::timeout(left, rx.recv())) {
+                Ok(Some(msg)) => {
+                    if Task::handle_message(id, msg, tx, state) {
+                        return true;
+                    }
+                }
+                Ok(None) => {
+                    warn!("id {id}: App is gone mid-nap(?). terminating");
+                    return true;
+                }
+                Err(_) => return false, // Slept the whole way through
+            }
+        }
+    }
+
+    /// Waits on the channel for as long as we're paused, which is no time at all if we aren't.
+    /// Same return as `check_messages`
+    fn hold(
+        id: Id,
+        rx: &mut mpsc::Receiver<TaskRxMsg>,
+        tx: &mpsc::Sender<Stamped<TaskTxMsg>>,
+        state: &mut WorkerState,
+    ) -> bool {
+        while state.paused {
+            let Some(msg) = rx.blocking_recv() else {
+                warn!("id {id}: App is gone mid-pause(?). terminating");
+                return true;
+            };
+            if Task::handle_message(id, msg, tx, state) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Deals with one message. True means stop working
+    fn handle_message(
+        id: Id,
+        msg: TaskRxMsg,
+        tx: &mpsc::Sender<Stamped<TaskTxMsg>>,
+        state: &mut WorkerState,
+    ) -> bool {
+        match msg {
+            TaskRxMsg::PleaseStop
+                if !state.ignored_stop && chaos::roll(&mut state.rng, state.chaos.ignore_stop) =>
+            {
+                warn!(target: chaos::TARGET, "id {id}: pretending that stop never came");
+                state.ignored_stop = true;
+                false
+            }
+            TaskRxMsg::PleaseStop => {
+                trace!("recieved strong suggestion to terminate, doing so");
+                if let Err(some) = tx.blocking_send(Stamped::new(TaskTxMsg::CancelReport(id))) {
+                    error!("id {}: problem sending cancel report to App {:?}", id, some)
+                } else {
+                    trace!("cancel report sent off to App")
+                }
+                true
+            }
+            TaskRxMsg::EveryoneStopPls => {
+                info!(
+                    "id {}: recieved terminate-all message, joining the club",
+                    id
+                );
+                true
+            }
+            TaskRxMsg::SetIntensity(new) => {
+                trace!("work intensity now {new}");
+                state.intensity = new.clamp(MIN_INTENSITY, MAX_INTENSITY);
+                false
+            }
+            TaskRxMsg::Bargain(granted_secs) => {
+                state.bargain = Some(granted_secs);
+                false
+            }
+            TaskRxMsg::Pause(paused) => {
+                trace!("paused: {paused}");
+                state.paused = paused;
+                false
+            }
+            TaskRxMsg::SetReporting(mode) => {
+                trace!("reporting now {mode:?}");
+                state.reporting = mode;
+                false
+            }
+        }
+    }
+}
+
+/// The task with `id`, out of everything being kept track of
+pub fn find_mut(tasks: &mut [Task], id: Id) -> Result<&mut Task, TaskError> {
+    tasks
+        .iter_mut()
+        .find(|task| task.id == id)
+        .ok_or(TaskError::TaskNotFound(id))
+}
+
+/// Which copy number a new task from `candidate` should get. This is one past the highest copy
+/// that still exists rather than the lowest free one, so if copies 1 and 3 are around the next is
+/// 4, not 2 - a number never comes back while its row is still around to be confused with it.
+/// Matching is on the candidate name, so a candidate that already has brackets in its name
+/// (or looks like "Foo (2)") can't fool it
+pub fn next_copy(tasks: &[Task], candidate: &str) -> usize {
+    tasks
+        .iter()
+        .filter(|t| t.candidate == candidate)
+        .map(|t| t.copy)
+        .max()
+        .map_or(1, |highest| highest + 1)
+}
+
+/// Fatigue after one more work block at `intensity`. Tops out at 100
+pub fn tire(fatigue: u8, intensity: f32) -> u8 {
+    (fatigue as f32 + FATIGUE_PER_BLOCK * intensity).min(100.0) as u8
+}
+
+/// Fatigue after sleeping for `slept`. Only whole seconds count
+pub fn rest(fatigue: u8, slept: Duration) -> u8 {
+    let shed = slept.as_secs().saturating_mul(REST_PER_SEC);
+    fatigue.saturating_sub(shed.min(u8::MAX as u64) as u8)
+}
+
+/// Makes every worker started from now on roll its dice from `seed` (plus its ID, so they don't
+/// all roll the same). Only the first call does anything. Without it they're seeded from the OS
+pub fn seed_workers(seed: u64) {
+    let _ = WORKER_SEED.set(seed);
+}
+
+fn worker_rng(id: Id) -> StdRng {
+    match WORKER_SEED.get() {
+        Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(id as u64)),
+        None => StdRng::from_os_rng(),
+    }
+}
+
+fn display_name(candidate: &str, copy: usize) -> String {
+    if copy > 1 {
+        format!("{candidate} ({copy})")
+    } else {
+        candidate.to_string()
+    }
+}
+
+/// `sh -c` (or `cmd /C`), in its own process group so signals reach whatever it spawned too
+fn shell_command(command: &str) -> Command {
+    #[cfg(unix)]
+    {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command).process_group(0);
+        cmd
+    }
+    #[cfg(not(unix))]
+    {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    }
+}
+
+/// SIGTERM to the child's group, then SIGKILL if it's still around after the grace period
+async fn terminate(id: Id, child: &mut Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // SAFETY: just a syscall. Worst case the group is already gone and we get ESRCH
+        unsafe { libc::kill(-(pid as libc::pid_t), libc::SIGTERM) };
+        if tokio::time::timeout(SHELL_GRACE_PERIOD, child.wait())
+            .await
+            .is_ok()
+        {
+            return;
+        }
+        warn!("task {id}: child ignored SIGTERM for {SHELL_GRACE_PERIOD:?}, killing it");
+        // SAFETY: as above
+        unsafe { libc::kill(-(pid as libc::pid_t), libc::SIGKILL) };
+    }
+    if let Err(e) = child.kill().await {
+        error!("task {id}: problem killing child: {e}");
+    }
 }
@@ -0,0 +1,150 @@
+//! `[[template]]`s in the config: one key that adds a few of the same candidate at once, with
+//! their own work intensity and time limit. `T` and then the key, from the main screen, and
+//! `T` then `?` lists them. They're checked against the pool at startup, and any that don't add
+//! up are left out and said why
+use std::{collections::HashSet, time::Duration};
+
+use ratatui::text::{Line, Span};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    health::Health,
+    tasks::{self, Id, Task},
+    theme, CandidateTask,
+};
+
+/// Lists them instead of adding one, so it can't be a template's
+pub const LIST_KEY: char = '?';
+
+/// As it's written in the config, like
+/// `key = "1"  name = "quick batch"  candidate = "Onson Sweemey"  count = 3  timeout_secs = 20`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateConfig {
+    pub key: String,
+    pub name: String,
+    /// A candidate's name, from the tasks file or the built-in ones
+    pub candidate: String,
+    /// 1 if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    /// Instead of `work.intensity`, until `<`/`>` changes everyone's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intensity: Option<f32>,
+    /// Each one's stopped once it's been running this long
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Everything starts as soon as it's added, so there's nothing for this to put first. Only
+    /// read to say so
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+}
+
+/// One that's been checked, ready to go
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    pub key: char,
+    pub name: String,
+    pub candidate: CandidateTask,
+    pub count: usize,
+    pub intensity: Option<f32>,
+    pub time_limit: Option<Duration>,
+}
+
+impl Template {
+    /// A task of it as the `id`th task and the `copy`th of its candidate, not started yet. Every
+    /// way of adding from a template makes its tasks here
+    pub fn task(&self, id: Id, copy: usize) -> Task {
+        let mut task = Task::pending(&self.candidate, id, copy);
+        task.intensity = self.intensity;
+        task.time_limit = self.time_limit;
+        task
+    }
+
+    /// "3× Onson Sweemey, 1.5x, stopped after 20s"
+    pub fn summary(&self) -> String {
+        let mut text = format!("{}× {}", self.count, self.candidate.name);
+        if let Some(intensity) = self.intensity {
+            text.push_str(&format!(", {intensity:.1}x"));
+        }
+        if let Some(limit) = self.time_limit {
+            text.push_str(&format!(", stopped after {}s", limit.as_secs()));
+        }
+        text
+    }
+
+    /// Its key, its name and what it adds, for the list
+    pub fn line(&self) -> Line<'static> {
+        let theme = theme::current();
+        Line::from(vec![
+            Span::styled(format!("{:<3}", self.key), theme.key),
+            self.name.clone().into(),
+            Span::styled(format!("  {}", self.summary()), theme.dim),
+        ])
+    }
+}
+
+/// The ones in `configs` that make sense with `pool`. What's wrong with the rest goes in
+/// `health`, and so does a `priority` that won't do anything
+pub fn compile(
+    configs: &[TemplateConfig],
+    pool: &[CandidateTask],
+    health: &mut Health,
+) -> Vec<Template> {
+    let mut keys = HashSet::new();
+    let mut templates = vec![];
+    for config in configs {
+        if config.priority.is_some() {
+            health.warn(format!(
+                "template `{}`: everything starts as soon as it's added, so priority doesn't do \
+                 anything",
+                config.name
+            ));
+        }
+        match check(config, pool) {
+            Ok(template) if !keys.insert(template.key) => health.error(format!(
+                "template `{}`: another template already has the key `{}`, leaving it out",
+                config.name, template.key
+            )),
+            Ok(template) => templates.push(template),
+            Err(e) => health.error(format!("template `{}`: {e}, leaving it out", config.name)),
+        }
+    }
+    templates
+}
+
+fn check(config: &TemplateConfig, pool: &[CandidateTask]) -> Result<Template, String> {
+    let mut chars = config.key.chars();
+    let key = match (chars.next(), chars.next()) {
+        (Some(LIST_KEY), None) => return Err(format!("`{LIST_KEY}` lists the templates")),
+        (Some(key), None) if !key.is_whitespace() => key,
+        _ => return Err(format!("key `{}` needs to be one character", config.key)),
+    };
+    let candidate = pool
+        .iter()
+        .find(|ct| ct.name == config.candidate)
+        .ok_or_else(|| format!("there's no candidate called `{}`", config.candidate))?;
+    let count = config.count.unwrap_or(1);
+    if count == 0 {
+        return Err("count must be at least 1".to_string());
+    }
+    if let Some(intensity) = config.intensity {
+        if !(tasks::MIN_INTENSITY..=tasks::MAX_INTENSITY).contains(&intensity) {
+            return Err(format!(
+                "intensity must be from {} to {}",
+                tasks::MIN_INTENSITY,
+                tasks::MAX_INTENSITY
+            ));
+        }
+    }
+    if config.timeout_secs == Some(0) {
+        return Err("timeout_secs can't be 0".to_string());
+    }
+    Ok(Template {
+        key,
+        name: config.name.clone(),
+        candidate: candidate.clone(),
+        count,
+        intensity: config.intensity,
+        time_limit: config.timeout_secs.map(Duration::from_secs),
+    })
+}
@@ -0,0 +1,407 @@
+//! Every style the UI uses, in one place. There's the usual colorful preset and a monochrome one
+//! that only uses attributes (bold, reversed...) for `NO_COLOR` fans and dumb terminals. Widgets
+//! ask `theme::current()` instead of picking colors themselves. The same goes for anything that
+//! moves or only makes sense in color: `ui.reduce_motion` and `ui.plain_output` are folded in here
+//! with `with_access` (and a console that can't blink with `with_legacy`), and widgets just ask
+//! how to draw things. How much room the frame around it all takes is here too, as `Chrome`,
+//! though that one can change while running (`z`). Each task has a color of its own too, from
+//! `TASK_PALETTE` by ID, for keeping track of a row while sorting moves it about
+use std::{borrow::Cow, env, sync::OnceLock, time::Duration};
+
+use clap::ValueEnum;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    symbols::border,
+    text::Span,
+    widgets::{List, Padding},
+};
+
+use crate::tasks::{Id, TaskStatus};
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// What took the color away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoColor {
+    /// `NO_COLOR` is set, so somebody wanted it that way
+    Asked,
+    /// `TERM=dumb`, which probably can't do it
+    DumbTerminal,
+}
+
+/// `--color`, same meaning as everywhere else that has one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Color unless `NO_COLOR` is set or the terminal is dumb
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    /// Body text: the table, the logger, popup borders
+    pub text: Style,
+    /// Every other table row
+    pub alt_row: Style,
+    /// Key names in the controls lines
+    pub key: Style,
+    /// Timestamps, legends, dividers, placeholder text
+    pub dim: Style,
+    /// A step up from dim, like the picker entries that aren't on top
+    pub muted: Style,
+    /// Where the cursor is in lists that don't use the "> " marker, and recent picks
+    pub accent: Style,
+    /// Wants attention but isn't bad news: hints, pending halts, shutting down
+    pub notice: Style,
+    /// Is bad news: strike demands, error borders
+    pub alert: Style,
+    pub good: Style,
+    /// Startup warnings
+    pub banner: Style,
+    /// Characters the table filter matched
+    pub matched: Style,
+    pub log_error: Style,
+    pub log_warn: Style,
+    pub log_info: Style,
+    pub log_debug: Style,
+    pub log_trace: Style,
+    /// Styles alone can't tell every status apart, so the text says it too (`!` for strikes
+    /// instead of blinking at you)
+    pub markers: bool,
+    /// Nothing blinks, flashes or spins, and toasts wait for a key instead of a timer
+    pub reduce_motion: bool,
+    /// Words instead of glyphs, and nothing that's only said with color or a highlight
+    pub plain: bool,
+    /// An old console that can't draw thick borders or blink, see `compat`
+    pub legacy: bool,
+    /// Whether tasks get their colors from `TASK_PALETTE`
+    pub task_colors: bool,
+    statuses: StatusStyles,
+}
+
+/// The frame around the main screen and the table, and what goes in its margins. `ROOMY` out of
+/// the box, `COMPACT` for tmux splits and the like, where 7 rows of border and padding hurt
+#[derive(Debug, Clone, Copy)]
+pub struct Chrome {
+    pub border: border::Set,
+    /// Inside the main border
+    pub padding: Padding,
+    /// Inside the table's own border
+    pub table_padding: Padding,
+    /// Rows up from the bottom edge the glyph legend and toasts go on, in the padding. Without
+    /// room for them the legend's only in the help (`?`) and toasts take the controls' place
+    pub legend_row: Option<u16>,
+    pub toast_row: Option<u16>,
+    /// One-letter column headers and controls cut down to a key and a word
+    pub terse: bool,
+}
+
+impl Chrome {
+    pub const ROOMY: Chrome = Chrome {
+        border: border::THICK,
+        padding: Padding::new(2, 2, 1, 4),
+        table_padding: Padding::new(1, 1, 0, 1),
+        legend_row: Some(3),
+        toast_row: Some(2),
+        terse: false,
+    };
+
+    pub const COMPACT: Chrome = Chrome {
+        border: border::PLAIN,
+        padding: Padding::ZERO,
+        table_padding: Padding::ZERO,
+        legend_row: None,
+        toast_row: None,
+        terse: true,
+    };
+
+    /// `ui.compact`
+    pub fn pick(compact: bool) -> &'static Chrome {
+        if compact {
+            &Chrome::COMPACT
+        } else {
+            &Chrome::ROOMY
+        }
+    }
+}
+
+/// Rows are bright for this long after a status change...
+const FLASH_BRIGHT: Duration = Duration::from_secs(1);
+/// ...and back to normal after this long
+const FLASH_FADE: Duration = Duration::from_secs(2);
+
+/// Tasks' own colors, handed out in turn by ID. None of them are the rows' White and Gray or the
+/// DarkGray and Black they'd vanish into, and they're the bright ones so they still stand out on
+/// a flashing (reversed) row
+pub const TASK_PALETTE: [Color; 6] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::LightBlue,
+    Color::LightRed,
+];
+
+/// Where in `TASK_PALETTE` task `id`'s color is. Only the ID goes in, so a task keeps its color
+/// from when it's made, and tasks made one after the other never share one
+pub fn task_palette_index(id: Id) -> usize {
+    id % TASK_PALETTE.len()
+}
+
+/// Shell tasks can't tell us how far along they are, so they get one of these instead
+const SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
+
+#[derive(Debug, Clone, Copy)]
+struct StatusStyles {
+    running: Style,
+    sleeping: Style,
+    on_strike: Style,
+    finished: Style,
+    failed: Style,
+    other: Style,
+}
+
+impl Theme {
+    pub const COLOR: Theme = Theme {
+        text: Style::new().fg(Color::White),
+        alt_row: Style::new().fg(Color::Gray),
+        key: Style::new().fg(Color::Blue).add_modifier(Modifier::BOLD),
+        dim: Style::new().fg(Color::DarkGray),
+        muted: Style::new().fg(Color::Gray),
+        accent: Style::new().fg(Color::Cyan),
+        notice: Style::new().fg(Color::Yellow),
+        alert: Style::new().fg(Color::Red),
+        good: Style::new().fg(Color::Green),
+        banner: Style::new().fg(Color::Black).bg(Color::Yellow),
+        matched: Style::new()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD.union(Modifier::UNDERLINED)),
+        log_error: Style::new().fg(Color::Red),
+        log_warn: Style::new().fg(Color::Yellow),
+        log_info: Style::new().fg(Color::Cyan),
+        log_debug: Style::new().fg(Color::Green),
+        log_trace: Style::new().fg(Color::Magenta),
+        markers: false,
+        reduce_motion: false,
+        plain: false,
+        legacy: false,
+        task_colors: true,
+        statuses: StatusStyles {
+            running: Style::new().fg(Color::White),
+            sleeping: Style::new().fg(Color::Gray),
+            on_strike: Style::new().fg(Color::Red),
+            finished: Style::new().fg(Color::Green),
+            failed: Style::new().fg(Color::Red),
+            other: Style::new(),
+        },
+    };
+
+    pub const MONO: Theme = Theme {
+        text: Style::new(),
+        alt_row: Style::new(),
+        key: Style::new().add_modifier(Modifier::BOLD),
+        dim: Style::new().add_modifier(Modifier::DIM),
+        muted: Style::new(),
+        accent: Style::new().add_modifier(Modifier::REVERSED),
+        notice: Style::new().add_modifier(Modifier::BOLD),
+        alert: Style::new().add_modifier(Modifier::BOLD),
+        good: Style::new(),
+        banner: Style::new().add_modifier(Modifier::REVERSED),
+        matched: Style::new().add_modifier(Modifier::BOLD.union(Modifier::UNDERLINED)),
+        log_error: Style::new().add_modifier(Modifier::BOLD.union(Modifier::REVERSED)),
+        log_warn: Style::new().add_modifier(Modifier::BOLD),
+        log_info: Style::new(),
+        log_debug: Style::new(),
+        log_trace: Style::new().add_modifier(Modifier::DIM),
+        markers: true,
+        reduce_motion: false,
+        plain: false,
+        legacy: false,
+        task_colors: false,
+        statuses: StatusStyles {
+            running: Style::new(),
+            sleeping: Style::new().add_modifier(Modifier::DIM),
+            on_strike: Style::new().add_modifier(Modifier::BOLD.union(Modifier::REVERSED)),
+            finished: Style::new().add_modifier(Modifier::BOLD),
+            failed: Style::new().add_modifier(Modifier::BOLD.union(Modifier::UNDERLINED)),
+            other: Style::new(),
+        },
+    };
+
+    /// What `--color` comes down to in this environment
+    pub fn pick(choice: ColorChoice) -> Theme {
+        match choice {
+            ColorChoice::Always => Theme::COLOR,
+            ColorChoice::Never => Theme::MONO,
+            ColorChoice::Auto => match Theme::no_color() {
+                Some(_) => Theme::MONO,
+                None => Theme::COLOR,
+            },
+        }
+    }
+
+    /// Why `Auto` would go without color here, if it would
+    pub fn no_color() -> Option<NoColor> {
+        // https://no-color.org says any non-empty value counts
+        if env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            return Some(NoColor::Asked);
+        }
+        env::var("TERM")
+            .is_ok_and(|term| term == "dumb")
+            .then_some(NoColor::DumbTerminal)
+    }
+
+    /// Turns on `ui.reduce_motion` and `ui.plain_output`. Either one means strikes get their `!`,
+    /// since it's no longer blinking (or the color's not to be relied on)
+    pub fn with_access(self, reduce_motion: bool, plain: bool) -> Theme {
+        Theme {
+            markers: self.markers || reduce_motion || plain,
+            reduce_motion,
+            plain,
+            ..self
+        }
+    }
+
+    /// For `ui.legacy_terminal`: thin borders, and strikes get their `!` since they can't blink
+    pub fn with_legacy(self, legacy: bool) -> Theme {
+        Theme {
+            markers: self.markers || legacy,
+            legacy,
+            ..self
+        }
+    }
+
+    /// `set`, unless this terminal can only manage the plain one
+    pub fn border(&self, set: border::Set) -> border::Set {
+        if self.legacy {
+            border::PLAIN
+        } else {
+            set
+        }
+    }
+
+    /// The status column's text, marked and styled
+    pub fn status_text(&self, status: &TaskStatus) -> Span<'static> {
+        let style = self.status(status);
+        match status {
+            // Blinking is the whole point in color. Without it, say so in words (well, in a `!`)
+            TaskStatus::OnStrike if self.markers => {
+                Span::styled(format!("!{}", status.label()), style)
+            }
+            TaskStatus::OnStrike => {
+                Span::styled(status.label(), style.add_modifier(Modifier::SLOW_BLINK))
+            }
+            _ => Span::styled(status.label(), style),
+        }
+    }
+
+    /// What a row gets `since_change` after its status changed. Modifiers only add to the row
+    /// style, so this stacks with the alternating colors and selection
+    pub fn flash(&self, since_change: Duration) -> Modifier {
+        if self.reduce_motion || since_change >= FLASH_FADE {
+            Modifier::empty()
+        } else if since_change < FLASH_BRIGHT {
+            Modifier::BOLD | Modifier::REVERSED
+        } else {
+            Modifier::BOLD
+        }
+    }
+
+    /// A running shell task's progress, `millis` into the session
+    pub fn spinner(&self, millis: i64) -> &'static str {
+        if self.reduce_motion {
+            "running"
+        } else {
+            SPINNER[(millis / 250).rem_euclid(SPINNER.len() as i64) as usize]
+        }
+    }
+
+    /// In front of the selected row in lists and tables. Plain output makes it hard to miss,
+    /// since that's all there is to go on
+    pub fn highlight_symbol(&self) -> &'static str {
+        if self.plain {
+            ">> "
+        } else {
+            "> "
+        }
+    }
+
+    /// For lists that only color the selected row. Plain output gets the symbol instead
+    pub fn highlight_quietly<'a>(&self, list: List<'a>) -> List<'a> {
+        if self.plain {
+            list.highlight_symbol(self.highlight_symbol())
+        } else {
+            list.highlight_style(self.accent)
+        }
+    }
+
+    /// What a timeline bar for `status` is drawn with. Plain output can't leave it to the color,
+    /// so each status gets its own letter
+    pub fn bar_fill(&self, status: &TaskStatus, fill: char) -> char {
+        if !self.plain {
+            return fill;
+        }
+        match status {
+            TaskStatus::Scheduled => 'w',
+            TaskStatus::Running => 'r',
+            TaskStatus::Sleeping => 's',
+            TaskStatus::Finalizing => 'l',
+            TaskStatus::OnStrike => '!',
+            TaskStatus::KnownUnknown => '?',
+            TaskStatus::WaitingForThread => 'q',
+            TaskStatus::Finished => 'd',
+            TaskStatus::Canceled => 'c',
+            TaskStatus::Failed => 'f',
+            TaskStatus::FailedToStart => 'x',
+        }
+    }
+
+    /// Task `id`'s own color, unless there's no color to be had. Plain output goes without, since
+    /// it's only ever said in color
+    pub fn task_color(&self, id: Id) -> Option<Color> {
+        self.tints_tasks()
+            .then(|| TASK_PALETTE[task_palette_index(id)])
+    }
+
+    /// Whether `task_color` has anything to give
+    pub fn tints_tasks(&self) -> bool {
+        self.task_colors && !self.plain
+    }
+
+    /// Whether glyph-only columns (like the table's first one) are worth drawing
+    pub fn glyphs(&self) -> bool {
+        !self.plain
+    }
+
+    /// Shared by the status column, the glyphs, the legend and the timeline so they all agree
+    pub fn status(&self, status: &TaskStatus) -> Style {
+        match status {
+            TaskStatus::Running | TaskStatus::Finalizing => self.statuses.running,
+            TaskStatus::Sleeping => self.statuses.sleeping,
+            TaskStatus::OnStrike => self.statuses.on_strike,
+            TaskStatus::Finished => self.statuses.finished,
+            TaskStatus::Failed | TaskStatus::FailedToStart => self.statuses.failed,
+            TaskStatus::Scheduled
+            | TaskStatus::KnownUnknown
+            | TaskStatus::WaitingForThread
+            | TaskStatus::Canceled => self.statuses.other,
+        }
+    }
+}
+
+/// Only the first call does anything, the theme doesn't change once we're going
+pub fn init(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+/// A key name for a controls line, like `<F1>`
+pub fn key<'a>(label: impl Into<Cow<'a, str>>) -> Span<'a> {
+    Span::styled(label, current().key)
+}
+
+/// Colorful until `init` says otherwise
+pub fn current() -> &'static Theme {
+    THEME.get().unwrap_or(&Theme::COLOR)
+}
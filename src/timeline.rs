@@ -0,0 +1,188 @@
+//! Gantt-ish view of the whole session: a bar per task from when it started to when it ended (or
+//! now, if it hasn't), all on one time axis that runs from startup to right now. So everything
+//! squishes left as time goes on, which is kind of the point
+use chrono::{DateTime, Local};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Style,
+    text::Line,
+    widgets::{Block, Padding, StatefulWidget, Widget},
+};
+
+use crate::{layout_util::saturating_height, tasks::Task, theme};
+
+/// Room for the name in front of each bar
+const LABEL_WIDTH: u16 = 22;
+/// Axis rows under the bars: the line, then the labels
+const AXIS_HEIGHT: u16 = 2;
+/// Tick spacings to choose from, in minutes. The first one that isn't crowded wins
+const TICK_STEPS: [i64; 8] = [1, 2, 5, 10, 15, 30, 60, 120];
+/// Closest two labels are allowed to get, in columns
+const MIN_TICK_GAP: u16 = 8;
+
+#[derive(Debug)]
+pub struct Timeline {
+    /// Left edge of the axis
+    pub session_start: DateTime<Local>,
+    /// Tasks scrolled off the top
+    scroll: usize,
+    ascii: bool,
+}
+
+impl Timeline {
+    pub fn new(session_start: DateTime<Local>, ascii: bool) -> Self {
+        Self {
+            session_start,
+            scroll: 0,
+            ascii,
+        }
+    }
+
+    /// Clamped on render, since only that knows how many rows fit
+    pub fn next(&mut self) {
+        self.scroll = self.scroll.saturating_add(1);
+    }
+
+    pub fn previous(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    /// The line with tick marks, and relative minutes under them. Ends in "now"
+    fn render_axis(&self, area: Rect, x: u16, width: u16, now: DateTime<Local>, buf: &mut Buffer) {
+        let theme = theme::current();
+        let (line_y, label_y) = (area.y, area.y + 1);
+        if area.height < AXIS_HEIGHT {
+            return;
+        }
+        for col in 0..width {
+            buf[(x + col, line_y)].set_char('─').set_style(theme.dim);
+        }
+        let minutes = (now - self.session_start).num_minutes();
+        let step = TICK_STEPS
+            .iter()
+            .copied()
+            .find(|&step| {
+                let one_step = self.session_start + chrono::Duration::minutes(step);
+                column(one_step, self.session_start, now, width) >= MIN_TICK_GAP
+            })
+            .unwrap_or(*TICK_STEPS.last().unwrap());
+        let now_label = "now";
+        let now_label_x = (x + width).saturating_sub(now_label.len() as u16);
+        for minute in (0..=minutes).step_by(step as usize) {
+            let at = self.session_start + chrono::Duration::minutes(minute);
+            let col = x + column(at, self.session_start, now, width);
+            let label = format!("{minute}m");
+            // Don't run into "now" at the end
+            if col + label.len() as u16 >= now_label_x && minute > 0 {
+                break;
+            }
+            buf[(col, line_y)].set_char('┬').set_style(theme.dim);
+            buf.set_string(col, label_y, label, theme.dim);
+        }
+        buf[(x + width - 1, line_y)]
+            .set_char('┤')
+            .set_style(theme.dim);
+        buf.set_string(now_label_x, label_y, now_label, theme.muted);
+    }
+}
+
+/// Which of `width` columns `at` lands in, with `start` in the first and `now` in the last. Only
+/// ever proportional, so a resize moves every bar the same way instead of bending them
+pub fn column(
+    at: DateTime<Local>,
+    start: DateTime<Local>,
+    now: DateTime<Local>,
+    width: u16,
+) -> u16 {
+    let span = (now - start).num_milliseconds();
+    if width == 0 || span <= 0 {
+        return 0;
+    }
+    let offset = (at - start).num_milliseconds().clamp(0, span);
+    let last = (width - 1) as i64;
+    ((offset * last + span / 2) / span) as u16
+}
+
+impl<'a> StatefulWidget for &'a mut Timeline {
+    type State = &'a Vec<Task>;
+
+    fn render(self, area: Rect, buf: &mut Buffer, tasks: &mut Self::State) {
+        let theme = theme::current();
+        let now = Local::now();
+        let block = Block::bordered()
+            .title(format!(
+                " Timeline ({} since start) ",
+                elapsed(now - self.session_start)
+            ))
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [rows_area, axis_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(AXIS_HEIGHT)]).areas(inner);
+        let [_, bars_area] =
+            Layout::horizontal([Constraint::Length(LABEL_WIDTH), Constraint::Min(0)])
+                .areas(rows_area);
+        let width = bars_area.width;
+        if tasks.is_empty() {
+            Line::from("Nothing's happened yet — press Esc, then F1 to add a task")
+                .style(theme.dim)
+                .render(rows_area, buf);
+        }
+
+        // Keep the bottom full when scrolled all the way, rather than scrolling into nothing
+        let visible = rows_area.height as usize;
+        self.scroll = self.scroll.min(tasks.len().saturating_sub(visible));
+        let (fill, now_mark) = if self.ascii {
+            ('#', '|')
+        } else {
+            ('█', '│')
+        };
+        let now_col = bars_area.x + width.saturating_sub(1);
+        for (row, task) in tasks.iter().skip(self.scroll).take(visible).enumerate() {
+            let y = rows_area.y + saturating_height(row);
+            let label = Rect {
+                y,
+                height: 1,
+                width: LABEL_WIDTH - 1,
+                ..rows_area
+            }
+            .intersection(rows_area);
+            Line::from(format!("{:>3} {}", task.id, task.name)).render(label, buf);
+            // Nothing to draw for the ones still waiting (or that never got going)
+            let Some(start) = task.start.filter(|_| width > 0) else {
+                continue;
+            };
+            let from = column(start, self.session_start, now, width);
+            let to = column(task.end.unwrap_or(now), self.session_start, now, width);
+            // Its own color if there's any, so it's the same one as its row
+            let style = match theme.task_color(task.id) {
+                Some(color) => Style::new().fg(color),
+                None => theme.status(&task.status),
+            };
+            let fill = theme.bar_fill(&task.status, fill);
+            for x in from..=to.max(from) {
+                buf[(bars_area.x + x, y)].set_char(fill).set_style(style);
+            }
+            // Anyone not reaching the edge gets a marker for where now is
+            if to < width - 1 {
+                buf[(now_col, y)].set_char(now_mark).set_style(theme.dim);
+            }
+        }
+
+        if width > 0 {
+            self.render_axis(axis_area, bars_area.x, width, now, buf);
+        }
+    }
+}
+
+/// "4m 12s" for the title
+fn elapsed(since: chrono::Duration) -> String {
+    let secs = since.num_seconds().max(0);
+    if secs < 60 {
+        format!("{secs}s")
+    } else {
+        format!("{}m {}s", secs / 60, secs % 60)
+    }
+}
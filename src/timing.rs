@@ -0,0 +1,150 @@
+//! Decides when the main loop redraws. Draws happen when something changed or an animation tick
+//! is due, and never closer together than the frame cap allows - busy tasks can send hundreds of
+//! reports a second and none of them deserve a frame each. Nobody's watching while the terminal's
+//! out of focus, so then it's one frame every couple of seconds, whatever changed. Low power
+//! only slows the ticks, so keys still get a frame straight away
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::stats::RollingStats;
+
+use crate::config::TimingConfig;
+
+/// How far back the measured frame rate looks
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+/// How many frames' render times the debug overlay gets to look at
+const RENDER_WINDOW: usize = 120;
+/// Both the tick and the frame cap while unfocused
+pub const UNFOCUSED_CADENCE: Duration = Duration::from_secs(2);
+/// The tick in low power, if it isn't slower already
+pub const LOW_POWER_TICK: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub struct FrameLimiter {
+    pub config: TimingConfig,
+    poll: Duration,
+    tick: Duration,
+    frame: Duration,
+    /// Something user-visible changed since the last draw
+    dirty: bool,
+    last_draw: Option<Instant>,
+    next_tick: Instant,
+    /// Recent draws, for the debug overlay
+    draws: VecDeque<Instant>,
+    /// How long recent draws took, in milliseconds. Also for the overlay
+    pub render_ms: RollingStats,
+    /// Whether the terminal's said it lost focus, and not got it back since
+    unfocused: bool,
+    low_power: bool,
+}
+
+impl FrameLimiter {
+    pub fn new(config: TimingConfig) -> Self {
+        Self {
+            config,
+            poll: Duration::from_millis(config.poll_ms),
+            tick: Duration::from_millis(config.tick_ms),
+            frame: Duration::from_secs(1) / config.max_fps,
+            dirty: true, // Nothing's on screen yet
+            last_draw: None,
+            next_tick: Instant::now(),
+            draws: VecDeque::new(),
+            render_ms: RollingStats::new(RENDER_WINDOW),
+            unfocused: false,
+            low_power: false,
+        }
+    }
+
+    /// Slows right down while unfocused, back up straight away (with a frame) when not
+    pub fn set_focused(&mut self, focused: bool, now: Instant) {
+        self.unfocused = !focused;
+        self.next_tick = now + self.tick();
+        self.dirty = true;
+    }
+
+    pub fn is_focused(&self) -> bool {
+        !self.unfocused
+    }
+
+    pub fn set_low_power(&mut self, low_power: bool, now: Instant) {
+        self.low_power = low_power;
+        self.next_tick = now + self.tick();
+        self.dirty = true;
+    }
+
+    pub fn is_low_power(&self) -> bool {
+        self.low_power
+    }
+
+    fn tick(&self) -> Duration {
+        if self.unfocused {
+            UNFOCUSED_CADENCE
+        } else if self.low_power {
+            self.tick.max(LOW_POWER_TICK)
+        } else {
+            self.tick
+        }
+    }
+
+    fn frame(&self) -> Duration {
+        if self.unfocused {
+            UNFOCUSED_CADENCE
+        } else {
+            self.frame
+        }
+    }
+
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Whether to draw right now. Ticks count as a change, since spinners and flashes move on
+    /// their own
+    pub fn should_draw(&mut self, now: Instant) -> bool {
+        if now >= self.next_tick {
+            self.dirty = true;
+            self.next_tick = now + self.tick();
+        }
+        self.dirty
+            && self
+                .since_draw(now)
+                .is_none_or(|since| since >= self.frame())
+    }
+
+    /// `took` is how long the draw itself was, start to flush
+    pub fn drew(&mut self, now: Instant, took: Duration) {
+        self.dirty = false;
+        self.render_ms.push(took.as_secs_f64() * 1000.0);
+        self.last_draw = Some(now);
+        self.draws.push_back(now);
+        while self
+            .draws
+            .front()
+            .is_some_and(|&at| now.duration_since(at) > FPS_WINDOW)
+        {
+            self.draws.pop_front();
+        }
+    }
+
+    /// How long to wait for input before coming back around: the poll interval, or less if a
+    /// held-back frame or the next tick is due sooner
+    pub fn wait(&self, now: Instant) -> Duration {
+        let until_tick = self.next_tick.saturating_duration_since(now);
+        let until_frame = match (self.dirty, self.since_draw(now)) {
+            (true, Some(since)) => self.frame().saturating_sub(since),
+            _ => self.poll,
+        };
+        self.poll.min(until_tick).min(until_frame)
+    }
+
+    /// Draws in the last second
+    pub fn fps(&self) -> usize {
+        self.draws.len()
+    }
+
+    fn since_draw(&self, now: Instant) -> Option<Duration> {
+        self.last_draw.map(|at| now.duration_since(at))
+    }
+}
@@ -0,0 +1,75 @@
+//! Recently removed task IDs, so a message that turns up for one (its worker was still winding
+//! down) can be told apart from a message for an ID we never had, which means the plumbing's
+//! broken somewhere. A removed row is only buried once it's discarded (see `removals`), since
+//! until then it can still be put back
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::tasks::Id;
+
+/// How long a removed ID is remembered. Workers don't take anywhere near this long to notice
+pub const TOMBSTONE_TTL: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone)]
+pub struct Tombstones {
+    removed: HashMap<Id, Instant>,
+    ttl: Duration,
+}
+
+/// Whose a message for an untracked ID was
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stray {
+    /// A task removed this long ago
+    Late(Duration),
+    /// Never ours, or removed so long ago it may as well not have been
+    Unknown,
+}
+
+impl Default for Tombstones {
+    fn default() -> Self {
+        Self::new(TOMBSTONE_TTL)
+    }
+}
+
+impl Tombstones {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            removed: HashMap::new(),
+            ttl,
+        }
+    }
+
+    /// `id` was removed at `now`. Removing it again starts its clock over
+    pub fn bury(&mut self, id: Id, now: Instant) {
+        self.removed.insert(id, now);
+    }
+
+    /// Forgets everything removed more than the TTL ago
+    pub fn expire(&mut self, now: Instant) {
+        let ttl = self.ttl;
+        self.removed
+            .retain(|_, &mut at| now.saturating_duration_since(at) <= ttl);
+    }
+
+    /// What a message for untracked `id` arriving at `now` is. Something past the TTL counts as
+    /// unknown even if `expire` hasn't got round to it
+    pub fn classify(&self, id: Id, now: Instant) -> Stray {
+        match self.removed.get(&id) {
+            Some(&at) if now.saturating_duration_since(at) <= self.ttl => {
+                Stray::Late(now.saturating_duration_since(at))
+            }
+            _ => Stray::Unknown,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.removed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty()
+    }
+}
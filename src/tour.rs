@@ -0,0 +1,131 @@
+//! A few boxes walking someone through their first run: open the picker, start a task, then
+//! inspect it. Each step points at where to look and waits for them to actually do it, it
+//! doesn't take any keys itself bar `Esc` to skip the lot. Only offered when there's no config
+//! or history file yet, and never again once it's been finished or skipped
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::{layout_util::saturating_height, theme, view::ViewState};
+
+/// What the tour's waiting for them to do next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// `F1`, from the main screen
+    OpenPicker,
+    /// Choose something in the picker and start it
+    PickTask,
+    /// `F2` now there's a task to look at
+    Inspect,
+}
+
+impl Step {
+    /// What the box says
+    pub fn lines(self) -> [&'static str; 2] {
+        match self {
+            Step::OpenPicker => [
+                "Welcome! Nothing's running yet.",
+                "Press F1 to pick your first task",
+            ],
+            Step::PickTask => ["J/K (or the arrows) choose a task,", "and Enter starts it"],
+            Step::Inspect => [
+                "It's going. Press F2 to inspect it:",
+                "stop it, read its details, and more",
+            ],
+        }
+    }
+}
+
+const SKIP: &str = "Esc skips the tour";
+
+#[derive(Debug, Clone, Default)]
+pub struct Tour {
+    /// None once it's over, either way
+    step: Option<Step>,
+}
+
+impl Tour {
+    pub fn new() -> Self {
+        Self {
+            step: Some(Step::OpenPicker),
+        }
+    }
+
+    pub fn step(&self) -> Option<Step> {
+        self.step
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.step.is_none()
+    }
+
+    /// They're on `view`, and `has_tasks` is whether anything's been added. Moves on from any
+    /// step that's been done, skipping ahead if they got there some other way (a batch, say).
+    /// True if that was the last one
+    pub fn observe(&mut self, view: ViewState, has_tasks: bool) -> bool {
+        let picking = matches!(view, ViewState::TaskAdd | ViewState::Duration);
+        let next = match self.step {
+            None => return false,
+            Some(Step::OpenPicker | Step::PickTask) if has_tasks && !picking => Some(Step::Inspect),
+            Some(Step::OpenPicker) if picking => Some(Step::PickTask),
+            Some(Step::Inspect) if view == ViewState::Inspect => None,
+            step => step,
+        };
+        self.step = next;
+        next.is_none()
+    }
+
+    /// `Esc`, whatever step it's on
+    pub fn skip(&mut self) {
+        self.step = None;
+    }
+}
+
+/// Where a `width` by `height` box goes to point at `target` without covering it: just above it
+/// if there's room, otherwise just below, centered on it and kept on `screen`
+pub fn place(target: Rect, screen: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(screen.width);
+    let height = height.min(screen.height);
+    let y = if target.y >= screen.y.saturating_add(height) {
+        target.y - height
+    } else {
+        target.bottom().min(screen.bottom().saturating_sub(height))
+    };
+    let centered = (target.x.saturating_add(target.width / 2)).saturating_sub(width / 2);
+    let x = centered.clamp(screen.x, screen.right().saturating_sub(width));
+    Rect {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+/// Dims everything on `screen` but `target`, then draws `step`'s box next to it
+pub fn render(step: Step, target: Rect, screen: Rect, buf: &mut Buffer) {
+    let dim = Style::new().add_modifier(Modifier::DIM);
+    for y in screen.top()..screen.bottom() {
+        for x in screen.left()..screen.right() {
+            let lit = (target.left()..target.right()).contains(&x)
+                && (target.top()..target.bottom()).contains(&y);
+            if !lit {
+                buf[(x, y)].set_style(dim);
+            }
+        }
+    }
+    let theme = theme::current();
+    let mut lines: Vec<Line> = step.lines().into_iter().map(Line::from).collect();
+    lines.push(Line::styled(SKIP, theme.dim));
+    let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 4;
+    let area = place(target, screen, width, saturating_height(lines.len() + 2));
+    Clear.render(area, buf);
+    Paragraph::new(lines)
+        .block(Block::bordered().title(" tour ").border_style(theme.accent))
+        .centered()
+        .render(area, buf);
+}
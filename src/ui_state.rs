@@ -0,0 +1,73 @@
+//! Where the UI was when the app last closed, so it comes back the way it was left. Settings
+//! (columns, the split) live in the config file, this is for what changes all the time: how the
+//! table's sorted and what it's filtered down to, and whether the tour's been seen. Never worth
+//! failing to start over, so a file that won't parse is moved out of the way and we start fresh
+
+use std::{
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::Result;
+use serde::{Deserialize, Serialize};
+
+/// Where it's kept, next to the config file's default spot
+pub const DEFAULT_PATH: &str = "./clustrctrl-ui.toml";
+
+/// Files from older (or newer) versions still load: anything missing is the default, anything
+/// unknown is ignored
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    /// `S` once, soonest ETA first
+    pub sort_by_eta: bool,
+    /// `S` twice, grouped by status. Wins if both are somehow set
+    pub sort_by_status: bool,
+    /// Whatever `/` left in the table filter
+    pub filter: String,
+    /// `h`, Finished and Canceled rows left out
+    pub hide_finished: bool,
+    /// The first-run tour's been finished or skipped
+    pub tour_done: bool,
+}
+
+impl UiState {
+    /// What was saved at `path`, plus a complaint if there's one worth making. No file is the
+    /// normal case. One that won't parse is renamed to `aside(path)` so it's not lost, or
+    /// overwritten next save
+    pub fn load(path: &Path) -> (Self, Option<String>) {
+        let Ok(text) = fs::read_to_string(path) else {
+            return (Self::default(), None);
+        };
+        let e = match toml::from_str(&text) {
+            Ok(state) => return (state, None),
+            Err(e) => e,
+        };
+        let aside = aside(path);
+        let problem = match fs::rename(path, &aside) {
+            Ok(()) => format!(
+                "UI state in {} is invalid, moved it to {} and started fresh: {e}",
+                path.display(),
+                aside.display()
+            ),
+            Err(rename) => format!(
+                "UI state in {} is invalid ({e}), and it couldn't be moved aside: {rename}",
+                path.display()
+            ),
+        };
+        (Self::default(), Some(problem))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Where a broken file at `path` goes: the same name with `.bad` on the end
+pub fn aside(path: &Path) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(".bad");
+    path.with_file_name(name)
+}
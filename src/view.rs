@@ -0,0 +1,39 @@
+//! Where the user is in the app. Lives out here so the parts that only depend on it (like what the
+//! controls line offers) can be checked without an App around them
+
+/// Which screen (or popup) keys go to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewState {
+    /// Modal should be active, and we can add tasks here
+    TaskAdd,
+    /// Main screen. Can't do anything but enter other modes & watch
+    Monitor,
+    /// Main screen, but we can select tasks on the table and cancel them
+    Inspect,
+    /// Everything about the task that was selected in Inspect
+    Detail,
+    /// Typing into the table filter, over the Inspect screen
+    Filter,
+    /// Modal asking whether to add what's in the batch file
+    Batch,
+    /// Modal asking whether to add a task that a guard had doubts about
+    Guard,
+    /// Modal for answering a striking task, over the Inspect screen
+    Negotiate,
+    /// Every task on one time axis, from startup to now
+    Timeline,
+    /// What can be done to the selected task, in a popup next to its row
+    Menu,
+    /// Writing a note on the selected task, over the Inspect screen
+    Note,
+    /// Everything the picker can pick from, to add to, switch off or delete
+    Pool,
+    /// Which table columns show and in what order, over the Inspect screen
+    Columns,
+    /// Choosing how long a new task takes, over the picker
+    Duration,
+    /// The message stream with the screen to itself, bar a line about the tasks
+    Logs,
+    /// How the channels fit together, live. From `a` in the help
+    Architecture,
+}
@@ -0,0 +1,105 @@
+//! The line over the table header saying what's been done to it, like `sort: eta ↑ · filter:
+//! "db"`, since once a few of those pile up there's no telling from the rows. Only what's been
+//! changed from the default is on it, and with nothing changed there's no line at all. `V` puts
+//! all of it back. There's no follow mode for the table (only the message stream follows, and it
+//! doesn't say whether it is), so that's not on here
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+
+use crate::{groups, task_table::SortBy, tasks::StatusClass, theme};
+
+/// How the table's rearranged right now, as far as this line cares
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ViewSpec {
+    pub sort: SortBy,
+    pub filter: String,
+    /// Folded groups, in the order they're shown. They only count while sorted by status
+    pub folded: Vec<StatusClass>,
+    /// Rows opened up with `O`
+    pub expanded: usize,
+}
+
+impl ViewSpec {
+    pub fn is_default(&self) -> bool {
+        self.segments(false).is_empty()
+    }
+
+    /// Each thing that's changed, in words, with the style it's drawn in
+    fn segments(&self, ascii: bool) -> Vec<(String, Style)> {
+        let theme = theme::current();
+        let mut segments = vec![];
+        match self.sort {
+            SortBy::Added => {}
+            SortBy::Eta => {
+                let arrow = if ascii { "^" } else { "↑" };
+                segments.push((format!("sort: eta {arrow}"), theme.accent));
+            }
+            SortBy::Status => {
+                segments.push(("sort: status".to_string(), theme.accent));
+                let group = match self.folded.len() {
+                    0 => "group: on".to_string(),
+                    n => format!("group: {n} folded"),
+                };
+                segments.push((group, theme.notice));
+            }
+        }
+        if !self.filter.is_empty() {
+            segments.push((format!("filter: \"{}\"", self.filter), theme.matched));
+        }
+        if self.expanded > 0 {
+            segments.push((format!("expanded: {}", self.expanded), theme.good));
+        }
+        segments
+    }
+}
+
+/// Folded groups out of `groups`, in `groups::ORDER`
+pub fn folded(groups: &groups::Groups) -> Vec<StatusClass> {
+    groups::ORDER
+        .into_iter()
+        .filter(|&class| groups.is_folded(class))
+        .collect()
+}
+
+/// The line, cut down to `width` columns with an ellipsis if it doesn't fit. `None` when
+/// everything's the default, so there's no empty row over the table
+pub fn line(spec: &ViewSpec, width: u16, ascii: bool) -> Option<Line<'static>> {
+    let segments = spec.segments(ascii);
+    if segments.is_empty() {
+        return None;
+    }
+    let separator = if ascii { " | " } else { " · " };
+    let dim = theme::current().dim;
+    let mut spans = vec![];
+    for (at, (text, style)) in segments.into_iter().enumerate() {
+        if at > 0 {
+            spans.push(Span::styled(separator, dim));
+        }
+        spans.push(Span::styled(text, style));
+    }
+    Some(fit(spans, width as usize))
+}
+
+/// Spans up to `width` characters, the last one given over to `…` if any had to go
+fn fit(spans: Vec<Span<'static>>, width: usize) -> Line<'static> {
+    let total: usize = spans.iter().map(|span| span.content.chars().count()).sum();
+    if total <= width {
+        return Line::from(spans);
+    }
+    let mut room = width.saturating_sub(1);
+    let mut kept = vec![];
+    for span in spans {
+        if room == 0 {
+            break;
+        }
+        let text: String = span.content.chars().take(room).collect();
+        room -= text.chars().count();
+        kept.push(Span::styled(text, span.style));
+    }
+    if width > 0 {
+        kept.push(Span::styled("…", theme::current().dim));
+    }
+    Line::from(kept)
+}
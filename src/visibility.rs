@@ -0,0 +1,91 @@
+//! Rows left out of the table by status. Lighter than the filter and kept apart from it: `h`
+//! hides everything Finished or Canceled, and `ui.hidden_statuses` in the config hides those
+//! from the start. They're still tasks, so the summary, the stats and the report have them, they
+//! just don't get a row. One that changes status while it's hidden (nothing should, they're all
+//! over by then) is shown again from then on and stays shown, since that's worth a look
+use std::collections::{hash_map, HashMap, HashSet};
+
+use tracing::warn;
+
+use crate::tasks::{Id, Task, TaskStatus};
+
+/// What `h` hides
+pub const QUICK: [TaskStatus; 2] = [TaskStatus::Finished, TaskStatus::Canceled];
+
+#[derive(Debug, Clone, Default)]
+pub struct Visibility {
+    /// `ui.hidden_statuses`, hidden whatever `h` says
+    statuses: Vec<TaskStatus>,
+    /// `h`
+    quick: bool,
+    /// What each hidden task was when it was first hidden
+    seen: HashMap<Id, TaskStatus>,
+    /// Hidden ones that moved anyway, shown for good
+    pinned: HashSet<Id>,
+}
+
+impl Visibility {
+    pub fn new(statuses: Vec<TaskStatus>) -> Self {
+        Self {
+            statuses,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_quick(&self) -> bool {
+        self.quick
+    }
+
+    /// `h`. True if it's hiding now. Anything shown for having moved is up for hiding again
+    pub fn toggle_quick(&mut self) -> bool {
+        self.set_quick(!self.quick);
+        self.quick
+    }
+
+    pub fn set_quick(&mut self, quick: bool) {
+        self.quick = quick;
+        self.seen.clear();
+        self.pinned.clear();
+    }
+
+    fn hides_status(&self, status: TaskStatus) -> bool {
+        self.statuses.contains(&status) || (self.quick && QUICK.contains(&status))
+    }
+
+    pub fn hides(&self, task: &Task) -> bool {
+        !self.pinned.contains(&task.id) && self.hides_status(task.status)
+    }
+
+    /// Catches up with `tasks`: remembers what each hidden one is, and shows any that's changed
+    /// since. How many came back just now
+    pub fn watch(&mut self, tasks: &[Task]) -> usize {
+        let mut back = 0;
+        for task in tasks {
+            if self.pinned.contains(&task.id) {
+                continue;
+            }
+            if !self.hides_status(task.status) {
+                self.seen.remove(&task.id);
+                continue;
+            }
+            match self.seen.entry(task.id) {
+                hash_map::Entry::Vacant(entry) => {
+                    entry.insert(task.status);
+                }
+                hash_map::Entry::Occupied(entry) if *entry.get() != task.status => {
+                    warn!(
+                        "task {} went from {} to {} while hidden, showing it again",
+                        task.id,
+                        entry.get(),
+                        task.status
+                    );
+                    entry.remove();
+                    self.pinned.insert(task.id);
+                    back += 1;
+                }
+                hash_map::Entry::Occupied(_) => {}
+            }
+        }
+        back
+    }
+}
@@ -0,0 +1,108 @@
+//! Tells some URL when tasks start and end, for chat bots and the like. Every event goes out from
+//! its own tokio task, so a slow or dead endpoint costs the UI nothing but a warning in the log
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use tokio::task::JoinHandle;
+use tracing::{trace, warn};
+
+use crate::{summary::TaskSummary, tasks::Task};
+
+/// How long one attempt gets before we give up on it
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(3);
+/// First try plus one retry
+const ATTEMPTS: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Created,
+    Finished,
+    Failed,
+    Canceled,
+}
+
+/// What gets POSTed: `{event, id, name, status, timestamp}`
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    pub event: EventKind,
+    #[serde(flatten)]
+    pub task: TaskSummary,
+    pub timestamp: String,
+}
+
+impl Event {
+    pub fn new(event: EventKind, task: &Task, at: DateTime<Local>) -> Self {
+        Self {
+            event,
+            task: TaskSummary::from(task),
+            timestamp: at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    client: reqwest::Client,
+    url: String,
+    /// Events that never made it, retry and all. Shared with the tasks doing the sending
+    failures: Arc<AtomicUsize>,
+}
+
+impl Webhook {
+    pub fn new(url: String, timeout: Duration) -> Result<Self, reqwest::Error> {
+        Ok(Self {
+            client: reqwest::Client::builder().timeout(timeout).build()?,
+            url,
+            failures: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// Sends `event` in the background. Needs a runtime, like spawning tasks does. The handle says
+    /// whether it got through, for anyone who wants to wait - the app never does
+    pub fn send(&self, event: Event) -> JoinHandle<bool> {
+        let (client, url, failures) =
+            (self.client.clone(), self.url.clone(), self.failures.clone());
+        tokio::spawn(async move {
+            let mut last_error = None;
+            for attempt in 1..=ATTEMPTS {
+                match client
+                    .post(&url)
+                    .json(&event)
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status())
+                {
+                    Ok(_) => {
+                        trace!("webhook took {:?} for task {}", event.event, event.task.id);
+                        return true;
+                    }
+                    Err(e) => {
+                        trace!("webhook attempt {attempt} failed: {e}");
+                        last_error = Some(e);
+                    }
+                }
+            }
+            failures.fetch_add(1, Ordering::Relaxed);
+            if let Some(e) = last_error {
+                warn!(
+                    "couldn't tell the webhook about task {} ({:?}): {e}",
+                    event.task.id, event.event
+                );
+            }
+            false
+        })
+    }
+
+    pub fn failures(&self) -> usize {
+        self.failures.load(Ordering::Relaxed)
+    }
+}
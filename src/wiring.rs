@@ -0,0 +1,145 @@
+//! Whether the channels between App and the workers still work. If one breaks (a receiver
+//! dropped too early, say) nothing crashes, things just quietly stop happening, so the first sign
+//! of it is latched here for good and App puts it in a red banner that doesn't go away. After
+//! that, sends that can't possibly arrive aren't made at all, just counted. There's no broadcast
+//! channel, telling everyone is each task's control channel in turn, so those are what's watched
+//! going out, and the one mpsc every worker reports on coming in
+//!
+//! A closed control channel on its own isn't news: a worker that's finished drops its end before
+//! App gets round to harvesting it. It's only broken when the worker's still going
+use std::fmt;
+
+use tokio::sync::mpsc::{
+    self,
+    error::{TryRecvError, TrySendError},
+};
+use tracing::{error, warn};
+
+use crate::{
+    clock::Clock,
+    tasks::{Id, Task, TaskError, TaskRxMsg},
+};
+
+/// Which channel stopped working
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Link {
+    /// Workers to App. Nothing they say arrives any more
+    Inbound,
+    /// App to this task's worker, while it was still running
+    Control(Id),
+}
+
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Link::Inbound => write!(f, "the channel workers report on is closed"),
+            Link::Control(id) => {
+                write!(f, "task {id}'s control channel closed while it was running")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Wiring {
+    /// The first of each kind to break, in the order they did
+    broken: Vec<Link>,
+    /// Sends not made, since they'd have gone nowhere
+    suppressed: usize,
+}
+
+impl Wiring {
+    /// What `try_recv` said when the inbound channel had nothing for us
+    pub fn heard(&mut self, error: TryRecvError) {
+        if error == TryRecvError::Disconnected {
+            self.latch(Link::Inbound);
+        }
+    }
+
+    /// Checked every step through App's own sender, in case the receiver's gone without
+    /// `try_recv` saying so yet
+    pub fn check_inbound<T>(&mut self, tx: &mpsc::Sender<T>) {
+        if tx.is_closed() {
+            self.latch(Link::Inbound);
+        }
+    }
+
+    /// `Task::tell`, skipped for a channel that's closed. True if it went. Only the ones
+    /// actually tried are logged when they fail, the rest are in the count
+    pub fn tell(&mut self, task: &mut Task, msg: TaskRxMsg, clock: &dyn Clock) -> bool {
+        if self.skips(task) {
+            return false;
+        }
+        let sent = task
+            .try_tell(msg, clock)
+            .inspect_err(|e| error!("problem sending {msg:?}: {e}"));
+        self.sent(task, &sent);
+        sent.is_ok()
+    }
+
+    /// `Task::request_stop`, skipped for a channel that's closed
+    pub fn request_stop(&mut self, task: &mut Task, clock: &dyn Clock) -> Result<(), TaskError> {
+        // One that's only scheduled has nobody to tell, so it's never skipped
+        if task.completion.is_running() && self.skips(task) {
+            let msg = TaskRxMsg::PleaseStop;
+            return Err(TaskError::SendFailed(task.id, TrySendError::Closed(msg)));
+        }
+        let stopped = task.request_stop(clock);
+        self.sent(task, &stopped);
+        stopped
+    }
+
+    /// Once something's broken, a send down a closed channel is counted instead of made
+    fn skips(&mut self, task: &Task) -> bool {
+        let skip = self.is_broken() && task.control.is_closed();
+        self.suppressed += skip as usize;
+        skip
+    }
+
+    /// How a send to `task` went. Closed with the worker still going is the wiring's fault
+    fn sent(&mut self, task: &Task, result: &Result<(), TaskError>) {
+        let Err(TaskError::SendFailed(id, TrySendError::Closed(_))) = result else {
+            return;
+        };
+        if task.completion.is_running() && !task.completion.is_finished() {
+            self.latch(Link::Control(*id));
+        }
+    }
+
+    fn latch(&mut self, link: Link) {
+        let kind =
+            |other: &Link| matches!(other, Link::Control(_)) == matches!(link, Link::Control(_));
+        if self.broken.iter().any(kind) {
+            return;
+        }
+        warn!("task control is degraded: {link}");
+        self.broken.push(link);
+    }
+
+    pub fn broken(&self) -> &[Link] {
+        &self.broken
+    }
+
+    pub fn is_broken(&self) -> bool {
+        !self.broken.is_empty()
+    }
+
+    pub fn suppressed(&self) -> usize {
+        self.suppressed
+    }
+
+    /// "Task control is degraded: ..." for the banner, once anything's broken
+    pub fn banner(&self) -> Option<String> {
+        if self.broken.is_empty() {
+            return None;
+        }
+        let links: Vec<String> = self.broken.iter().map(Link::to_string).collect();
+        let mut text = format!("Task control is degraded: {}", links.join(", and "));
+        match self.suppressed {
+            0 => {}
+            1 => text.push_str(" (1 send skipped)"),
+            n => text.push_str(&format!(" ({n} sends skipped)")),
+        }
+        Some(text)
+    }
+}
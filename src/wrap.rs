@@ -0,0 +1,50 @@
+//! Word wrapping (or cutting short) for table cells. Unlike a paragraph, a row has to know how
+//! many lines its text takes before anything's drawn, so the row can be made that tall
+
+/// `text` broken into lines no wider than `width` columns, at spaces where it can be. A word too
+/// long for a whole line is cut wherever the line runs out. Line breaks already in `text` are
+/// kept. Never empty, so even "" takes a (blank) line
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = vec![];
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut used = 0;
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            // Goes on the end of this line, with a space, if it fits
+            if used > 0 && used + 1 + word.len() <= width {
+                line.push(' ');
+                line.extend(&word);
+                used += 1 + word.len();
+                continue;
+            }
+            if used > 0 {
+                lines.push(std::mem::take(&mut line));
+            }
+            while word.len() > width {
+                lines.push(word.drain(..width).collect());
+            }
+            used = word.len();
+            line.extend(word);
+        }
+        lines.push(line);
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+/// `text` cut down to `width` characters, with an ellipsis in the last one if anything was cut.
+/// For one-line cells that can't grow a row
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+    if width > 0 {
+        cut.push('…');
+    }
+    cut
+}
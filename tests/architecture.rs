@@ -0,0 +1,141 @@
+//! The architecture screen: how boxes are laid out for any number of tasks, the diagram when
+//! there's room and the list when there isn't, and getting there from the help
+
+use std::time::Duration;
+
+use clustrctrl::{
+    app::App,
+    architecture::{self, Channel, Plumbing, Worker, BOX_HEIGHT, BOX_WIDTH},
+    config::Config,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, buffer::Buffer, layout::Rect, widgets::Widget, Terminal};
+
+#[test]
+fn boxes_fill_rows_and_fit() {
+    let area = Rect::new(2, 3, 70, 20);
+    // 70 columns is three boxes across with the gaps, centered
+    let boxes = architecture::grid(area, 7).expect("three rows fit in twenty");
+    assert_eq!(boxes.len(), 7);
+    assert_eq!(boxes[0], Rect::new(6, 3, BOX_WIDTH, BOX_HEIGHT));
+    assert_eq!(boxes[2].x, boxes[0].x + 2 * (BOX_WIDTH + 1));
+    assert_eq!(
+        boxes[3],
+        Rect::new(6, 3 + BOX_HEIGHT, BOX_WIDTH, BOX_HEIGHT)
+    );
+    assert_eq!(boxes[6].y, 3 + 2 * BOX_HEIGHT);
+    // Fewer tasks than fit across are centered on their own
+    assert_eq!(
+        architecture::grid(area, 1).expect("plenty of room")[0].x,
+        27
+    );
+    assert_eq!(architecture::grid(area, 0), Some(vec![]));
+
+    // Every count that fits stays inside and never overlaps
+    for width in 0..=120 {
+        for height in 0..=30 {
+            let area = Rect::new(1, 1, width, height);
+            for count in 0..=20 {
+                let Some(boxes) = architecture::grid(area, count) else {
+                    continue;
+                };
+                assert_eq!(boxes.len(), count);
+                for (n, rect) in boxes.iter().enumerate() {
+                    assert_eq!(rect.intersection(area), *rect, "{rect:?} outside {area:?}");
+                    assert!(boxes[..n].iter().all(|other| !other.intersects(*rect)));
+                }
+            }
+        }
+    }
+    // Too many for the height, or too narrow for one
+    assert_eq!(architecture::grid(area, 13), None);
+    assert_eq!(
+        architecture::grid(Rect::new(0, 0, BOX_WIDTH - 1, 40), 1),
+        None
+    );
+}
+
+fn plumbing() -> Plumbing {
+    Plumbing {
+        inbound: Channel {
+            used: 12,
+            capacity: 100,
+        },
+        workers: vec![
+            Worker {
+                id: 0,
+                control: Channel {
+                    used: 0,
+                    capacity: 16,
+                },
+                last: Some(("Progress", Duration::from_secs(3))),
+            },
+            Worker {
+                id: 4,
+                control: Channel {
+                    used: 2,
+                    capacity: 16,
+                },
+                last: None,
+            },
+        ],
+    }
+}
+
+fn text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            (area.left()..area.right())
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn a_diagram_with_room_and_a_list_without() {
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 80, 20));
+    plumbing().render(buffer.area, &mut buffer);
+    let diagram = text(&buffer);
+    assert!(
+        diagram.contains("▲ mpsc 12/100, every worker → App"),
+        "{diagram}"
+    );
+    assert!(diagram.contains("▼ 2 control channels"), "{diagram}");
+    assert!(diagram.contains("┌ task 4 "), "{diagram}");
+    assert!(diagram.contains("3s ago"), "{diagram}");
+    assert!(diagram.contains("control 2/16"), "{diagram}");
+
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 60, 8));
+    plumbing().render(buffer.area, &mut buffer);
+    let list = text(&buffer);
+    assert!(!list.contains("┌ task"), "{list}");
+    assert!(
+        list.contains("task 0: Progress 3s ago, control 0/16"),
+        "{list}"
+    );
+    assert!(list.contains("task 4: nothing yet, control 2/16"), "{list}");
+
+    // And nothing to panic about when there's no room at all
+    for (width, height) in [(0, 0), (1, 1), (3, 40), (200, 2)] {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, width, height));
+        plumbing().render(buffer.area, &mut buffer);
+    }
+}
+
+#[test]
+fn a_from_the_help() {
+    let mut app = App::with_tasks(Config::default(), vec![]);
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('?')));
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('a')));
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let screen = text(terminal.backend().buffer());
+    assert!(screen.contains("Architecture"), "{screen}");
+    assert!(screen.contains("▲ mpsc 0/100"), "{screen}");
+    assert!(screen.contains("▼ 0 control channels"), "{screen}");
+}
@@ -0,0 +1,50 @@
+//! Selection stepping over the archive's summary row, folded or open, and what the summary says
+
+use clustrctrl::archive::{self, Archive};
+
+#[test]
+fn rows_step_over_the_summary() {
+    // Three live tasks, then the summary, then two archived ones showing under it
+    let summary = Some(3);
+    let rows: Vec<usize> = (0..5).map(|at| archive::row(at, summary)).collect();
+    assert_eq!(rows, [0, 1, 2, 4, 5]);
+    for at in 0..5 {
+        assert_eq!(archive::unrow(archive::row(at, summary), summary), at);
+    }
+    // Nothing archived, nothing to step over
+    assert_eq!(archive::row(3, None), 3);
+    assert_eq!(archive::unrow(3, None), 3);
+}
+
+#[test]
+fn the_summary_is_never_selected() {
+    // Folded: the summary is last, and no position lands on it
+    let folded = Some(2);
+    assert!((0..2).all(|at| archive::row(at, folded) != 2));
+    // Open, or with nothing live above it
+    for summary in [Some(2), Some(0)] {
+        assert!((0..6).all(|at| Some(archive::row(at, summary)) != summary));
+    }
+    // Its own row maps to the task after it
+    assert_eq!(archive::unrow(2, Some(2)), 2);
+}
+
+#[test]
+fn summary_counts_and_says_which_way() {
+    let mut archive = Archive::default();
+    archive.set(7, true);
+    archive.set(8, true);
+    assert_eq!(archive.len(), 2);
+    assert_eq!(
+        archive.summary(2, false),
+        "▸ 2 archived tasks — press A to expand"
+    );
+    archive.toggle_open();
+    assert_eq!(
+        archive.summary(1, true),
+        "v 1 archived task — press A to collapse"
+    );
+    archive.set(7, false);
+    assert!(!archive.contains(7));
+    assert!(archive.contains(8));
+}
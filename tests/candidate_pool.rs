@@ -0,0 +1,83 @@
+//! The rules for editing the candidate pool, and the tasks file it's saved to
+
+use std::{env, fs, process};
+
+use clustrctrl::{
+    candidates::{self, PoolError, TaskFile},
+    CandidateTask,
+};
+
+fn pool(names: &[&str]) -> Vec<CandidateTask> {
+    names
+        .iter()
+        .map(|&name| CandidateTask::new(name, "Does a thing"))
+        .collect()
+}
+
+#[test]
+fn last_enabled_one_stays() {
+    let mut pool = pool(&["Only", "Other"]);
+    assert_eq!(candidates::toggle(&mut pool, 1), Ok(false));
+    assert_eq!(
+        candidates::toggle(&mut pool, 0),
+        Err(PoolError::LastEnabled)
+    );
+    assert_eq!(
+        candidates::remove(&mut pool, 0),
+        Err(PoolError::LastEnabled)
+    );
+    // Disabled ones can go, they weren't doing anything
+    assert_eq!(candidates::remove(&mut pool, 1).unwrap().name, "Other");
+    assert_eq!(pool.len(), 1);
+}
+
+#[test]
+fn names_are_unique_and_present() {
+    let mut pool = pool(&["Taken"]);
+    assert_eq!(
+        candidates::add(&mut pool, CandidateTask::new("Taken", "Again")),
+        Err(PoolError::Duplicate("Taken".to_string()))
+    );
+    assert_eq!(
+        candidates::add(&mut pool, CandidateTask::new("  ", "Nameless")),
+        Err(PoolError::NoName)
+    );
+    assert_eq!(
+        candidates::add(&mut pool, CandidateTask::new("New", "Fresh")),
+        Ok(())
+    );
+    assert_eq!(pool.len(), 2);
+}
+
+#[test]
+fn saved_pools_come_back_the_same() {
+    let mut saved = pool(&["Kept", "Benched"]);
+    saved[1].enabled = false;
+    saved.push(CandidateTask {
+        command: Some("echo hi".to_string()),
+        ..CandidateTask::new("Real", "Runs something")
+    });
+    let path = env::temp_dir().join(format!("clustrctrl-pool-{}.toml", process::id()));
+    TaskFile::save(&path, &saved).unwrap();
+    let loaded = TaskFile::load(&path);
+    fs::remove_file(&path).unwrap();
+    let loaded = loaded.unwrap();
+    // What's saved is the whole pool, so it doesn't get the built-ins stacked on again
+    assert!(!loaded.include_builtins);
+    assert_eq!(loaded.task, saved);
+}
+
+#[test]
+fn hand_written_files_keep_old_meaning() {
+    let file: TaskFile = toml::from_str(
+        r#"
+        [[task]]
+        name = "Extra"
+        description = "From before the editor"
+        "#,
+    )
+    .unwrap();
+    assert!(file.include_builtins);
+    assert!(file.task[0].enabled);
+    assert_eq!(file.task[0].command, None);
+}
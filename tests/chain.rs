@@ -0,0 +1,159 @@
+//! Chains found from any member, whichever way the links point, and links to tasks that are gone.
+//! And the batch file's `after` setting them up, with the waiting task starting once what it waits
+//! on has finished, or called off when that didn't
+
+use std::{env, fs, process};
+
+use clustrctrl::{
+    app::App,
+    chain::{self, Chain},
+    config::Config,
+    tasks::{Id, Task, TaskStatus, MIN_INTENSITY},
+    CandidateTask,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+
+/// What a task registry comes down to: each ID and what it waits on
+fn find(links: &[(Id, &[Id])], from: Id) -> Chain {
+    Chain::find(links.iter().copied(), from)
+}
+
+#[test]
+fn straight_lines_come_out_in_order() {
+    let links: &[(Id, &[Id])] = &[(9, &[]), (12, &[9]), (15, &[12]), (20, &[])];
+    // Same chain from the start, the middle or the end
+    for from in [9, 12, 15] {
+        let chain = find(links, from);
+        assert_eq!(chain.members, [9, 12, 15]);
+        assert!(chain.dangling.is_empty());
+        assert_eq!(chain.describe(), "#9 ⇢ #12 ⇢ #15");
+    }
+    // Nothing to do with it
+    let alone = find(links, 20);
+    assert_eq!(alone.members, [20]);
+    assert!(alone.is_lone());
+}
+
+#[test]
+fn fan_in_pulls_in_every_branch() {
+    // 3 waits on both 1 and 2, and 4 waits on 3
+    let links: &[(Id, &[Id])] = &[(1, &[]), (2, &[]), (3, &[1, 2]), (4, &[3]), (5, &[])];
+    // Starting from one branch still finds the other, by way of what they feed
+    assert_eq!(find(links, 1).members, [1, 2, 3, 4]);
+    assert_eq!(find(links, 4).members, [1, 2, 3, 4]);
+    assert_eq!(chain::waits_on(&[1, 2]), "⇠#1,#2");
+    assert_eq!(chain::waits_on(&[]), "");
+}
+
+#[test]
+fn removed_tasks_leave_a_gap() {
+    // 6 was removed, and 7 waited on it. 8 waits on 7
+    let links: &[(Id, &[Id])] = &[(7, &[6]), (8, &[7])];
+    let chain = find(links, 8);
+    assert_eq!(chain.members, [7, 8]);
+    assert_eq!(chain.dangling, [6]);
+    assert!(!chain.is_lone());
+    assert_eq!(chain.describe(), "#7 ⇢ #8 (#6 gone)");
+    // A task whose only link is gone still has that to say for itself
+    let links: &[(Id, &[Id])] = &[(2, &[1])];
+    assert!(!find(links, 2).is_lone());
+}
+
+#[test]
+fn circles_dont_go_round_forever() {
+    let links: &[(Id, &[Id])] = &[(1, &[3]), (2, &[1]), (3, &[2]), (4, &[4])];
+    assert_eq!(find(links, 2).members, [1, 2, 3]);
+    assert_eq!(find(links, 4).members, [4]);
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key_event(KeyEvent::from(code));
+}
+
+fn screen(app: &mut App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    buffer.content().iter().map(|cell| cell.symbol()).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_batch_sets_up_the_chain() {
+    let path = env::temp_dir().join(format!("clustrctrl-chain-{}.toml", process::id()));
+    fs::write(
+        &path,
+        r#"
+[[task]]
+name = "Build"
+description = "Makes the thing"
+count = 2
+
+[[task]]
+name = "Ship"
+description = "Sends it out"
+after = "Build"
+"#,
+    )
+    .expect("temp is writable");
+    let mut config = Config::default();
+    // So the workers stop as soon as they're told
+    config.work.intensity = MIN_INTENSITY;
+    config.batch_file = Some(path.clone());
+    let mut app = App::with_tasks(config, vec![]);
+    press(&mut app, KeyCode::F(7));
+    assert!(screen(&mut app).contains("1× Ship, after Build"));
+    press(&mut app, KeyCode::Enter);
+    fs::remove_file(path).expect("it's ours");
+
+    let ship = &app.tasks()[2];
+    assert_eq!(ship.depends_on, [0, 1]);
+    assert_eq!(ship.status, TaskStatus::Scheduled);
+    let shown = screen(&mut app);
+    assert!(shown.contains("2 ⇠#0,#1"), "{shown}");
+    // Still going, so still waiting
+    app.step().await;
+    assert_eq!(app.tasks()[2].status, TaskStatus::Scheduled);
+    assert_eq!(app.tasks()[2].start, None);
+
+    // Nobody starts anything on the way out
+    press(&mut app, KeyCode::F(3));
+    assert_eq!(app.tasks()[2].status, TaskStatus::Canceled);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn waiting_ends_with_what_it_waited_on() {
+    let ct = |name: &str| CandidateTask::new(name, "Part of the pipeline");
+    let over = |id, name, status| {
+        let mut task = Task::pending(&ct(name), id, 1);
+        task.status = status;
+        task
+    };
+    let waiting = |id, name, depends_on: Vec<Id>| {
+        let mut task = Task::pending(&ct(name), id, 1);
+        task.depends_on = depends_on;
+        task
+    };
+    let mut config = Config::default();
+    config.work.intensity = MIN_INTENSITY;
+    let tasks = vec![
+        over(0, "Build", TaskStatus::Finished),
+        over(1, "Lint", TaskStatus::Failed),
+        waiting(2, "Ship", vec![0]),
+        waiting(3, "Test", vec![0, 1]),
+        waiting(4, "Tag", vec![3]),
+    ];
+    let mut app = App::with_tasks(config, tasks);
+    assert!(app.step().await);
+    let tasks = app.tasks();
+    assert!(tasks[2].start.is_some(), "everything it waits on finished");
+    assert_ne!(tasks[2].status, TaskStatus::Scheduled);
+    assert_eq!(tasks[3].status, TaskStatus::Canceled);
+    assert_eq!(tasks[3].cancel_reason.as_deref(), Some("#1 didn't finish"));
+    // And what comes after that in turn
+    assert_eq!(tasks[4].status, TaskStatus::Canceled);
+    assert_eq!(tasks[4].cancel_reason.as_deref(), Some("#3 didn't finish"));
+    press(&mut app, KeyCode::F(3));
+}
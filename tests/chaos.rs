@@ -0,0 +1,136 @@
+//! Each injected fault on its own, turned all the way up so it happens every time
+
+use std::time::Duration;
+
+use clustrctrl::{
+    chaos::ChaosConfig,
+    clock::SystemClock,
+    completion::Outcome,
+    latency::Stamped,
+    tasks::{Task, TaskRxMsg, TaskStatus, TaskTxMsg, WorkerStatus, MIN_INTENSITY},
+    CandidateTask,
+};
+use tokio::{sync::mpsc, time::timeout};
+
+const PATIENCE: Duration = Duration::from_secs(15);
+
+fn spawn(chaos: ChaosConfig) -> (Task, mpsc::Receiver<Stamped<TaskTxMsg>>) {
+    let (tx, rx) = mpsc::channel(32);
+    let ct = CandidateTask::new("Gremlin", "Does its best, mostly");
+    let task =
+        Task::spawn(&ct, tx, 0, MIN_INTENSITY, 1, chaos, &SystemClock).expect("there's a runtime");
+    (task, rx)
+}
+
+async fn next(rx: &mut mpsc::Receiver<Stamped<TaskTxMsg>>) -> TaskTxMsg {
+    timeout(PATIENCE, rx.recv())
+        .await
+        .expect("worker went quiet")
+        .expect("worker hung up")
+        .msg
+}
+
+/// Reads up to the first sleep or strike (or the last block, which doesn't sleep first), when the
+/// worker's definitely listening
+async fn settle(rx: &mut mpsc::Receiver<Stamped<TaskTxMsg>>) -> Vec<TaskTxMsg> {
+    let mut msgs = vec![];
+    loop {
+        let msg = next(rx).await;
+        let settled = matches!(
+            msg,
+            TaskTxMsg::StatusChange {
+                status: WorkerStatus::Sleeping
+                    | WorkerStatus::OnStrike { .. }
+                    | WorkerStatus::Finalizing,
+                ..
+            }
+        );
+        msgs.push(msg);
+        if settled {
+            return msgs;
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reports_go_missing() {
+    let (mut task, mut rx) = spawn(ChaosConfig {
+        drop_report: 1.0,
+        ..ChaosConfig::default()
+    });
+    let msgs = settle(&mut rx).await;
+    assert!(
+        !msgs.iter().any(|m| matches!(m, TaskTxMsg::Progress { .. })),
+        "a report got through: {msgs:?}"
+    );
+    task.tell(TaskRxMsg::EveryoneStopPls, &SystemClock);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn panics_show_as_failed() {
+    let (mut task, mut rx) = spawn(ChaosConfig {
+        panic: 1.0,
+        ..ChaosConfig::default()
+    });
+    // Its status and the one report after the first work block, then nothing. Unless that's
+    // the last block too, and then it's finalizing at 99%
+    match next(&mut rx).await {
+        TaskTxMsg::StatusChange {
+            status: WorkerStatus::Running,
+            ..
+        } => assert!(matches!(
+            next(&mut rx).await,
+            TaskTxMsg::Progress { pct: 0, .. }
+        )),
+        TaskTxMsg::StatusChange {
+            status: WorkerStatus::Finalizing,
+            ..
+        } => assert!(matches!(
+            next(&mut rx).await,
+            TaskTxMsg::Progress { pct: 99, .. }
+        )),
+        msg => panic!("didn't expect {msg:?}"),
+    }
+    assert!(timeout(PATIENCE, rx.recv()).await.unwrap().is_none());
+    let outcome = task.join(PATIENCE).await.expect("worker never finished");
+    assert!(
+        matches!(&outcome, Outcome::Panicked(_)),
+        "should have panicked: {outcome:?}"
+    );
+    assert_eq!(task.completion.outcome(), Some(&outcome));
+    assert_eq!(task.status, TaskStatus::Failed);
+    assert_eq!(task.progress, 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn first_stop_is_ignored_second_isnt() {
+    let (mut task, mut rx) = spawn(ChaosConfig {
+        ignore_stop: 1.0,
+        ..ChaosConfig::default()
+    });
+    settle(&mut rx).await;
+    assert!(task.tell(TaskRxMsg::PleaseStop, &SystemClock));
+    // A worker that heard it answers straight away, so a short wait is plenty
+    while let Ok(Some(msg)) = timeout(Duration::from_millis(300), rx.recv()).await {
+        assert!(
+            !matches!(msg.msg, TaskTxMsg::CancelReport(_)),
+            "first stop got through"
+        );
+    }
+    assert!(task.tell(TaskRxMsg::PleaseStop, &SystemClock));
+    loop {
+        if let TaskTxMsg::CancelReport(0) = next(&mut rx).await {
+            break;
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn control_channel_shrinks() {
+    let (mut task, _rx) = spawn(ChaosConfig {
+        control_capacity: Some(1),
+        ..ChaosConfig::default()
+    });
+    assert_eq!(task.control.max_capacity(), 1);
+    task.tell(TaskRxMsg::EveryoneStopPls, &SystemClock);
+}
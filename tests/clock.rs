@@ -0,0 +1,144 @@
+//! Time-based behaviour driven off a mock clock: the thread watchdog, toasts running out,
+//! start/end stamps, and when a status changed or a note was made, none of them waiting on real
+//! time
+
+use std::time::{Duration, Instant};
+
+use clustrctrl::{
+    banner::Banner,
+    chaos::ChaosConfig,
+    clock::{Clock, MockClock},
+    completion::Outcome,
+    tasks::{Orders, Task, TaskRxMsg, TaskStatus, MIN_INTENSITY},
+    CandidateTask,
+};
+use tokio::{sync::mpsc, time::timeout};
+
+const PATIENCE: Duration = Duration::from_secs(15);
+
+fn dummy() -> CandidateTask {
+    CandidateTask::new("Test dummy", "Pretends to work until told otherwise")
+}
+
+#[test]
+fn mock_clocks_only_move_when_told() {
+    let clock = MockClock::new();
+    let (local, instant) = (clock.now_local(), clock.now_instant());
+    assert_eq!(clock.now_instant(), instant);
+    // A clone is the same clock, not a copy of it
+    let shared = clock.clone();
+    shared.advance(Duration::from_secs(90));
+    assert_eq!(clock.now_instant() - instant, Duration::from_secs(90));
+    assert_eq!((clock.now_local() - local).num_seconds(), 90);
+}
+
+#[test]
+fn toasts_run_out_on_time() {
+    let clock = MockClock::new();
+    let toast = Banner::new("Added 3 tasks".to_string(), Duration::from_secs(3), &clock);
+    assert!(!toast.is_expired(&clock));
+    clock.advance(Duration::from_millis(2999));
+    assert!(!toast.is_expired(&clock));
+    clock.advance(Duration::from_millis(1));
+    assert!(toast.is_expired(&clock));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn watchdog_flips_once_patience_runs_out() {
+    let clock = MockClock::new();
+    let patience = Duration::from_secs(5);
+    let (tx, mut rx) = mpsc::channel(32);
+    let mut task = Task::pending(&dummy(), 0, 1);
+    task.activate(
+        tx,
+        MIN_INTENSITY,
+        ChaosConfig::default(),
+        Orders::default(),
+        &clock,
+    )
+    .expect("there's a runtime right here");
+    // Nothing's read off the channel, so as far as the task knows it's never heard a word
+    assert!(!task.check_thread_wait(patience, &clock));
+    clock.advance(patience - Duration::from_millis(1));
+    assert!(!task.check_thread_wait(patience, &clock));
+    clock.advance(Duration::from_millis(1));
+    assert!(task.check_thread_wait(patience, &clock));
+    assert_eq!(task.status, TaskStatus::WaitingForThread);
+    // Only flips the once
+    assert!(!task.check_thread_wait(patience, &clock));
+
+    task.tell(TaskRxMsg::EveryoneStopPls, &clock);
+    while timeout(PATIENCE, rx.recv())
+        .await
+        .expect("worker never finished")
+        .is_some()
+    {}
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stamps_come_from_the_clock() {
+    let clock = MockClock::new();
+    let (tx, mut rx) = mpsc::channel(32);
+    let mut task = Task::pending(&dummy(), 0, 1);
+    task.activate(
+        tx,
+        MIN_INTENSITY,
+        ChaosConfig::default(),
+        Orders::default(),
+        &clock,
+    )
+    .expect("there's a runtime right here");
+    assert_eq!(task.start, Some(clock.now_local()));
+    task.tell(TaskRxMsg::EveryoneStopPls, &clock);
+    while timeout(PATIENCE, rx.recv())
+        .await
+        .expect("worker never finished")
+        .is_some()
+    {}
+    clock.advance(Duration::from_secs(42));
+    // The channel's closed, so the handle's about to finish if it hasn't. Real time, this wait
+    let waited = Instant::now();
+    let outcome = loop {
+        if let Some(outcome) = task.check_done(&clock).await {
+            break outcome;
+        }
+        assert!(waited.elapsed() < PATIENCE, "handle never finished");
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    };
+    assert!(matches!(outcome, Outcome::Returned(_)), "{outcome:?}");
+    assert!(
+        task.check_done(&clock).await.is_none(),
+        "only harvested the once"
+    );
+    let took = task.end.zip(task.start).map(|(end, start)| end - start);
+    assert_eq!(took.map(|took| took.num_seconds()), Some(42));
+}
+
+#[test]
+fn status_changes_and_notes_go_by_the_clock() {
+    let clock = MockClock::new();
+    let mut task = Task::pending(&dummy(), 0, 1);
+    task.set_status(TaskStatus::Running, &clock);
+    assert_eq!(task.status_changed_at, Some(clock.now_instant()));
+    // Back and forth between blocks isn't a change
+    clock.advance(Duration::from_secs(10));
+    task.set_status(TaskStatus::Sleeping, &clock);
+    assert_eq!(
+        task.status_changed_at,
+        Some(clock.now_instant() - Duration::from_secs(10))
+    );
+    assert!(task.report(TaskStatus::Canceled, &clock));
+    assert_eq!(task.status_changed_at, Some(clock.now_instant()));
+
+    clock.advance(Duration::from_secs(5));
+    task.record("← a note".to_string(), &clock);
+    task.milestone("warmed up", &clock);
+    assert_eq!(
+        task.audit.back().map(|(at, _)| *at),
+        Some(clock.now_local())
+    );
+    assert_eq!(
+        task.milestones.back().map(|(at, _)| *at),
+        Some(clock.now_local())
+    );
+}
@@ -0,0 +1,112 @@
+//! Column order and visibility, through the config file and back
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use clap::Parser;
+use clustrctrl::{
+    columns::{Column, ColumnSetting, Columns},
+    config::{Cli, Config},
+};
+
+/// Where a test's config file goes, fresh
+fn scratch(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("clustrctrl-columns-{name}-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("temp is writable");
+    dir.join("clustrctrl.toml")
+}
+
+/// The config at `path`, the way the app loads it
+fn load(path: &Path) -> Config {
+    let cli = Cli::parse_from(["clustrctrl", "--config", path.to_str().expect("utf-8")]);
+    let (config, problems) = Config::load(&cli);
+    assert!(problems.is_empty(), "{problems:?}");
+    config
+}
+
+/// `columns` saved as `ui.columns` in a real config file, and what loading it gives back
+fn round_trip(columns: Columns) -> Columns {
+    let path = scratch("round-trip");
+    let mut config = Config::default();
+    config.ui.columns = columns;
+    config.path = Some(path.clone());
+    config.save();
+    let loaded = load(&path).ui.columns;
+    fs::remove_dir_all(path.parent().expect("it's in one")).expect("it's ours");
+    loaded
+}
+
+#[test]
+fn order_and_visibility_survive_saving() {
+    let mut columns = Columns::default();
+    // Description to the front, by way of a lot of K presses
+    let mut idx = Column::ALL.len() - 1;
+    while idx > 0 {
+        idx = columns.move_up(idx);
+    }
+    assert_eq!(columns.toggle(2), Some(false)); // Name, now it's third
+    let saved = columns.clone();
+    let loaded = round_trip(columns);
+    assert_eq!(loaded, saved);
+    assert_eq!(loaded.visible().next(), Some(Column::Description));
+    assert!(!loaded.visible().any(|c| c == Column::Name));
+}
+
+#[test]
+fn id_stays_put_in_view() {
+    let mut columns = Columns::default();
+    assert_eq!(columns.toggle(0), None);
+    // Moving is fine though, and off either end does nothing
+    assert_eq!(columns.move_up(0), 0);
+    assert_eq!(columns.move_down(0), 1);
+    let last = Column::ALL.len() - 1;
+    assert_eq!(columns.move_down(last), last);
+    assert_eq!(columns.visible().nth(1), Some(Column::Id));
+}
+
+#[test]
+fn hand_edited_lists_get_tidied() {
+    let path = scratch("hand-edited");
+    fs::write(
+        &path,
+        r#"
+        [[ui.columns]]
+        column = "eta"
+
+        [[ui.columns]]
+        column = "id"
+        visible = false
+
+        [[ui.columns]]
+        column = "eta"
+        visible = false
+        "#,
+    )
+    .expect("temp is writable");
+    let loaded = load(&path);
+    fs::remove_dir_all(path.parent().expect("it's in one")).expect("it's ours");
+    let settings = loaded.ui.columns.settings();
+    // The repeat's gone, the ID shows anyway, and the rest turn up after in the usual order
+    assert_eq!(settings.len(), Column::ALL.len());
+    assert_eq!(
+        settings[..3],
+        [
+            ColumnSetting {
+                column: Column::Eta,
+                visible: true
+            },
+            ColumnSetting {
+                column: Column::Id,
+                visible: true
+            },
+            ColumnSetting {
+                column: Column::Name,
+                visible: true
+            },
+        ]
+    );
+}
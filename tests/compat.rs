@@ -0,0 +1,138 @@
+//! Windows' quirks without Windows: which terminals count as legacy, the `Ctrl` stand-ins for
+//! F1 to F3, held keys, and a legacy screen drawn with thin borders and nothing blinking
+
+use clustrctrl::{
+    app::App,
+    compat::{self, Compat},
+    config::Config,
+    controls::{self, Toggles},
+    tasks::{Task, TaskStatus},
+    theme::{self, Theme},
+    view::ViewState,
+    CandidateTask,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
+use ratatui::{backend::TestBackend, style::Modifier, symbols::border, Terminal};
+
+fn ctrl(ch: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(ch), KeyModifiers::CONTROL)
+}
+
+fn held(code: KeyCode, kind: KeyEventKind) -> KeyEvent {
+    KeyEvent::new_with_kind_and_state(code, KeyModifiers::NONE, kind, KeyEventState::NONE)
+}
+
+#[test]
+fn only_the_old_console_is_legacy() {
+    let conhost = Compat::for_platform(true, false, None);
+    assert!(conhost.legacy && conhost.show_alt_keys);
+    let windows_terminal = Compat::for_platform(true, true, None);
+    assert!(!windows_terminal.legacy && windows_terminal.show_alt_keys);
+    assert_eq!(Compat::for_platform(false, false, None), Compat::default());
+    // The config has the last word either way
+    assert!(Compat::for_platform(false, false, Some(true)).legacy);
+    assert!(!Compat::for_platform(true, false, Some(false)).legacy);
+}
+
+#[test]
+fn ctrl_keys_stand_in_for_f1_to_f3() {
+    assert_eq!(compat::alternate(ctrl('n')).code, KeyCode::F(1));
+    assert_eq!(compat::alternate(ctrl('i')).code, KeyCode::F(2));
+    let quit = compat::alternate(ctrl('q'));
+    assert_eq!(quit.code, KeyCode::F(3));
+    assert_eq!(quit.modifiers, KeyModifiers::NONE);
+    // Everything else goes through as it was
+    assert_eq!(compat::alternate(ctrl('k')), ctrl('k'));
+    let n = KeyEvent::from(KeyCode::Char('n'));
+    assert_eq!(compat::alternate(n), n);
+}
+
+#[test]
+fn only_moving_keys_repeat() {
+    assert!(compat::accepts(&held(KeyCode::Enter, KeyEventKind::Press)));
+    assert!(!compat::accepts(&held(
+        KeyCode::Enter,
+        KeyEventKind::Repeat
+    )));
+    assert!(!compat::accepts(&held(
+        KeyCode::Enter,
+        KeyEventKind::Release
+    )));
+    assert!(compat::accepts(&held(KeyCode::Down, KeyEventKind::Repeat)));
+    assert!(compat::accepts(&held(
+        KeyCode::Char('j'),
+        KeyEventKind::Repeat
+    )));
+    assert!(!compat::accepts(&held(
+        KeyCode::Down,
+        KeyEventKind::Release
+    )));
+    assert!(!compat::accepts(&held(
+        KeyCode::Delete,
+        KeyEventKind::Repeat
+    )));
+}
+
+#[test]
+fn the_controls_say_which_ctrl_keys_work() {
+    let toggles = Toggles {
+        alt_keys: true,
+        ..Toggles::default()
+    };
+    let keys: Vec<&str> = controls::controls(ViewState::Monitor, None, toggles)
+        .iter()
+        .map(|c| c.key)
+        .collect();
+    assert!(keys.contains(&"F1/^N") && keys.contains(&"F2/^I") && keys.contains(&"F3/^Q"));
+    assert!(keys.contains(&"F7"), "only the three with stand-ins change");
+    let plain = controls::controls(ViewState::Monitor, None, Toggles::default());
+    assert_eq!(plain[0].key, "F1");
+}
+
+#[test]
+fn legacy_themes_dont_blink_or_go_thick() {
+    let legacy = Theme::COLOR.with_legacy(true);
+    assert_eq!(legacy.border(border::THICK), border::PLAIN);
+    assert_eq!(Theme::COLOR.border(border::THICK), border::THICK);
+    let strike = legacy.status_text(&TaskStatus::OnStrike);
+    assert!(strike.content.starts_with('!'), "{strike:?}");
+    assert!(!strike.style.add_modifier.contains(Modifier::SLOW_BLINK));
+    let blinking = Theme::COLOR.status_text(&TaskStatus::OnStrike);
+    assert!(blinking.style.add_modifier.contains(Modifier::SLOW_BLINK));
+}
+
+/// The only test here that sets the theme for everyone, so the others don't care when it runs
+#[test]
+fn a_legacy_screen_is_drawn_thin() {
+    theme::init(Theme::COLOR.with_legacy(true));
+    let mut striking = Task::pending(&CandidateTask::new("Alpha", "Does a thing"), 0, 1);
+    striking.status = TaskStatus::OnStrike;
+    let mut app = App::with_tasks(Config::default(), vec![striking]).with_compat(Compat {
+        show_alt_keys: true,
+        legacy: true,
+    });
+    let mut terminal = Terminal::new(TestBackend::new(160, 30)).expect("nothing to fail");
+    let mut draw = |app: &mut App| {
+        terminal
+            .draw(|frame| app.view(frame))
+            .expect("nothing to fail");
+        let buffer = terminal.backend().buffer().clone();
+        (0..30)
+            .map(|y| {
+                (0..160)
+                    .map(|x| buffer[(x, y)].symbol().to_string())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let screen = draw(&mut app);
+    assert!(screen.starts_with('┌'), "{screen}");
+    assert!(!screen.contains('┏') && !screen.contains('━'), "{screen}");
+    assert!(screen.contains("!Strike!"), "{screen}");
+    assert!(screen.contains("<F1/^N>"), "{screen}");
+
+    // Ctrl+N does what F1 does
+    app.handle_key_event(ctrl('n'));
+    assert!(draw(&mut app).contains("New Task"));
+}
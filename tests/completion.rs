@@ -0,0 +1,108 @@
+//! A worker handle from nothing to harvested, on handles made up here: waiting on one that's
+//! still going, harvesting twice, a panic's message kept, an abort, and starting a second worker
+
+use std::time::Duration;
+
+use clustrctrl::completion::{Completion, HarvestError, Outcome};
+use tokio::{sync::oneshot, task::JoinHandle};
+
+/// Way more than any of these should take. Hitting it means something's stuck
+const PATIENCE: Duration = Duration::from_secs(5);
+
+async fn until_finished(completion: &Completion) {
+    tokio::time::timeout(PATIENCE, async {
+        while !completion.is_finished() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("the worker never finished");
+}
+
+/// A worker that returns `sum` when told to
+fn worker(sum: i128) -> (JoinHandle<Option<i128>>, oneshot::Sender<()>) {
+    let (go, wait) = oneshot::channel();
+    let handle = tokio::spawn(async move {
+        let _ = wait.await;
+        Some(sum)
+    });
+    (handle, go)
+}
+
+#[tokio::test]
+async fn pending_to_harvested() {
+    let mut completion = Completion::default();
+    assert!(!completion.is_running());
+    assert_eq!(completion.harvest().await, Err(HarvestError::NotStarted));
+
+    let (handle, go) = worker(12);
+    completion.start(handle);
+    assert!(completion.is_running());
+    assert!(!completion.is_finished());
+    // Never waits on a worker that's still at it
+    assert_eq!(completion.harvest().await, Err(HarvestError::StillRunning));
+    assert!(completion.is_running(), "still has its handle");
+
+    go.send(()).unwrap();
+    until_finished(&completion).await;
+    assert_eq!(completion.harvest().await, Ok(&Outcome::Returned(Some(12))));
+    assert!(!completion.is_running() && !completion.is_finished());
+    assert_eq!(completion.outcome(), Some(&Outcome::Returned(Some(12))));
+}
+
+#[tokio::test]
+async fn harvesting_twice_gets_nothing_the_second_time() {
+    let mut completion = Completion::default();
+    let (handle, go) = worker(3);
+    completion.start(handle);
+    go.send(()).unwrap();
+    until_finished(&completion).await;
+    assert!(completion.harvest().await.is_ok());
+    assert_eq!(
+        completion.harvest().await,
+        Err(HarvestError::AlreadyHarvested)
+    );
+    // What it came back with is still there
+    assert_eq!(completion.outcome(), Some(&Outcome::Returned(Some(3))));
+}
+
+#[tokio::test]
+async fn a_panicked_worker_keeps_its_message() {
+    let mut completion = Completion::default();
+    completion.start(tokio::spawn(async { panic!("sum went sideways {}", 7) }));
+    until_finished(&completion).await;
+    assert_eq!(
+        completion.harvest().await,
+        Ok(&Outcome::Panicked("sum went sideways 7".to_string()))
+    );
+
+    let mut completion = Completion::default();
+    completion.start(tokio::spawn(async { std::panic::panic_any(42) }));
+    until_finished(&completion).await;
+    assert_eq!(
+        completion.harvest().await,
+        Ok(&Outcome::Panicked("something that wasn't text".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn an_aborted_worker_never_returned() {
+    let mut completion = Completion::default();
+    assert!(!completion.abort(), "nothing to abort yet");
+    let (handle, _go) = worker(1);
+    completion.start(handle);
+    assert!(completion.abort());
+    until_finished(&completion).await;
+    assert_eq!(completion.harvest().await, Ok(&Outcome::Aborted));
+    assert!(!completion.abort(), "nothing left to abort");
+}
+
+#[tokio::test]
+#[should_panic(expected = "started twice")]
+async fn a_second_worker_is_a_bug() {
+    let mut completion = Completion::default();
+    let (first, _go) = worker(1);
+    let (second, _go_too) = worker(2);
+    completion.start(first);
+    completion.start(second);
+}
@@ -0,0 +1,65 @@
+//! The config file and runtime changes: one that won't parse is left alone whatever gets toggled,
+//! and one that does gets the change written back whole, with no temp file left behind
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use clap::Parser;
+use clustrctrl::{
+    app::App,
+    config::{Cli, Config},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("clustrctrl-config-{name}-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("temp is writable");
+    dir
+}
+
+fn load(path: &Path) -> (Config, Vec<String>) {
+    let cli = Cli::parse_from(["clustrctrl", "--config", path.to_str().expect("utf-8")]);
+    Config::load(&cli)
+}
+
+#[test]
+fn invalid_files_are_left_alone() {
+    let dir = scratch("invalid");
+    let path = dir.join("clustrctrl.toml");
+    let text = "# mine, hands off\n[log]\npath = '/var/x'\n[work\nintensity = 0.5\n";
+    fs::write(&path, text).expect("temp is writable");
+    let (config, problems) = load(&path);
+    assert!(
+        problems.iter().any(|p| p.contains("invalid")),
+        "{problems:?}"
+    );
+    assert_eq!(config.path, None);
+
+    let mut app = App::with_tasks(config, vec![]);
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('>')));
+    assert_eq!(fs::read_to_string(&path).expect("still there"), text);
+    assert!(!dir.join("clustrctrl.toml.tmp").exists());
+    fs::remove_dir_all(dir).expect("it's ours");
+}
+
+#[test]
+fn valid_files_get_the_change() {
+    let dir = scratch("valid");
+    let path = dir.join("clustrctrl.toml");
+    fs::write(&path, "[work]\nintensity = 0.5\n").expect("temp is writable");
+    let (config, problems) = load(&path);
+    assert!(problems.is_empty(), "{problems:?}");
+    assert_eq!(config.path.as_ref(), Some(&path));
+
+    let mut app = App::with_tasks(config, vec![]);
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('>')));
+    let (saved, problems) = load(&path);
+    assert!(problems.is_empty(), "{problems:?}");
+    assert!(saved.work.intensity > 0.5, "{}", saved.work.intensity);
+    assert!(!dir.join("clustrctrl.toml.tmp").exists());
+    fs::remove_dir_all(dir).expect("it's ours");
+}
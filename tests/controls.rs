@@ -0,0 +1,181 @@
+//! The controls line for a few screens and task states, and how it copes with narrow terminals,
+//! compact or not. And the note editor being on `Shift+N`, as the line says
+
+use clustrctrl::{
+    app::App,
+    config::Config,
+    controls::{self, Control, Toggles},
+    tasks::{Task, TaskStatus},
+    view::ViewState,
+    CandidateTask,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+
+fn labels(controls: &[Control]) -> Vec<&'static str> {
+    controls.iter().map(|c| c.label).collect()
+}
+
+fn inspecting(status: TaskStatus, toggles: Toggles) -> Vec<&'static str> {
+    labels(&controls::controls(
+        ViewState::Inspect,
+        Some(status),
+        toggles,
+    ))
+}
+
+#[test]
+fn strikes_offer_negotiating() {
+    let striking = inspecting(TaskStatus::OnStrike, Toggles::default());
+    assert!(striking.contains(&"Negotiate"));
+    assert!(striking.contains(&"Terminate Task"));
+    assert!(!inspecting(TaskStatus::Running, Toggles::default()).contains(&"Negotiate"));
+}
+
+#[test]
+fn finished_tasks_cant_be_terminated() {
+    for status in [
+        TaskStatus::Finished,
+        TaskStatus::Failed,
+        TaskStatus::Canceled,
+        TaskStatus::FailedToStart,
+    ] {
+        let offered = inspecting(status, Toggles::default());
+        assert!(!offered.contains(&"Terminate Task"), "{status:?}");
+        assert!(offered.contains(&"Details"));
+        assert!(offered.contains(&"Archive"), "{status:?}");
+        assert!(offered.contains(&"Remove"), "{status:?}");
+    }
+    assert!(!inspecting(TaskStatus::Running, Toggles::default()).contains(&"Archive"));
+    let archived = Toggles {
+        archived: true,
+        ..Toggles::default()
+    };
+    let offered = inspecting(TaskStatus::Finished, archived);
+    assert_eq!(
+        offered,
+        [
+            "Back",
+            "Details",
+            "Unarchive",
+            "Remove",
+            "Sort",
+            "Filter",
+            "Quit"
+        ]
+    );
+    // Nothing highlighted is the same deal
+    let nothing = controls::controls(ViewState::Inspect, None, Toggles::default());
+    assert!(!labels(&nothing).contains(&"Terminate Task"));
+}
+
+#[test]
+fn toggles_change_the_line() {
+    let toggles = Toggles {
+        enter_opens_menu: true,
+        filtering: true,
+        archived: false,
+        header: None,
+        repeat: false,
+        templates: false,
+        alt_keys: false,
+    };
+    let offered = inspecting(TaskStatus::Finished, toggles);
+    assert_eq!(offered[0], "Clear Filter");
+    // The menu knows what's possible, so it's always worth offering
+    assert_eq!(offered[1], "Actions");
+    // Only folding is worth offering on a header, whatever Enter usually is
+    let header = Toggles {
+        header: Some(true),
+        ..toggles
+    };
+    let offered = labels(&controls::controls(ViewState::Inspect, None, header));
+    assert_eq!(
+        offered,
+        ["Clear Filter", "Unfold", "Sort", "Filter", "Quit"]
+    );
+    // Outside inspect the task doesn't matter
+    assert_eq!(
+        controls::controls(ViewState::Monitor, Some(TaskStatus::OnStrike), toggles),
+        controls::controls(ViewState::Monitor, None, Toggles::default())
+    );
+    // Something to repeat goes right by where it came from
+    let repeat = Toggles {
+        repeat: true,
+        ..Toggles::default()
+    };
+    let offered = labels(&controls::controls(ViewState::Monitor, None, repeat));
+    assert_eq!(offered[..3], ["New Task", "Again", "Manage Tasks"]);
+}
+
+#[test]
+fn narrow_terminals_get_an_ellipsis() {
+    let all = controls::controls(ViewState::Monitor, None, Toggles::default());
+    let full = controls::width(&all);
+    assert_eq!(
+        controls::fit(all.clone(), full, false),
+        (all.clone(), false)
+    );
+
+    let (kept, cut) = controls::fit(all.clone(), full - 1, false);
+    assert!(cut);
+    assert_eq!(kept, all[..all.len() - 1]);
+    // " …" has to fit too
+    assert!(controls::width(&kept) + 2 < full);
+
+    let (kept, cut) = controls::fit(all, 3, false);
+    assert!(cut && kept.is_empty());
+}
+
+#[test]
+fn compact_controls_fit_in_less() {
+    let all = controls::controls(ViewState::Monitor, None, Toggles::default());
+    let shorts: Vec<&str> = all.iter().map(Control::short).collect();
+    assert_eq!(shorts[..3], ["New", "Manage", "Batch"]);
+    // " F1 New" and so on, against " New Task <F1>"
+    let terse = controls::terse_width(&all);
+    assert!(terse < controls::width(&all));
+    assert_eq!(
+        controls::fit(all.clone(), terse, true),
+        (all.clone(), false)
+    );
+    let (kept, cut) = controls::fit(all.clone(), terse - 1, true);
+    assert!(cut);
+    assert_eq!(kept, all[..all.len() - 1]);
+}
+
+#[test]
+fn notes_are_on_shift_n() {
+    let offered = controls::controls(
+        ViewState::Inspect,
+        Some(TaskStatus::Running),
+        Toggles::default(),
+    );
+    let note = offered
+        .iter()
+        .find(|c| c.label == "Note")
+        .expect("always on offer");
+    assert_eq!(note.key, "S-N");
+
+    let mut task = Task::pending(&CandidateTask::new("Alpha", "Does a thing"), 0, 1);
+    task.status = TaskStatus::Running;
+    let mut app = App::with_tasks(Config::default(), vec![task]);
+    let screen = |app: &mut App| {
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).expect("nothing to fail");
+        terminal
+            .draw(|frame| app.view(frame))
+            .expect("nothing to fail");
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>()
+    };
+    app.handle_key_event(KeyEvent::from(KeyCode::F(2)));
+    // Plain `n` is still negotiating, which a running task has nothing to negotiate
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('n')));
+    assert!(!screen(&mut app).contains("Note for task 0"));
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('N')));
+    assert!(screen(&mut app).contains("Note for task 0"));
+}
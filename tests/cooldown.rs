@@ -0,0 +1,123 @@
+//! Candidates held back after they've just finished, on made-up finish times: how long they've
+//! got left, the page filled from everything else first, and never an empty picker
+
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{DateTime, Local, TimeDelta};
+use clustrctrl::{
+    nav::ListNav,
+    task_picker::{self, TaskPicker, FETCH_AMOUNT},
+    CandidateTask,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+const COOLDOWN: Duration = Duration::from_secs(60);
+
+fn ago(now: DateTime<Local>, secs: i64) -> DateTime<Local> {
+    now - TimeDelta::seconds(secs)
+}
+
+fn pool(names: &[&str]) -> Vec<CandidateTask> {
+    names
+        .iter()
+        .map(|&name| CandidateTask::new(name, "Does a thing"))
+        .collect()
+}
+
+#[test]
+fn only_the_recently_finished_are_cooling() {
+    let now = Local::now();
+    let finished = HashMap::from([
+        ("Repaint fence".to_string(), ago(now, 18)),
+        ("Sort socks".to_string(), ago(now, 60)),
+        ("Tune piano".to_string(), ago(now, 600)),
+        // A clock that went backwards
+        ("Count sheep".to_string(), ago(now, -5)),
+    ]);
+    let cooling = task_picker::cooling_down(&finished, now, COOLDOWN);
+    assert_eq!(
+        cooling,
+        HashMap::from([
+            ("Repaint fence".to_string(), Duration::from_secs(42)),
+            ("Count sheep".to_string(), COOLDOWN),
+        ])
+    );
+    assert!(task_picker::cooling_down(&finished, now, Duration::ZERO).is_empty());
+}
+
+#[test]
+fn the_page_fills_from_everything_else_first() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let names = ["A", "B", "C", "D", "E", "F", "G", "H"];
+    let cooling = HashMap::from([
+        ("A".to_string(), Duration::from_secs(5)),
+        ("B".to_string(), Duration::from_secs(5)),
+    ]);
+    for _ in 0..20 {
+        let picks = task_picker::gen_list(&pool(&names), &cooling, &mut rng);
+        assert_eq!(picks.len(), FETCH_AMOUNT);
+        assert!(picks.iter().all(|ct| !cooling.contains_key(&ct.name)));
+    }
+
+    // Not enough without them, so they're on the end
+    let picks = task_picker::gen_list(&pool(&["A", "B", "C", "D"]), &cooling, &mut rng);
+    assert_eq!(picks.len(), 4);
+    assert!(picks[..2].iter().all(|ct| !cooling.contains_key(&ct.name)));
+    assert!(picks[2..].iter().all(|ct| cooling.contains_key(&ct.name)));
+
+    // Everything's cooling, and they're all still offered
+    let picks = task_picker::gen_list(&pool(&["A", "B"]), &cooling, &mut rng);
+    assert_eq!(picks.len(), 2);
+}
+
+fn drawn(picker: &mut TaskPicker) -> String {
+    let area = Rect::new(0, 0, 70, picker.height() as u16);
+    let mut buf = Buffer::empty(area);
+    picker.render(area, &mut buf);
+    (0..area.height)
+        .map(|y| {
+            (0..area.width)
+                .map(|x| buf[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn a_cooling_candidate_says_so_and_is_never_picked_at_random() {
+    let mut picker = TaskPicker::new(
+        false,
+        pool(&["Repaint fence", "Sort socks"]),
+        ListNav::new(true),
+    );
+    picker.seed(3);
+    let now = Local::now();
+    picker.finished("Repaint fence", ago(now, 18));
+    picker.regen(now);
+    let shown = drawn(&mut picker);
+    assert!(
+        shown.contains("(Repaint fence): Does a thing (cooling down, 42s)"),
+        "{shown}"
+    );
+    assert!(
+        !shown.contains("(Sort socks): Does a thing (cooling"),
+        "{shown}"
+    );
+    for _ in 0..20 {
+        let ct = picker
+            .select_random()
+            .expect("there's one that isn't cooling");
+        assert_eq!(ct.name, "Sort socks");
+    }
+
+    // Both cooling still leaves something to pick
+    picker.finished("Sort socks", ago(now, 1));
+    picker.regen(now);
+    assert!(picker.select_random().is_some());
+
+    // Past the cooldown it's back to normal
+    picker.regen(now + TimeDelta::seconds(60));
+    assert!(!drawn(&mut picker).contains("cooling down"));
+}
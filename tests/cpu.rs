@@ -0,0 +1,74 @@
+//! Adding up a worker's CPU from made-up clock readings: a thread that came with time on its
+//! clock already, a clock that's lost partway, and totals across tasks
+
+use std::time::Duration;
+
+use clustrctrl::cpu::{self, CpuMeter, CpuTime};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn measured(ms: u64) -> CpuTime {
+    CpuTime {
+        ms,
+        estimated: false,
+    }
+}
+
+fn estimated(ms: u64) -> CpuTime {
+    CpuTime {
+        ms,
+        estimated: true,
+    }
+}
+
+#[test]
+fn counts_from_the_first_reading() {
+    // A reused blocking thread, already 5s into its clock
+    let mut meter = CpuMeter::new(Some(ms(5000)));
+    assert_eq!(meter.record(Some(ms(5400)), ms(450)), measured(400));
+    assert_eq!(meter.record(Some(ms(6250)), ms(900)), measured(1250));
+    // A clock that somehow reads less never takes any back
+    assert_eq!(meter.record(Some(ms(6000)), ms(10)), measured(1250));
+}
+
+#[test]
+fn no_clock_falls_back_to_wall_time() {
+    let mut meter = CpuMeter::new(None);
+    assert_eq!(meter.record(None, ms(300)), estimated(300));
+    assert_eq!(meter.record(None, ms(450)), estimated(750));
+    // One that turns up later has nothing to count from
+    assert_eq!(meter.record(Some(ms(9000)), ms(50)), estimated(800));
+}
+
+#[test]
+fn losing_the_clock_partway_stays_estimated() {
+    let mut meter = CpuMeter::new(Some(Duration::ZERO));
+    assert_eq!(meter.record(Some(ms(200)), ms(250)), measured(200));
+    assert_eq!(meter.record(None, ms(250)), estimated(500));
+    assert_eq!(meter.record(Some(ms(600)), ms(250)), estimated(750));
+}
+
+#[test]
+fn totals_and_how_theyre_shown() {
+    assert_eq!(cpu::total([]), None);
+    assert_eq!(
+        cpu::total([measured(1200), measured(300)]),
+        Some(measured(1500))
+    );
+    let mixed = cpu::total([measured(12_000), estimated(400)]).unwrap();
+    assert_eq!(mixed, estimated(12_400));
+    assert_eq!(mixed.to_string(), "~12.4s");
+    assert_eq!(measured(12_449).to_string(), "12.4s");
+    assert_eq!(measured(0).to_string(), "0.0s");
+}
+
+#[cfg(unix)]
+#[test]
+fn this_thread_has_a_clock() {
+    let before = cpu::thread_time().expect("unix threads have a CPU clock");
+    let busy: u64 = (0..2_000_000u64).map(|n| n % 7).sum();
+    assert!(busy > 0);
+    assert!(cpu::thread_time().unwrap() >= before);
+}
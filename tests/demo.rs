@@ -0,0 +1,163 @@
+//! F10's demo macros: their keys coming out on a mock clock, key names from the config, and the
+//! built-in one played through an app until a real key stops it
+
+use std::time::Duration;
+
+use clustrctrl::{
+    app::App,
+    clock::{Clock, MockClock},
+    config::Config,
+    demo::{self, DemoConfig, MacroConfig, Player},
+    tasks::{TaskStatus, MIN_INTENSITY},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn custom(name: &str, keys: &[&str]) -> MacroConfig {
+    MacroConfig {
+        name: name.to_string(),
+        keys: keys.iter().map(|key| key.to_string()).collect(),
+    }
+}
+
+#[test]
+fn keys_come_out_a_step_apart() {
+    let clock = MockClock::new();
+    let steps = demo::compile(&["f1", "pause", "ctrl+k", "enter"], ms(300)).expect("all real keys");
+    assert_eq!(
+        steps,
+        [
+            (ms(300), KeyEvent::from(KeyCode::F(1))),
+            // The pause is another step on top
+            (
+                ms(600),
+                KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL)
+            ),
+            (ms(300), KeyEvent::from(KeyCode::Enter)),
+        ]
+    );
+    let mut player = Player::new(steps, clock.now_instant());
+    assert_eq!(player.due(clock.now_instant()), None);
+    clock.advance(ms(299));
+    assert_eq!(player.due(clock.now_instant()), None);
+    clock.advance(ms(1));
+    assert_eq!(
+        player.due(clock.now_instant()),
+        Some(KeyEvent::from(KeyCode::F(1)))
+    );
+    // Way behind, and still only one at a time, each waiting from the last
+    clock.advance(ms(5_000));
+    assert!(player.due(clock.now_instant()).is_some());
+    assert_eq!(player.due(clock.now_instant()), None);
+    assert!(!player.is_done());
+    clock.advance(ms(300));
+    assert_eq!(
+        player.due(clock.now_instant()),
+        Some(KeyEvent::from(KeyCode::Enter))
+    );
+    assert!(player.is_done());
+    assert_eq!(player.next_at(), None);
+}
+
+#[test]
+fn bad_macros_are_warned_about() {
+    let config = DemoConfig {
+        play: "missing".to_string(),
+        macros: vec![
+            custom("fine", &["f1", "esc"]),
+            custom("typo", &["f1", "entr"]),
+            custom("loop", &["f10"]),
+            custom("nothing", &["pause"]),
+        ],
+        ..DemoConfig::default()
+    };
+    assert_eq!(
+        config.problems(),
+        [
+            "demo macro `typo` has a key we don't know: `entr`",
+            "demo macro `loop` can't press F10, that's what stops it",
+            "demo macro `nothing` has no keys to press",
+            "there's no demo macro called `missing`",
+        ]
+    );
+    assert!(config.steps().is_err());
+    assert!(DemoConfig::default().problems().is_empty());
+}
+
+#[test]
+fn a_custom_macro_can_take_the_builtins_place() {
+    let config = DemoConfig {
+        macros: vec![custom(demo::BUILTIN_NAME, &["f8"])],
+        ..DemoConfig::default()
+    };
+    assert_eq!(
+        config.steps(),
+        Ok(vec![(ms(300), KeyEvent::from(KeyCode::F(8)))])
+    );
+}
+
+fn app() -> (App, MockClock) {
+    let mut config = Config::default();
+    // Lowest intensity, so the workers stop as soon as they're told
+    config.work.intensity = MIN_INTENSITY;
+    let clock = MockClock::new();
+    let app = App::with_tasks(config, vec![]).with_clock(Box::new(clock.clone()));
+    (app, clock)
+}
+
+/// Steps the clock on until the demo's put `keys` more in
+fn play(app: &mut App, clock: &MockClock, keys: usize) {
+    for _ in 0..keys {
+        clock.advance(ms(300));
+        while !app.play_due() {
+            clock.advance(ms(300));
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn the_builtin_adds_three_and_cancels_the_second() {
+    let (mut app, clock) = app();
+    app.handle_real_key(KeyEvent::from(KeyCode::F(10)));
+    assert!(!app.play_due(), "nothing's due straight away");
+    // Three lots of picker and random pick
+    play(&mut app, &clock, 6);
+    assert_eq!(app.tasks().len(), 3);
+    // F2, down to the second, the menu, and its first entry
+    play(&mut app, &clock, 4);
+    let halted: Vec<usize> = app
+        .tasks()
+        .iter()
+        .filter(|task| task.cancel_requested_at.is_some() || task.status == TaskStatus::Canceled)
+        .map(|task| task.id)
+        .collect();
+    assert_eq!(halted, [1]);
+    clock.advance(ms(10_000));
+    assert!(!app.play_due(), "it's over");
+    app.handle_real_key(KeyEvent::from(KeyCode::F(3)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_real_key_stops_it() {
+    let (mut app, clock) = app();
+    app.handle_real_key(KeyEvent::from(KeyCode::F(10)));
+    play(&mut app, &clock, 2);
+    assert_eq!(app.tasks().len(), 1);
+    // F10 again only stops it, it doesn't start over
+    app.handle_real_key(KeyEvent::from(KeyCode::F(10)));
+    clock.advance(ms(10_000));
+    assert!(!app.play_due());
+    assert_eq!(app.tasks().len(), 1);
+    // And any other key goes where it would have anyway
+    app.handle_real_key(KeyEvent::from(KeyCode::F(10)));
+    clock.advance(ms(300));
+    assert!(app.play_due(), "the picker's opened");
+    app.handle_real_key(KeyEvent::from(KeyCode::Enter));
+    clock.advance(ms(10_000));
+    assert!(!app.play_due());
+    assert_eq!(app.tasks().len(), 2);
+    app.handle_real_key(KeyEvent::from(KeyCode::F(3)));
+}
@@ -0,0 +1,63 @@
+//! A bounced Enter in the picker, or the same candidate added twice in a blink, only makes the
+//! one task
+
+use std::time::Duration;
+
+use clustrctrl::{
+    app::App,
+    clock::MockClock,
+    config::Config,
+    nav::ListNav,
+    task_picker::{self, TaskPicker},
+    tasks::MIN_INTENSITY,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+
+fn app() -> (App, MockClock) {
+    let mut config = Config::default();
+    // So the workers stop as soon as they're told
+    config.work.intensity = MIN_INTENSITY;
+    let clock = MockClock::new();
+    let app = App::with_tasks(config, vec![]).with_clock(Box::new(clock.clone()));
+    (app, clock)
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key_event(KeyEvent::from(code));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn mashing_enter_adds_one() {
+    let (mut app, _clock) = app();
+    press(&mut app, KeyCode::F(1));
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.tasks().len(), 1);
+    press(&mut app, KeyCode::F(3));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn the_same_candidate_waits_out_the_window() {
+    let (mut app, clock) = app();
+    press(&mut app, KeyCode::F(1));
+    press(&mut app, KeyCode::Enter);
+    // `n` is the same candidate by another way in
+    press(&mut app, KeyCode::Char('n'));
+    assert_eq!(app.tasks().len(), 1);
+    clock.advance(Duration::from_millis(250));
+    press(&mut app, KeyCode::Char('n'));
+    assert_eq!(app.tasks().len(), 2);
+    assert_eq!(app.tasks()[0].candidate, app.tasks()[1].candidate);
+    press(&mut app, KeyCode::F(3));
+}
+
+#[test]
+fn the_picker_takes_one_pick_per_opening() {
+    let mut picker = TaskPicker::new(false, task_picker::builtin_pool(), ListNav::new(true));
+    assert!(!picker.is_committed());
+    let ct = picker.select().expect("something's highlighted");
+    picker.confirm(&ct);
+    assert!(picker.is_committed());
+    picker.regen(chrono::Local::now());
+    assert!(!picker.is_committed());
+}
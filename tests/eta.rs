@@ -0,0 +1,32 @@
+//! Time left from a rate fit, and from a total that was chosen up front
+use std::time::{Duration, Instant};
+
+use clustrctrl::eta::{Eta, EtaEstimator};
+
+#[test]
+fn rates_need_progress_to_go_on() {
+    let start = Instant::now();
+    let mut eta = EtaEstimator::default();
+    assert_eq!(eta.eta(), Eta::Unknown);
+    eta.record(start, 0);
+    eta.record(start + Duration::from_secs(10), 50);
+    eta.update(start + Duration::from_secs(10));
+    assert_eq!(eta.eta(), Eta::Remaining(Duration::from_secs(10)));
+}
+
+#[test]
+fn chosen_totals_count_down() {
+    let start = Instant::now();
+    let mut eta = EtaEstimator::default();
+    eta.set_target(Duration::from_secs(40));
+    // One report is enough, since there's no rate to fit
+    eta.record(start, 25);
+    eta.update(start);
+    assert_eq!(eta.eta(), Eta::Remaining(Duration::from_secs(30)));
+    // Between reports it keeps going down, but never past nothing
+    let mut later = EtaEstimator::default();
+    later.set_target(Duration::from_secs(40));
+    later.record(start, 25);
+    later.update(start + Duration::from_secs(45));
+    assert_eq!(later.eta(), Eta::Remaining(Duration::ZERO));
+}
@@ -0,0 +1,158 @@
+//! Sorted by status: headers between the groups, folding them away, getting round a folded one
+//! and stopping the task under it
+
+use clustrctrl::{
+    app::App,
+    columns::Columns,
+    config::Config,
+    groups::{self, Entry, Groups},
+    nav::{ListNav, Move},
+    task_table::{SortBy, TaskTable},
+    tasks::{StatusClass, Task, TaskStatus},
+    CandidateTask,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+
+/// Two running, then two waiting on their batch delay, none with a worker behind it
+fn tasks() -> Vec<Task> {
+    let mut tasks: Vec<Task> = ["Alpha", "Beta", "Gamma", "Delta"]
+        .into_iter()
+        .enumerate()
+        .map(|(id, name)| Task::pending(&CandidateTask::new(name, "Does a thing"), id, 1))
+        .collect();
+    tasks[0].status = TaskStatus::Running;
+    tasks[1].status = TaskStatus::Running;
+    // Added before the others but shown after them
+    tasks.swap(0, 2);
+    for (id, task) in tasks.iter_mut().enumerate() {
+        task.id = id;
+    }
+    tasks
+}
+
+fn by_status() -> TaskTable {
+    let mut table = TaskTable::new(false, false, Columns::default(), ListNav::new(false));
+    table.toggle_sort();
+    table.toggle_sort();
+    assert_eq!(table.sort(), SortBy::Status);
+    table
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key_event(KeyEvent::from(code));
+}
+
+/// The table's rows as drawn, with their IDs and statuses
+fn draw(app: &mut App) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(160, 30)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    (0..30)
+        .map(|y| (0..160).map(|x| buffer[(x, y)].symbol()).collect())
+        .collect()
+}
+
+#[test]
+fn folded_groups_are_just_their_header() {
+    use StatusClass::*;
+    let mut groups = Groups::default();
+    let classes = [Working, Working, Waiting, Done];
+    assert_eq!(
+        groups.entries(&classes),
+        [
+            Entry::Header(Working),
+            Entry::Task(0),
+            Entry::Task(1),
+            Entry::Header(Waiting),
+            Entry::Task(2),
+            Entry::Header(Done),
+            Entry::Task(3),
+        ]
+    );
+    assert!(groups.toggle(Working));
+    assert_eq!(groups.header(Working, 2, false), "▸ Working (2)");
+    assert_eq!(groups.header(Waiting, 1, true), "v Waiting (1)");
+    assert_eq!(
+        groups.entries(&classes)[..3],
+        [
+            Entry::Header(Working),
+            Entry::Header(Waiting),
+            Entry::Task(2)
+        ]
+    );
+    assert!(!groups.toggle(Working));
+    assert!(groups.entries(&[]).is_empty());
+    // Whatever's going on comes before however it ended
+    assert!(groups::rank(Working) < groups::rank(Waiting));
+    assert!(groups::rank(Failed) < groups::rank(Done));
+}
+
+#[test]
+fn navigation_steps_over_a_folded_group() {
+    let mut tasks = tasks();
+    let mut table = by_status();
+    table.select_first(&tasks);
+    // The first row's the Working header, not a task
+    assert_eq!(table.selected(&tasks), None);
+    assert_eq!(table.selected_group(&tasks), Some(StatusClass::Working));
+    assert!(table.toggle_fold(&tasks));
+    assert_eq!(table.order(&tasks), [0, 3]);
+    table.next(&tasks);
+    assert_eq!(table.selected_group(&tasks), Some(StatusClass::Waiting));
+    table.next(&tasks);
+    assert_eq!(table.selected(&tasks), Some(0));
+    // Back up over it without landing in it
+    table.navigate(&tasks, Move::Previous);
+    table.navigate(&tasks, Move::Previous);
+    assert_eq!(table.selected_group(&tasks), Some(StatusClass::Working));
+    // A task coming and going doesn't unfold it
+    tasks[0].status = TaskStatus::Running;
+    assert_eq!(table.order(&tasks), [3]);
+    tasks[0].status = TaskStatus::Scheduled;
+    assert!(table.groups.is_folded(StatusClass::Working));
+    // Nor does sorting some other way and coming back
+    table.toggle_sort();
+    assert_eq!(table.order(&tasks), [0, 1, 2, 3]);
+    table.toggle_sort();
+    table.toggle_sort();
+    assert_eq!(table.order(&tasks), [0, 3]);
+}
+
+#[test]
+fn enter_on_a_row_below_a_folded_group_stops_that_row() {
+    let mut app = App::with_tasks(Config::default(), tasks());
+    press(&mut app, KeyCode::F(2));
+    press(&mut app, KeyCode::Char('s'));
+    press(&mut app, KeyCode::Char('s'));
+    // Onto the Working header, fold it, then down past Waiting's header to Gamma
+    press(&mut app, KeyCode::Home);
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    let screen = draw(&mut app);
+    let at = |text: &str| {
+        screen
+            .iter()
+            .position(|line| line.contains(text))
+            .unwrap_or_else(|| panic!("{text} isn't showing"))
+    };
+    assert!(screen[at("Task Table")].contains("(by status)"));
+    // Still folded, so neither of the running ones is showing
+    assert_eq!(at("▸ Working (2)") + 1, at("▾ Waiting (1)"));
+    // Names are cut down to fit, IDs and statuses aren't
+    let waiting = &screen[at("▾ Waiting (1)") + 1];
+    assert!(
+        waiting.contains("◷ 3") && waiting.contains("Wait"),
+        "{waiting}"
+    );
+    // Gamma's moved to a group of its own, with the highlight
+    let stopped = &screen[at("▾ Cancelled (1)") + 1];
+    assert!(
+        stopped.contains("> ■ 0") && stopped.contains("Canc"),
+        "{stopped}"
+    );
+}
@@ -0,0 +1,94 @@
+//! Guards as they come out of the config: what compiles, what matches, and who wins
+
+use clustrctrl::{
+    guards::{GuardAction, GuardConfig, Guards, Verdict},
+    CandidateTask,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct File {
+    guards: Vec<GuardConfig>,
+}
+
+fn compile(toml: &str) -> (Guards, Vec<String>) {
+    let file: File = toml::from_str(toml).expect("guards should parse");
+    let mut problems = vec![];
+    let guards = Guards::compile(&file.guards, &mut problems);
+    (guards, problems)
+}
+
+fn reason(verdict: Verdict) -> Option<(GuardAction, String)> {
+    match verdict {
+        Verdict::Allow => None,
+        Verdict::Confirm(guard) | Verdict::Block(guard) => Some((guard.action, guard.reason())),
+    }
+}
+
+#[test]
+fn bad_patterns_only_take_themselves_out() {
+    let (guards, problems) = compile(
+        r#"
+        [[guards]]
+        pattern = "gravity("
+        action = "block"
+
+        [[guards]]
+        pattern = "gravity"
+        action = "confirm"
+        message = "this one crashed last time"
+        "#,
+    );
+    assert_eq!(guards.len(), 1);
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].starts_with("guard 1 (`gravity(`)"));
+}
+
+#[test]
+fn names_and_descriptions_match_in_any_case() {
+    let (guards, problems) = compile(
+        r#"
+        [[guards]]
+        pattern = "^gravity"
+        action = "confirm"
+        message = "this one crashed last time"
+        "#,
+    );
+    assert!(problems.is_empty());
+    let warned = Some((
+        GuardAction::Confirm,
+        "this one crashed last time".to_string(),
+    ));
+    let by_name = CandidateTask::new("GRAVITY check", "Harmless");
+    assert_eq!(reason(guards.check(&by_name)), warned);
+    let by_description = CandidateTask::new("Bob", "Gravity, but reversed");
+    assert_eq!(reason(guards.check(&by_description)), warned);
+    // Anchored, so somewhere in the middle doesn't count
+    let neither = CandidateTask::new("Zero gravity", "Floaty");
+    assert!(matches!(guards.check(&neither), Verdict::Allow));
+}
+
+#[test]
+fn a_block_beats_any_confirm() {
+    let (guards, _) = compile(
+        r#"
+        [[guards]]
+        pattern = "toaster"
+        action = "confirm"
+        message = "it's a bit warm"
+
+        [[guards]]
+        pattern = "overclock"
+        action = "block"
+        "#,
+    );
+    let ct = CandidateTask::new("Mike", "Overclock the toaster");
+    // No message, so it says which pattern it was
+    assert_eq!(
+        reason(guards.check(&ct)),
+        Some((GuardAction::Block, "matches `overclock`".to_string()))
+    );
+    let mild = CandidateTask::new("Mike", "Clean the toaster");
+    assert!(matches!(guards.check(&mild), Verdict::Confirm(_)));
+    assert!(matches!(Guards::default().check(&ct), Verdict::Allow));
+}
@@ -0,0 +1,121 @@
+//! What startup found: worst first, when it's worth stopping for, and the panel it gets before
+//! the main screen until Enter
+
+use clustrctrl::{
+    app::App,
+    config::Config,
+    health::{Health, HealthItem, Severity},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+
+fn messages(health: &Health) -> Vec<&str> {
+    health
+        .sorted()
+        .into_iter()
+        .map(|item| item.message.as_str())
+        .collect()
+}
+
+#[test]
+fn worst_first_then_as_found() {
+    let mut health = Health::default();
+    health.info("serving status");
+    health.warn("intensity clamped");
+    health.error("tasks file broken");
+    health.warn("no color");
+    health.info("no history yet");
+    assert_eq!(
+        messages(&health),
+        [
+            "tasks file broken",
+            "intensity clamped",
+            "no color",
+            "serving status",
+            "no history yet",
+        ]
+    );
+    assert_eq!(
+        health.sorted()[0],
+        &HealthItem {
+            severity: Severity::Error,
+            message: "tasks file broken".to_string(),
+        }
+    );
+    assert_eq!(health.worst(), Some(Severity::Error));
+}
+
+#[test]
+fn only_warnings_and_worse_need_attention() {
+    let mut health = Health::default();
+    assert!(health.is_empty() && !health.needs_attention());
+    assert_eq!(health.worst(), None);
+    health.info("serving status");
+    assert!(!health.needs_attention());
+    health.warn("no color");
+    assert!(health.needs_attention());
+}
+
+#[test]
+fn merged_lists_keep_both() {
+    let mut from_main = Health::default();
+    from_main.warn("log file");
+    let mut from_app = Health::default();
+    from_app.error("webhook");
+    from_app.warn("ui state");
+    from_main.merge(from_app);
+    assert_eq!(messages(&from_main), ["webhook", "log file", "ui state"]);
+}
+
+fn screen(app: &mut App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    (0..30)
+        .map(|y| {
+            (0..100)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn the_panel_waits_for_enter() {
+    let mut health = Health::default();
+    health.warn("work.max_blocking_threads is 2");
+    health.info("serving status on http://127.0.0.1:8090");
+    let mut app = App::with_tasks(Config::default(), vec![]).with_health(health);
+    let shown = screen(&mut app);
+    assert!(shown.contains(" startup "), "{shown}");
+    assert!(
+        shown.contains("warning work.max_blocking_threads is 2"),
+        "{shown}"
+    );
+    assert!(shown.contains("info serving status"), "{shown}");
+
+    // Nothing else gets past it, and it says what will
+    app.handle_key_event(KeyEvent::from(KeyCode::F(1)));
+    let shown = screen(&mut app);
+    assert!(shown.contains(" startup "), "{shown}");
+    assert!(shown.contains("Enter to carry on"), "{shown}");
+    assert!(!shown.contains("New Task ─"), "the picker didn't open");
+
+    app.handle_key_event(KeyEvent::from(KeyCode::Enter));
+    assert!(!screen(&mut app).contains(" startup "));
+    // Still there in the help
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('?')));
+    let shown = screen(&mut app);
+    assert!(shown.contains("Found at startup"), "{shown}");
+}
+
+#[test]
+fn nothing_worse_than_info_goes_straight_on() {
+    let mut health = Health::default();
+    health.info("serving status on http://127.0.0.1:8090");
+    let mut app = App::with_tasks(Config::default(), vec![]).with_health(health);
+    assert!(!screen(&mut app).contains(" startup "));
+}
@@ -0,0 +1,120 @@
+//! Heartbeats: a chunked block beating every interval by a mock clock, a heartbeating task not
+//! going red however long its progress sits still, and heartbeats staying out of the audit trail
+//! while being counted in the debug overlay
+
+use std::time::Duration;
+
+use clustrctrl::{
+    app::App,
+    candidates::CandidateTask,
+    clock::{Clock, MockClock},
+    config::Config,
+    latency::Stamped,
+    momentum::{self, Momentum, MomentumConfig},
+    tasks::{self, Heartbeat, Task, TaskStatus, TaskTxMsg},
+    theme,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+#[test]
+fn a_beat_every_interval_of_crunching() {
+    let clock = MockClock::new();
+    // Chunks of 100, each taking a second by the clock
+    let mut heartbeat = Heartbeat::new(secs(5), 100, clock.now_instant());
+    let samples = (1..=2_000).inspect(|_| clock.advance(Duration::from_millis(10)));
+    let mut beats = vec![];
+    let start = clock.now_instant();
+    let sum = tasks::crunch_chunked(0, samples, 500, &mut heartbeat, &clock, || {
+        beats.push(clock.now_instant() - start)
+    });
+    assert_eq!(sum, tasks::crunch(0, 1..=2_000, 500));
+    assert_eq!(beats, [secs(5), secs(10), secs(15), secs(20)]);
+
+    // Not due again until the interval's gone by, even from the next block
+    let mut count = 0;
+    let samples = (0..300).inspect(|_| clock.advance(Duration::from_millis(10)));
+    tasks::crunch_chunked(0, samples, 500, &mut heartbeat, &clock, || count += 1);
+    assert_eq!(count, 0);
+    // Overflowing still gives up, beat or not
+    let sum = tasks::crunch_chunked(i128::MAX, [1, 2], 500, &mut heartbeat, &clock, || {});
+    assert_eq!(sum, None);
+}
+
+#[test]
+fn heartbeating_is_idle_not_stalled() {
+    let config = MomentumConfig::default();
+    let judge = |quiet| momentum::with_heartbeat(TaskStatus::Running, secs(60), quiet, &config);
+    // Never heartbeats, so it's progress or nothing
+    assert_eq!(judge(None), Some(Momentum::Stalled));
+    assert_eq!(judge(Some(secs(3))), Some(Momentum::Idle));
+    assert_eq!(judge(Some(secs(20))), Some(Momentum::Stalled));
+    // It only ever lets off, never makes anything worse
+    assert_eq!(
+        momentum::with_heartbeat(TaskStatus::Running, secs(1), Some(secs(60)), &config),
+        Some(Momentum::Moving)
+    );
+}
+
+#[test]
+fn counted_but_not_audited() {
+    let clock = MockClock::new();
+    let mut task = Task::pending(&CandidateTask::new("Think", "thinking hard"), 0, 1);
+    task.status = TaskStatus::Running;
+    task.progressed(10, 0, clock.now_instant());
+    let mut app =
+        App::with_tasks(Config::default(), vec![task]).with_clock(Box::new(clock.clone()));
+    clock.advance(secs(30));
+
+    let progress_color = |app: &mut App| {
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).expect("nothing to fail");
+        terminal
+            .draw(|frame| app.view(frame))
+            .expect("nothing to fail");
+        let buffer = terminal.backend().buffer();
+        let (x, y) = (0..30)
+            .flat_map(|y| (0..118).map(move |x| (x, y)))
+            .find(|&(x, y)| {
+                ["1", "0", "%"]
+                    .iter()
+                    .zip(x..)
+                    .all(|(symbol, x)| buffer[(x, y)].symbol() == *symbol)
+            })
+            .expect("the progress is on screen");
+        buffer[(x, y)].fg
+    };
+    let alert = theme::current().alert.fg.expect("alert has a color");
+    assert_eq!(progress_color(&mut app), alert);
+
+    app.task_sender()
+        .try_send(Stamped::new(TaskTxMsg::Heartbeat(0)))
+        .expect("there's room");
+    app.drain_messages();
+    assert_eq!(app.tasks()[0].heartbeat_at, Some(clock.now_instant()));
+    assert_eq!(
+        app.tasks()[0].quiet_for(clock.now_instant()),
+        Some(Duration::ZERO)
+    );
+    assert!(!app.tasks()[0]
+        .audit
+        .iter()
+        .any(|(_, line)| line.contains("Heartbeat")));
+    assert_ne!(progress_color(&mut app), alert);
+
+    app.handle_key_event(KeyEvent::from(KeyCode::F(12)));
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+    assert!(screen.contains("heartbeats 1"), "{screen}");
+
+    // Quiet for long enough and it's red again
+    clock.advance(secs(20));
+    assert_eq!(progress_color(&mut app), alert);
+}
@@ -0,0 +1,118 @@
+//! Per-candidate stats from a made-up history, how a run compares to them, and the history file
+//! itself
+
+use std::{env, fs, process};
+
+use clustrctrl::{
+    history::{self, Pace, PaceConfig, Record},
+    tasks::TaskStatus,
+};
+
+fn record(name: &str, candidate: Option<&str>, status: TaskStatus, secs: Option<u64>) -> Record {
+    Record {
+        name: name.to_string(),
+        candidate: candidate.map(str::to_string),
+        status,
+        secs,
+        ended: String::new(),
+        session: None,
+    }
+}
+
+#[test]
+fn copies_count_for_their_candidate() {
+    let records = [
+        record("Bingus", Some("Bingus"), TaskStatus::Finished, Some(30)),
+        record("Bingus (2)", Some("Bingus"), TaskStatus::Failed, Some(50)),
+        // Older lines without a candidate go by the name, copy number and all taken off
+        record("Bingus (3)", None, TaskStatus::Canceled, Some(34)),
+        record("Bingus (12)", None, TaskStatus::FailedToStart, None),
+        record("Mike Truk", None, TaskStatus::Finished, Some(5)),
+    ];
+    let stats = history::aggregate(&records);
+    assert_eq!(stats.len(), 2);
+    let bingus = stats["Bingus"];
+    assert_eq!((bingus.runs, bingus.failures), (4, 2));
+    // The one that never started has no time to count
+    assert_eq!(bingus.avg_secs(), Some(38));
+    assert_eq!(bingus.to_string(), "run 4 times, avg 38s, 2 failures");
+    assert_eq!(stats["Mike Truk"].to_string(), "run once, avg 5s");
+    assert_eq!(history::describe(stats.get("Todd Bonzalez")), "never run");
+}
+
+#[test]
+fn slow_runs_stand_out() {
+    let pace = PaceConfig::default();
+    let stats = history::aggregate(&[
+        record("Bingus", None, TaskStatus::Finished, Some(20)),
+        record("Bingus", None, TaskStatus::Finished, Some(40)),
+    ]);
+    let bingus = stats.get("Bingus");
+    let at = |secs| history::compare(secs, bingus, &pace).map(|c| c.pace);
+    assert_eq!(at(0), Some(Pace::Usual));
+    // Right on a threshold isn't past it yet
+    assert_eq!(at(45), Some(Pace::Usual));
+    assert_eq!(at(46), Some(Pace::Slow));
+    assert_eq!(at(60), Some(Pace::Slow));
+    assert_eq!(at(61), Some(Pace::VerySlow));
+    assert_eq!(
+        history::compare(38, bingus, &pace).unwrap().to_string(),
+        "38s elapsed vs 30s avg"
+    );
+    let strict = PaceConfig {
+        slow: 1.0,
+        very_slow: 1.2,
+    };
+    assert_eq!(
+        history::compare(37, bingus, &strict).map(|c| c.pace),
+        Some(Pace::VerySlow)
+    );
+}
+
+#[test]
+fn nothing_to_compare_says_nothing() {
+    let pace = PaceConfig::default();
+    assert_eq!(history::compare(10, None, &pace), None);
+    // Runs, but none that ever started, so there's no average
+    let stats = history::aggregate(&[record("DOA", None, TaskStatus::FailedToStart, None)]);
+    assert_eq!(history::compare(10, stats.get("DOA"), &pace), None);
+    // Under a second on average counts as a second, instead of everything being infinitely slow
+    let stats = history::aggregate(&[record("Quick", None, TaskStatus::Finished, Some(0))]);
+    let quick = stats.get("Quick");
+    assert_eq!(history::compare(0, quick, &pace).unwrap().pace, Pace::Usual);
+    assert_eq!(history::compare(1, quick, &pace).unwrap().pace, Pace::Usual);
+    assert_eq!(history::compare(2, quick, &pace).unwrap().pace, Pace::Slow);
+    assert_eq!(
+        history::compare(3, quick, &pace).unwrap().pace,
+        Pace::VerySlow
+    );
+}
+
+#[test]
+fn only_copy_numbers_come_off() {
+    assert_eq!(history::base_name("Bingus (2)"), "Bingus");
+    assert_eq!(history::base_name("Bingus"), "Bingus");
+    assert_eq!(
+        history::base_name("Mike (the toaster)"),
+        "Mike (the toaster)"
+    );
+    assert_eq!(history::base_name("()"), "()");
+}
+
+#[test]
+fn bad_lines_dont_lose_the_rest() {
+    let path = env::temp_dir().join(format!("clustrctrl-history-{}.jsonl", process::id()));
+    let _ = fs::remove_file(&path);
+    assert_eq!(history::load(&path).unwrap(), (vec![], 0));
+
+    let first = record("Bingus", Some("Bingus"), TaskStatus::Finished, Some(3));
+    history::append(&path, &first).unwrap();
+    fs::write(&path, fs::read_to_string(&path).unwrap() + "{not json\n").unwrap();
+    let second = record("Bingus (2)", Some("Bingus"), TaskStatus::Failed, None);
+    history::append(&path, &second).unwrap();
+
+    let (records, bad) = history::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(records, [first, second]);
+    assert_eq!(bad, 1);
+}
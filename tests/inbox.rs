@@ -0,0 +1,148 @@
+//! A frame's worth of worker messages: everything in order when it fits, how it ended ahead of
+//! how it's going when it doesn't, a storm that still lets keys through and drains over a few
+//! frames, and one that outruns them left on the channel instead of piling up
+
+use clustrctrl::{
+    app::App,
+    config::Config,
+    inbox::{self, Inbox},
+    latency::Stamped,
+    tasks::{Task, TaskStatus, TaskTxMsg},
+    CandidateTask,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+
+fn progress(id: usize, pct: u8) -> Stamped<TaskTxMsg> {
+    Stamped::new(TaskTxMsg::Progress {
+        id,
+        pct,
+        fatigue: 0,
+        cpu_ms: 0,
+        cpu_estimated: false,
+    })
+}
+
+fn fault(id: usize) -> Stamped<TaskTxMsg> {
+    Stamped::new(TaskTxMsg::Fault {
+        id,
+        error: "out of paint".to_string(),
+    })
+}
+
+/// Which task and what it was, short enough to compare
+fn summary(taken: &[Stamped<TaskTxMsg>]) -> Vec<(usize, bool)> {
+    taken
+        .iter()
+        .map(|stamped| (stamped.msg.id(), inbox::is_urgent(&stamped.msg)))
+        .collect()
+}
+
+#[test]
+fn everything_that_fits_goes_in_order() {
+    let mut inbox = Inbox::default();
+    inbox.push(progress(0, 10));
+    inbox.push(fault(1));
+    inbox.push(progress(2, 30));
+    assert_eq!(summary(&inbox.take(3)), [(0, false), (1, true), (2, false)]);
+    assert!(inbox.is_empty());
+}
+
+#[test]
+fn how_it_ended_jumps_the_queue() {
+    let mut inbox = Inbox::default();
+    for id in 0..4 {
+        inbox.push(progress(id, 50));
+    }
+    inbox.push(fault(4));
+    inbox.push(progress(5, 50));
+    inbox.push(fault(6));
+    // Both faults, and the oldest progress to fill up, still in the order they came
+    assert_eq!(summary(&inbox.take(3)), [(0, false), (4, true), (6, true)]);
+    assert_eq!(inbox.len(), 4);
+    assert!(inbox.holds(5) && !inbox.holds(4));
+    assert_eq!(
+        summary(&inbox.take(10)),
+        [(1, false), (2, false), (3, false), (5, false)]
+    );
+
+    // More urgent ones than fit are first come, first served too
+    for id in 0..3 {
+        inbox.push(fault(id));
+    }
+    assert_eq!(summary(&inbox.take(2)), [(0, true), (1, true)]);
+    assert!(inbox.holds(2));
+}
+
+fn overlay(app: &mut App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    (0..40)
+        .map(|y| {
+            (0..120)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[tokio::test]
+async fn a_storm_waits_its_turn() {
+    let mut config = Config::default();
+    config.timing.message_budget = 10;
+    let mut task = Task::pending(&CandidateTask::new("Alpha", "Does a thing"), 0, 1);
+    task.status = TaskStatus::Running;
+    let mut app = App::with_tasks(config, vec![task]);
+    let tx = app.task_sender();
+    for pct in 0..99 {
+        tx.try_send(progress(0, pct)).expect("room in the channel");
+    }
+    tx.try_send(fault(0)).expect("room in the channel");
+
+    assert!(app.step().await);
+    // The fault got in first, even with 99 reports ahead of it
+    assert_eq!(app.tasks()[0].status, TaskStatus::Failed);
+    app.handle_key_event(KeyEvent::from(KeyCode::F(12)));
+    assert!(overlay(&mut app).contains("msgs deferred 90, 10 a frame"));
+
+    // Keys aren't waiting on the rest
+    app.handle_key_event(KeyEvent::from(KeyCode::F(1)));
+    assert!(overlay(&mut app).contains("New Task"));
+
+    for _ in 0..9 {
+        assert!(app.step().await);
+    }
+    assert!(!app.step().await, "all heard by now");
+    assert!(overlay(&mut app).contains("msgs deferred 0"));
+}
+
+#[tokio::test]
+async fn a_storm_that_outruns_the_frames_waits_on_the_channel() {
+    let mut config = Config::default();
+    config.timing.message_budget = 1;
+    let mut task = Task::pending(&CandidateTask::new("Alpha", "Does a thing"), 0, 1);
+    task.status = TaskStatus::Running;
+    let mut app = App::with_tasks(config, vec![task]);
+    let tx = app.task_sender();
+    let flood = |tx: &tokio::sync::mpsc::Sender<_>| {
+        let mut sent = 0;
+        while tx.try_send(progress(0, 50)).is_ok() {
+            sent += 1;
+        }
+        sent
+    };
+    assert_eq!(flood(&tx), inbox::CHANNEL_CAPACITY);
+    assert!(app.step().await);
+    app.handle_key_event(KeyEvent::from(KeyCode::F(12)));
+    assert!(overlay(&mut app).contains("msgs deferred 99, 1 a frame"));
+
+    // Only a channel's worth more than a frame takes, the rest stays where it was
+    assert_eq!(flood(&tx), inbox::CHANNEL_CAPACITY);
+    assert!(app.step().await);
+    assert!(overlay(&mut app).contains("msgs deferred 100, 1 a frame"));
+    assert_eq!(flood(&tx), 2, "the channel's still full");
+}
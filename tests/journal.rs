@@ -0,0 +1,167 @@
+//! The journal: a crash's half-written last line skipped on the way back in, a clean close
+//! leaving nothing to recover, and what an unclosed one left coming back as abandoned rows in
+//! the archive
+
+use std::{env, fs, path::PathBuf, process};
+
+use chrono::Local;
+use clustrctrl::{
+    app::App,
+    clock::{Clock, MockClock},
+    config::Config,
+    journal::{self, Entry, Journal},
+    tasks::{CancelState, Task, TaskStatus},
+    CandidateTask,
+};
+use ratatui::{backend::TestBackend, Terminal};
+
+fn scratch(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("clustrctrl-{}-{name}.log", process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn created(id: usize, candidate: &str) -> Entry {
+    Entry::Created {
+        id,
+        candidate: candidate.to_string(),
+        copy: 1,
+        description: "Does a thing".to_string(),
+        at: Local::now().to_rfc3339(),
+    }
+}
+
+fn status(id: usize, status: TaskStatus, progress: u8) -> Entry {
+    Entry::Status {
+        id,
+        status,
+        progress,
+        at: Local::now().to_rfc3339(),
+    }
+}
+
+#[test]
+fn a_cut_off_last_line_is_skipped() {
+    let path = scratch("truncated");
+    let clock = MockClock::new();
+    let mut journal = Journal::create(
+        &path,
+        Some("wobbly-otter-42"),
+        clock.now_local(),
+        clock.now_instant(),
+    )
+    .expect("temp is writable");
+    journal.write(&created(0, "Alpha")).expect("just a buffer");
+    journal.write(&created(1, "Beta")).expect("just a buffer");
+    journal
+        .write(&status(0, TaskStatus::Running, 0))
+        .expect("just a buffer");
+    journal
+        .write(&Entry::CancelRequested {
+            id: 0,
+            at: Local::now().to_rfc3339(),
+        })
+        .expect("just a buffer");
+    // Straight to the disk, no flush needed
+    journal
+        .write(&status(1, TaskStatus::Finished, 100))
+        .expect("temp is writable");
+    let synced = fs::read_to_string(&path).expect("it's there");
+    assert_eq!(synced.lines().count(), 6);
+    assert!(
+        synced.contains("\"event\":\"cancel_requested\""),
+        "{synced}"
+    );
+    journal
+        .write(&status(0, TaskStatus::Running, 60))
+        .expect("just a buffer");
+    drop(journal);
+
+    // The crash came halfway through the last line
+    let text = fs::read_to_string(&path).expect("it's there");
+    let cut = &text[..text.trim_end().len() - 12];
+    let unclean = journal::read(cut).expect("it never closed");
+    assert_eq!(unclean.skipped, 1);
+    assert_eq!(unclean.session.as_deref(), Some("wobbly-otter-42"));
+    assert_eq!(unclean.tasks.len(), 2);
+    let alpha = &unclean.tasks[0];
+    assert_eq!(
+        (alpha.status, alpha.progress, alpha.cancel_requested),
+        (TaskStatus::Running, 0, true)
+    );
+    let beta = &unclean.tasks[1];
+    assert_eq!((beta.status, beta.progress), (TaskStatus::Finished, 100));
+
+    // All of it, line included, from the file itself
+    let whole = journal::recover(&path)
+        .expect("it's readable")
+        .expect("it never closed");
+    assert_eq!(whole.skipped, 0);
+    assert_eq!(whole.tasks[0].progress, 60);
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn closed_is_clean() {
+    let path = scratch("closed");
+    let clock = MockClock::new();
+    let mut journal = Journal::create(&path, None, clock.now_local(), clock.now_instant())
+        .expect("temp is writable");
+    journal.write(&created(0, "Alpha")).expect("just a buffer");
+    journal.close(clock.now_local()).expect("temp is writable");
+    assert_eq!(journal::recover(&path).expect("it's readable"), None);
+
+    // Nothing there, or nothing that says it opened, is a clean start too
+    let _ = fs::remove_file(&path);
+    assert_eq!(journal::recover(&path).expect("not there is fine"), None);
+    assert_eq!(journal::read(""), None);
+    assert_eq!(journal::read("{\"event\":\"ope"), None);
+}
+
+#[test]
+fn recovered_rows_are_abandoned_and_archived() {
+    let path = scratch("app");
+    let clock = MockClock::new();
+    let journal = Journal::create(&path, None, clock.now_local(), clock.now_instant())
+        .expect("temp is writable");
+    let mut running = Task::pending(&CandidateTask::new("Alpha", "Does a thing"), 0, 1);
+    running.status = TaskStatus::Running;
+    running.progress = 30;
+    let mut done = Task::pending(&CandidateTask::new("Beta", "Does another"), 1, 2);
+    done.status = TaskStatus::Finished;
+    let mut app = App::with_tasks(Config::default(), vec![running, done])
+        .with_clock(Box::new(clock.clone()))
+        .with_journal(journal);
+    app.tick();
+    // Gone without a clean exit, like a crash
+    drop(app);
+
+    let unclean = journal::recover(&path)
+        .expect("it's readable")
+        .expect("it never closed");
+    let mut again = App::with_tasks(Config::default(), vec![]);
+    assert_eq!(again.recover(&unclean), 2);
+    let [alpha, beta] = again.tasks() else {
+        panic!("two rows back");
+    };
+    assert_eq!(alpha.candidate, "Alpha");
+    assert_eq!(
+        (alpha.status, alpha.cancel, alpha.progress),
+        (TaskStatus::Canceled, CancelState::Abandoned, 30)
+    );
+    assert!(alpha
+        .cancel_reason
+        .as_deref()
+        .is_some_and(|reason| reason.contains("Running")));
+    assert_eq!((beta.copy, beta.status), (2, TaskStatus::Finished));
+    assert_eq!(beta.cancel, CancelState::None);
+
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).expect("nothing to fail");
+    terminal
+        .draw(|frame| again.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+    assert!(screen.contains("2 archived tasks"), "{screen}");
+    let _ = fs::remove_file(&path);
+}
@@ -0,0 +1,46 @@
+//! Which bucket a delivery time lands in, right at the edges, and the histogram as the report has it
+
+use std::time::{Duration, Instant};
+
+use clustrctrl::latency::{Histogram, Stamped, LABELS};
+
+#[test]
+fn edges_go_in_the_slower_bucket() {
+    let mut histogram = Histogram::default();
+    for ms in [0, 9, 10, 49, 50, 199, 200, 999, 1000, 60_000] {
+        histogram.record(Duration::from_millis(ms));
+    }
+    assert_eq!(histogram.counts(), [2, 2, 2, 2, 2]);
+    assert_eq!(histogram.total(), 10);
+    let just_under = Duration::from_millis(10) - Duration::from_nanos(1);
+    let mut histogram = Histogram::default();
+    histogram.record(just_under);
+    assert_eq!(histogram.counts(), [1, 0, 0, 0, 0]);
+}
+
+#[test]
+fn buckets_line_up_with_the_labels() {
+    let mut histogram = Histogram::default();
+    histogram.record(Duration::from_secs(3));
+    let buckets: Vec<_> = histogram.buckets().collect();
+    assert_eq!(buckets.len(), LABELS.len());
+    assert_eq!(buckets.last(), Some(&("≥1s", 1)));
+    assert!(buckets[..4].iter().all(|&(_, count)| count == 0));
+}
+
+#[test]
+fn report_keeps_bucket_order() {
+    let mut histogram = Histogram::default();
+    histogram.record(Duration::from_millis(120));
+    let json = serde_json::to_string(&histogram).unwrap();
+    assert_eq!(json, r#"{"<10ms":0,"<50ms":0,"<200ms":1,"<1s":0,"≥1s":0}"#);
+}
+
+#[test]
+fn age_never_goes_negative() {
+    let stamped = Stamped::new(());
+    assert!(stamped.age(stamped.sent + Duration::from_millis(5)) >= Duration::from_millis(5));
+    // A clock read from before the stamp comes out as no time at all
+    let earlier = Instant::now() - Duration::from_secs(1);
+    assert_eq!(stamped.age(earlier), Duration::ZERO);
+}
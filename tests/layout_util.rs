@@ -0,0 +1,99 @@
+//! The rect helpers at every parent size from 0x0 to 500x200, never outside it and never out of
+//! range, and whole screens with a modal open on terminals far too small for it
+
+use clustrctrl::{
+    app::App,
+    config::Config,
+    layout_util::{centered_rect, saturating_height, split_with_min},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, layout::Rect, Terminal};
+
+/// Sizes to ask for: nothing, a sliver, typical ones, and far too much
+const ASKS: [u16; 6] = [0, 1, 7, 80, 250, u16::MAX];
+
+fn parents() -> impl Iterator<Item = Rect> {
+    (0..=500u16).flat_map(|width| {
+        (0..=200u16).flat_map(move |height| {
+            // At the origin, and somewhere off it like an inner area would be
+            [
+                Rect::new(0, 0, width, height),
+                Rect::new(3, 5, width, height),
+            ]
+        })
+    })
+}
+
+fn assert_inside(rect: Rect, parent: Rect) {
+    assert!(
+        rect.left() >= parent.left()
+            && rect.top() >= parent.top()
+            && rect.right() <= parent.right()
+            && rect.bottom() <= parent.bottom(),
+        "{rect:?} is outside {parent:?}"
+    );
+}
+
+#[test]
+fn centered_stays_inside() {
+    for parent in parents() {
+        for width in ASKS {
+            for height in ASKS {
+                let rect = centered_rect(parent, width, height);
+                assert_inside(rect, parent);
+                assert_eq!(rect.width, width.min(parent.width));
+                assert_eq!(rect.height, height.min(parent.height));
+            }
+        }
+    }
+    // Even split either side, the odd one out going after
+    assert_eq!(
+        centered_rect(Rect::new(0, 0, 11, 11), 4, 5),
+        Rect::new(3, 3, 4, 5)
+    );
+}
+
+#[test]
+fn splits_stay_inside_and_add_up() {
+    for parent in parents() {
+        for primary_min in ASKS {
+            for secondary_min in ASKS {
+                let [top, bottom] = split_with_min(parent, primary_min, secondary_min);
+                assert_inside(top, parent);
+                assert_inside(bottom, parent);
+                assert_eq!(top.height + bottom.height, parent.height);
+                assert_eq!(top.bottom(), bottom.top());
+                if parent.height >= primary_min.saturating_add(secondary_min) {
+                    assert_eq!(top.height, primary_min);
+                } else {
+                    assert_eq!(bottom.height, secondary_min.min(parent.height));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn heights_stop_at_the_top() {
+    assert_eq!(saturating_height(0), 0);
+    assert_eq!(saturating_height(42), 42);
+    assert_eq!(saturating_height(u16::MAX as usize), u16::MAX);
+    assert_eq!(saturating_height(u16::MAX as usize + 1), u16::MAX);
+    assert_eq!(saturating_height(usize::MAX), u16::MAX);
+}
+
+#[test]
+fn modals_fit_tiny_terminals() {
+    let mut app = App::with_tasks(Config::default(), vec![]);
+    // The picker's taller than most of these, which used to underflow
+    app.handle_key_event(KeyEvent::from(KeyCode::F(1)));
+    for width in 1..=30 {
+        for height in 1..=12 {
+            let mut terminal =
+                Terminal::new(TestBackend::new(width, height)).expect("nothing to fail");
+            terminal
+                .draw(|frame| app.view(frame))
+                .expect("nothing to fail");
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Collapsing runs of the same line: one line whose count goes up while it's held, handed over
+//! once something else comes, its window's up or it's hit the cap, lines that only look the
+//! same, and a run in the Message Stream as the one line
+
+use std::time::{Duration, Instant};
+
+use clustrctrl::{
+    app::App,
+    config::Config,
+    log_dedup::{Collapser, DedupLayer, Line},
+};
+use ratatui::{backend::TestBackend, Terminal};
+use tracing::Level;
+use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+const WINDOW: Duration = Duration::from_secs(2);
+
+fn line(level: Level, text: &str) -> Line {
+    Line {
+        target: "clustrctrl::tasks".to_string(),
+        level,
+        text: text.to_string(),
+    }
+}
+
+fn trace(text: &str) -> Line {
+    line(Level::TRACE, text)
+}
+
+/// Just the text of what got shown
+fn texts(lines: Vec<Line>) -> Vec<String> {
+    lines.into_iter().map(|line| line.text).collect()
+}
+
+/// The held line's text
+fn held(collapser: &Collapser) -> Option<String> {
+    collapser.held().map(|line| line.text)
+}
+
+#[test]
+fn one_line_counting_up_in_place() {
+    let mut collapser = Collapser::new(WINDOW, 100);
+    let now = Instant::now();
+    assert!(collapser.push(trace("sent"), now).is_empty());
+    assert_eq!(held(&collapser).as_deref(), Some("sent"));
+    for count in 2..=23 {
+        assert!(collapser.push(trace("sent"), now).is_empty());
+        assert_eq!(held(&collapser), Some(format!("sent ×{count}")));
+    }
+    // Something else and it's handed over as the one line, and that's held instead
+    assert_eq!(texts(collapser.push(trace("slept"), now)), ["sent ×23"]);
+    assert_eq!(held(&collapser).as_deref(), Some("slept"));
+    // One on its own goes over without a count
+    assert_eq!(texts(collapser.push(trace("sent"), now)), ["slept"]);
+}
+
+#[test]
+fn levels_and_targets_keep_runs_apart() {
+    let mut collapser = Collapser::new(WINDOW, 100);
+    let now = Instant::now();
+    collapser.push(trace("stuck"), now);
+    collapser.push(trace("stuck"), now);
+    // Same words, but one's a warning
+    let done = collapser.push(line(Level::WARN, "stuck"), now);
+    assert_eq!(texts(done.clone()), ["stuck ×2"]);
+    assert_eq!(done[0].level, Level::TRACE);
+    assert_eq!(collapser.held().expect("a run").level, Level::WARN);
+    let elsewhere = Line {
+        target: "clustrctrl::app".to_string(),
+        ..line(Level::WARN, "stuck")
+    };
+    assert_eq!(texts(collapser.push(elsewhere, now)), ["stuck"]);
+    assert_eq!(collapser.held().expect("a run").target, "clustrctrl::app");
+}
+
+#[test]
+fn runs_end_with_their_window() {
+    let mut collapser = Collapser::new(WINDOW, 100);
+    let start = Instant::now();
+    collapser.push(trace("sent"), start);
+    collapser.push(trace("sent"), start + Duration::from_secs(1));
+    // Nothing else came, so it's only handed over once the window's up
+    assert_eq!(collapser.flush(start + Duration::from_secs(1)), None);
+    let done = collapser.flush(start + WINDOW).expect("the window's up");
+    assert_eq!(done.text, "sent ×2");
+    assert_eq!(collapser.held(), None);
+    assert_eq!(collapser.flush(start + WINDOW * 2), None);
+    // And a repeat after the window starts over
+    collapser.push(trace("sent"), start + WINDOW * 2);
+    collapser.push(trace("sent"), start + WINDOW * 2);
+    assert_eq!(
+        texts(collapser.push(trace("sent"), start + WINDOW * 3)),
+        ["sent ×2"]
+    );
+    // A line that was never repeated goes over as it is
+    assert_eq!(
+        collapser.flush(start + WINDOW * 4).map(|line| line.text),
+        Some("sent".to_string())
+    );
+}
+
+#[test]
+fn runs_stop_at_the_cap() {
+    let mut collapser = Collapser::new(WINDOW, 3);
+    let now = Instant::now();
+    let done: Vec<String> = (0..5)
+        .flat_map(|_| texts(collapser.push(trace("sent"), now)))
+        .collect();
+    assert_eq!(done, ["sent ×3"]);
+    assert_eq!(held(&collapser).as_deref(), Some("sent ×2"));
+    assert_eq!(collapser.flush(now + WINDOW).unwrap().text, "sent ×2");
+    // A cap of 1 is no collapsing at all, and nothing's held
+    let mut every = Collapser::new(WINDOW, 1);
+    for _ in 0..3 {
+        assert_eq!(texts(every.push(trace("sent"), now)), ["sent"]);
+        assert_eq!(every.held(), None);
+    }
+    assert_eq!(every.flush(now + WINDOW), None);
+}
+
+#[test]
+fn the_stream_shows_the_run_once() {
+    let subscriber = Registry::default().with(DedupLayer::new(WINDOW * 30, 100));
+    tracing::subscriber::with_default(subscriber, || {
+        for _ in 0..23 {
+            tracing::info!("sent a run report");
+        }
+    });
+    let mut app = App::with_tasks(Config::default(), vec![]);
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    let rows: Vec<String> = (0..24)
+        .map(|y| (0..80).map(|x| buffer[(x, y)].symbol()).collect())
+        .collect();
+    let shown: Vec<&String> = rows
+        .iter()
+        .filter(|row| row.contains("sent a run report"))
+        .collect();
+    assert_eq!(shown.len(), 1, "{rows:#?}");
+    assert!(
+        shown[0].contains("|INFO | sent a run report ×23"),
+        "{rows:#?}"
+    );
+}
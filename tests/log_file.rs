@@ -0,0 +1,51 @@
+//! Log paths that can't be written: a directory where the file should be and a path through a
+//! regular file come back as a warning saying which path and why, never a panic. And one whose
+//! directories aren't there yet gets them made
+
+use std::{env, fs, io::Write, path::PathBuf, process};
+
+use clustrctrl::log_file;
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("clustrctrl-log-{name}-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("temp is writable");
+    dir
+}
+
+#[test]
+fn unwritable_paths_are_a_warning() {
+    let dir = scratch("unwritable");
+    // Already a directory, so there's no file to open
+    let taken = dir.join("log");
+    fs::create_dir(&taken).expect("temp is writable");
+    let warning = log_file::open(&taken).expect_err("it's a directory");
+    assert!(warning.starts_with("can't write log file"), "{warning}");
+    assert!(warning.contains(&taken.display().to_string()), "{warning}");
+    assert!(
+        warning.ends_with("Logging to the Message Stream only"),
+        "{warning}"
+    );
+
+    // Its directory would have to go where a regular file is
+    let file = dir.join("not-a-dir");
+    fs::write(&file, "").expect("temp is writable");
+    let through = file.join("log");
+    let warning = log_file::open(&through).expect_err("a file's in the way");
+    assert!(
+        warning.contains(&through.display().to_string()),
+        "{warning}"
+    );
+    fs::remove_dir_all(dir).expect("it's ours");
+}
+
+#[test]
+fn missing_directories_are_made() {
+    let dir = scratch("nested");
+    let path = dir.join("deep").join("er").join("log");
+    let mut appender = log_file::open(&path).expect("all writable");
+    appender.write_all(b"hello\n").expect("it's open");
+    appender.flush().expect("it's open");
+    assert_eq!(fs::read_to_string(&path).expect("it's there"), "hello\n");
+    fs::remove_dir_all(dir).expect("it's ours");
+}
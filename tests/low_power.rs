@@ -0,0 +1,116 @@
+//! Low power: how often a sparse worker gets to report, a real one sticking to it for a whole
+//! run, and `b` saying so in the title, from the main screen only
+
+use std::time::{Duration, Instant};
+
+use clustrctrl::{
+    app::App,
+    chaos::ChaosConfig,
+    clock::SystemClock,
+    config::Config,
+    tasks::{
+        Orders, ReportGate, ReportingMode, Task, TaskRxMsg, TaskTxMsg, WorkerStatus, MIN_INTENSITY,
+        SPARSE_INTERVAL,
+    },
+    CandidateTask,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+use tokio::{sync::mpsc, time::timeout};
+
+#[test]
+fn sparse_reports_are_five_seconds_apart() {
+    let start = Instant::now();
+    let second = Duration::from_secs(1);
+    let mut normal = ReportGate::default();
+    let mut sparse = ReportGate::default();
+    let (mut heard, mut sparse_heard) = (0, 0);
+    // A block a second for 30 seconds, the last of them last
+    for block in 1..=30 {
+        let now = start + second * block;
+        let last = block == 30;
+        heard += u32::from(normal.due(ReportingMode::Normal, now, last));
+        sparse_heard += u32::from(sparse.due(ReportingMode::Sparse, now, last));
+    }
+    assert_eq!(heard, 30);
+    // The first, one every 5s after it, and the last on top
+    assert_eq!(sparse_heard, 7);
+
+    // Never two inside the interval, bar the last block
+    let mut gate = ReportGate::default();
+    assert!(gate.due(ReportingMode::Sparse, start, false));
+    assert!(!gate.due(ReportingMode::Sparse, start + SPARSE_INTERVAL / 2, false));
+    assert!(gate.due(ReportingMode::Sparse, start + SPARSE_INTERVAL / 2, true));
+    assert!(!gate.due(ReportingMode::Sparse, start + SPARSE_INTERVAL, false));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_sparse_worker_keeps_it_down() {
+    let (tx, mut rx) = mpsc::channel(32);
+    let ct = CandidateTask::new("Test dummy", "Pretends to work until told otherwise");
+    let mut task = Task::pending(&ct, 0, 1);
+    task.target_secs = Some(8);
+    let orders = Orders {
+        reporting: ReportingMode::Sparse,
+        ..Orders::default()
+    };
+    let started = Instant::now();
+    task.activate(
+        tx,
+        MIN_INTENSITY,
+        ChaosConfig::default(),
+        orders,
+        &SystemClock,
+    )
+    .expect("there's a runtime right here");
+    let (mut reports, mut finished) = (0, false);
+    while let Some(msg) = timeout(Duration::from_secs(30), rx.recv())
+        .await
+        .expect("worker went quiet")
+    {
+        match msg.msg {
+            TaskTxMsg::StatusChange {
+                status: WorkerStatus::OnStrike { .. },
+                ..
+            } => assert!(task.tell(TaskRxMsg::Bargain(1), &SystemClock)),
+            TaskTxMsg::Progress { pct, .. } => {
+                reports += 1;
+                finished |= pct == 99;
+            }
+            _ => {}
+        }
+    }
+    let allowed = started.elapsed().as_secs() / SPARSE_INTERVAL.as_secs() + 2;
+    assert!(finished, "the last block always reports");
+    assert!(reports <= allowed, "{reports} reports, {allowed} allowed");
+}
+
+fn title(app: &mut App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(100, 20)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    (0..100).map(|x| buffer[(x, 0)].symbol()).collect()
+}
+
+#[test]
+fn b_says_so_in_the_title() {
+    let mut app = App::with_tasks(Config::default(), vec![]);
+    assert!(!title(&mut app).contains("(low power)"));
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('b')));
+    let on = title(&mut app);
+    assert!(on.contains("work 1.0x (low power)"), "{on}");
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('b')));
+    assert!(!title(&mut app).contains("(low power)"));
+
+    // Main screen only, like the other toggles
+    app.handle_key_event(KeyEvent::from(KeyCode::F(8)));
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('b')));
+    app.handle_key_event(KeyEvent::from(KeyCode::Esc));
+    let off = title(&mut app);
+    assert!(
+        off.contains("work 1.0x") && !off.contains("(low power)"),
+        "{off}"
+    );
+}
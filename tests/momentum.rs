@@ -0,0 +1,85 @@
+//! Coloring Progress by how long it's sat still: the thresholds, sleeping never going red, and
+//! reports that say the same again not counting as movement
+
+use std::time::{Duration, Instant};
+
+use clustrctrl::{
+    candidates::CandidateTask,
+    momentum::{classify, Momentum, MomentumConfig},
+    tasks::{Task, TaskStatus},
+};
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+#[test]
+fn running_goes_green_yellow_red() {
+    let config = MomentumConfig::default();
+    let running = |still| classify(TaskStatus::Running, still, &config);
+    assert_eq!(running(Duration::ZERO), Some(Momentum::Moving));
+    assert_eq!(
+        running(Duration::from_millis(4_999)),
+        Some(Momentum::Moving)
+    );
+    assert_eq!(running(secs(5)), Some(Momentum::Idle));
+    assert_eq!(running(Duration::from_millis(19_999)), Some(Momentum::Idle));
+    assert_eq!(running(secs(20)), Some(Momentum::Stalled));
+}
+
+#[test]
+fn sleeping_never_stalls() {
+    let config = MomentumConfig::default();
+    let sleeping = |still| classify(TaskStatus::Sleeping, still, &config);
+    assert_eq!(sleeping(secs(1)), Some(Momentum::Moving));
+    assert_eq!(sleeping(secs(10)), Some(Momentum::Idle));
+    assert_eq!(sleeping(secs(600)), Some(Momentum::Idle));
+}
+
+#[test]
+fn only_working_tasks_get_one() {
+    let config = MomentumConfig::default();
+    for status in [
+        TaskStatus::Scheduled,
+        TaskStatus::KnownUnknown,
+        TaskStatus::OnStrike,
+        TaskStatus::Finalizing,
+        TaskStatus::WaitingForThread,
+        TaskStatus::Finished,
+        TaskStatus::Failed,
+        TaskStatus::Canceled,
+    ] {
+        assert_eq!(classify(status, secs(60), &config), None, "{status}");
+    }
+}
+
+#[test]
+fn thresholds_come_from_the_config() {
+    let config = MomentumConfig {
+        idle_ms: 1_000,
+        stalled_ms: 3_000,
+    };
+    assert_eq!(
+        classify(TaskStatus::Running, secs(2), &config),
+        Some(Momentum::Idle)
+    );
+    assert_eq!(
+        classify(TaskStatus::Running, secs(3), &config),
+        Some(Momentum::Stalled)
+    );
+}
+
+#[test]
+fn only_a_new_value_counts_as_moving() {
+    let mut task = Task::pending(&CandidateTask::new("Sort", "sorting"), 0, 1);
+    let start = Instant::now();
+    // Never started and never reported, so there's nothing to count from
+    assert_eq!(task.still_for(start), None);
+    task.progressed(10, 0, start);
+    assert_eq!(task.progress_changed_at, Some(start));
+    // The same again a while later is still sitting at 10
+    task.progressed(10, 5, start + secs(8));
+    assert_eq!(task.still_for(start + secs(8)), Some(secs(8)));
+    task.progressed(11, 5, start + secs(9));
+    assert_eq!(task.still_for(start + secs(10)), Some(secs(1)));
+}
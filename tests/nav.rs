@@ -0,0 +1,87 @@
+//! Cursor moves at the edges: nothing to move through, one row, and pages that land right on
+//! the end. Jumps to the next match, round the end or nowhere
+
+use clustrctrl::nav::{next_match, ListNav, Move};
+
+const EVERY_MOVE: [Move; 6] = [
+    Move::Next,
+    Move::Previous,
+    Move::First,
+    Move::Last,
+    Move::PageDown,
+    Move::PageUp,
+];
+
+#[test]
+fn empty_lists_select_nothing() {
+    for wrap in [true, false] {
+        for to in EVERY_MOVE {
+            assert_eq!(ListNav::new(wrap).apply(None, 0, 5, to), None);
+            // Even if something was selected before everything went away
+            assert_eq!(ListNav::new(wrap).apply(Some(3), 0, 5, to), None);
+        }
+    }
+}
+
+#[test]
+fn one_row_has_nowhere_to_go() {
+    for wrap in [true, false] {
+        for to in EVERY_MOVE {
+            assert_eq!(ListNav::new(wrap).apply(Some(0), 1, 5, to), Some(0));
+            assert_eq!(ListNav::new(wrap).apply(None, 1, 5, to), Some(0));
+        }
+    }
+}
+
+#[test]
+fn pages_stop_at_the_ends() {
+    let nav = ListNav::new(true);
+    // Two pages exactly
+    assert_eq!(nav.apply(Some(0), 10, 5, Move::PageDown), Some(5));
+    assert_eq!(nav.apply(Some(5), 10, 5, Move::PageDown), Some(9));
+    assert_eq!(nav.apply(Some(4), 10, 5, Move::PageDown), Some(9));
+    assert_eq!(nav.apply(Some(9), 10, 5, Move::PageDown), Some(9));
+    assert_eq!(nav.apply(Some(5), 10, 5, Move::PageUp), Some(0));
+    assert_eq!(nav.apply(Some(0), 10, 5, Move::PageUp), Some(0));
+    // Nothing fits on screen, but paging still goes somewhere
+    assert_eq!(nav.apply(Some(2), 10, 0, Move::PageDown), Some(3));
+    assert_eq!(nav.apply(None, 10, 5, Move::Last), Some(9));
+    assert_eq!(nav.apply(None, 10, 5, Move::PageUp), Some(0));
+}
+
+#[test]
+fn wrapping_is_one_flag() {
+    let (wraps, stops) = (ListNav::new(true), ListNav::new(false));
+    assert_eq!(wraps.apply(Some(9), 10, 5, Move::Next), Some(0));
+    assert_eq!(wraps.apply(Some(0), 10, 5, Move::Previous), Some(9));
+    assert_eq!(stops.apply(Some(9), 10, 5, Move::Next), Some(9));
+    assert_eq!(stops.apply(Some(0), 10, 5, Move::Previous), Some(0));
+    // A selection left over from a longer list comes back in range first
+    assert_eq!(stops.apply(Some(15), 10, 5, Move::Next), Some(9));
+    assert_eq!(wraps.apply(Some(15), 10, 5, Move::Next), Some(0));
+}
+
+#[test]
+fn jumps_go_round_the_end() {
+    let strikes = [false, true, false, false, true, false];
+    let matches = |row: usize| strikes[row];
+    assert_eq!(next_match(Some(1), 6, true, matches), Some(4));
+    assert_eq!(next_match(Some(4), 6, true, matches), Some(1));
+    assert_eq!(next_match(Some(5), 6, true, matches), Some(1));
+    assert_eq!(next_match(Some(4), 6, false, matches), Some(1));
+    assert_eq!(next_match(Some(1), 6, false, matches), Some(4));
+    assert_eq!(next_match(Some(0), 6, false, matches), Some(4));
+    // Nothing selected goes from the top, or from the bottom going back
+    assert_eq!(next_match(None, 6, true, matches), Some(1));
+    assert_eq!(next_match(None, 6, false, matches), Some(4));
+}
+
+#[test]
+fn jumps_with_nowhere_to_go() {
+    // The only match is where we are already
+    assert_eq!(next_match(Some(2), 4, true, |row| row == 2), Some(2));
+    assert_eq!(next_match(Some(2), 4, false, |row| row == 2), Some(2));
+    assert_eq!(next_match(Some(2), 4, true, |_| false), None);
+    assert_eq!(next_match(None, 4, false, |_| false), None);
+    assert_eq!(next_match(None, 0, true, |_| true), None);
+}
@@ -0,0 +1,76 @@
+//! The order `App::update` does things in: a stopped worker's `CancelReport` and its finished
+//! handle, taken the wrong way round and then the way `step` takes them
+
+use std::time::{Duration, Instant};
+
+use clustrctrl::{
+    app::App,
+    config::Config,
+    tasks::{CancelState, TaskStatus, MIN_INTENSITY},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+
+/// Way more than any of these should take. Hitting it means something's stuck
+const PATIENCE: Duration = Duration::from_secs(15);
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key_event(KeyEvent::from(code));
+}
+
+/// An app with one task that's been told to stop, has said so, and whose worker's returned.
+/// Its `CancelReport` is still sitting in the channel, unread
+async fn stopped_but_unheard() -> App {
+    let mut config = Config::default();
+    config.work.intensity = MIN_INTENSITY;
+    let mut app = App::with_tasks(config, vec![]);
+    press(&mut app, KeyCode::F(1));
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::F(2));
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.tasks()[0].cancel, CancelState::Requested);
+    let given_up = Instant::now() + PATIENCE;
+    while !app.tasks()[0].is_done() {
+        assert!(Instant::now() < given_up, "the worker never stopped");
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    app
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn harvesting_first_loses_the_cancel_report() {
+    let mut app = stopped_but_unheard().await;
+    // The race: the handle's taken before the channel's read, so it looks like it finished
+    let done = app.finished_workers();
+    app.reconcile(&done).await;
+    app.drain_messages();
+    // Heard, but too late to say how it ended
+    assert_eq!(app.tasks()[0].status, TaskStatus::Finished);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_step_hears_the_last_word_first() {
+    let mut app = stopped_but_unheard().await;
+    assert!(app.step().await);
+    let task = &app.tasks()[0];
+    assert_eq!(task.status, TaskStatus::Canceled);
+    assert_eq!(task.cancel, CancelState::Acked);
+    assert!(task.cancel_acked_at.is_some());
+    assert!(!task.completion.is_running(), "harvested in the same step");
+    // And it stays that way
+    assert!(!app.step().await);
+    assert_eq!(app.tasks()[0].status, TaskStatus::Canceled);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_workers_done_before_the_drain_are_harvested() {
+    let mut app = stopped_but_unheard().await;
+    // Taken before the worker returned, as far as it knows
+    let before = vec![];
+    app.drain_messages();
+    assert!(!app.reconcile(&before).await);
+    let task = &app.tasks()[0];
+    assert!(task.completion.is_running(), "left for the next pass");
+    assert_eq!(task.status, TaskStatus::Canceled);
+    assert!(app.step().await);
+    assert!(!app.tasks()[0].completion.is_running());
+}
@@ -0,0 +1,53 @@
+//! The root check with made-up uids: refused without the flag, a warning with it, no opinion
+//! when there's no uid at all, and ROOT in the title when it's going ahead
+
+use clap::Parser;
+use clustrctrl::{
+    app::App,
+    config::{Cli, Config},
+    preflight::{self, Root},
+};
+use ratatui::{backend::TestBackend, Terminal};
+
+#[test]
+fn root_needs_the_flag() {
+    assert_eq!(preflight::decide(Some(0), false), Root::Refuse);
+    assert_eq!(preflight::decide(Some(0), true), Root::Allowed);
+    for allow_root in [false, true] {
+        assert_eq!(preflight::decide(Some(1000), allow_root), Root::No);
+        // Nothing to go on off unix, so it's not in the way
+        assert_eq!(preflight::decide(None, allow_root), Root::No);
+    }
+
+    let refused = preflight::check_root(Some(0), false).expect_err("root without the flag");
+    assert!(refused.contains("--allow-root"), "{refused}");
+    let warning = preflight::check_root(Some(0), true)
+        .expect("the flag lets it through")
+        .expect("with a warning");
+    assert!(warning.contains("root"), "{warning}");
+    assert_eq!(preflight::check_root(Some(1000), false), Ok(None));
+    assert_eq!(preflight::check_root(None, false), Ok(None));
+}
+
+#[test]
+fn the_flag_parses() {
+    assert!(Cli::parse_from(["clustrctrl", "--allow-root"]).allow_root);
+    assert!(!Cli::parse_from(["clustrctrl"]).allow_root);
+}
+
+#[test]
+fn root_is_in_the_title() {
+    let title = |root: bool| {
+        let mut app = App::with_tasks(Config::default(), vec![]).with_root(root);
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).expect("nothing to fail");
+        terminal
+            .draw(|frame| app.view(frame))
+            .expect("nothing to fail");
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.width)
+            .map(|x| buffer[(x, 0)].symbol())
+            .collect::<String>()
+    };
+    assert!(title(true).contains(" ROOT  work 1.0x "), "{}", title(true));
+    assert!(!title(false).contains("ROOT"), "{}", title(false));
+}
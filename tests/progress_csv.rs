@@ -0,0 +1,109 @@
+//! The progress CSV: a header, a line per move, held in the buffer until it's been long enough
+//! or the writer's gone, and only moves making it in from the app
+
+use std::{env, fs, path::PathBuf, process, time::Duration};
+
+use chrono::{Local, SecondsFormat, TimeZone};
+use clustrctrl::{
+    app::App,
+    clock::{Clock, MockClock},
+    config::Config,
+    latency::Stamped,
+    progress_csv::{ProgressCsv, FLUSH_INTERVAL, HEADER},
+    tasks::{Task, TaskStatus, TaskTxMsg},
+    CandidateTask,
+};
+
+fn scratch(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("clustrctrl-{}-{name}.csv", process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn lines(path: &PathBuf) -> Vec<String> {
+    fs::read_to_string(path)
+        .expect("it's there")
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn flushed_now_and_then_and_on_drop() {
+    let path = scratch("writer");
+    let clock = MockClock::new();
+    let mut csv = ProgressCsv::create(&path, clock.now_instant()).expect("temp is writable");
+    let at = Local
+        .with_ymd_and_hms(2026, 3, 14, 15, 9, 26)
+        .single()
+        .expect("a real time");
+    csv.record(at, 0, 10).expect("just a buffer");
+    csv.record(at + Duration::from_millis(250), 1, 5)
+        .expect("just a buffer");
+    assert!(lines(&path).is_empty(), "nothing out until a flush");
+
+    clock.advance(FLUSH_INTERVAL / 2);
+    assert!(!csv.tick(clock.now_instant()).expect("nothing to fail"));
+    clock.advance(FLUSH_INTERVAL);
+    assert!(csv.tick(clock.now_instant()).expect("nothing to fail"));
+    let stamp = at.to_rfc3339_opts(SecondsFormat::Millis, false);
+    let later = (at + Duration::from_millis(250)).to_rfc3339_opts(SecondsFormat::Millis, false);
+    assert_eq!(
+        lines(&path),
+        [
+            HEADER.to_string(),
+            format!("{stamp},0,10"),
+            format!("{later},1,5")
+        ]
+    );
+
+    csv.record(at, 0, 20).expect("just a buffer");
+    drop(csv);
+    assert_eq!(lines(&path).last(), Some(&format!("{stamp},0,20")));
+
+    // Back on the end of it, with no second header
+    let mut again = ProgressCsv::create(&path, clock.now_instant()).expect("temp is writable");
+    again.record(at, 2, 99).expect("just a buffer");
+    again.close().expect("nothing to fail");
+    let all = lines(&path);
+    assert_eq!(all.len(), 5);
+    assert_eq!(all.iter().filter(|line| *line == HEADER).count(), 1);
+    let _ = fs::remove_file(&path);
+}
+
+fn progress(pct: u8) -> Stamped<TaskTxMsg> {
+    Stamped::new(TaskTxMsg::Progress {
+        id: 0,
+        pct,
+        fatigue: 0,
+        cpu_ms: 0,
+        cpu_estimated: false,
+    })
+}
+
+#[tokio::test]
+async fn only_moves_are_written() {
+    let path = scratch("app");
+    let clock = MockClock::new();
+    let csv = ProgressCsv::create(&path, clock.now_instant()).expect("temp is writable");
+    let mut task = Task::pending(&CandidateTask::new("Alpha", "Does a thing"), 0, 1);
+    task.status = TaskStatus::Running;
+    let mut app = App::with_tasks(Config::default(), vec![task])
+        .with_clock(Box::new(clock.clone()))
+        .with_progress_csv(csv);
+    let tx = app.task_sender();
+    for pct in [10, 10, 40, 40, 40, 75] {
+        tx.try_send(progress(pct)).expect("room in the channel");
+    }
+    assert!(app.step().await);
+    // Dropped without a clean exit, like a forced one, and it's all there anyway
+    drop(app);
+    let progress: Vec<String> = lines(&path)
+        .iter()
+        .skip(1)
+        .map(|line| line.rsplit_once(',').expect("three columns").1.to_string())
+        .collect();
+    assert_eq!(progress, ["10", "40", "75"]);
+    assert!(lines(&path)[1].ends_with(",0,10"));
+    let _ = fs::remove_file(&path);
+}
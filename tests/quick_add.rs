@@ -0,0 +1,119 @@
+//! `n` adding another of the last task added: nothing to repeat yet, the repeat itself, and the
+//! guards still getting a say
+
+use std::time::Duration;
+
+use clustrctrl::{
+    app::App,
+    clock::MockClock,
+    config::Config,
+    guards::{GuardConfig, Guards},
+    tasks::MIN_INTENSITY,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+use serde::Deserialize;
+
+/// What's on screen, wide enough for the whole controls line
+fn screen(app: &mut App) -> String {
+    let (width, height) = (200, 30);
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    (0..height)
+        .map(|y| (0..width).map(|x| buffer[(x, y)].symbol()).collect())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Lowest intensity, so the workers stop as soon as they're told
+fn config() -> Config {
+    let mut config = Config::default();
+    config.work.intensity = MIN_INTENSITY;
+    config
+}
+
+/// An app on a clock of its own, so adds can be spaced out further than a key bounce
+fn app(config: Config) -> (App, MockClock) {
+    let clock = MockClock::new();
+    let app = App::with_tasks(config, vec![]).with_clock(Box::new(clock.clone()));
+    (app, clock)
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key_event(KeyEvent::from(code));
+}
+
+#[test]
+fn nothing_to_repeat_at_first() {
+    let mut app = App::with_tasks(Config::default(), vec![]);
+    assert!(!screen(&mut app).contains("Again <N>"));
+    press(&mut app, KeyCode::Char('n'));
+    let shown = screen(&mut app);
+    assert!(shown.contains("Nothing to repeat"), "{shown}");
+    assert!(!shown.contains("Added "));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn the_last_add_comes_again() {
+    let (mut app, clock) = app(config());
+    press(&mut app, KeyCode::F(1));
+    press(&mut app, KeyCode::Enter);
+    let first = screen(&mut app);
+    assert!(first.contains("Again <N>"), "{first}");
+    clock.advance(Duration::from_secs(1));
+    press(&mut app, KeyCode::Char('n'));
+    let again = screen(&mut app);
+    let toast = again
+        .lines()
+        .find(|line| line.contains("Added "))
+        .unwrap_or_else(|| panic!("no toast in {again}"));
+    assert!(toast.contains("(#1)"), "{toast}");
+    // The picker's rerolled since, but it's the same candidate
+    clock.advance(Duration::from_secs(1));
+    press(&mut app, KeyCode::Char('n'));
+    assert!(screen(&mut app).contains("(#2)"));
+    // Or the runtime waits out their naps before it lets the test end
+    press(&mut app, KeyCode::F(3));
+}
+
+#[derive(Deserialize)]
+struct File {
+    guards: Vec<GuardConfig>,
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn guards_still_get_asked() {
+    let file: File = toml::from_str(
+        r#"
+        [[guards]]
+        pattern = "."
+        action = "confirm"
+        message = "everything's scary"
+        "#,
+    )
+    .expect("guards should parse");
+    let config = Config {
+        compiled_guards: Guards::compile(&file.guards, &mut vec![]),
+        ..config()
+    };
+    let (mut app, clock) = app(config);
+    press(&mut app, KeyCode::F(1));
+    press(&mut app, KeyCode::Enter);
+    assert!(screen(&mut app).contains("everything's scary"));
+    press(&mut app, KeyCode::Enter);
+    clock.advance(Duration::from_secs(1));
+    press(&mut app, KeyCode::Char('n'));
+    let asked = screen(&mut app);
+    assert!(asked.contains("everything's scary"), "{asked}");
+    assert!(!asked.contains("Added "));
+    // Turning it down adds nothing, and it's still there to repeat
+    press(&mut app, KeyCode::Esc);
+    clock.advance(Duration::from_secs(1));
+    press(&mut app, KeyCode::Char('n'));
+    assert!(screen(&mut app).contains("everything's scary"));
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::F(3));
+}
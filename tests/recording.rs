@@ -0,0 +1,124 @@
+//! Recordings written and read back, key names both ways, and the ways an old or broken one is
+//! turned away instead of replayed
+
+use std::{env, fs, path::PathBuf, process};
+
+use clustrctrl::{
+    recording::{self, Recorder, Recording, RecordingError, VERSION},
+    tasks::{TaskTxMsg, WorkerStatus},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A path of its own per test, since they run at the same time
+fn scratch(name: &str) -> PathBuf {
+    env::temp_dir().join(format!(
+        "clustrctrl-recording-{name}-{}.jsonl",
+        process::id()
+    ))
+}
+
+fn load(name: &str, text: &str) -> Result<Recording, RecordingError> {
+    let path = scratch(name);
+    fs::write(&path, text).unwrap();
+    let recording = Recording::load(&path);
+    fs::remove_file(&path).unwrap();
+    recording
+}
+
+#[test]
+fn keys_keep_their_names() {
+    let keys = [
+        (KeyEvent::new(KeyCode::F(12), KeyModifiers::NONE), "f12"),
+        (
+            KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL),
+            "ctrl+k",
+        ),
+        (
+            KeyEvent::new(KeyCode::Char('J'), KeyModifiers::SHIFT),
+            "shift+J",
+        ),
+        (KeyEvent::new(KeyCode::Char('+'), KeyModifiers::NONE), "+"),
+        (
+            KeyEvent::new(KeyCode::Char('+'), KeyModifiers::CONTROL),
+            "ctrl++",
+        ),
+        (
+            KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE),
+            "space",
+        ),
+        (KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE), "f"),
+        (
+            KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE),
+            "pagedown",
+        ),
+        (
+            KeyEvent::new(KeyCode::Enter, KeyModifiers::CONTROL | KeyModifiers::ALT),
+            "ctrl+alt+enter",
+        ),
+    ];
+    for (key, name) in keys {
+        assert_eq!(recording::key_name(&key).as_deref(), Some(name));
+        assert_eq!(recording::parse_key(name), Some(key), "{name}");
+    }
+    // Nothing in the app listens for these, so they're not worth naming
+    assert_eq!(
+        recording::key_name(&KeyEvent::new(KeyCode::CapsLock, KeyModifiers::NONE)),
+        None
+    );
+    assert_eq!(recording::parse_key("hyper+q"), None);
+}
+
+#[test]
+fn plays_back_what_was_recorded() {
+    let path = scratch("back");
+    let mut recorder = Recorder::create(&path, 42).unwrap();
+    let f1 = KeyEvent::new(KeyCode::F(1), KeyModifiers::NONE);
+    let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+    recorder.key(&f1).unwrap();
+    recorder
+        .task(&TaskTxMsg::StatusChange {
+            id: 0,
+            status: WorkerStatus::Sleeping,
+        })
+        .unwrap();
+    recorder.key(&enter).unwrap();
+    drop(recorder);
+
+    let recording = Recording::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(recording.seed, 42);
+    // Task messages are only there to read
+    let keys: Vec<KeyEvent> = recording.keys.iter().map(|&(_, key)| key).collect();
+    assert_eq!(keys, [f1, enter]);
+    assert!(recording.keys[0].0 <= recording.keys[1].0);
+}
+
+#[test]
+fn anything_off_fails_the_whole_load() {
+    let header = format!("{{\"clustrctrl_recording\":{VERSION},\"seed\":1}}\n");
+    let key = "{\"at_ms\":5,\"key\":\"f1\"}\n";
+    assert!(load("ok", &format!("{header}{key}\n{key}")).is_ok_and(|r| r.keys.len() == 2));
+
+    let old = format!(
+        "{{\"clustrctrl_recording\":{},\"seed\":1}}\n{key}",
+        VERSION + 1
+    );
+    assert!(matches!(load("version", &old), Err(RecordingError::Version(v)) if v == VERSION + 1));
+    assert!(matches!(
+        load("history", "{\"name\":\"Bingus\",\"status\":\"Finished\"}\n"),
+        Err(RecordingError::NotARecording)
+    ));
+    assert!(matches!(
+        load("empty", ""),
+        Err(RecordingError::NotARecording)
+    ));
+    // Counted from the top, header and all
+    assert!(matches!(
+        load("line", &format!("{header}{key}{{\"at_ms\":\"soon\"}}\n")),
+        Err(RecordingError::BadLine(3, _))
+    ));
+    assert!(matches!(
+        load("key", &format!("{header}{{\"at_ms\":9,\"key\":\"hyper+q\"}}\n")),
+        Err(RecordingError::UnknownKey(2, key)) if key == "hyper+q"
+    ));
+}
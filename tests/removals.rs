@@ -0,0 +1,64 @@
+//! Removals undone newest first, and discarded once they're too old or pushed out by newer ones,
+//! timed off a mock clock
+
+use std::time::Duration;
+
+use clustrctrl::{
+    clock::{Clock, MockClock},
+    removals::{Removals, UNDO_DEPTH, UNDO_TTL},
+};
+
+#[test]
+fn undo_goes_newest_first() {
+    let clock = MockClock::new();
+    let mut removals = Removals::default();
+    assert_eq!(removals.remove(3, clock.now_instant()), None);
+    assert_eq!(removals.remove(7, clock.now_instant()), None);
+    assert!(removals.contains(3) && removals.contains(7));
+    assert!(!removals.is_gone(3));
+    // Removing it twice doesn't put it in twice
+    assert_eq!(removals.remove(7, clock.now_instant()), None);
+    assert_eq!(removals.undoable(), 2);
+    assert_eq!(removals.undo(clock.now_instant()), Some(7));
+    assert!(!removals.contains(7));
+    assert_eq!(removals.undo(clock.now_instant()), Some(3));
+    assert_eq!(removals.undo(clock.now_instant()), None);
+}
+
+#[test]
+fn too_old_to_undo() {
+    let clock = MockClock::new();
+    let mut removals = Removals::default();
+    removals.remove(1, clock.now_instant());
+    clock.advance(UNDO_TTL / 2);
+    removals.remove(2, clock.now_instant());
+    // Right on the TTL is still in time
+    clock.advance(UNDO_TTL / 2);
+    assert!(removals.expire(clock.now_instant()).is_empty());
+    clock.advance(Duration::from_millis(1));
+    assert_eq!(removals.expire(clock.now_instant()), [1]);
+    assert!(removals.is_gone(1));
+    assert!(!removals.is_gone(2));
+    // Past it before expire gets round to it is too late as well
+    clock.advance(UNDO_TTL);
+    assert_eq!(removals.undo(clock.now_instant()), None);
+    assert!(removals.contains(2));
+    assert_eq!(removals.expire(clock.now_instant()), [2]);
+    assert_eq!(removals.undoable(), 0);
+}
+
+#[test]
+fn newer_removals_push_out_the_oldest() {
+    let clock = MockClock::new();
+    let mut removals = Removals::default();
+    for id in 0..UNDO_DEPTH {
+        assert_eq!(removals.remove(id, clock.now_instant()), None);
+    }
+    assert_eq!(removals.remove(UNDO_DEPTH, clock.now_instant()), Some(0));
+    assert!(removals.is_gone(0));
+    assert_eq!(removals.undoable(), UNDO_DEPTH);
+    // Gone for good can't come back, however many undos
+    while removals.undo(clock.now_instant()).is_some() {}
+    assert!(removals.contains(0));
+    assert!(!removals.contains(1));
+}
@@ -0,0 +1,240 @@
+//! Whole screens drawn into a `TestBackend`: no tasks, a few at different stages, the picker
+//! open, a table with exactly as many tasks as it has rows, a logger squeezed down to its borders,
+//! and a terminal too small for any of it to fit comfortably
+
+use clustrctrl::{
+    app::App,
+    config::Config,
+    tasks::{Task, TaskStatus},
+    CandidateTask,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+
+/// What's on screen, a string per row, without the styling
+fn draw(app: &mut App, width: u16, height: u16) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect()
+}
+
+// As drawn, styling aside. Anything that moves a character of these needs new ones, on purpose
+const THREE_TASKS: &str = "\
+┏  clustrctrl  ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ work 1.0x ┓
+┃                                                                              ┃
+┃  ┌ Task Table ────────────────────────────────────────────────────────────┐  ┃
+┃  │   ID   Name                 Status                   Progress          │  ┃
+┃  │▎◆ 0    Alpha                Running                  40%               │  ┃
+┃  │▎✔ 1    Beta                 Done                     100%              │  ┃
+┃  │▎✘ 2    Gamma                Failed                   0%                │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  └────────────────────────────────────────────────────────────────────────┘  ┃
+┃  ┌ Message Stream ────────────────────────────────────────────────────────┐  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  └────────────────────────────────────────────────────────────────────────┘  ┃
+┃                                                                              ┃
+┃                                                                              ┃
+┃                        ◆ working  ✔ done  ✘ failed                           ┃
+┃                                                                              ┃
+┗━━ New Task <F1> Manage Tasks <F2> Batch <F7> Timeline <F8> Minimize <F9> … ━━┛";
+
+const PICKER: &str = "\
+┏  clustrctrl ━ [task add] ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ work 1.0x ┓
+┃                                                                              ┃
+┃  ┌ Task Table ────────────────────────────────────────────────────────────┐  ┃
+┃  │   ID   Name                 Status                   Progress          │  ┃
+┃  │▎◆ 0    Alpha                Running                  40%               │  ┃
+┃  │▎✔ 1    Beta                 Done                     100%              │  ┃
+┃  │▎✘ 2    Gamma                Failed                   0%                │  ┃
+┃  │                                                                        │  ┃
+┃  │  ┌ New Task ────────────────────────────────────────────────────────┐  │  ┃
+┃  │  │  (Bingus): <REDACTED>                                            │  │  ┃
+┃  └──│  (Todd Bonzalez): Uninstall gravity temporarily                  │──┘  ┃
+┃  ┌ M│> (Dean Wesrey): Re-enact fax machine error codes via mime        │──┐  ┃
+┃  │  │  (Anatoli Smorin): Revandalize fence                             │  │  ┃
+┃  │  │  (Sleve McDichael): Re-attach turbo encabulator                  │  │  ┃
+┃  │  │  (Raul Chamgerlain): Translate whale songs into Excel formulas   │  │  ┃
+┃  │  └── Pick for Me! <R> Pick Selected <ENTER> Choose Duration <TAB> ──┘  │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  └────────────────────────────────────────────────────────────────────────┘  ┃
+┃                                                                              ┃
+┃                                                                              ┃
+┃                        ◆ working  ✔ done  ✘ failed                           ┃
+┃                                                                              ┃
+┗━━━━━━━━━━━ Back <ESC> Set Duration <TAB> Edit Pool <E> Quit <F3> ━━━━━━━━━━━━┛";
+
+const EMPTY: &str = "\
+┏  clustrctrl  ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ work 1.0x ┓
+┃                                                                              ┃
+┃  ┌ Task Table ────────────────────────────────────────────────────────────┐  ┃
+┃  │                                                                        │  ┃
+┃  │                   No tasks yet — press F1 to add one                   │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  └────────────────────────────────────────────────────────────────────────┘  ┃
+┃  ┌ Message Stream ────────────────────────────────────────────────────────┐  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  └────────────────────────────────────────────────────────────────────────┘  ┃
+┃                                                                              ┃
+┃                                                                              ┃
+┃                                                                              ┃
+┃                                                                              ┃
+┗━━ New Task <F1> Manage Tasks <F2> Batch <F7> Timeline <F8> Minimize <F9> … ━━┛";
+
+const SMALL: &str = "\
+┏  clustrctrl  ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ work 1.0x ┓
+┃                                                          ┃
+┃  ┌ Task Table ────────────────────────────────────────┐  ┃
+┃  │   ID   Name           Status        Progress       │  ┃
+┃  │▎◆ 0    Alpha          Running       40%            │  ┃
+┃  │                                                    │  ┃
+┃  └────────────────────────────────────────────────────┘  ┃
+┃                                                          ┃
+┃                                                          ┃
+┃                  ◆ working  ◷ waiting                    ┃
+┃                                                          ┃
+┗━━━━━━ New Task <F1> Manage Tasks <F2> Batch <F7> … ━━━━━━┛";
+
+const FULL: &str = "\
+┏  clustrctrl  ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ work 1.0x ┓
+┃                                                                              ┃
+┃  ┌ Task Table ────────────────────────────────────────────────────────────┐  ┃
+┃  │   ID   Name                 Status                   Progress          │  ┃
+┃  │▎◷ 0    Task0                Waiting                  -                 │  ┃
+┃  │▎◷ 1    Task1                Waiting                  -                 │  ┃
+┃  │▎◷ 2    Task2                Waiting                  -                 │  ┃
+┃  │▎◷ 3    Task3                Waiting                  -                 │  ┃
+┃  │▎◷ 4    Task4                Waiting                  -                 │  ┃
+┃  │                                                                        │  ┃
+┃  └────────────────────────────────────────────────────────────────────────┘  ┃
+┃  ┌ Message Stream ────────────────────────────────────────────────────────┐  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  └────────────────────────────────────────────────────────────────────────┘  ┃
+┃                                                                              ┃
+┃                                                                              ┃
+┃                                 ◷ waiting                                    ┃
+┃                                                                              ┃
+┗━━ New Task <F1> Manage Tasks <F2> Batch <F7> Timeline <F8> Minimize <F9> … ━━┛";
+
+const TINY_LOGGER: &str = "\
+┏  clustrctrl  ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ work 1.0x ┓
+┃                                                                              ┃
+┃  ┌ Task Table ────────────────────────────────────────────────────────────┐  ┃
+┃  │   ID   Name                 Status                   Progress          │  ┃
+┃  │▎◆ 0    Alpha                Running                  40%               │  ┃
+┃  │▎✔ 1    Beta                 Done                     100%              │  ┃
+┃  │▎✘ 2    Gamma                Failed                   0%                │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  │                                                                        │  ┃
+┃  └────────────────────────────────────────────────────────────────────────┘  ┃
+┃  ┌ Message Stream ────────────────────────────────────────────────────────┐  ┃
+┃  └────────────────────────────────────────────────────────────────────────┘  ┃
+┃                                                                              ┃
+┃                                                                              ┃
+┃                        ◆ working  ✔ done  ✘ failed                           ┃
+┃                                                                              ┃
+┗━━ New Task <F1> Manage Tasks <F2> Batch <F7> Timeline <F8> Minimize <F9> … ━━┛";
+
+/// Three tasks at different stages, none with a worker behind it
+fn three_tasks() -> Vec<Task> {
+    let mut tasks: Vec<Task> = ["Alpha", "Beta", "Gamma"]
+        .into_iter()
+        .enumerate()
+        .map(|(id, name)| Task::pending(&CandidateTask::new(name, "Does a thing"), id, 1))
+        .collect();
+    tasks[0].status = TaskStatus::Running;
+    tasks[0].progress = 40;
+    tasks[1].status = TaskStatus::Finished;
+    tasks[1].progress = 100;
+    tasks[2].status = TaskStatus::Failed;
+    tasks
+}
+
+/// `count` tasks that haven't started
+fn waiting(count: usize) -> Vec<Task> {
+    (0..count)
+        .map(|id| {
+            Task::pending(
+                &CandidateTask::new(format!("Task{id}"), "Does a thing"),
+                id,
+                1,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn nothing_yet_says_so() {
+    let mut app = App::with_tasks(Config::default(), vec![]);
+    assert_eq!(draw(&mut app, 80, 24).join("\n"), EMPTY);
+}
+
+#[test]
+fn three_tasks_and_their_legend() {
+    let mut app = App::with_tasks(Config::default(), three_tasks());
+    assert_eq!(draw(&mut app, 80, 24).join("\n"), THREE_TASKS);
+}
+
+#[test]
+fn picker_opens_over_the_table() {
+    let mut app = App::with_tasks(Config::default(), three_tasks());
+    app.handle_key_event(KeyEvent::from(KeyCode::F(1)));
+    assert_eq!(draw(&mut app, 80, 24).join("\n"), PICKER);
+}
+
+#[test]
+fn small_terminals_give_up_the_message_stream() {
+    let mut tasks = three_tasks();
+    for task in &mut tasks[1..] {
+        task.status = TaskStatus::Scheduled;
+    }
+    let mut app = App::with_tasks(Config::default(), tasks);
+    assert_eq!(draw(&mut app, 60, 12).join("\n"), SMALL);
+}
+
+#[test]
+fn exactly_full_table() {
+    // Five rows is all the table gets at 80x24, so the last one's down against the breathing room
+    let mut app = App::with_tasks(Config::default(), waiting(5));
+    assert_eq!(draw(&mut app, 80, 24).join("\n"), FULL);
+    // And one more is off the bottom, not squeezed in
+    let mut app = App::with_tasks(Config::default(), waiting(6));
+    assert_eq!(draw(&mut app, 80, 24).join("\n"), FULL);
+}
+
+#[test]
+fn tiny_logger_is_just_its_borders() {
+    let mut app = App::with_tasks(Config::default(), three_tasks());
+    assert_eq!(draw(&mut app, 80, 18).join("\n"), TINY_LOGGER);
+}
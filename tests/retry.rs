@@ -0,0 +1,91 @@
+//! When failed tasks get tried again: off, backing off and doubling, giving up after the last
+//! try, and every attempt found from any one of them
+
+use std::time::Duration;
+
+use clustrctrl::{
+    clock::{Clock, MockClock},
+    retry::{self, Attempt, Decision, RetryPolicy, MAX_BACKOFF},
+    tasks::Task,
+    CandidateTask,
+};
+
+fn policy(max_retries: u32, backoff_secs: u64) -> RetryPolicy {
+    RetryPolicy {
+        max_retries,
+        backoff_secs,
+    }
+}
+
+#[test]
+fn no_retries_means_nothing_happens() {
+    let clock = MockClock::new();
+    let now = clock.now_instant();
+    assert_eq!(
+        retry::decide(&RetryPolicy::default(), 1, now),
+        Decision::Off
+    );
+    // Not even a "last try", there was only ever going to be one
+    assert_eq!(retry::decide(&policy(0, 5), 4, now), Decision::Off);
+}
+
+#[test]
+fn backoff_doubles_until_the_last_try() {
+    let clock = MockClock::new();
+    let policy = policy(3, 5);
+    let now = clock.now_instant();
+    assert_eq!(
+        retry::decide(&policy, 1, now),
+        Decision::Retry {
+            next: Attempt { n: 2, of: 4 },
+            at: now + Duration::from_secs(5),
+        }
+    );
+    clock.advance(Duration::from_secs(30));
+    let later = clock.now_instant();
+    assert_eq!(
+        retry::decide(&policy, 3, later),
+        Decision::Retry {
+            next: Attempt { n: 4, of: 4 },
+            at: later + Duration::from_secs(20),
+        }
+    );
+    assert_eq!(retry::decide(&policy, 4, later), Decision::GiveUp);
+    assert_eq!(Attempt { n: 2, of: 4 }.to_string(), "attempt 2/4");
+}
+
+#[test]
+fn backoff_stops_growing() {
+    let policy = policy(100, 60);
+    assert_eq!(policy.backoff(2), Duration::from_secs(60));
+    assert_eq!(policy.backoff(6), MAX_BACKOFF);
+    // Far past where doubling would overflow
+    assert_eq!(policy.backoff(99), MAX_BACKOFF);
+}
+
+#[test]
+fn attempts_are_found_from_any_of_them() {
+    // 3 failed and was tried again as 7, then 9. 5 has nothing to do with it
+    let links = [(3, None), (5, None), (7, Some(3)), (9, Some(3))];
+    for id in [3, 7, 9] {
+        assert_eq!(retry::attempts(links, id), [3, 7, 9]);
+    }
+    assert_eq!(retry::attempts(links, 5), [5]);
+    assert_eq!(retry::describe(&[3, 7, 9]), "#3 ⇢ #7 ⇢ #9");
+}
+
+#[test]
+fn retries_point_back_at_the_first_try() {
+    let ct = CandidateTask {
+        retry: Some(policy(2, 1)),
+        ..CandidateTask::new("Flaky", "Fails now and then")
+    };
+    let first = Task::pending(&ct, 0, 1);
+    let second = first.retry(4, Attempt { n: 2, of: 3 });
+    let third = second.retry(8, Attempt { n: 3, of: 3 });
+    assert_eq!((second.id, second.retry_of), (4, Some(0)));
+    assert_eq!((third.id, third.retry_of), (8, Some(0)));
+    assert_eq!(third.name, first.name);
+    assert_eq!(third.retry, ct.retry);
+    assert_eq!(third.attempt, Some(Attempt { n: 3, of: 3 }));
+}
@@ -0,0 +1,40 @@
+//! The numbers the debug overlay (and whoever else) gets out of a window of samples
+
+use clustrctrl::stats::RollingStats;
+
+fn filled(capacity: usize, samples: impl IntoIterator<Item = f64>) -> RollingStats {
+    let mut stats = RollingStats::new(capacity);
+    samples.into_iter().for_each(|sample| stats.push(sample));
+    stats
+}
+
+#[test]
+fn nothing_to_say_when_empty() {
+    let stats = RollingStats::new(10);
+    assert!(stats.is_empty());
+    assert_eq!(stats.percentile(0.5), None);
+    assert_eq!(stats.max(), None);
+    assert_eq!(stats.mean(), None);
+}
+
+#[test]
+fn oldest_make_room() {
+    let stats = filled(3, [1.0, 2.0, 3.0, 4.0, 5.0]);
+    assert_eq!(stats.len(), 3);
+    assert_eq!(stats.samples().collect::<Vec<_>>(), [3.0, 4.0, 5.0]);
+    assert_eq!(stats.mean(), Some(4.0));
+}
+
+#[test]
+fn percentiles_are_real_samples() {
+    // Shuffled, since it's the sorted order that counts
+    let stats = filled(120, (1..=100).rev().map(f64::from));
+    assert_eq!(stats.percentile(0.5), Some(50.0));
+    assert_eq!(stats.percentile(0.95), Some(95.0));
+    assert_eq!(stats.percentile(1.0), stats.max());
+    assert_eq!(stats.percentile(0.0), Some(1.0));
+    // A single slow frame is the max but doesn't drag the median along
+    let spiky = filled(10, [1.0, 1.0, 1.0, 1.0, 40.0]);
+    assert_eq!(spiky.percentile(0.5), Some(1.0));
+    assert_eq!(spiky.max(), Some(40.0));
+}
@@ -0,0 +1,76 @@
+//! Session names: the made-up ones, which `--session` takes, and the file names they end up on
+
+use std::{env, fs, path::Path, process};
+
+use clap::Parser;
+use clustrctrl::{config::Cli, session};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn made_up_names_are_adjective_animal_number() {
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..50 {
+        let name = session::generate(&mut rng);
+        let parts: Vec<&str> = name.split('-').collect();
+        assert_eq!(parts.len(), 3, "{name}");
+        assert!(parts[..2].iter().all(|part| !part.is_empty()), "{name}");
+        let number: u32 = parts[2].parse().expect("should end in a number");
+        assert!((10..100).contains(&number), "{name}");
+        // Whatever's made up has to get past `--session` too
+        assert_eq!(session::parse(&name), Ok(name.clone()));
+    }
+    // The same dice, the same name
+    let again = |seed| session::generate(&mut StdRng::seed_from_u64(seed));
+    assert_eq!(again(3), again(3));
+}
+
+#[test]
+fn only_file_name_safe_names_are_taken() {
+    let parse = |args: &[&str]| Cli::try_parse_from([&["clustrctrl"], args].concat());
+    let cli = parse(&["--session", "demo_2-b"]).expect("should be fine");
+    assert_eq!(cli.session.as_deref(), Some("demo_2-b"));
+    assert_eq!(parse(&[]).expect("it's optional").session, None);
+    for bad in ["", "../up", "a b", "emoji🦦"] {
+        assert!(parse(&["--session", bad]).is_err(), "{bad:?}");
+    }
+}
+
+#[test]
+fn the_name_goes_before_the_extension() {
+    assert_eq!(
+        session::suffixed(Path::new("logs/clustrctrl.log"), "wobbly-otter-42"),
+        Path::new("logs/clustrctrl-wobbly-otter-42.log")
+    );
+    assert_eq!(
+        session::suffixed(Path::new("./log"), "demo"),
+        Path::new("./log-demo")
+    );
+}
+
+#[test]
+fn taken_names_get_a_count() {
+    let dir = env::temp_dir().join(format!("clustrctrl-session-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("temp dir should be writable");
+    let report = dir.join("report.json");
+    assert_eq!(
+        session::unique(&report, "demo"),
+        dir.join("report-demo.json")
+    );
+    fs::write(dir.join("report-demo.json"), "{}").expect("temp dir should be writable");
+    assert_eq!(
+        session::unique(&report, "demo"),
+        dir.join("report-demo-2.json")
+    );
+    fs::write(dir.join("report-demo-2.json"), "{}").expect("temp dir should be writable");
+    assert_eq!(
+        session::unique(&report, "demo"),
+        dir.join("report-demo-3.json")
+    );
+    // Another session doesn't care what this one left
+    assert_eq!(
+        session::unique(&report, "other"),
+        dir.join("report-other.json")
+    );
+    let _ = fs::remove_dir_all(&dir);
+}
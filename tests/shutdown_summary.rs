@@ -0,0 +1,83 @@
+//! The table printed at exit: a made-up snapshot laid out, a clean exit, and which tasks count as
+//! left behind
+
+use std::path::PathBuf;
+
+use chrono::{Local, TimeDelta};
+use clustrctrl::{
+    shutdown_summary::{Abandoned, ShutdownSummary},
+    summary::TaskSummary,
+    tasks::{Task, TaskStatus},
+    CandidateTask,
+};
+
+fn abandoned(id: usize, name: &str, status: &str, progress: u8, secs: Option<i64>) -> Abandoned {
+    Abandoned {
+        task: TaskSummary {
+            id,
+            name: name.to_string(),
+            status: status.to_string(),
+        },
+        progress,
+        stop_sent_secs: secs,
+    }
+}
+
+#[test]
+fn left_behind_tasks_get_a_table() {
+    let summary = ShutdownSummary {
+        abandoned: vec![
+            abandoned(3, "Sleve McDichael", "Sleeping", 40, Some(12)),
+            abandoned(11, "Bob", "Running", 100, None),
+        ],
+        report: Some(PathBuf::from("out/report.json")),
+    };
+    assert_eq!(
+        summary.to_string(),
+        "\
+Goodbye! 2 tasks hadn't stopped when we left:
+  ID  Name             Status    Progress  Stop sent
+  3   Sleve McDichael  Sleeping  40%       12s ago
+  11  Bob              Running   100%      never
+Full report: out/report.json
+"
+    );
+}
+
+#[test]
+fn clean_exits_just_say_goodbye() {
+    assert_eq!(
+        ShutdownSummary::default().to_string(),
+        "Goodbye! Every task stopped in time.\n"
+    );
+    let one = ShutdownSummary {
+        abandoned: vec![abandoned(0, "Bob", "???", 0, Some(0))],
+        report: None,
+    };
+    assert!(one.to_string().starts_with("Goodbye! 1 task hadn't"));
+}
+
+#[test]
+fn only_unfinished_tasks_are_left_behind() {
+    let now = Local::now();
+    let mut tasks: Vec<Task> = [
+        TaskStatus::Sleeping,
+        TaskStatus::Finished,
+        TaskStatus::Canceled,
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(id, status)| {
+        let mut task = Task::pending(&CandidateTask::new("Bob", "Does a thing"), id, 1);
+        task.status = status;
+        task
+    })
+    .collect();
+    tasks[0].progress = 55;
+    tasks[0].stop_requested(now - TimeDelta::seconds(9));
+    let summary = ShutdownSummary::of(&tasks, None, now);
+    assert_eq!(
+        summary.abandoned,
+        [abandoned(0, "Bob", "Sleeping", 55, Some(9))]
+    );
+}
@@ -0,0 +1,51 @@
+//! Table vs message stream at the sizes where it's easy to be off by one
+
+use clustrctrl::split::{table_height, SplitMode, LOGGER_MIN, TABLE_CHROME, TABLE_MIN};
+
+#[test]
+fn exactly_fits() {
+    // 50 rows, table-first: 60% is 30, and 24 tasks need exactly that
+    let tasks = 30 - TABLE_CHROME as usize;
+    assert_eq!(table_height(50, tasks, SplitMode::Table), 30);
+    // One more task and it's capped instead of growing
+    assert_eq!(table_height(50, tasks + 1, SplitMode::Table), 30);
+    // Fewer and the logger gets the rest
+    assert_eq!(table_height(50, tasks - 1, SplitMode::Table), 29);
+}
+
+#[test]
+fn one_row_short_of_both() {
+    // Just enough for the smallest table and the logger's minimum
+    let height = TABLE_MIN + LOGGER_MIN;
+    assert_eq!(table_height(height, 40, SplitMode::Table), TABLE_MIN);
+    assert_eq!(table_height(height, 40, SplitMode::Logger), TABLE_MIN);
+    // A row less and the logger can't be promised anything, so the table takes what it needs
+    assert_eq!(table_height(height - 1, 40, SplitMode::Table), height - 1);
+    assert_eq!(table_height(height - 1, 0, SplitMode::Table), TABLE_CHROME);
+}
+
+#[test]
+fn tiny_terminal() {
+    assert_eq!(table_height(0, 5, SplitMode::Table), 0);
+    assert_eq!(table_height(3, 5, SplitMode::Even), 3);
+}
+
+#[test]
+fn logger_keeps_its_minimum() {
+    // 20 rows: 60% would be 12, but that leaves the logger 8 exactly
+    assert_eq!(table_height(20, 100, SplitMode::Table), 12);
+    // 15 rows: 60% is 9, which would leave 6, so the table gives some back
+    assert_eq!(table_height(15, 100, SplitMode::Table), 15 - LOGGER_MIN);
+}
+
+#[test]
+fn modes_cap_the_table_differently() {
+    let heights: Vec<u16> = [SplitMode::Table, SplitMode::Even, SplitMode::Logger]
+        .into_iter()
+        .map(|mode| table_height(100, 500, mode))
+        .collect();
+    assert_eq!(heights, [60, 50, 40]);
+    // With nothing going on the table is small no matter who has priority
+    assert_eq!(table_height(100, 0, SplitMode::Logger), TABLE_CHROME);
+    assert_eq!(SplitMode::Logger.next(), SplitMode::Table);
+}
@@ -0,0 +1,67 @@
+//! The status file: swapped in whole so readers never see half of it, and written at most once a
+//! second, only when the counts have changed
+
+use std::{env, fs, io::Read, path::PathBuf, process, time::Duration};
+
+use clustrctrl::{clock::MockClock, status_file::StatusFile, tally::Tally};
+
+fn scratch(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("clustrctrl-{}-{name}.json", process::id()))
+}
+
+fn running(n: usize) -> Tally {
+    Tally {
+        running: n,
+        overall_pct: Some(40),
+        ..Tally::default()
+    }
+}
+
+fn read(path: &PathBuf) -> serde_json::Value {
+    serde_json::from_str(&fs::read_to_string(path).expect("it's there")).expect("it's JSON")
+}
+
+#[test]
+fn writes_swap_the_whole_file() {
+    let path = scratch("atomic");
+    let clock = MockClock::new();
+    let mut file = StatusFile::new(path.clone());
+    assert!(file.update(running(1), &clock));
+    let json = read(&path);
+    assert_eq!(json["running"], 1);
+    assert_eq!(json["overall_pct"], 40);
+    assert!(json["updated_at"].is_string());
+    // A reader part way through the old file keeps the old file, rather than seeing it change
+    let mut reader = fs::File::open(&path).expect("it's there");
+    clock.advance(Duration::from_secs(1));
+    assert!(file.update(running(2), &clock));
+    let mut old = String::new();
+    reader.read_to_string(&mut old).expect("still readable");
+    assert!(old.contains(r#""running":1"#));
+    assert_eq!(read(&path)["running"], 2);
+    assert!(!file.temp_path().exists());
+    file.remove();
+    assert!(!path.exists());
+    // Twice is fine
+    file.remove();
+}
+
+#[test]
+fn writes_wait_for_a_change_and_a_second() {
+    let path = scratch("throttle");
+    let clock = MockClock::new();
+    let mut file = StatusFile::new(path.clone());
+    assert!(file.update(running(1), &clock));
+    // Changed, but too soon
+    clock.advance(Duration::from_millis(500));
+    assert!(!file.update(running(2), &clock));
+    assert_eq!(read(&path)["running"], 1);
+    // Still changed, and now it's been long enough
+    clock.advance(Duration::from_millis(500));
+    assert!(file.update(running(2), &clock));
+    assert_eq!(read(&path)["running"], 2);
+    // Long enough, but nothing's changed
+    clock.advance(Duration::from_secs(10));
+    assert!(!file.update(running(2), &clock));
+    file.remove();
+}
@@ -0,0 +1,133 @@
+//! The HTTP status endpoints against a real listener on a random port, and the Prometheus text
+//! for a made-up tally
+
+use std::sync::Arc;
+
+use clustrctrl::{
+    candidates::CandidateTask,
+    status_server::{self, Shared},
+    tally::Tally,
+    tasks::{Task, TaskStatus},
+};
+use serde_json::Value;
+use tokio::net::TcpListener;
+
+fn tasks() -> Vec<Task> {
+    [
+        TaskStatus::Running,
+        TaskStatus::Finished,
+        TaskStatus::Finished,
+        TaskStatus::Canceled,
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(id, status)| {
+        let mut task = Task::pending(&CandidateTask::new("Bob", "Does a thing"), id, id + 1);
+        task.status = status;
+        task.progress = 40;
+        task
+    })
+    .collect()
+}
+
+/// A server on whatever port was free, and where to find it
+async fn serve() -> (Shared, String) {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("there's always a free port");
+    let url = format!("http://{}", listener.local_addr().expect("it's bound"));
+    let shared = Shared::default();
+    status_server::serve(listener, Arc::clone(&shared));
+    (shared, url)
+}
+
+async fn get(url: &str) -> reqwest::Response {
+    let response = reqwest::get(url).await.expect("server should answer");
+    assert!(
+        response.status().is_success(),
+        "{url}: {}",
+        response.status()
+    );
+    response
+}
+
+#[tokio::test]
+async fn endpoints_serve_the_latest_snapshot() {
+    let (shared, url) = serve().await;
+    // Nothing refreshed yet, so nothing to see
+    let empty: Value = get(&format!("{url}/tasks")).await.json().await.unwrap();
+    assert_eq!(empty, Value::Array(vec![]));
+
+    status_server::refresh(&shared, &tasks());
+    let tasks: Value = get(&format!("{url}/tasks")).await.json().await.unwrap();
+    let tasks = tasks.as_array().expect("a list of tasks");
+    assert_eq!(tasks.len(), 4);
+    // Laid out like the report
+    assert_eq!(tasks[1]["name"], "Bob (2)");
+    assert_eq!(tasks[1]["status"], "Done");
+    assert_eq!(tasks[0]["progress"], 40);
+
+    let stats: Value = get(&format!("{url}/stats")).await.json().await.unwrap();
+    assert_eq!(stats["tasks"], 4);
+    assert_eq!(stats["running"], 1);
+    assert_eq!(stats["done"], 2);
+    assert_eq!(stats["cancelled"], 1);
+    assert_eq!(stats["overall_pct"], 40);
+
+    let metrics = get(&format!("{url}/metrics")).await;
+    let kind = metrics.headers()["content-type"].to_str().unwrap();
+    assert!(kind.starts_with("text/plain"), "{kind}");
+    let text = metrics.text().await.unwrap();
+    assert!(
+        text.contains("clustrctrl_tasks{status=\"done\"} 2\n"),
+        "{text}"
+    );
+    assert!(
+        text.contains("clustrctrl_tasks_completed_total 2\n"),
+        "{text}"
+    );
+}
+
+#[tokio::test]
+async fn unknown_paths_are_not_found() {
+    let (_shared, url) = serve().await;
+    let response = reqwest::get(format!("{url}/nope")).await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn prometheus_text() {
+    let tally = Tally {
+        running: 2,
+        sleeping: 1,
+        done: 5,
+        cancelled: 3,
+        overall_pct: Some(62),
+        ..Tally::default()
+    };
+    assert_eq!(
+        status_server::prometheus(&tally),
+        "\
+# HELP clustrctrl_tasks Tasks in each status right now
+# TYPE clustrctrl_tasks gauge
+clustrctrl_tasks{status=\"running\"} 2
+clustrctrl_tasks{status=\"sleeping\"} 1
+clustrctrl_tasks{status=\"waiting\"} 0
+clustrctrl_tasks{status=\"striking\"} 0
+clustrctrl_tasks{status=\"done\"} 5
+clustrctrl_tasks{status=\"cancelled\"} 3
+clustrctrl_tasks{status=\"failed\"} 0
+# HELP clustrctrl_tasks_completed_total Tasks that finished
+# TYPE clustrctrl_tasks_completed_total counter
+clustrctrl_tasks_completed_total 5
+# HELP clustrctrl_tasks_cancelled_total Tasks that were stopped
+# TYPE clustrctrl_tasks_cancelled_total counter
+clustrctrl_tasks_cancelled_total 3
+# HELP clustrctrl_progress_percent Average progress of the tasks working
+# TYPE clustrctrl_progress_percent gauge
+clustrctrl_progress_percent 62
+"
+    );
+    // Nothing working, no average
+    assert!(!status_server::prometheus(&Tally::default()).contains("progress"));
+}
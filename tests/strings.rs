@@ -0,0 +1,124 @@
+//! `--strings` files: what they leave out and get wrong, and one loaded for real and drawn, with
+//! its words where the English ones were
+
+use std::{env, fs, process};
+
+use clustrctrl::{
+    app::App,
+    config::Config,
+    strings::{self, Strings, ENGLISH},
+    tasks::{Task, TaskStatus},
+    CandidateTask,
+};
+use ratatui::{backend::TestBackend, Terminal};
+
+#[test]
+fn everything_left_out_is_one_warning() {
+    let (strings, problems) = Strings::parse(
+        r#"
+        "control.new_task" = "Neue Aufgabe"
+        [status]
+        on_strike = "Streik"
+        "#,
+    )
+    .expect("it's fine TOML");
+    assert_eq!(strings.get("status.on_strike"), "Streik");
+    assert_eq!(strings.get("control.new_task"), "Neue Aufgabe");
+    assert_eq!(strings.get("status.running"), "Running");
+    assert_eq!(problems.len(), 1, "{problems:?}");
+    let missing = format!("leaves out {} keys", ENGLISH.len() - 2);
+    assert!(problems[0].contains(&missing), "{}", problems[0]);
+    assert!(problems[0].contains("status.running"));
+    assert!(!problems[0].contains("status.on_strike"));
+}
+
+#[test]
+fn a_whole_set_only_warns_about_extras() {
+    let mut text: String = ENGLISH
+        .iter()
+        .map(|(key, english)| format!("{key:?} = {english:?}\n"))
+        .collect();
+    assert_eq!(
+        Strings::parse(&text).map(|(_, problems)| problems),
+        Ok(vec![])
+    );
+    text.push_str("\"status.snoozing\" = \"Zzz\"\n");
+    let (_, problems) = Strings::parse(&text).unwrap();
+    assert_eq!(
+        problems,
+        ["the strings file has 1 key we don't use: status.snoozing"]
+    );
+}
+
+#[test]
+fn anything_but_text_is_refused() {
+    assert_eq!(
+        Strings::parse("[status]\nrunning = 3").map(|_| ()),
+        Err("`status.running` should be text".to_string())
+    );
+    assert!(Strings::parse("status = ").is_err());
+}
+
+#[test]
+fn placeholders_are_filled_in() {
+    assert_eq!(
+        strings::fill("toast.added_again", &[("name", &"Bob"), ("id", &4)]),
+        "Added Bob (#4)"
+    );
+    // Reports keep the English, whatever's on screen
+    assert_eq!(TaskStatus::OnStrike.to_string(), "Strike!");
+}
+
+const GERMAN: &str = r#"
+[status]
+on_strike = "Streik"
+finished = "Fertig"
+
+[column]
+status = "Zustand"
+description = "Beschreibung"
+
+[control]
+new_task = "Neue Aufgabe"
+"#;
+
+/// The only test here that loads them for everyone, so the others don't care when it runs
+#[test]
+fn an_override_file_is_drawn() {
+    let path = env::temp_dir().join(format!("clustrctrl-strings-{}.toml", process::id()));
+    fs::write(&path, GERMAN).unwrap();
+    let (loaded, problems) = Strings::load(&path).expect("just wrote it");
+    fs::remove_file(&path).unwrap();
+    assert_eq!(problems.len(), 1);
+    strings::init(loaded);
+
+    let mut tasks: Vec<Task> = ["Alpha", "Beta"]
+        .into_iter()
+        .enumerate()
+        .map(|(id, name)| Task::pending(&CandidateTask::new(name, "Does a thing"), id, 1))
+        .collect();
+    tasks[0].status = TaskStatus::OnStrike;
+    tasks[1].status = TaskStatus::Finished;
+    let mut app = App::with_tasks(Config::default(), tasks);
+    let mut terminal = Terminal::new(TestBackend::new(160, 30)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    let screen: String = (0..30)
+        .flat_map(|y| (0..160).map(move |x| (x, y)))
+        .map(|(x, y)| buffer[(x, y)].symbol().to_string())
+        .collect();
+    for german in [
+        "Streik",
+        "Fertig",
+        "Zustand",
+        "Beschreibung",
+        "Neue Aufgabe <F1>",
+    ] {
+        assert!(screen.contains(german), "no {german} in {screen}");
+    }
+    assert!(!screen.contains("Strike!") && !screen.contains("New Task"));
+    // Anything it left out is still English
+    assert!(screen.contains("Manage Tasks <F2>"), "{screen}");
+}
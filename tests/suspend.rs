@@ -0,0 +1,65 @@
+//! Ctrl+Z's way through asked, suspended and back, by key and by (pretend) signal, and never
+//! drawing while the terminal's given back. Actually stopping is left to trying it
+//!
+//! To try it: run it from a shell, add a task, Ctrl+Z. The shell prompt should be usable
+//! (typed text echoes, Enter gives a new line), `jobs` should list it as stopped, and `fg` should
+//! put the whole screen back at once, with the task carried on from where it was
+
+use clustrctrl::suspend::{self, Phase, Suspend};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[test]
+fn asked_suspended_and_back() {
+    let mut suspend = Suspend::default();
+    assert!(!suspend.due(), "nobody's asked");
+    assert!(suspend.request());
+    assert!(!suspend.request(), "once is enough");
+    assert_eq!(suspend.phase(), Phase::Requested);
+    assert!(
+        suspend.can_draw(),
+        "the terminal's still ours until it's given back"
+    );
+
+    assert!(suspend.due());
+    assert!(!suspend.due(), "only the once");
+    assert_eq!(suspend.phase(), Phase::Suspended);
+    assert!(!suspend.can_draw());
+    // Ctrl+Z again while it's all being torn down doesn't go round again
+    assert!(!suspend.request());
+
+    suspend.resumed();
+    assert_eq!(suspend.phase(), Phase::Awake);
+    assert!(suspend.can_draw());
+    assert!(!suspend.due());
+}
+
+#[test]
+fn signals_count_too() {
+    let mut suspend = Suspend::default();
+    let signals = suspend.signals();
+    signals.stop();
+    assert!(suspend.due(), "SIGTSTP asks the same as the key");
+    // The SIGCONT that comes with our own `fg` is nothing to redraw over twice
+    signals.continued();
+    assert!(!suspend.take_continued(), "not while we're still away");
+    suspend.resumed();
+    assert!(!suspend.take_continued());
+
+    // Somebody else's SIGSTOP and SIGCONT: still ours, but it needs drawing again
+    signals.continued();
+    assert!(suspend.take_continued());
+    assert!(!suspend.take_continued());
+    assert!(!suspend.due());
+}
+
+#[test]
+fn only_ctrl_z_is_the_key() {
+    assert!(suspend::is_suspend_key(&KeyEvent::new(
+        KeyCode::Char('z'),
+        KeyModifiers::CONTROL
+    )));
+    // Plain z is compact mode
+    assert!(!suspend::is_suspend_key(&KeyEvent::from(KeyCode::Char(
+        'z'
+    ))));
+}
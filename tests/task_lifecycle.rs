@@ -0,0 +1,700 @@
+//! Drives real workers through their lives with nothing but the channels, the way App would if it
+//! didn't also have a terminal to look after
+
+use std::time::Duration;
+
+use clustrctrl::{
+    chaos::ChaosConfig,
+    clock::{Clock, MockClock, SystemClock},
+    completion::Outcome,
+    latency::Stamped,
+    tasks::{
+        self, CancelEvent, CancelState, Orders, SpawnError, Task, TaskError, TaskRxMsg, TaskStatus,
+        TaskTxMsg, WorkProfile, WorkerStatus, FATIGUE_LIMIT, MILESTONES, MILESTONE_LABEL,
+        MIN_INTENSITY,
+    },
+    CandidateTask,
+};
+use tokio::{
+    sync::mpsc::{self, error::TrySendError},
+    time::timeout,
+};
+
+/// Way more than any of these should take. Hitting it means something's stuck
+const PATIENCE: Duration = Duration::from_secs(15);
+
+fn dummy() -> CandidateTask {
+    CandidateTask::new("Test dummy", "Pretends to work until told otherwise")
+}
+
+fn spawn(ct: &CandidateTask) -> (Task, mpsc::Receiver<Stamped<TaskTxMsg>>) {
+    let (tx, rx) = mpsc::channel(32);
+    // Lowest intensity so work blocks don't hold the test up
+    let task = Task::spawn(
+        ct,
+        tx,
+        0,
+        MIN_INTENSITY,
+        1,
+        ChaosConfig::default(),
+        &SystemClock,
+    )
+    .expect("there's a runtime right here");
+    (task, rx)
+}
+
+/// The next message, or a panic if the worker went quiet or hung up
+async fn next(rx: &mut mpsc::Receiver<Stamped<TaskTxMsg>>) -> TaskTxMsg {
+    timeout(PATIENCE, rx.recv())
+        .await
+        .expect("worker went quiet")
+        .expect("worker hung up")
+        .msg
+}
+
+/// Everything the worker still has to say. The channel closes when it returns
+async fn rest(rx: &mut mpsc::Receiver<Stamped<TaskTxMsg>>) -> Vec<TaskTxMsg> {
+    let mut msgs = Vec::new();
+    while let Some(msg) = timeout(PATIENCE, rx.recv())
+        .await
+        .expect("worker never finished")
+    {
+        msgs.push(msg.msg);
+    }
+    msgs
+}
+
+/// Harvests the task like App does and hands back what the worker returned
+async fn finish(task: &mut Task) -> Option<i128> {
+    match task.join(PATIENCE).await.expect("handle never finished") {
+        Outcome::Returned(sum) => sum,
+        outcome => panic!("worker didn't return: {outcome:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dummy_stops_when_asked() {
+    let (mut task, mut rx) = spawn(&dummy());
+    // Wait until it's properly into it - sleeping or striking, either way it should still listen
+    loop {
+        match next(&mut rx).await {
+            TaskTxMsg::StatusChange {
+                status:
+                    WorkerStatus::Sleeping | WorkerStatus::OnStrike { .. } | WorkerStatus::Finalizing,
+                ..
+            } => break,
+            TaskTxMsg::Progress { pct, .. } => task.progress = pct,
+            TaskTxMsg::StatusChange {
+                status: WorkerStatus::Running,
+                ..
+            } => {}
+            TaskTxMsg::Milestone { .. } => {}
+            msg => panic!("didn't expect {msg:?} yet"),
+        }
+    }
+    assert!(task.tell(TaskRxMsg::PleaseStop, &SystemClock));
+    let msgs = rest(&mut rx).await;
+    assert!(
+        matches!(msgs.last(), Some(TaskTxMsg::CancelReport(0))),
+        "no cancel report in {msgs:?}"
+    );
+    task.set_status(TaskStatus::Canceled, &SystemClock);
+    assert_eq!(finish(&mut task).await, None);
+    // Stopped tasks keep their status and progress instead of being rounded up to done
+    assert_eq!(task.status, TaskStatus::Canceled);
+    assert_eq!(task.progress_at_cancel, Some(task.progress));
+    assert!(task.end.is_some());
+    assert_eq!(task.audit.len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn overflowing_sums_fail_the_task() {
+    let (tx, mut rx) = mpsc::channel(32);
+    let mut task = Task::pending(&dummy(), 0, 1);
+    task.profile = WorkProfile::overflowing();
+    task.activate(
+        tx,
+        MIN_INTENSITY,
+        ChaosConfig::default(),
+        Orders::default(),
+        &SystemClock,
+    )
+    .expect("there's a runtime right here");
+    let msgs = rest(&mut rx).await;
+    match msgs.last() {
+        Some(TaskTxMsg::Fault { id: 0, error }) => {
+            assert_eq!(error, "arithmetic overflow after 1 blocks")
+        }
+        _ => panic!("no fault in {msgs:?}"),
+    }
+    // Nothing got reported for a block that never added up
+    assert!(!msgs.iter().any(|m| matches!(m, TaskTxMsg::Progress { .. })));
+    assert_eq!(finish(&mut task).await, None);
+}
+
+#[test]
+fn sums_are_checked() {
+    assert_eq!(tasks::crunch(0, [7, -7, 1003], 500), Some(17));
+    assert_eq!(tasks::crunch(i128::MAX - 1, [1], 500), Some(i128::MAX));
+    assert_eq!(tasks::crunch(i128::MAX - 1, [1, 1], 500), None);
+    // And so is how many numbers there are to sum
+    let normal = WorkProfile::default();
+    assert_eq!(normal.block_len(1.0), Some(normal.per_block as usize));
+    assert_eq!(normal.block_len(f32::NAN), None);
+    assert_eq!(WorkProfile::overflowing().block_len(MIN_INTENSITY), None);
+    assert!(tasks::is_sane_sum(0));
+    assert!(!tasks::is_sane_sum(-1));
+    assert!(!tasks::is_sane_sum(i128::MAX));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dummy_stops_with_everyone_else_quietly() {
+    let (mut task, mut rx) = spawn(&dummy());
+    assert!(task.tell(TaskRxMsg::EveryoneStopPls, &SystemClock));
+    let msgs = rest(&mut rx).await;
+    assert!(
+        !msgs.iter().any(|m| matches!(m, TaskTxMsg::CancelReport(_))),
+        "everyone-stop isn't answered, got {msgs:?}"
+    );
+    assert_eq!(finish(&mut task).await, None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dead_tasks_cant_be_told() {
+    let (mut task, mut rx) = spawn(&dummy());
+    task.tell(TaskRxMsg::EveryoneStopPls, &SystemClock);
+    rest(&mut rx).await;
+    finish(&mut task).await;
+    assert!(!task.tell(TaskRxMsg::PleaseStop, &SystemClock));
+    assert!(matches!(
+        task.try_tell(TaskRxMsg::PleaseStop, &SystemClock),
+        Err(TaskError::SendFailed(0, TrySendError::Closed(_)))
+    ));
+    // Nor stopped, since there's nothing left of it
+    assert!(matches!(
+        task.request_stop(&SystemClock),
+        Err(TaskError::AlreadyTerminal(0, TaskStatus::Finished))
+    ));
+    assert!(matches!(
+        task.join(PATIENCE).await,
+        Err(TaskError::AlreadyTerminal(..))
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn impatient_joins_time_out() {
+    let (mut task, mut rx) = spawn(&dummy());
+    assert!(matches!(
+        task.join(Duration::ZERO).await,
+        Err(TaskError::Timeout(0, Duration::ZERO))
+    ));
+    task.tell(TaskRxMsg::EveryoneStopPls, &SystemClock);
+    rest(&mut rx).await;
+    finish(&mut task).await;
+}
+
+#[test]
+fn missing_tasks_say_so() {
+    let mut everyone = vec![Task::pending(&dummy(), 0, 1)];
+    assert!(tasks::find_mut(&mut everyone, 0).is_ok());
+    assert!(matches!(
+        tasks::find_mut(&mut everyone, 7),
+        Err(TaskError::TaskNotFound(7))
+    ));
+}
+
+#[test]
+fn spawning_needs_a_runtime() {
+    let (tx, _rx) = mpsc::channel(1);
+    assert!(matches!(
+        Task::spawn(
+            &dummy(),
+            tx,
+            0,
+            MIN_INTENSITY,
+            1,
+            ChaosConfig::default(),
+            &SystemClock
+        ),
+        Err(TaskError::SpawnFailed(SpawnError::NoRuntime(_)))
+    ));
+}
+
+#[tokio::test]
+async fn failed_spawns_are_inert() {
+    let mut task = Task::failed_to_start(&dummy(), 0, 1, "no runtime".to_string(), &SystemClock);
+    assert_eq!(task.status, TaskStatus::FailedToStart);
+    assert!(task.check_done(&SystemClock).await.is_none());
+    assert!(!task.tell(TaskRxMsg::PleaseStop, &SystemClock));
+    assert!(matches!(
+        task.request_stop(&SystemClock),
+        Err(TaskError::AlreadyTerminal(0, TaskStatus::FailedToStart))
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pending_tasks_wait_for_activation() {
+    let mut task = Task::pending(&dummy(), 0, 1);
+    assert_eq!(task.status, TaskStatus::Scheduled);
+    assert!(task.start.is_none());
+    assert!(task.check_done(&SystemClock).await.is_none());
+    // No worker yet, so there's nobody to tell
+    assert!(!task.tell(TaskRxMsg::PleaseStop, &SystemClock));
+
+    let (tx, mut rx) = mpsc::channel(32);
+    task.activate(
+        tx,
+        MIN_INTENSITY,
+        ChaosConfig::default(),
+        Orders::default(),
+        &SystemClock,
+    )
+    .expect("there's a runtime right here");
+    assert!(task.start.is_some());
+    assert_eq!(task.status, TaskStatus::KnownUnknown);
+    assert!(task.tell(TaskRxMsg::EveryoneStopPls, &SystemClock));
+    rest(&mut rx).await;
+    assert_eq!(finish(&mut task).await, None);
+}
+
+#[test]
+fn pending_tasks_cancel_without_a_worker() {
+    let mut task = Task::pending(&dummy(), 0, 1);
+    task.request_stop(&SystemClock)
+        .expect("there's no worker to fail to reach");
+    assert_eq!(task.status, TaskStatus::Canceled);
+    assert_eq!(task.cancel, CancelState::Acked);
+    assert!(task.start.is_none() && task.end.is_some());
+    assert!(!task.completion.is_running());
+}
+
+#[test]
+fn late_finishing_doesnt_undo_the_end() {
+    let mut task = Task::pending(&dummy(), 0, 1);
+    task.set_status(TaskStatus::Sleeping, &SystemClock);
+    assert!(task.report(TaskStatus::Finalizing, &SystemClock));
+    assert_eq!(task.status, TaskStatus::Finalizing);
+    // Still counts as working, so it keeps its ETA and can be stopped
+    assert!(task.status.is_working() && task.status.is_stoppable());
+    for over in [
+        TaskStatus::Finished,
+        TaskStatus::Canceled,
+        TaskStatus::Failed,
+        TaskStatus::FailedToStart,
+    ] {
+        task.set_status(over, &SystemClock);
+        assert!(!task.report(TaskStatus::Finalizing, &SystemClock));
+        assert_eq!(task.status, over);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn chosen_durations_replace_the_roll() {
+    let (tx, mut rx) = mpsc::channel(32);
+    let mut task = Task::pending(&dummy(), 0, 1);
+    task.target_secs = Some(1);
+    task.activate(
+        tx,
+        MIN_INTENSITY,
+        ChaosConfig::default(),
+        Orders::default(),
+        &SystemClock,
+    )
+    .expect("there's a runtime right here");
+    // A second in all can only be one block, so it's the last from the start
+    assert!(matches!(
+        next(&mut rx).await,
+        TaskTxMsg::StatusChange {
+            id: 0,
+            status: WorkerStatus::Finalizing
+        }
+    ));
+    assert!(matches!(
+        next(&mut rx).await,
+        TaskTxMsg::Progress { pct: 99, .. }
+    ));
+    task.tell(TaskRxMsg::EveryoneStopPls, &SystemClock);
+    rest(&mut rx).await;
+    finish(&mut task).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn paused_dummies_wait_it_out() {
+    let (tx, mut rx) = mpsc::channel(32);
+    let mut task = Task::pending(&dummy(), 0, 1);
+    task.target_secs = Some(1);
+    task.activate(
+        tx,
+        MIN_INTENSITY,
+        ChaosConfig::default(),
+        Orders::default(),
+        &SystemClock,
+    )
+    .expect("there's a runtime right here");
+    // One block, so it's napping its last second once it's said how far it got
+    while !matches!(next(&mut rx).await, TaskTxMsg::Progress { pct: 99, .. }) {}
+    assert!(task.tell(TaskRxMsg::Pause(true), &SystemClock));
+    // Well past the end of the nap and it's still there. A strike still wants answering
+    let paused = timeout(Duration::from_secs(2), async {
+        while let Some(msg) = rx.recv().await {
+            if let TaskTxMsg::StatusChange {
+                status: WorkerStatus::OnStrike { .. },
+                ..
+            } = msg.msg
+            {
+                assert!(task.tell(TaskRxMsg::Bargain(1), &SystemClock));
+            }
+        }
+    })
+    .await;
+    assert!(paused.is_err(), "finished while paused");
+    assert!(task.tell(TaskRxMsg::Pause(false), &SystemClock));
+    rest(&mut rx).await;
+    assert!(finish(&mut task).await.is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dummies_added_while_everyone_is_paused_start_paused() {
+    let (tx, mut rx) = mpsc::channel(32);
+    let mut task = Task::pending(&dummy(), 0, 1);
+    task.target_secs = Some(1);
+    let orders = Orders {
+        paused: true,
+        ..Orders::default()
+    };
+    task.activate(
+        tx,
+        MIN_INTENSITY,
+        ChaosConfig::default(),
+        orders,
+        &SystemClock,
+    )
+    .expect("there's a runtime right here");
+    // It heard before its first block, so there's no progress at all, let alone the end
+    let paused = timeout(Duration::from_secs(2), async {
+        while let Some(msg) = rx.recv().await {
+            assert!(
+                !matches!(msg.msg, TaskTxMsg::Progress { .. }),
+                "worked while paused: {:?}",
+                msg.msg
+            );
+        }
+    })
+    .await;
+    assert!(paused.is_err(), "finished while paused");
+    assert!(task.tell(TaskRxMsg::Pause(false), &SystemClock));
+    // Let go, it gets on with it. Strikes still want answering
+    let mut finished = false;
+    while let Some(msg) = timeout(PATIENCE, rx.recv())
+        .await
+        .expect("worker went quiet")
+    {
+        match msg.msg {
+            TaskTxMsg::StatusChange {
+                status: WorkerStatus::OnStrike { .. },
+                ..
+            } => assert!(task.tell(TaskRxMsg::Bargain(1), &SystemClock)),
+            TaskTxMsg::Progress { pct: 99, .. } => finished = true,
+            _ => {}
+        }
+    }
+    assert!(finished, "never got to its last block");
+    assert!(finish(&mut task).await.is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dummies_started_during_shutdown_stop_straight_away() {
+    let (tx, mut rx) = mpsc::channel(32);
+    let mut task = Task::pending(&dummy(), 0, 1);
+    // Stopping wins over pausing, or it'd sit there waiting to be let go
+    let orders = Orders {
+        paused: true,
+        stopping: true,
+        ..Orders::default()
+    };
+    assert!(matches!(
+        orders.first_word(),
+        Some(TaskRxMsg::EveryoneStopPls)
+    ));
+    task.activate(
+        tx,
+        MIN_INTENSITY,
+        ChaosConfig::default(),
+        orders,
+        &SystemClock,
+    )
+    .expect("there's a runtime right here");
+    let said = rest(&mut rx).await;
+    assert!(
+        !said
+            .iter()
+            .any(|msg| matches!(msg, TaskTxMsg::Progress { .. })),
+        "{said:?}"
+    );
+    assert_eq!(finish(&mut task).await, None);
+    assert!(Orders::default().first_word().is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dummies_announce_milestones() {
+    let (tx, mut rx) = mpsc::channel(32);
+    let mut task = Task::pending(&dummy(), 0, 1);
+    task.target_secs = Some(3);
+    task.activate(
+        tx,
+        MIN_INTENSITY,
+        ChaosConfig::default(),
+        Orders::default(),
+        &SystemClock,
+    )
+    .expect("there's a runtime right here");
+    let mut labels = vec![];
+    while let Some(msg) = timeout(PATIENCE, rx.recv())
+        .await
+        .expect("worker never finished")
+    {
+        match msg.msg {
+            TaskTxMsg::Milestone { label, .. } => labels.push(label),
+            // Anything but a refusal, or it'd wait on us forever (or quit)
+            TaskTxMsg::StatusChange {
+                status: WorkerStatus::OnStrike { .. },
+                ..
+            } => assert!(task.tell(TaskRxMsg::Bargain(1), &SystemClock)),
+            _ => {}
+        }
+    }
+    // However the blocks fell, it warms up first (unless it's all one block) and flushes last
+    assert!((1..=3).contains(&labels.len()), "{labels:?}");
+    assert_eq!(labels.last().map(String::as_str), Some("flushing"));
+    if labels.len() > 1 {
+        assert_eq!(labels[0], "warmup done");
+    }
+    finish(&mut task).await;
+}
+
+#[test]
+fn milestones_are_bounded_and_cut_short() {
+    let mut task = Task::pending(&dummy(), 0, 1);
+    assert_eq!(task.latest_milestone(), None);
+    for n in 0..MILESTONES + 5 {
+        task.milestone(&format!("phase {n}"), &SystemClock);
+    }
+    assert_eq!(task.milestones.len(), MILESTONES);
+    assert_eq!(task.milestones[0].1, "phase 5");
+    task.milestone(&"x".repeat(MILESTONE_LABEL * 2), &SystemClock);
+    assert_eq!(
+        task.latest_milestone().map(|label| label.chars().count()),
+        Some(MILESTONE_LABEL)
+    );
+}
+
+#[test]
+fn cancel_states_only_move_forward() {
+    use CancelEvent::*;
+    use CancelState::*;
+    let cases = [
+        (None, Request, Requested),
+        (Requested, Request, Requested),
+        (Requested, Ack, Acked),
+        // Asking again once it's answered changes nothing
+        (Acked, Request, Acked),
+        // A CancelReport nobody asked for still means it's stopping
+        (None, Ack, Acked),
+        (Requested, Force, Forced),
+        (Acked, Abandon, Abandoned),
+        // Nothing gets out of the last two
+        (Forced, Ack, Forced),
+        (Forced, Abandon, Forced),
+        (Abandoned, Request, Abandoned),
+        (Abandoned, Force, Abandoned),
+    ];
+    for (from, event, to) in cases {
+        assert_eq!(from.next(event), to, "{from:?} + {event:?}");
+    }
+}
+
+#[test]
+fn finishing_before_the_ack_stops_the_clock() {
+    let mut task = Task::pending(&dummy(), 0, 1);
+    assert_eq!(task.halt_label(chrono::Local::now()), "—");
+    let asked = chrono::Local::now();
+    task.stop_requested(asked);
+    assert_eq!(
+        task.halt_label(asked + chrono::Duration::seconds(5)),
+        "req 5s"
+    );
+    assert_eq!(
+        task.halt_label(asked + chrono::Duration::seconds(150)),
+        "req 2m"
+    );
+    // The worker got to the end of its last block before it read the request
+    task.set_status(TaskStatus::Finished, &SystemClock);
+    task.end = Some(asked + chrono::Duration::seconds(3));
+    let much_later = asked + chrono::Duration::seconds(60);
+    assert_eq!(task.cancel, CancelState::Requested);
+    assert_eq!(task.halt_label(much_later), "req 3s");
+    // Its CancelReport can still turn up after that, and still counts
+    task.stop_acked(much_later);
+    assert_eq!(task.cancel, CancelState::Acked);
+    assert_eq!(task.halt_label(much_later), "ack");
+    // Asking again doesn't reset anything
+    task.stop_requested(much_later);
+    assert_eq!(task.cancel, CancelState::Acked);
+    assert_eq!(task.cancel_requested_at, Some(asked));
+}
+
+#[test]
+fn full_blocking_pools_leave_tasks_waiting() {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .max_blocking_threads(2)
+        .build()
+        .unwrap();
+    let clock = MockClock::new();
+    runtime.block_on(async {
+        let mut spawned: Vec<_> = (0..4)
+            .map(|id| {
+                let (tx, rx) = mpsc::channel(32);
+                let task = Task::spawn(
+                    &dummy(),
+                    tx,
+                    id,
+                    MIN_INTENSITY,
+                    1,
+                    ChaosConfig::default(),
+                    &clock,
+                )
+                .expect("there's a runtime right here");
+                (task, rx)
+            })
+            .collect();
+        // The first two get the pool's threads and say so straight away
+        for (task, rx) in &mut spawned[..2] {
+            next(rx).await;
+            task.heard_from(clock.now_instant());
+        }
+        // The other two are queued, with nothing to say for themselves
+        clock.advance(Duration::from_secs(5));
+        let patience = Duration::from_secs(3);
+        let flipped: Vec<bool> = spawned
+            .iter_mut()
+            .map(|(task, _)| task.check_thread_wait(patience, &clock))
+            .collect();
+        assert_eq!(flipped, [false, false, true, true]);
+        assert_eq!(spawned[2].0.status, TaskStatus::WaitingForThread);
+        assert!(spawned[0].0.thread_wait.is_some() && spawned[2].0.thread_wait.is_none());
+        // Only ever flips the once
+        assert!(!spawned[2].0.check_thread_wait(patience, &clock));
+
+        // Stopping them frees the threads, and the queued ones hear it as soon as they start
+        for (task, _) in &mut spawned {
+            task.request_stop(&SystemClock)
+                .expect("all still stoppable");
+        }
+        for (task, rx) in &mut spawned {
+            rest(rx).await;
+            assert_eq!(finish(task).await, None);
+        }
+    });
+}
+
+#[test]
+fn overwork_adds_up() {
+    // Enough blocks in a row at full tilt and a strike is guaranteed, no luck needed
+    let blocks = (0..)
+        .scan(0, |fatigue, _| {
+            *fatigue = tasks::tire(*fatigue, 1.0);
+            Some(*fatigue)
+        })
+        .position(|fatigue| fatigue >= FATIGUE_LIMIT)
+        .unwrap();
+    assert_eq!(blocks + 1, 4);
+    // Going easy takes longer to get there
+    assert!(tasks::tire(0, MIN_INTENSITY) < tasks::tire(0, 1.0));
+    assert_eq!(tasks::tire(95, 1.0), 100);
+}
+
+#[test]
+fn sleep_wears_it_off() {
+    assert_eq!(tasks::rest(60, Duration::from_secs(5)), 35);
+    // Part seconds don't count, and there's no going below rested
+    assert_eq!(tasks::rest(60, Duration::from_millis(900)), 60);
+    assert_eq!(tasks::rest(10, Duration::from_secs(3600)), 0);
+}
+
+#[cfg(unix)]
+mod shell {
+    use super::*;
+
+    fn chatty() -> CandidateTask {
+        CandidateTask {
+            command: Some("echo hello; echo oops >&2".to_string()),
+            ..CandidateTask::new("Chatty", "Says a couple things and leaves")
+        }
+    }
+
+    fn grumpy() -> CandidateTask {
+        CandidateTask {
+            command: Some("exit 3".to_string()),
+            ..CandidateTask::new("Grumpy", "Exits unhappy")
+        }
+    }
+
+    fn sleepy() -> CandidateTask {
+        CandidateTask {
+            command: Some("sleep 60".to_string()),
+            ..CandidateTask::new("Sleepy", "Would sleep for ages if we let it")
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn runs_to_completion() {
+        let (mut task, mut rx) = spawn(&chatty());
+        let msgs = rest(&mut rx).await;
+        let mut output: Vec<_> = msgs
+            .iter()
+            .filter_map(|m| match m {
+                TaskTxMsg::Output { line, .. } => Some(line.as_str()),
+                _ => None,
+            })
+            .collect();
+        // stdout and stderr are read side by side, so their order isn't promised
+        output.sort();
+        assert_eq!(output, ["hello", "oops"]);
+        assert!(matches!(
+            msgs.last(),
+            Some(TaskTxMsg::ExitReport { code: Some(0), .. })
+        ));
+        assert_eq!(finish(&mut task).await, Some(0));
+        assert_eq!(task.status, TaskStatus::Finished);
+        assert_eq!(task.progress, 100);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn reports_exit_code() {
+        let (mut task, mut rx) = spawn(&grumpy());
+        let msgs = rest(&mut rx).await;
+        assert!(matches!(
+            msgs.last(),
+            Some(TaskTxMsg::ExitReport { code: Some(3), .. })
+        ));
+        assert_eq!(finish(&mut task).await, Some(3));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stops_when_asked() {
+        let (mut task, mut rx) = spawn(&sleepy());
+        // It says it's started once the child is up
+        assert!(matches!(
+            next(&mut rx).await,
+            TaskTxMsg::StatusChange {
+                status: WorkerStatus::Running,
+                ..
+            }
+        ));
+        assert!(task.tell(TaskRxMsg::PleaseStop, &SystemClock));
+        let msgs = rest(&mut rx).await;
+        assert!(matches!(msgs.as_slice(), [TaskTxMsg::CancelReport(0)]));
+        assert_eq!(finish(&mut task).await, None);
+    }
+}
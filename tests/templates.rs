@@ -0,0 +1,179 @@
+//! Templates: what's checked against the pool at startup, `T` and a key adding the lot with
+//! their intensity and time limit, the list behind `T ?`, and limits stopping what runs past them
+
+use std::time::Duration;
+
+use clustrctrl::{
+    app::App,
+    clock::MockClock,
+    config::Config,
+    health::{Health, Severity},
+    task_picker,
+    tasks::CancelState,
+    templates::{self, TemplateConfig},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, Terminal};
+
+fn template(key: &str, name: &str, candidate: &str) -> TemplateConfig {
+    TemplateConfig {
+        key: key.to_string(),
+        name: name.to_string(),
+        candidate: candidate.to_string(),
+        count: None,
+        intensity: None,
+        timeout_secs: None,
+        priority: None,
+    }
+}
+
+fn quick_batch() -> TemplateConfig {
+    TemplateConfig {
+        count: Some(3),
+        intensity: Some(1.5),
+        timeout_secs: Some(20),
+        ..template("1", "quick batch", "Onson Sweemey")
+    }
+}
+
+#[test]
+fn config_spells_them_out() {
+    let config: Config = toml::from_str(
+        r#"
+        [[template]]
+        key = "1"
+        name = "quick batch"
+        candidate = "Onson Sweemey"
+        count = 3
+        intensity = 1.5
+        timeout_secs = 20
+        "#,
+    )
+    .expect("it's valid");
+    assert_eq!(config.templates, [quick_batch()]);
+    // And back the same when `<`/`>` saves the config
+    let saved = toml::to_string(&config).expect("it serializes");
+    let again: Config = toml::from_str(&saved).expect("it's valid");
+    assert_eq!(again.templates, [quick_batch()]);
+}
+
+#[test]
+fn only_sensible_ones_are_kept() {
+    let pool = task_picker::builtin_pool();
+    let mut health = Health::default();
+    let configs = [
+        quick_batch(),
+        template("2", "nobody", "Nobody At All"),
+        template("1", "same key", "Onson Sweemey"),
+        template("?", "the list key", "Onson Sweemey"),
+        template("ab", "too long", "Onson Sweemey"),
+        TemplateConfig {
+            count: Some(0),
+            ..template("3", "none of them", "Onson Sweemey")
+        },
+        TemplateConfig {
+            priority: Some("high".to_string()),
+            ..template("4", "in a hurry", "Onson Sweemey")
+        },
+    ];
+    let kept = templates::compile(&configs, &pool, &mut health);
+    let keys: Vec<char> = kept.iter().map(|template| template.key).collect();
+    assert_eq!(keys, ['1', '4']);
+    assert_eq!(
+        kept[0].summary(),
+        "3× Onson Sweemey, 1.5x, stopped after 20s"
+    );
+
+    let problems: Vec<(Severity, &str)> = health
+        .sorted()
+        .into_iter()
+        .map(|item| (item.severity, item.message.as_str()))
+        .collect();
+    assert_eq!(problems.len(), 6, "{problems:?}");
+    assert!(problems.contains(&(
+        Severity::Error,
+        "template `nobody`: there's no candidate called `Nobody At All`, leaving it out"
+    )));
+    assert!(problems
+        .iter()
+        .any(|&(severity, message)| severity == Severity::Warning
+            && message.starts_with("template `in a hurry`")));
+}
+
+fn screen(app: &mut App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    (0..30)
+        .map(|y| {
+            (0..120)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn key(ch: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(ch), KeyModifiers::SHIFT)
+}
+
+#[tokio::test]
+async fn t_and_a_key_adds_the_lot() {
+    let config = Config {
+        templates: vec![quick_batch()],
+        ..Config::default()
+    };
+    let clock = MockClock::new();
+    let mut app = App::with_tasks(config, vec![]).with_clock(Box::new(clock.clone()));
+
+    app.handle_key_event(key('T'));
+    app.handle_key_event(key('?'));
+    let list = screen(&mut app);
+    assert!(list.contains("quick batch"), "{list}");
+    assert!(
+        list.contains("3× Onson Sweemey, 1.5x, stopped after 20s"),
+        "{list}"
+    );
+    app.handle_key_event(KeyEvent::from(KeyCode::Esc));
+
+    // Not one of them
+    app.handle_key_event(key('T'));
+    app.handle_key_event(key('9'));
+    assert!(app.tasks().is_empty());
+
+    app.handle_key_event(key('T'));
+    app.handle_key_event(key('1'));
+    let tasks = app.tasks();
+    assert_eq!(tasks.len(), 3);
+    for (copy, task) in tasks.iter().enumerate() {
+        assert_eq!(task.candidate, "Onson Sweemey");
+        assert_eq!(task.copy, copy + 1);
+        assert_eq!(task.intensity, Some(1.5));
+        assert_eq!(task.time_limit, Some(Duration::from_secs(20)));
+    }
+
+    clock.advance(Duration::from_secs(19));
+    app.tick();
+    assert!(app
+        .tasks()
+        .iter()
+        .all(|task| task.cancel == CancelState::None));
+    clock.advance(Duration::from_secs(1));
+    app.tick();
+    for task in app.tasks() {
+        assert_ne!(task.cancel, CancelState::None, "asked to stop at 20s");
+        assert_eq!(task.cancel_reason.as_deref(), Some("ran out of time (20s)"));
+    }
+}
+
+#[test]
+fn no_templates_no_t() {
+    let mut app = App::with_tasks(Config::default(), vec![]);
+    app.handle_key_event(key('T'));
+    // The next key isn't swallowed waiting for a template
+    app.handle_key_event(KeyEvent::from(KeyCode::F(1)));
+    assert!(screen(&mut app).contains("New Task"));
+}
@@ -0,0 +1,185 @@
+//! Reduced motion and plain output, checked on what actually lands in the buffer, the room
+//! compact mode gives back, and each task's own color
+
+use std::time::Duration;
+
+use clustrctrl::{
+    app::App,
+    config::Config,
+    task_table::MARKER,
+    tasks::{Task, TaskStatus},
+    theme::{self, Chrome, Theme, TASK_PALETTE},
+    CandidateTask,
+};
+use ratatui::{
+    backend::TestBackend,
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+    text::Line,
+    widgets::{Block, List, ListState, StatefulWidget, Widget},
+    Terminal,
+};
+
+const EVERY_STATUS: [TaskStatus; 11] = [
+    TaskStatus::Scheduled,
+    TaskStatus::Running,
+    TaskStatus::Sleeping,
+    TaskStatus::Finalizing,
+    TaskStatus::OnStrike,
+    TaskStatus::KnownUnknown,
+    TaskStatus::WaitingForThread,
+    TaskStatus::Finished,
+    TaskStatus::Canceled,
+    TaskStatus::Failed,
+    TaskStatus::FailedToStart,
+];
+
+/// A line per status, the way the table draws them, with each row flashing as if it had only
+/// just changed
+fn render_statuses(theme: &Theme) -> Buffer {
+    let area = Rect::new(0, 0, 20, EVERY_STATUS.len() as u16);
+    let mut buf = Buffer::empty(area);
+    for (row, status) in EVERY_STATUS.iter().enumerate() {
+        let line = Rect::new(0, row as u16, area.width, 1);
+        let spinner = theme.spinner(row as i64 * 250);
+        Line::from(vec![theme.status_text(status), " ".into(), spinner.into()])
+            .style(
+                theme
+                    .status(status)
+                    .add_modifier(theme.flash(Duration::ZERO)),
+            )
+            .render(line, &mut buf);
+    }
+    buf
+}
+
+fn modifiers(buf: &Buffer) -> Modifier {
+    buf.content()
+        .iter()
+        .fold(Modifier::empty(), |all, cell| all | cell.modifier)
+}
+
+#[test]
+fn nothing_moves_with_reduce_motion() {
+    for base in [Theme::COLOR, Theme::MONO] {
+        let theme = base.with_access(true, false);
+        let used = modifiers(&render_statuses(&theme));
+        assert!(!used.intersects(Modifier::SLOW_BLINK | Modifier::RAPID_BLINK));
+        // No flash either, so a status change doesn't light the row up for a bit
+        assert_eq!(theme.flash(Duration::ZERO), Modifier::empty());
+        // Same frame, whatever the time
+        assert_eq!(theme.spinner(0), theme.spinner(250));
+        assert_eq!(theme.status_text(&TaskStatus::OnStrike).content, "!Strike!");
+    }
+}
+
+#[test]
+fn strikes_blink_without_it() {
+    let used = modifiers(&render_statuses(&Theme::COLOR));
+    assert!(used.contains(Modifier::SLOW_BLINK));
+    assert_ne!(Theme::COLOR.spinner(0), Theme::COLOR.spinner(250));
+}
+
+#[test]
+fn plain_output_says_it_in_words() {
+    let theme = Theme::COLOR.with_access(false, true);
+    assert!(!theme.glyphs());
+    assert!(theme.markers);
+    // Every bar on the timeline is told apart by more than its color
+    let mut fills: Vec<char> = EVERY_STATUS
+        .iter()
+        .map(|status| theme.bar_fill(status, '█'))
+        .collect();
+    fills.sort();
+    fills.dedup();
+    assert_eq!(fills.len(), EVERY_STATUS.len());
+
+    // The selection's a symbol, not a background
+    let area = Rect::new(0, 0, 12, 2);
+    let mut buf = Buffer::empty(area);
+    let list = theme.highlight_quietly(List::new(["first", "second"]));
+    let mut state = ListState::default().with_selected(Some(1));
+    StatefulWidget::render(list, area, &mut buf, &mut state);
+    assert_eq!(buf[(0, 1)].symbol(), ">");
+    assert_eq!(buf[(1, 1)].symbol(), ">");
+    assert!(buf.content().iter().all(|cell| cell.bg == Color::Reset));
+    assert!(!modifiers(&buf).contains(Modifier::REVERSED));
+}
+
+#[test]
+fn compact_gives_back_the_padding() {
+    let area = Rect::new(0, 0, 80, 24);
+    let inner = |chrome: &Chrome| {
+        Block::bordered()
+            .border_set(chrome.border)
+            .padding(chrome.padding)
+            .inner(area)
+    };
+    // Border plus 1 above and 4 below, 2 either side
+    assert_eq!(inner(Chrome::pick(false)), Rect::new(3, 2, 74, 17));
+    assert_eq!(inner(Chrome::pick(true)), Rect::new(1, 1, 78, 22));
+    // Nowhere to put them, so the legend goes in the help and toasts go on the controls line
+    assert!(Chrome::COMPACT.legend_row.is_none() && Chrome::COMPACT.toast_row.is_none());
+}
+
+#[test]
+fn tasks_get_colors_of_their_own() {
+    // The same every time, and never the same as the task made before or after
+    for id in 0..1000 {
+        assert_eq!(theme::task_palette_index(id), theme::task_palette_index(id));
+        assert_ne!(
+            theme::task_palette_index(id),
+            theme::task_palette_index(id + 1)
+        );
+        assert_eq!(
+            Theme::COLOR.task_color(id),
+            Some(TASK_PALETTE[theme::task_palette_index(id)])
+        );
+    }
+    // All different, and none of them what the rows are written in or on
+    for (n, color) in TASK_PALETTE.iter().enumerate() {
+        assert!(!TASK_PALETTE[..n].contains(color));
+        assert!(![
+            Color::Reset,
+            Color::Black,
+            Color::White,
+            Color::Gray,
+            Color::DarkGray
+        ]
+        .contains(color));
+    }
+    // Nothing to give without color, or when nothing's to be said only in color
+    assert!(!Theme::MONO.tints_tasks());
+    assert_eq!(Theme::MONO.task_color(3), None);
+    assert_eq!(Theme::COLOR.with_access(false, true).task_color(3), None);
+    assert_eq!(
+        Theme::COLOR.with_access(true, false).task_color(3),
+        Some(TASK_PALETTE[3])
+    );
+}
+
+#[test]
+fn each_row_is_marked_in_its_color() {
+    let tasks = (0..3)
+        .map(|id| {
+            let mut task = Task::pending(&CandidateTask::new("Alpha", "Does a thing"), id, id + 1);
+            task.status = TaskStatus::Running;
+            task
+        })
+        .collect();
+    let mut app = App::with_tasks(Config::default(), tasks);
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let marks: Vec<Color> = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .filter(|cell| cell.symbol() == MARKER)
+        .map(|cell| cell.fg)
+        .collect();
+    assert_eq!(marks, TASK_PALETTE[..3]);
+}
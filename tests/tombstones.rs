@@ -0,0 +1,56 @@
+//! Removed IDs remembered for a while and then forgotten, and strays told apart by them
+
+use std::time::{Duration, Instant};
+
+use clustrctrl::tombstones::{Stray, Tombstones, TOMBSTONE_TTL};
+
+#[test]
+fn late_messages_say_how_late() {
+    let start = Instant::now();
+    let mut tombstones = Tombstones::default();
+    tombstones.bury(12, start);
+    let later = start + Duration::from_secs(34);
+    assert_eq!(
+        tombstones.classify(12, later),
+        Stray::Late(Duration::from_secs(34))
+    );
+    // Never removed, so never ours
+    assert_eq!(tombstones.classify(13, later), Stray::Unknown);
+}
+
+#[test]
+fn forgotten_after_the_ttl() {
+    let start = Instant::now();
+    let mut tombstones = Tombstones::new(Duration::from_secs(10));
+    tombstones.bury(1, start);
+    tombstones.bury(2, start + Duration::from_secs(5));
+    // Right on the TTL still counts
+    let edge = start + Duration::from_secs(10);
+    assert_eq!(
+        tombstones.classify(1, edge),
+        Stray::Late(Duration::from_secs(10))
+    );
+    // Past it and it's unknown, even before anything's expired
+    let past = edge + Duration::from_millis(1);
+    assert_eq!(tombstones.classify(1, past), Stray::Unknown);
+    assert_eq!(tombstones.len(), 2);
+    tombstones.expire(past);
+    assert_eq!(tombstones.len(), 1);
+    assert!(matches!(tombstones.classify(2, past), Stray::Late(_)));
+    tombstones.expire(past + Duration::from_secs(5));
+    assert!(tombstones.is_empty());
+}
+
+#[test]
+fn burying_again_starts_over() {
+    let start = Instant::now();
+    let mut tombstones = Tombstones::default();
+    tombstones.bury(3, start);
+    let again = start + TOMBSTONE_TTL;
+    tombstones.bury(3, again);
+    tombstones.expire(again + Duration::from_secs(1));
+    assert_eq!(
+        tombstones.classify(3, again + Duration::from_secs(1)),
+        Stray::Late(Duration::from_secs(1))
+    );
+}
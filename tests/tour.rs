@@ -0,0 +1,88 @@
+//! The first-run tour: each step waiting for what it asks for, skipping ahead when it's done some
+//! other way, skipping it altogether, and where its boxes go
+
+use clustrctrl::{
+    tour::{self, Step, Tour},
+    view::ViewState,
+};
+use ratatui::layout::Rect;
+
+#[test]
+fn each_step_waits_for_its_thing() {
+    let mut tour = Tour::new();
+    assert_eq!(tour.step(), Some(Step::OpenPicker));
+    // Looking about the main screen doesn't count
+    for view in [ViewState::Monitor, ViewState::Logs, ViewState::Timeline] {
+        assert!(!tour.observe(view, false));
+        assert_eq!(tour.step(), Some(Step::OpenPicker));
+    }
+    tour.observe(ViewState::TaskAdd, false);
+    assert_eq!(tour.step(), Some(Step::PickTask));
+    // Choosing how long it takes is still picking, and backing out without one isn't done
+    tour.observe(ViewState::Duration, false);
+    tour.observe(ViewState::Monitor, false);
+    assert_eq!(tour.step(), Some(Step::PickTask));
+    tour.observe(ViewState::TaskAdd, true);
+    assert_eq!(tour.step(), Some(Step::PickTask));
+    tour.observe(ViewState::Monitor, true);
+    assert_eq!(tour.step(), Some(Step::Inspect));
+    assert!(!tour.observe(ViewState::Monitor, true));
+    assert!(tour.observe(ViewState::Inspect, true));
+    assert!(tour.is_over());
+    // And it only says so the once
+    assert!(!tour.observe(ViewState::Inspect, true));
+}
+
+#[test]
+fn getting_there_some_other_way_skips_ahead() {
+    // A batch, say, with the picker never opened
+    let mut tour = Tour::new();
+    tour.observe(ViewState::Batch, false);
+    tour.observe(ViewState::Monitor, true);
+    assert_eq!(tour.step(), Some(Step::Inspect));
+}
+
+#[test]
+fn skipping_ends_it_for_good() {
+    for steps in 0..3 {
+        let mut tour = Tour::new();
+        let views = [
+            (ViewState::TaskAdd, false),
+            (ViewState::Monitor, true),
+            (ViewState::Monitor, true),
+        ];
+        for &(view, has_tasks) in &views[..steps] {
+            tour.observe(view, has_tasks);
+        }
+        tour.skip();
+        assert!(tour.is_over(), "after {steps} steps");
+        for (view, has_tasks) in views {
+            assert!(!tour.observe(view, has_tasks));
+        }
+        assert_eq!(tour.step(), None);
+    }
+    // Never started is the same as over
+    assert!(Tour::default().is_over());
+}
+
+#[test]
+fn boxes_sit_next_to_what_they_point_at() {
+    let screen = Rect::new(0, 0, 80, 24);
+    // The controls line: above it, in the middle
+    let controls = Rect::new(0, 23, 80, 1);
+    assert_eq!(
+        tour::place(controls, screen, 40, 5),
+        Rect::new(20, 18, 40, 5)
+    );
+    // Nothing above, so under it instead
+    let top = Rect::new(10, 1, 20, 3);
+    assert_eq!(tour::place(top, screen, 30, 5), Rect::new(5, 4, 30, 5));
+    // Kept on screen at the edges, and no bigger than it
+    let corner = Rect::new(78, 0, 2, 1);
+    assert_eq!(tour::place(corner, screen, 30, 5), Rect::new(50, 1, 30, 5));
+    let tiny = Rect::new(0, 0, 20, 4);
+    assert_eq!(
+        tour::place(Rect::new(0, 3, 20, 1), tiny, 40, 5),
+        Rect::new(0, 0, 20, 4)
+    );
+}
@@ -0,0 +1,263 @@
+//! Which status changes a worker's word is good for, and the orders its news can turn up in
+//! without undoing anything: late progress, late status changes and stops that cross with the end.
+//! And progress that only goes up, until another try starts it again, and a quit that comes too
+//! late not leaving its reason behind
+
+use std::time::Instant;
+
+use clustrctrl::{
+    app::App,
+    clock::SystemClock,
+    config::Config,
+    latency::Stamped,
+    retry::Attempt,
+    tasks::{CancelState, Task, TaskStatus, TaskTxMsg, WorkerStatus},
+    CandidateTask,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+
+const EVERY: [TaskStatus; 11] = [
+    TaskStatus::Scheduled,
+    TaskStatus::Running,
+    TaskStatus::Sleeping,
+    TaskStatus::Finalizing,
+    TaskStatus::OnStrike,
+    TaskStatus::KnownUnknown,
+    TaskStatus::WaitingForThread,
+    TaskStatus::Finished,
+    TaskStatus::Canceled,
+    TaskStatus::Failed,
+    TaskStatus::FailedToStart,
+];
+
+/// A task as it is once its worker's been spawned and has said it's `status`
+fn task(status: TaskStatus) -> Task {
+    let ct = CandidateTask::new("Test dummy", "Pretends to work until told otherwise");
+    let mut task = Task::pending(&ct, 0, 1);
+    task.set_status(TaskStatus::KnownUnknown, &SystemClock);
+    task.set_status(status, &SystemClock);
+    task
+}
+
+#[test]
+fn over_is_over() {
+    for from in EVERY.into_iter().filter(TaskStatus::is_terminal) {
+        for to in EVERY {
+            assert_eq!(from.can_become(to), from == to, "{from:?} -> {to:?}");
+        }
+    }
+}
+
+#[test]
+fn workers_only_get_to_say_what_theyre_up_to() {
+    let apps_own = [
+        TaskStatus::Scheduled,
+        TaskStatus::KnownUnknown,
+        TaskStatus::WaitingForThread,
+        TaskStatus::FailedToStart,
+    ];
+    for from in [
+        TaskStatus::Running,
+        TaskStatus::OnStrike,
+        TaskStatus::KnownUnknown,
+    ] {
+        for to in apps_own.into_iter().filter(|&to| to != from) {
+            assert!(!from.can_become(to), "{from:?} -> {to:?}");
+        }
+    }
+    // Nothing's listening for a task that hasn't started
+    for to in EVERY.into_iter().filter(|&to| to != TaskStatus::Scheduled) {
+        assert!(!TaskStatus::Scheduled.can_become(to), "{to:?}");
+    }
+    // Whatever it is when it's first heard from
+    for to in [
+        TaskStatus::Running,
+        TaskStatus::Finalizing,
+        TaskStatus::Canceled,
+        TaskStatus::Failed,
+        TaskStatus::Finished,
+    ] {
+        assert!(TaskStatus::WaitingForThread.can_become(to), "{to:?}");
+    }
+}
+
+#[test]
+fn the_last_block_only_gives_way_to_a_strike() {
+    let last = TaskStatus::Finalizing;
+    assert!(!last.can_become(TaskStatus::Running));
+    assert!(!last.can_become(TaskStatus::Sleeping));
+    assert!(last.can_become(TaskStatus::OnStrike));
+    assert!(last.can_become(TaskStatus::Finished));
+    // Striking over it goes back through Running, and the worker says it's finalizing again
+    let mut task = task(TaskStatus::Finalizing);
+    let strike = WorkerStatus::OnStrike { demand_secs: 5 };
+    for status in [strike, WorkerStatus::Running, WorkerStatus::Finalizing] {
+        assert!(task.report(status.into(), &SystemClock), "{status:?}");
+    }
+    assert_eq!(task.status, TaskStatus::Finalizing);
+}
+
+#[test]
+fn progress_doesnt_say_anything_about_status() {
+    let mut task = task(TaskStatus::OnStrike);
+    // Sent before the strike, read after
+    task.progressed(40, 80, Instant::now());
+    assert_eq!(task.status, TaskStatus::OnStrike);
+    assert_eq!((task.progress, task.fatigue), (40, 80));
+}
+
+#[test]
+fn progress_only_goes_up() {
+    let mut task = task(TaskStatus::Running);
+    let start = Instant::now();
+    assert!(task.progressed(40, 10, start));
+    // Backwards keeps the higher one, and says so
+    assert!(!task.progressed(25, 20, start));
+    assert_eq!((task.progress, task.fatigue, task.backwards), (40, 20, 1));
+    assert_eq!(task.smooth.reported(), Some(40));
+    // The same again is fine
+    assert!(task.progressed(40, 20, start));
+    assert!(task.progressed(55, 20, start));
+    assert_eq!((task.progress, task.backwards), (55, 1));
+}
+
+#[test]
+fn another_try_starts_from_nothing() {
+    let mut first = task(TaskStatus::Running);
+    first.progressed(80, 0, Instant::now());
+    first.progressed(70, 0, Instant::now());
+    assert!(first.report(TaskStatus::Failed, &SystemClock));
+    let mut retry = first.retry(1, Attempt { n: 2, of: 3 });
+    retry.set_status(TaskStatus::KnownUnknown, &SystemClock);
+    retry.set_status(TaskStatus::Running, &SystemClock);
+    assert_eq!((retry.progress, retry.backwards), (0, 0));
+    assert!(retry.progressed(10, 0, Instant::now()));
+    assert_eq!((retry.progress, retry.backwards), (10, 0));
+}
+
+fn progress(pct: u8) -> Stamped<TaskTxMsg> {
+    Stamped::new(TaskTxMsg::Progress {
+        id: 0,
+        pct,
+        fatigue: 0,
+        cpu_ms: 0,
+        cpu_estimated: false,
+    })
+}
+
+#[tokio::test]
+async fn going_backwards_is_counted_where_it_shows() {
+    let mut config = Config::default();
+    config.ui.debug_overlay = true;
+    let mut app = App::with_tasks(config, vec![task(TaskStatus::Running)]);
+    let tx = app.task_sender();
+    for pct in [50, 30, 50, 20] {
+        tx.try_send(progress(pct)).expect("room in the channel");
+    }
+    assert!(app.step().await);
+    assert_eq!(app.tasks()[0].progress, 50);
+    assert_eq!(app.tasks()[0].backwards, 2);
+
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).expect("nothing to fail");
+    let mut screen = |app: &mut App| {
+        terminal
+            .draw(|frame| app.view(frame))
+            .expect("nothing to fail");
+        let buffer = terminal.backend().buffer().clone();
+        (0..40)
+            .map(|y| {
+                (0..120)
+                    .map(|x| buffer[(x, y)].symbol().to_string())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    app.handle_key_event(KeyEvent::from(KeyCode::F(12)));
+    let overlay = screen(&mut app);
+    assert!(overlay.contains("protocol errors 2"), "{overlay}");
+    assert!(
+        overlay.contains("progress backwards 2 in 1 tasks"),
+        "{overlay}"
+    );
+    app.handle_key_event(KeyEvent::from(KeyCode::F(12)));
+
+    app.handle_key_event(KeyEvent::from(KeyCode::F(2)));
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('d')));
+    let detail = screen(&mut app);
+    assert!(
+        detail.contains("Went backwards: 2 reports, kept the higher"),
+        "{detail}"
+    );
+}
+
+#[test]
+fn late_news_doesnt_undo_a_stop() {
+    let mut task = task(TaskStatus::Running);
+    task.progressed(30, 0, Instant::now());
+    assert!(task.report(TaskStatus::Canceled, &SystemClock));
+    assert_eq!(task.progress_at_cancel, Some(30));
+    for status in [
+        WorkerStatus::Running,
+        WorkerStatus::Sleeping,
+        WorkerStatus::Finalizing,
+        WorkerStatus::OnStrike { demand_secs: 5 },
+    ] {
+        assert!(!task.report(status.into(), &SystemClock), "{status:?}");
+    }
+    task.progressed(60, 0, Instant::now());
+    // Nor does its handle finishing
+    assert!(!task.report(TaskStatus::Finished, &SystemClock));
+    assert_eq!(task.status, TaskStatus::Canceled);
+    assert_eq!(task.progress, 30);
+}
+
+#[test]
+fn a_failure_isnt_rounded_up_to_done() {
+    // A shell task's non-zero exit, then its handle
+    let mut task = task(TaskStatus::Running);
+    assert!(task.report(TaskStatus::Failed, &SystemClock));
+    assert!(!task.report(TaskStatus::Finished, &SystemClock));
+    assert_eq!(task.status, TaskStatus::Failed);
+}
+
+#[test]
+fn a_stop_that_crosses_with_the_end_is_still_answered() {
+    let mut task = task(TaskStatus::Finalizing);
+    task.stop_requested(chrono::Local::now());
+    assert!(task.report(TaskStatus::Finished, &SystemClock));
+    task.progress = 100;
+    // Its CancelReport was already on the way
+    assert!(!task.report(TaskStatus::Canceled, &SystemClock));
+    task.stop_acked(chrono::Local::now());
+    task.progressed(99, 0, Instant::now());
+    assert_eq!(task.status, TaskStatus::Finished);
+    assert_eq!(task.cancel, CancelState::Acked);
+    assert_eq!(task.progress, 100);
+}
+
+#[test]
+fn a_refused_resignation_leaves_no_reason() {
+    let mut app = App::with_tasks(Config::default(), vec![task(TaskStatus::Finished)]);
+    let resign = |reason: &str| {
+        Stamped::new(TaskTxMsg::Resignation {
+            id: 0,
+            reason: reason.to_string(),
+        })
+    };
+    let tx = app.task_sender();
+    tx.try_send(resign("too late"))
+        .expect("room in the channel");
+    app.drain_messages();
+    assert_eq!(app.tasks()[0].status, TaskStatus::Finished);
+    assert_eq!(app.tasks()[0].cancel_reason, None);
+
+    let mut app = App::with_tasks(Config::default(), vec![task(TaskStatus::Running)]);
+    let tx = app.task_sender();
+    tx.try_send(resign("had enough"))
+        .expect("room in the channel");
+    app.drain_messages();
+    assert_eq!(app.tasks()[0].status, TaskStatus::Canceled);
+    assert_eq!(app.tasks()[0].cancel_reason.as_deref(), Some("had enough"));
+}
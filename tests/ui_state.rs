@@ -0,0 +1,56 @@
+//! Saved UI state from this version, other versions, and nowhere near any version
+
+use std::{env, fs, path::PathBuf, process};
+
+use clustrctrl::ui_state::{self, UiState};
+
+/// A path of its own per test, since they run at the same time
+fn scratch(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("clustrctrl-ui-{name}-{}.toml", process::id()));
+    let _ = fs::remove_file(&path);
+    let _ = fs::remove_file(ui_state::aside(&path));
+    path
+}
+
+#[test]
+fn comes_back_the_same() {
+    let path = scratch("same");
+    assert_eq!(UiState::load(&path), (UiState::default(), None));
+    let state = UiState {
+        sort_by_eta: false,
+        sort_by_status: true,
+        filter: "bob".to_string(),
+        hide_finished: true,
+        tour_done: true,
+    };
+    state.save(&path).unwrap();
+    assert_eq!(UiState::load(&path), (state, None));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn other_versions_keep_what_they_can() {
+    let path = scratch("versions");
+    // Something from the future, and no filter at all
+    fs::write(&path, "sort_by_eta = true\nfollow = true\n").unwrap();
+    let (state, problem) = UiState::load(&path);
+    assert_eq!(problem, None);
+    assert!(state.sort_by_eta);
+    assert_eq!(state.filter, "");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn broken_files_are_moved_aside() {
+    let path = scratch("broken");
+    fs::write(&path, "sort_by_eta = \"sometimes\"\n[[[").unwrap();
+    let (state, problem) = UiState::load(&path);
+    assert_eq!(state, UiState::default());
+    assert!(problem.is_some_and(|p| p.contains("moved it to")));
+    assert!(!path.exists());
+    let aside = ui_state::aside(&path);
+    assert!(fs::read_to_string(&aside)
+        .unwrap()
+        .starts_with("sort_by_eta"));
+    fs::remove_file(aside).unwrap();
+}
@@ -0,0 +1,108 @@
+//! The view spec line: nothing for the defaults, only what's changed otherwise, cut down with an
+//! ellipsis when it's narrow, and `V` putting the table back so the line goes
+
+use clustrctrl::{
+    app::App,
+    config::Config,
+    task_table::SortBy,
+    tasks::{StatusClass, Task, TaskStatus},
+    view_spec::{self, ViewSpec},
+    CandidateTask,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, text::Line, Terminal};
+
+fn text(line: &Line) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key_event(KeyEvent::from(code));
+}
+
+fn screen(app: &mut App) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(120, 24)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    (0..24)
+        .map(|y| (0..120).map(|x| buffer[(x, y)].symbol()).collect())
+        .collect()
+}
+
+#[test]
+fn only_whats_changed() {
+    let spec = ViewSpec::default();
+    assert!(spec.is_default());
+    assert_eq!(view_spec::line(&spec, 80, false), None);
+
+    let eta = ViewSpec {
+        sort: SortBy::Eta,
+        filter: "db".to_string(),
+        ..ViewSpec::default()
+    };
+    let line = view_spec::line(&eta, 80, false).expect("it's changed");
+    assert_eq!(text(&line), "sort: eta ↑ · filter: \"db\"");
+    let line = view_spec::line(&eta, 80, true).expect("it's changed");
+    assert_eq!(text(&line), "sort: eta ^ | filter: \"db\"");
+
+    // Folds only count while there are groups to fold
+    let folded = ViewSpec {
+        folded: vec![StatusClass::Done],
+        ..ViewSpec::default()
+    };
+    assert!(folded.is_default());
+    let grouped = ViewSpec {
+        sort: SortBy::Status,
+        expanded: 2,
+        ..folded
+    };
+    let line = view_spec::line(&grouped, 80, false).expect("it's changed");
+    assert_eq!(text(&line), "sort: status · group: 1 folded · expanded: 2");
+}
+
+#[test]
+fn narrow_is_cut_off() {
+    let spec = ViewSpec {
+        sort: SortBy::Status,
+        filter: "something long".to_string(),
+        ..ViewSpec::default()
+    };
+    let line = view_spec::line(&spec, 20, false).expect("it's changed");
+    assert_eq!(text(&line), "sort: status · grou…");
+    assert_eq!(line.width(), 20);
+    let line = view_spec::line(&spec, 1, false).expect("it's changed");
+    assert_eq!(text(&line), "…");
+}
+
+#[test]
+fn v_puts_it_all_back() {
+    let ct = CandidateTask::new("Alpha", "Does a thing");
+    let mut done = Task::pending(&ct, 0, 1);
+    done.status = TaskStatus::Finished;
+    let mut app = App::with_tasks(Config::default(), vec![done, Task::pending(&ct, 1, 2)]);
+    let before = screen(&mut app);
+    assert!(!before.iter().any(|row| row.contains("sort:")));
+
+    press(&mut app, KeyCode::Char('s'));
+    press(&mut app, KeyCode::Char('s'));
+    let grouped = screen(&mut app);
+    let at = grouped
+        .iter()
+        .position(|row| row.contains("sort: status · group: on"))
+        .expect("it's on the screen");
+    // Straight over the header
+    assert!(grouped[at + 1].contains("Name"), "{}", grouped[at + 1]);
+
+    press(&mut app, KeyCode::Char('V'));
+    assert_eq!(screen(&mut app), before);
+    // Nothing left to put back
+    press(&mut app, KeyCode::Char('V'));
+    assert!(screen(&mut app)
+        .iter()
+        .any(|row| row.contains("already sorted and shown the default way")));
+}
@@ -0,0 +1,146 @@
+//! Hiding rows by status: `h` and the config's list, selection stepping over what's hidden, the
+//! filter going its own way, a hidden task that moves coming back, and the count in the title
+
+use clustrctrl::{
+    app::App,
+    columns::Columns,
+    config::Config,
+    nav::ListNav,
+    task_table::TaskTable,
+    tasks::{Task, TaskStatus},
+    visibility::Visibility,
+    CandidateTask,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+
+/// Running, Finished, Running, Canceled, Failed
+fn tasks() -> Vec<Task> {
+    [
+        TaskStatus::Running,
+        TaskStatus::Finished,
+        TaskStatus::Running,
+        TaskStatus::Canceled,
+        TaskStatus::Failed,
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(id, status)| {
+        let name = format!("Task{id}");
+        let mut task = Task::pending(&CandidateTask::new(name, "Does a thing"), id, 1);
+        task.status = status;
+        task
+    })
+    .collect()
+}
+
+fn table() -> TaskTable {
+    TaskTable::new(false, false, Columns::default(), ListNav::new(false))
+}
+
+fn screen(app: &mut App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    buffer.content().iter().map(|cell| cell.symbol()).collect()
+}
+
+#[test]
+fn quick_and_configured() {
+    let tasks = tasks();
+    let mut table = table();
+    assert_eq!(table.order(&tasks), [0, 1, 2, 3, 4]);
+    assert!(table.visibility.toggle_quick());
+    assert_eq!(table.order(&tasks), [0, 2, 4]);
+    assert_eq!(table.hidden(&tasks), 2);
+    assert!(!table.visibility.toggle_quick());
+    assert_eq!(table.hidden(&tasks), 0);
+
+    // The config's hidden whatever `h` is
+    table.visibility = Visibility::new(vec![TaskStatus::Failed]);
+    assert_eq!(table.order(&tasks), [0, 1, 2, 3]);
+    table.visibility.toggle_quick();
+    assert_eq!(table.order(&tasks), [0, 2]);
+}
+
+#[test]
+fn selection_steps_over_hidden_rows() {
+    let tasks = tasks();
+    let mut table = table();
+    table.visibility.toggle_quick();
+    table.select_first(&tasks);
+    assert_eq!(table.selected(&tasks), Some(0));
+    table.next(&tasks);
+    assert_eq!(table.selected(&tasks), Some(2));
+    table.next(&tasks);
+    assert_eq!(table.selected(&tasks), Some(4));
+
+    // Highlighting a hidden one doesn't take
+    table.select(&tasks, 3);
+    assert_eq!(table.selected(&tasks), Some(4));
+}
+
+#[test]
+fn the_filter_goes_its_own_way() {
+    let tasks = tasks();
+    let mut table = table();
+    for ch in "Task1".chars() {
+        table.push_filter(ch);
+    }
+    assert_eq!(table.order(&tasks), [1]);
+    table.visibility.toggle_quick();
+    assert_eq!(table.order(&tasks), Vec::<usize>::new());
+    table.clear_filter();
+    assert_eq!(table.order(&tasks), [0, 2, 4]);
+    // Still hidden with the filter gone
+    assert!(table.visibility.is_quick());
+}
+
+#[test]
+fn moving_while_hidden_shows_it_again() {
+    let mut tasks = tasks();
+    let mut visibility = Visibility::default();
+    visibility.toggle_quick();
+    assert_eq!(visibility.watch(&tasks), 0);
+    assert!(visibility.hides(&tasks[1]));
+    // Over already, and over again some other way
+    tasks[1].status = TaskStatus::Canceled;
+    assert_eq!(visibility.watch(&tasks), 1);
+    assert!(!visibility.hides(&tasks[1]));
+    // For good, until `h` starts over
+    assert_eq!(visibility.watch(&tasks), 0);
+    assert!(!visibility.hides(&tasks[1]));
+    assert!(visibility.hides(&tasks[3]));
+    visibility.toggle_quick();
+    visibility.toggle_quick();
+    assert!(visibility.hides(&tasks[1]));
+}
+
+#[test]
+fn h_on_the_main_screen() {
+    let mut app = App::with_tasks(Config::default(), tasks());
+    assert!(screen(&mut app).contains("Task1"));
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('h')));
+    let hiding = screen(&mut app);
+    assert!(hiding.contains("Task Table (+2 hidden)"), "{hiding}");
+    assert!(
+        !hiding.contains("Task1") && !hiding.contains("Task3"),
+        "{hiding}"
+    );
+    assert!(hiding.contains("Task4"), "{hiding}");
+    // Still tasks, just not rows
+    assert_eq!(app.tasks().len(), 5);
+
+    app.handle_key_event(KeyEvent::from(KeyCode::Char('h')));
+    let showing = screen(&mut app);
+    assert!(showing.contains("Task1") && !showing.contains("hidden)"));
+
+    let mut config = Config::default();
+    config.ui.hidden_statuses = vec![TaskStatus::Failed];
+    let mut app = App::with_tasks(config, tasks());
+    let configured = screen(&mut app);
+    assert!(configured.contains("(+1 hidden)"), "{configured}");
+    assert!(!configured.contains("Task4"), "{configured}");
+}
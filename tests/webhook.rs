@@ -0,0 +1,104 @@
+//! The webhook against a stub that's just a socket, so there's no server framework to pull in for
+//! one happy path and one endpoint that never answers
+
+use std::time::Duration;
+
+use clustrctrl::{
+    clock::SystemClock,
+    tasks::Task,
+    webhook::{Event, EventKind, Webhook},
+    CandidateTask,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time::timeout,
+};
+
+const PATIENCE: Duration = Duration::from_secs(15);
+
+fn event(kind: EventKind) -> Event {
+    let ct = CandidateTask::new("Hooked", "Gets talked about");
+    let task = Task::failed_to_start(&ct, 7, 1, "not really".to_string(), &SystemClock);
+    Event::new(kind, &task, chrono::Local::now())
+}
+
+/// Reads one whole request off `stream` and hands back its body
+async fn read_request(stream: &mut TcpStream) -> String {
+    let mut raw = Vec::new();
+    let mut buf = [0; 1024];
+    loop {
+        let n = stream.read(&mut buf).await.unwrap();
+        assert!(n > 0, "client hung up mid-request");
+        raw.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&raw);
+        let Some(split) = text.find("\r\n\r\n") else {
+            continue;
+        };
+        let length = text[..split]
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.eq_ignore_ascii_case("content-length")
+                    .then(|| value.trim().parse::<usize>().unwrap())
+            })
+            .unwrap_or(0);
+        if raw.len() >= split + 4 + length {
+            return text[split + 4..split + 4 + length].to_string();
+        }
+    }
+}
+
+#[tokio::test]
+async fn posts_the_summary() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let body = read_request(&mut stream).await;
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        body
+    });
+
+    let webhook = Webhook::new(url, Duration::from_secs(5)).unwrap();
+    let sent = timeout(PATIENCE, webhook.send(event(EventKind::Failed)))
+        .await
+        .expect("send never finished")
+        .unwrap();
+    assert!(sent);
+    assert_eq!(webhook.failures(), 0);
+
+    let body: serde_json::Value = serde_json::from_str(&server.await.unwrap()).unwrap();
+    assert_eq!(body["event"], "failed");
+    assert_eq!(body["id"], 7);
+    assert_eq!(body["name"], "Hooked");
+    assert_eq!(body["status"], "DOA");
+    assert!(body["timestamp"].is_string());
+}
+
+#[tokio::test]
+async fn gives_up_on_a_silent_endpoint() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    // Takes every connection and never says a word
+    let server = tokio::spawn(async move {
+        let mut held = Vec::new();
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            held.push(stream);
+        }
+    });
+
+    let webhook = Webhook::new(url, Duration::from_millis(100)).unwrap();
+    let sent = timeout(PATIENCE, webhook.send(event(EventKind::Created)))
+        .await
+        .expect("timeout didn't apply")
+        .unwrap();
+    assert!(!sent);
+    // Retry and all, it's one event that didn't make it
+    assert_eq!(webhook.failures(), 1);
+    server.abort();
+}
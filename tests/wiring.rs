@@ -0,0 +1,124 @@
+//! Broken channels, by dropping their far ends: the inbound one latched either way it shows, a
+//! control channel only when its worker's still going, sends skipped and counted after that, and
+//! the banner on the main screen
+
+use std::time::Duration;
+
+use clustrctrl::{
+    app::App,
+    chaos::ChaosConfig,
+    clock::SystemClock,
+    config::Config,
+    tasks::{Task, TaskRxMsg, MIN_INTENSITY},
+    wiring::{Link, Wiring},
+    CandidateTask,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+use tokio::sync::mpsc::{self, error::TryRecvError};
+
+fn dummy() -> CandidateTask {
+    CandidateTask::new("Test dummy", "Pretends to work until told otherwise")
+}
+
+/// A real worker, whose control channel App's side is then swapped for one nobody's listening
+/// on. What's handed back still reaches the worker, to stop it with afterwards
+fn cut_off(id: usize) -> (Task, mpsc::Sender<TaskRxMsg>) {
+    let (tx, _rx) = mpsc::channel(32);
+    let mut task = Task::spawn(
+        &dummy(),
+        tx,
+        id,
+        MIN_INTENSITY,
+        1,
+        ChaosConfig::default(),
+        &SystemClock,
+    )
+    .expect("there's a runtime right here");
+    let (closed, rx) = mpsc::channel(1);
+    drop(rx);
+    let real = std::mem::replace(&mut task.control, closed);
+    (task, real)
+}
+
+#[test]
+fn inbound_latches_either_way() {
+    let (tx, rx) = mpsc::channel::<u8>(1);
+    let mut wiring = Wiring::default();
+    wiring.check_inbound(&tx);
+    assert!(!wiring.is_broken());
+    drop(rx);
+    wiring.check_inbound(&tx);
+    assert_eq!(wiring.broken(), [Link::Inbound]);
+
+    // Every sender gone, as `try_recv` tells it. Only empty is nothing to worry about
+    let (tx, mut rx) = mpsc::channel::<u8>(1);
+    let mut wiring = Wiring::default();
+    wiring.heard(TryRecvError::Empty);
+    assert_eq!(wiring.banner(), None);
+    drop(tx);
+    wiring.heard(rx.try_recv().expect_err("nobody's sending"));
+    assert_eq!(wiring.broken(), [Link::Inbound]);
+    // Latched once, however many times it's seen
+    wiring.heard(TryRecvError::Disconnected);
+    assert_eq!(wiring.broken().len(), 1);
+    assert!(wiring
+        .banner()
+        .is_some_and(|text| text.contains("the channel workers report on is closed")));
+}
+
+#[tokio::test]
+async fn a_running_workers_closed_channel_latches_and_then_skips() {
+    let mut wiring = Wiring::default();
+    // No worker at all is just a task that's over, not broken wiring
+    let mut pending = Task::pending(&dummy(), 1, 1);
+    assert!(!wiring.tell(&mut pending, TaskRxMsg::EveryoneStopPls, &SystemClock));
+    assert!(!wiring.is_broken());
+
+    let (mut task, real) = cut_off(0);
+    assert!(!wiring.tell(&mut task, TaskRxMsg::EveryoneStopPls, &SystemClock));
+    assert_eq!(wiring.broken(), [Link::Control(0)]);
+    assert_eq!(wiring.suppressed(), 0);
+    // Now it's known to go nowhere, it isn't tried
+    assert!(!wiring.tell(&mut task, TaskRxMsg::EveryoneStopPls, &SystemClock));
+    assert!(wiring.request_stop(&mut task, &SystemClock).is_err());
+    assert_eq!(wiring.suppressed(), 2);
+    let banner = wiring.banner().expect("something's broken");
+    assert!(
+        banner.contains("task 0's control channel closed while it was running"),
+        "{banner}"
+    );
+    assert!(banner.contains("(2 sends skipped)"), "{banner}");
+
+    // Back on the real one, which still works
+    task.control = real;
+    task.request_stop(&SystemClock)
+        .expect("the worker's listening");
+    task.join(Duration::from_secs(15))
+        .await
+        .expect("it stops when asked");
+}
+
+#[tokio::test]
+async fn the_banner_stays_up() {
+    let (task, real) = cut_off(0);
+    let mut app = App::with_tasks(Config::default(), vec![task]);
+    // Inspect, and terminate the only row
+    app.handle_key_event(KeyEvent::from(KeyCode::F(2)));
+    app.handle_key_event(KeyEvent::from(KeyCode::Enter));
+    assert_eq!(app.wiring().broken(), [Link::Control(0)]);
+    app.handle_key_event(KeyEvent::from(KeyCode::Enter));
+    assert_eq!(app.wiring().suppressed(), 1);
+
+    let mut terminal = Terminal::new(TestBackend::new(120, 24)).expect("nothing to fail");
+    terminal
+        .draw(|frame| app.view(frame))
+        .expect("nothing to fail");
+    let buffer = terminal.backend().buffer();
+    let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+    assert!(screen.contains("✘ Task control is degraded"), "{screen}");
+    assert!(screen.contains("(1 send skipped)"), "{screen}");
+
+    real.try_send(TaskRxMsg::PleaseStop)
+        .expect("the worker's listening");
+}
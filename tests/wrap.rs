@@ -0,0 +1,46 @@
+//! Wrapping descriptions for expanded rows, including words that don't fit on a line at all, and
+//! cutting milestones short
+
+use clustrctrl::wrap::{truncate, wrap};
+
+#[test]
+fn breaks_at_spaces() {
+    assert_eq!(
+        wrap("Wait for Pokemon cards to arrive", 12),
+        ["Wait for", "Pokemon", "cards to", "arrive"]
+    );
+    // Exactly the width still fits, and runs of spaces don't make blank-ish lines
+    assert_eq!(wrap("abc  def", 7), ["abc def"]);
+    assert_eq!(wrap("short", 80), ["short"]);
+}
+
+#[test]
+fn long_words_are_cut() {
+    assert_eq!(
+        wrap("see antidisestablishmentarianism", 10),
+        ["see", "antidisest", "ablishment", "arianism"]
+    );
+    // What's left of a cut word shares its line with what comes after, if there's room
+    assert_eq!(wrap("abcdefg hi", 5), ["abcde", "fg hi"]);
+    // Counted in characters, not bytes
+    assert_eq!(wrap("ééééé", 2), ["éé", "éé", "é"]);
+}
+
+#[test]
+fn always_at_least_a_line() {
+    assert_eq!(wrap("", 10), [""]);
+    assert_eq!(wrap("   ", 10), [""]);
+    // A width of nothing gets a character per line instead of looping forever
+    assert_eq!(wrap("ab", 0), ["a", "b"]);
+    // Line breaks already there are kept
+    assert_eq!(wrap("one\ntwo three", 20), ["one", "two three"]);
+}
+
+#[test]
+fn truncating_leaves_an_ellipsis() {
+    assert_eq!(truncate("warmup done", 20), "warmup done");
+    assert_eq!(truncate("warmup done", 11), "warmup done");
+    assert_eq!(truncate("warmup done", 7), "warmup…");
+    assert_eq!(truncate("ééé", 2), "é…");
+    assert_eq!(truncate("anything", 0), "");
+}